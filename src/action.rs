@@ -0,0 +1,503 @@
+//! Main view actions
+//!
+//! Every command available from the network list is an [`Action`], so key
+//! presses (see [`crate::keymap`]), the command palette and `:` commands run
+//! through the same code path.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Search,
+    ClearSearch,
+    /// Jump to the next network matching the search, only bound while searching
+    NextMatch,
+    PrevMatch,
+    AddNetwork,
+    /// Fill the add-network form from a `WIFI:` URI on the clipboard
+    JoinUri,
+    Back,
+    Quit,
+    Down,
+    Up,
+    Top,
+    Bottom,
+    Connect,
+    /// Join through the router's WPS push-button
+    WpsConnect,
+    /// Connect to the n-th visible network (1-based)
+    QuickConnect(u8),
+    Refresh,
+    ToggleAutoConnect,
+    ToggleRandomMac,
+    ToggleMetered,
+    Forget,
+    /// Restore the profile deleted by the last forget
+    UndoForget,
+    /// Replace a saved profile whose security the network no longer offers
+    RecreateProfile,
+    ToggleMark,
+    Compare,
+    ToggleAccessPoints,
+    NextDetailsTab,
+    PrevDetailsTab,
+    /// Show the n-th details tab (1-based)
+    DetailsTab(u8),
+    Share,
+    SwitchBand,
+    /// Reconnect to the strongest access point of the connected network
+    RoamNow,
+    NextInterface,
+    ToggleRadio,
+    Hotspot,
+    /// Hotspot under a throwaway SSID and passphrase that removes itself
+    GuestNetwork,
+    SavedProfiles,
+    /// Change the saved profile's connection mode, hidden flag, security or password
+    EditProfile,
+    /// Give the saved profile a name other than its SSID
+    RenameProfile,
+    ExportProfile,
+    ImportProfile,
+    RevealPassword,
+    CopyPassword,
+    CopySsid,
+    OpenPortal,
+    SpeedTest,
+    ChannelGraph,
+    CycleSort,
+    CycleFilter,
+    /// Switch to the next built-in or custom color theme
+    CycleTheme,
+    ReloadConfig,
+    /// Show or hide the debug log panel
+    ToggleDebugLog,
+    ToggleDebugPane,
+    ToggleSignalLog,
+    ToggleSurvey,
+    TagSurvey,
+    CommandPalette,
+    CommandLine,
+    /// Overlay listing every key binding
+    Help,
+}
+
+impl Action {
+    /// Actions listed in the command palette, in display order
+    pub const PALETTE: &[Action] = &[
+        Action::Connect,
+        Action::WpsConnect,
+        Action::Refresh,
+        Action::Search,
+        Action::ClearSearch,
+        Action::CycleSort,
+        Action::CycleFilter,
+        Action::AddNetwork,
+        Action::JoinUri,
+        Action::ToggleAutoConnect,
+        Action::ToggleRandomMac,
+        Action::ToggleMetered,
+        Action::Forget,
+        Action::UndoForget,
+        Action::RecreateProfile,
+        Action::Share,
+        Action::RevealPassword,
+        Action::CopyPassword,
+        Action::CopySsid,
+        Action::OpenPortal,
+        Action::SpeedTest,
+        Action::ChannelGraph,
+        Action::ToggleMark,
+        Action::Compare,
+        Action::ToggleAccessPoints,
+        Action::NextDetailsTab,
+        Action::PrevDetailsTab,
+        Action::SwitchBand,
+        Action::RoamNow,
+        Action::NextInterface,
+        Action::ToggleRadio,
+        Action::Hotspot,
+        Action::GuestNetwork,
+        Action::SavedProfiles,
+        Action::EditProfile,
+        Action::RenameProfile,
+        Action::ExportProfile,
+        Action::ImportProfile,
+        Action::ToggleSignalLog,
+        Action::ToggleSurvey,
+        Action::TagSurvey,
+        Action::Help,
+        Action::CycleTheme,
+        Action::ReloadConfig,
+        Action::ToggleDebugLog,
+        Action::ToggleDebugPane,
+        Action::Top,
+        Action::Bottom,
+        Action::Quit,
+    ];
+
+    /// Sections of the key binding overlay; bound actions missing here are
+    /// listed under "Other"
+    pub const HELP_GROUPS: &[(&str, &[Action])] = &[
+        (
+            "Navigation",
+            &[
+                Action::Down,
+                Action::Up,
+                Action::Top,
+                Action::Bottom,
+                Action::Search,
+                Action::ClearSearch,
+                Action::NextMatch,
+                Action::PrevMatch,
+                Action::CycleSort,
+                Action::CycleFilter,
+                Action::NextInterface,
+                Action::NextDetailsTab,
+                Action::PrevDetailsTab,
+                Action::DetailsTab(1),
+                Action::Back,
+            ],
+        ),
+        (
+            "Connection",
+            &[
+                Action::Connect,
+                Action::QuickConnect(1),
+                Action::WpsConnect,
+                Action::Refresh,
+                Action::AddNetwork,
+                Action::JoinUri,
+                Action::SwitchBand,
+                Action::RoamNow,
+                Action::ToggleRadio,
+                Action::OpenPortal,
+            ],
+        ),
+        (
+            "Saved Networks",
+            &[
+                Action::ToggleAutoConnect,
+                Action::ToggleRandomMac,
+                Action::ToggleMetered,
+                Action::Forget,
+                Action::UndoForget,
+                Action::RecreateProfile,
+                Action::SavedProfiles,
+                Action::EditProfile,
+                Action::RenameProfile,
+                Action::ExportProfile,
+                Action::ImportProfile,
+            ],
+        ),
+        (
+            "Sharing",
+            &[
+                Action::Share,
+                Action::RevealPassword,
+                Action::CopyPassword,
+                Action::CopySsid,
+                Action::Hotspot,
+                Action::GuestNetwork,
+            ],
+        ),
+        (
+            "Tools",
+            &[
+                Action::SpeedTest,
+                Action::ChannelGraph,
+                Action::ToggleMark,
+                Action::Compare,
+                Action::ToggleAccessPoints,
+                Action::ToggleSignalLog,
+                Action::ToggleSurvey,
+                Action::TagSurvey,
+            ],
+        ),
+        (
+            "App",
+            &[
+                Action::CommandPalette,
+                Action::CommandLine,
+                Action::Help,
+                Action::CycleTheme,
+                Action::ReloadConfig,
+                Action::ToggleDebugLog,
+                Action::ToggleDebugPane,
+                Action::Quit,
+            ],
+        ),
+    ];
+
+    /// Human-readable description shown in the command palette
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Search => "Search networks",
+            Action::ClearSearch => "Clear search",
+            Action::NextMatch => "Next search match",
+            Action::PrevMatch => "Previous search match",
+            Action::AddNetwork => "Add network manually",
+            Action::JoinUri => "Join from WIFI: QR text on the clipboard",
+            Action::Back => "Back",
+            Action::Quit => "Quit",
+            Action::Down => "Select next network",
+            Action::Up => "Select previous network",
+            Action::Top => "Go to first network",
+            Action::Bottom => "Go to last network",
+            Action::Connect => "Connect / disconnect",
+            Action::WpsConnect => "Connect with WPS push-button",
+            Action::QuickConnect(_) => "Connect to numbered network",
+            Action::Refresh => "Refresh networks",
+            Action::ToggleAutoConnect => "Toggle auto-connect",
+            Action::ToggleRandomMac => "Toggle randomized MAC address",
+            Action::ToggleMetered => "Toggle metered connection",
+            Action::Forget => "Forget network",
+            Action::UndoForget => "Undo forget network",
+            Action::RecreateProfile => "Recreate profile with current security",
+            Action::ToggleMark => "Mark for comparison",
+            Action::Compare => "Compare marked networks",
+            Action::ToggleAccessPoints => "Show / hide access points",
+            Action::NextDetailsTab => "Next details tab",
+            Action::PrevDetailsTab => "Previous details tab",
+            Action::DetailsTab(_) => "Show numbered details tab",
+            Action::Share => "Share network (QR code)",
+            Action::SwitchBand => "Switch to faster band",
+            Action::RoamNow => "Roam to strongest access point",
+            Action::NextInterface => "Switch WiFi adapter",
+            Action::ToggleRadio => "Turn WiFi radio on / off",
+            Action::Hotspot => "Mobile hotspot",
+            Action::GuestNetwork => "Start guest network (QR code)",
+            Action::SavedProfiles => "Saved profiles (priority order)",
+            Action::EditProfile => "Edit saved profile",
+            Action::RenameProfile => "Rename saved profile",
+            Action::ExportProfile => "Export profile to XML",
+            Action::ImportProfile => "Import profile from XML",
+            Action::RevealPassword => "Show / hide saved password",
+            Action::CopyPassword => "Copy saved password",
+            Action::CopySsid => "Copy SSID",
+            Action::OpenPortal => "Open captive portal login",
+            Action::SpeedTest => "Speed test",
+            Action::ChannelGraph => "Channel congestion graph",
+            Action::CycleSort => "Cycle sort order",
+            Action::CycleFilter => "Cycle filter (saved / open / 5 GHz)",
+            Action::CycleTheme => "Cycle color theme",
+            Action::ReloadConfig => "Reload config file",
+            Action::ToggleDebugLog => "Show / hide debug log",
+            Action::ToggleDebugPane => "Expand / collapse debug pane",
+            Action::ToggleSignalLog => "Start / stop signal logging",
+            Action::ToggleSurvey => "Start / finish site survey",
+            Action::TagSurvey => "Tag survey location",
+            Action::CommandPalette => "Command palette",
+            Action::CommandLine => "Command line",
+            Action::Help => "Keybinding help",
+        }
+    }
+
+    /// Name of the action in the `[keys]` config table
+    pub fn name(self) -> &'static str {
+        const QUICK_CONNECT: [&str; 9] = [
+            "quick_connect_1",
+            "quick_connect_2",
+            "quick_connect_3",
+            "quick_connect_4",
+            "quick_connect_5",
+            "quick_connect_6",
+            "quick_connect_7",
+            "quick_connect_8",
+            "quick_connect_9",
+        ];
+        const DETAILS_TAB: [&str; 4] = [
+            "details_tab_1",
+            "details_tab_2",
+            "details_tab_3",
+            "details_tab_4",
+        ];
+        match self {
+            Action::Search => "search",
+            Action::ClearSearch => "clear_search",
+            Action::NextMatch => "next_match",
+            Action::PrevMatch => "prev_match",
+            Action::AddNetwork => "add_network",
+            Action::JoinUri => "join_uri",
+            Action::Back => "back",
+            Action::Quit => "quit",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::Connect => "connect",
+            Action::WpsConnect => "wps_connect",
+            Action::QuickConnect(n) => QUICK_CONNECT[(n.clamp(1, 9) - 1) as usize],
+            Action::Refresh => "refresh",
+            Action::ToggleAutoConnect => "toggle_auto_connect",
+            Action::ToggleRandomMac => "toggle_random_mac",
+            Action::ToggleMetered => "toggle_metered",
+            Action::Forget => "forget",
+            Action::UndoForget => "undo_forget",
+            Action::RecreateProfile => "recreate_profile",
+            Action::ToggleMark => "toggle_mark",
+            Action::Compare => "compare",
+            Action::ToggleAccessPoints => "toggle_access_points",
+            Action::NextDetailsTab => "next_details_tab",
+            Action::PrevDetailsTab => "prev_details_tab",
+            Action::DetailsTab(n) => DETAILS_TAB[(n.clamp(1, 4) - 1) as usize],
+            Action::Share => "share",
+            Action::SwitchBand => "switch_band",
+            Action::RoamNow => "roam_now",
+            Action::NextInterface => "next_interface",
+            Action::ToggleRadio => "toggle_radio",
+            Action::Hotspot => "hotspot",
+            Action::GuestNetwork => "guest_network",
+            Action::SavedProfiles => "saved_profiles",
+            Action::EditProfile => "edit_profile",
+            Action::RenameProfile => "rename_profile",
+            Action::ExportProfile => "export_profile",
+            Action::ImportProfile => "import_profile",
+            Action::RevealPassword => "reveal_password",
+            Action::CopyPassword => "copy_password",
+            Action::CopySsid => "copy_ssid",
+            Action::OpenPortal => "open_portal",
+            Action::SpeedTest => "speed_test",
+            Action::ChannelGraph => "channel_graph",
+            Action::CycleSort => "cycle_sort",
+            Action::CycleFilter => "cycle_filter",
+            Action::CycleTheme => "cycle_theme",
+            Action::ReloadConfig => "reload_config",
+            Action::ToggleDebugLog => "toggle_debug_log",
+            Action::ToggleDebugPane => "toggle_debug_pane",
+            Action::ToggleSignalLog => "toggle_signal_log",
+            Action::ToggleSurvey => "toggle_survey",
+            Action::TagSurvey => "tag_survey",
+            Action::CommandPalette => "command_palette",
+            Action::CommandLine => "command_line",
+            Action::Help => "help",
+        }
+    }
+
+    /// Whether the action only has its keys while a search is active
+    pub fn needs_search(self) -> bool {
+        matches!(self, Action::NextMatch | Action::PrevMatch)
+    }
+
+    /// Look up an action by its config name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::PALETTE
+            .iter()
+            .chain(&[
+                Action::Back,
+                Action::Down,
+                Action::Up,
+                Action::NextMatch,
+                Action::PrevMatch,
+                Action::CommandPalette,
+                Action::CommandLine,
+            ])
+            .copied()
+            .chain((1..=9).map(Action::QuickConnect))
+            .chain((1..=4).map(Action::DetailsTab))
+            .find(|action| action.name() == name)
+    }
+}
+
+/// A parsed `:` command line, e.g. `:connect MySSID`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    pub action: Action,
+    /// Network SSID, search query, survey label, sort order, filter, theme,
+    /// file path, new profile name or `WIFI:` URI, depending on the action
+    pub argument: Option<String>,
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim().trim_start_matches(':');
+        let (name, argument) = match line.split_once(char::is_whitespace) {
+            Some((name, rest)) => (name, Some(rest.trim().to_string())),
+            None => (line, None),
+        };
+        let argument = argument.filter(|a| !a.is_empty());
+
+        let action = match name {
+            "connect" => Action::Connect,
+            "wps" => Action::WpsConnect,
+            "forget" => Action::Forget,
+            "undo" => Action::UndoForget,
+            "recreate" => Action::RecreateProfile,
+            "autoconnect" | "auto" => Action::ToggleAutoConnect,
+            "randommac" | "mac" => Action::ToggleRandomMac,
+            "metered" => Action::ToggleMetered,
+            "share" | "qr" => Action::Share,
+            "mark" => Action::ToggleMark,
+            "compare" => Action::Compare,
+            "aps" => Action::ToggleAccessPoints,
+            "tab" => Action::NextDetailsTab,
+            "search" => Action::Search,
+            "nohl" | "clear" => Action::ClearSearch,
+            "add" => Action::AddNetwork,
+            "join" => Action::JoinUri,
+            "refresh" => Action::Refresh,
+            "band" => Action::SwitchBand,
+            "roam" => Action::RoamNow,
+            "adapter" => Action::NextInterface,
+            "radio" => Action::ToggleRadio,
+            "hotspot" => Action::Hotspot,
+            "guest" => Action::GuestNetwork,
+            "profiles" => Action::SavedProfiles,
+            "edit" => Action::EditProfile,
+            "rename" => Action::RenameProfile,
+            "export" => Action::ExportProfile,
+            "import" => Action::ImportProfile,
+            "password" | "pw" => Action::RevealPassword,
+            "yank" | "copy" => Action::CopyPassword,
+            "yankssid" | "copyssid" => Action::CopySsid,
+            "portal" | "login" => Action::OpenPortal,
+            "speedtest" | "speed" => Action::SpeedTest,
+            "channels" | "graph" => Action::ChannelGraph,
+            "sort" => Action::CycleSort,
+            "filter" => Action::CycleFilter,
+            "theme" => Action::CycleTheme,
+            "reload" => Action::ReloadConfig,
+            "debug" => Action::ToggleDebugPane,
+            "debuglog" | "logs" => Action::ToggleDebugLog,
+            "log" => Action::ToggleSignalLog,
+            "survey" => Action::ToggleSurvey,
+            "tag" => Action::TagSurvey,
+            "top" => Action::Top,
+            "bottom" => Action::Bottom,
+            "palette" => Action::CommandPalette,
+            "help" | "keys" => Action::Help,
+            "q" | "quit" => Action::Quit,
+            "" => return Err("Empty command".to_string()),
+            _ => return Err(format!("Unknown command: {}", name)),
+        };
+
+        let takes_argument = matches!(
+            action,
+            Action::Connect
+                | Action::WpsConnect
+                | Action::Forget
+                | Action::RecreateProfile
+                | Action::ToggleAutoConnect
+                | Action::ToggleRandomMac
+                | Action::ToggleMetered
+                | Action::Share
+                | Action::EditProfile
+                | Action::RenameProfile
+                | Action::ExportProfile
+                | Action::ImportProfile
+                | Action::JoinUri
+                | Action::RevealPassword
+                | Action::CopyPassword
+                | Action::CopySsid
+                | Action::ToggleMark
+                | Action::Search
+                | Action::TagSurvey
+                | Action::CycleSort
+                | Action::CycleFilter
+                | Action::CycleTheme
+        );
+        if argument.is_some() && !takes_argument {
+            return Err(format!(":{} takes no argument", name));
+        }
+
+        Ok(Self { action, argument })
+    }
+}
@@ -1,29 +1,242 @@
 use crate::{
-    config::{self, IconSet},
+    action::{Action, Command},
+    clipboard::ClipboardState,
+    config::{self, Config, ConfigOverrides, IconSet},
+    counters::CounterHistory,
+    filter::ListFilter,
+    guest::GuestNetwork,
     input::InputState,
-    wifi::{ConnectionEvent, WifiInfo, WifiListener},
+    keymap::Keymap,
+    latency::{LatencyStats, LatencyUpdate},
+    monitor::{SignalLogger, format_timestamp},
+    net::Connectivity,
+    notify::{self, NotifyMode},
+    presence::Presence,
+    query::Query,
+    roaming::{self, RoamCandidate},
+    rogue::{self, KnownBssids, Suspicion},
+    rules::{self, Plan},
+    session::Session,
+    sort::{self, SortMode},
+    speedtest::{SpeedTestPhase, SpeedTestRecord, SpeedTestUpdate},
+    steering::{self, SteeringSuggestion},
+    survey::Survey,
+    theme::{self, Theme},
+    wifi::{
+        self, Band, ConnectionEvent, EapMethod, HotspotStatus, InterfaceInfo, InterfaceStats,
+        IpInfo, ProfileOptions, RadioState, WifiInfo, WifiListener,
+    },
 };
 use color_eyre::eyre::Result;
-use ratatui::widgets::ListState;
-use std::time::{Duration, Instant};
+use ratatui::{layout::Rect, widgets::ListState};
+use secrecy::SecretString;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc::{Receiver, UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Result of a background refresh: visible networks and the connected SSID
+pub type NetworkUpdate = (Vec<WifiInfo>, Option<String>);
 
 /// Network-related state
+///
+/// Entries are shared between the full and filtered lists, so refreshing and
+/// filtering only clone pointers.
 #[derive(Debug)]
 pub struct NetworkState {
-    pub wifi_list: Vec<WifiInfo>,
-    pub filtered_wifi_list: Vec<WifiInfo>,
+    pub wifi_list: Vec<Arc<WifiInfo>>,
+    pub filtered_wifi_list: Vec<Arc<WifiInfo>>,
     pub connected_ssid: Option<String>,
+    pub marked: Vec<(String, String)>,
+    pub interfaces: Vec<InterfaceInfo>,
+    pub active_interface: Option<InterfaceInfo>,
+    pub interfaces_rx: Option<Receiver<crate::error::WifiResult<Vec<InterfaceInfo>>>>,
+    /// `None` until the first query completes
+    pub radio: Option<RadioState>,
+    /// Rendered rows for `filtered_wifi_list`, `None` when stale
+    pub list_rows: Option<Vec<ListRow>>,
+    pub sort: SortMode,
+    pub filter: ListFilter,
+    /// Unix seconds each SSID was last seen connected, for [`SortMode::LastConnected`]
+    pub last_connected: BTreeMap<String, u64>,
+    /// Access points seen while connected, for evil twin detection
+    pub known_bssids: KnownBssids,
+    /// Open networks joined despite the unencrypted warning, not asked about again
+    pub accepted_open: BTreeSet<String>,
+    /// Metered setting of saved profiles, read when first selected; `None`
+    /// when it couldn't be read
+    pub metered: HashMap<String, Option<bool>>,
+    /// The last forgotten profile while it can still be restored
+    pub forgotten: Option<ForgottenProfile>,
+    /// Which networks are fading out or new, and the order they keep
+    pub presence: Presence,
+}
+
+/// Pre-built text for one row of the network list
+#[derive(Debug, Clone)]
+pub struct ListRow {
+    pub text: String,
+    /// Signal bars and percentage, right-aligned when there is room
+    pub signal: String,
+    pub security: String,
+    pub is_saved: bool,
+    pub is_connected: bool,
+    /// The saved profile's security no longer matches the network
+    pub profile_mismatch: bool,
+    /// Might be an evil twin, see [`rogue::check`]
+    pub suspicious: bool,
+    /// Missing from the latest scan, see [`Presence::is_fading`]
+    pub fading: bool,
+    /// Only just appeared, see [`Presence::is_new`]
+    pub is_new: bool,
+    /// Char positions in `text` matching the search
+    pub matched: Vec<usize>,
 }
 
 impl NetworkState {
-    pub fn new(wifi_list: Vec<WifiInfo>) -> Self {
+    pub fn new(wifi_list: Vec<WifiInfo>, sort: SortMode) -> Self {
+        let wifi_list: Vec<Arc<WifiInfo>> = wifi_list.into_iter().map(Arc::new).collect();
         Self {
             filtered_wifi_list: wifi_list.clone(),
             wifi_list,
             connected_ssid: None,
+            marked: Vec::new(),
+            interfaces: Vec::new(),
+            active_interface: None,
+            interfaces_rx: None,
+            radio: None,
+            list_rows: None,
+            sort,
+            filter: ListFilter::default(),
+            last_connected: BTreeMap::new(),
+            known_bssids: KnownBssids::new(),
+            accepted_open: BTreeSet::new(),
+            metered: HashMap::new(),
+            forgotten: None,
+            presence: Presence::default(),
         }
     }
+
+    /// Merge new scan results into the list, see [`Presence::merge`]
+    pub fn set_networks(&mut self, wifi_list: Vec<WifiInfo>) {
+        self.wifi_list = self.presence.merge(&self.wifi_list, wifi_list);
+    }
+
+    /// Replace the list outright, without fading out what is missing
+    pub fn replace_networks(&mut self, wifi_list: Vec<WifiInfo>) {
+        self.presence.clear();
+        self.wifi_list = wifi_list.into_iter().map(Arc::new).collect();
+    }
+
+    /// Networks in the latest scan, leaving out those fading from the list
+    pub fn in_range(&self) -> Vec<Arc<WifiInfo>> {
+        self.wifi_list
+            .iter()
+            .filter(|w| !self.presence.is_fading(w))
+            .cloned()
+            .collect()
+    }
+
+    /// Remember that the connected network was seen just now
+    pub fn touch_last_connected(&mut self) {
+        let Some(ssid) = &self.connected_ssid else {
+            return;
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.last_connected.insert(ssid.clone(), now);
+        if self.last_connected.len() > config::LAST_CONNECTED_LIMIT
+            && let Some(oldest) = self
+                .last_connected
+                .iter()
+                .min_by_key(|&(_, &secs)| secs)
+                .map(|(ssid, _)| ssid.clone())
+        {
+            self.last_connected.remove(&oldest);
+        }
+    }
+
+    /// Trust the access points of the connected network from now on
+    pub fn learn_access_points(&mut self) {
+        rogue::learn(&self.wifi_list, &mut self.known_bssids);
+    }
+}
+
+/// Saved password fetched for the details pane
+///
+/// The secret is zeroized when this is dropped: on hiding it, selecting
+/// another network, or after `PASSWORD_REVEAL_SECS`.
+#[derive(Debug)]
+pub struct RevealedPassword {
+    pub ssid: String,
+    pub password: SecretString,
+    /// Shown in plain text rather than masked
+    pub visible: bool,
+    pub fetched_at: Instant,
+}
+
+/// What a saved password is read for
+#[derive(Debug, Clone)]
+pub enum PasswordUse {
+    /// Show it masked in the details pane
+    Reveal,
+    Copy,
+    /// Put it in the QR code of a network with this authentication
+    Share(String),
+}
+
+/// A saved password read in the background, with its SSID
+pub type PasswordRead = (
+    PasswordUse,
+    String,
+    crate::error::WifiResult<Option<SecretString>>,
+);
+
+/// A destructive action shown in the confirm popup before it runs
+#[derive(Debug, Clone)]
+pub struct Confirmation {
+    pub title: &'static str,
+    pub message: String,
+    /// Dimmed second line, e.g. how to undo
+    pub detail: Option<String>,
+    /// Run again once confirmed, with the network or file it applies to
+    pub command: Command,
+    /// Open network that `a` connects to without asking again
+    pub accept_open: Option<String>,
+}
+
+/// A profile deleted with forget, kept so it can be restored
+///
+/// The exported XML holds the key in plain text and is zeroized when this is
+/// dropped: on undo, or after `FORGET_UNDO_SECS`.
+#[derive(Debug)]
+pub struct ForgottenProfile {
+    pub ssid: String,
+    pub xml: SecretString,
+    pub forgotten_at: Instant,
+}
+
+impl ForgottenProfile {
+    /// Time left to undo
+    pub fn remaining(&self) -> Duration {
+        Duration::from_secs(config::FORGET_UNDO_SECS).saturating_sub(self.forgotten_at.elapsed())
+    }
+}
+
+/// Screen areas of the last frame that respond to mouse clicks
+#[derive(Debug, Default)]
+pub struct ClickAreas {
+    /// Rows of the network list, inside its border
+    pub list: Rect,
+    /// The manual add popup, empty while it is closed
+    pub manual_popup: Rect,
+    /// Fields of the manual add popup by `manual_input_field` index
+    pub manual_fields: Vec<(usize, Rect)>,
 }
 
 /// UI state for display and navigation
@@ -32,14 +245,65 @@ pub struct UiState {
     pub l_state: ListState,
     pub is_searching: bool,
     pub show_password_popup: bool,
+    /// Why the last attempt failed, when the password popup was reopened to retry
+    pub password_error: Option<String>,
+    /// Show the password popup's text instead of bullets
+    pub show_password_text: bool,
+    /// Destructive action waiting for a yes or no
+    pub confirmation: Option<Confirmation>,
+    /// Set while a confirmed command runs again, so it isn't asked twice
+    pub confirmed: bool,
     pub show_manual_add_popup: bool,
     pub show_qr_popup: bool,
+    pub show_share_password_popup: bool,
+    pub show_survey_label_popup: bool,
+    pub show_compare_popup: bool,
+    pub show_command_palette: bool,
+    pub palette_state: ListState,
+    pub show_command_line: bool,
+    pub show_hotspot_popup: bool,
+    pub show_profiles_popup: bool,
+    pub show_speedtest_popup: bool,
+    pub show_wps_popup: bool,
+    pub show_channel_graph: bool,
+    /// Band plotted by the channel graph
+    pub channel_graph_band: Band,
+    pub show_help: bool,
+    /// First line of the key binding overlay in view
+    pub help_scroll: u16,
     pub qr_code_lines: Vec<String>,
-    pub error_message: Option<String>,
+    pub qr_ssid: String,
+    pub qr_auth: String,
+    pub qr_password: Option<SecretString>,
+    pub show_qr_password: bool,
+    pub revealed_password: Option<RevealedPassword>,
+    /// Saved password read in the background, with its SSID and use
+    pub password_rx: Option<Receiver<PasswordRead>>,
+    pub share_dir: PathBuf,
+    pub restore_ssid: Option<String>,
+    pub search_history: Vec<String>,
+    pub search_history_pos: Option<usize>,
+    pub search_draft: String,
     pub loading_frame: usize,
     pub show_key_logger: bool,
+    pub show_debug_pane: bool,
+    pub debug_pane_expanded: bool,
+    pub details_tab: DetailsTab,
     pub last_key_press: Option<(String, Instant)>,
+    pub nav_repeat: NavRepeat,
+    pub pending_keys: Option<PendingKeys>,
+    /// When the list is filtered by an edited search, once typing pauses
+    pub search_due: Option<Instant>,
+    /// Timed notifications, oldest first
+    pub toasts: VecDeque<Toast>,
+    /// Last finished action and whether it worked, kept until the next one
+    pub status: Option<StatusLine>,
     pub icon_set: IconSet,
+    pub click_areas: ClickAreas,
+    /// Cleared while the terminal reports it lost focus
+    pub has_focus: bool,
+    /// Network row last clicked, to detect double-clicks
+    pub last_click: Option<(usize, Instant)>,
 }
 
 impl UiState {
@@ -48,35 +312,232 @@ impl UiState {
             l_state: ListState::default().with_selected(if has_networks { Some(0) } else { None }),
             is_searching: false,
             show_password_popup: false,
+            password_error: None,
+            show_password_text: false,
+            confirmation: None,
+            confirmed: false,
             show_manual_add_popup: false,
             show_qr_popup: false,
+            show_share_password_popup: false,
+            show_survey_label_popup: false,
+            show_compare_popup: false,
+            show_command_palette: false,
+            palette_state: ListState::default(),
+            show_command_line: false,
+            show_hotspot_popup: false,
+            show_profiles_popup: false,
+            show_speedtest_popup: false,
+            show_wps_popup: false,
+            show_channel_graph: false,
+            channel_graph_band: Band::Ghz2_4,
+            show_help: false,
+            help_scroll: 0,
             qr_code_lines: Vec::new(),
-            error_message: None,
+            qr_ssid: String::new(),
+            qr_auth: String::new(),
+            qr_password: None,
+            show_qr_password: false,
+            revealed_password: None,
+            password_rx: None,
+            share_dir: config::data_dir().join(config::SHARE_DIR),
+            restore_ssid: None,
+            search_history: Vec::new(),
+            search_history_pos: None,
+            search_draft: String::new(),
             loading_frame: 0,
             show_key_logger,
+            show_debug_pane: false,
+            debug_pane_expanded: true,
+            details_tab: DetailsTab::Overview,
             last_key_press: None,
+            nav_repeat: NavRepeat::default(),
+            pending_keys: None,
+            search_due: None,
+            toasts: VecDeque::new(),
+            status: None,
             icon_set: if use_ascii_icons {
                 IconSet::Ascii
             } else {
                 IconSet::Nerd
             },
+            click_areas: ClickAreas::default(),
+            has_focus: true,
+            last_click: None,
+        }
+    }
+}
+
+/// Tabs of the details pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DetailsTab {
+    #[default]
+    Overview,
+    Security,
+    /// Every access point of the SSID with its radio capabilities
+    Radio,
+    /// Addresses, latency and frame rates of the connected network
+    Ip,
+}
+
+impl DetailsTab {
+    pub const ALL: [DetailsTab; 4] = [
+        DetailsTab::Overview,
+        DetailsTab::Security,
+        DetailsTab::Radio,
+        DetailsTab::Ip,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DetailsTab::Overview => "Overview",
+            DetailsTab::Security => "Security",
+            DetailsTab::Radio => "Radio",
+            DetailsTab::Ip => "IP",
+        }
+    }
+
+    /// Parse the name the session file stores, see [`DetailsTab::label`]
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tab| tab.label() == label)
+    }
+
+    /// The tab `step` places after this one, wrapping around
+    pub fn cycle(self, step: isize) -> Self {
+        let len = Self::ALL.len() as isize;
+        let index = Self::ALL.iter().position(|&t| t == self).unwrap_or(0) as isize;
+        Self::ALL[(index + step).rem_euclid(len) as usize]
+    }
+}
+
+/// Start of a vim-style key sequence, e.g. the `5` of `5j` or the first `g`
+/// of `gg`
+#[derive(Debug, Clone, Copy)]
+pub struct PendingKeys {
+    pub count: Option<usize>,
+    /// A `g` is waiting for a second one
+    pub g: bool,
+    pub since: Instant,
+}
+
+impl PendingKeys {
+    pub fn is_due(&self) -> bool {
+        self.since.elapsed() >= Duration::from_millis(config::PENDING_KEYS_MS)
+    }
+
+    /// What was typed so far, e.g. "12" or "g"
+    pub fn label(&self) -> String {
+        let count = self.count.map(|n| n.to_string()).unwrap_or_default();
+        if self.g { count + "g" } else { count }
+    }
+}
+
+/// Tracks a held navigation key so list movement can accelerate
+#[derive(Debug, Default)]
+pub struct NavRepeat {
+    direction: i8,
+    held_since: Option<Instant>,
+    last_press: Option<Instant>,
+}
+
+impl NavRepeat {
+    /// Register a press in `direction` and return how many items to move
+    pub fn step(&mut self, direction: i8) -> usize {
+        let now = Instant::now();
+        let is_repeat = self.direction == direction
+            && self.last_press.is_some_and(|last| {
+                now.duration_since(last) <= Duration::from_millis(config::KEY_REPEAT_WINDOW_MS)
+            });
+        if !is_repeat {
+            self.held_since = Some(now);
+        }
+        self.direction = direction;
+        self.last_press = Some(now);
+
+        match self.held_since {
+            Some(since)
+                if now.duration_since(since)
+                    >= Duration::from_millis(config::KEY_REPEAT_ACCEL_DELAY_MS) =>
+            {
+                config::KEY_REPEAT_ACCEL_STEP
+            }
+            _ => 1,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastKind {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastKind {
+    /// Time on screen; errors stay twice as long as `toast_secs`
+    pub fn duration(self, toast_secs: u64) -> Duration {
+        match self {
+            Self::Error => Duration::from_secs(toast_secs * 2),
+            _ => Duration::from_secs(toast_secs),
+        }
+    }
+}
+
+/// Outcome of the last action, shown on the bottom border
+#[derive(Debug, Clone)]
+pub struct StatusLine {
+    pub message: String,
+    pub ok: bool,
+}
+
+/// A timed notification in the corner of the screen
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub kind: ToastKind,
+    pub message: String,
+    pub shown_at: Instant,
+}
+
 /// Connection operation state
 #[derive(Debug)]
 pub struct ConnectionState {
     pub is_connecting: bool,
     pub connecting_to_ssid: Option<String>,
+    /// Security of the entry the password is asked for, since an SSID can be
+    /// listed once per security
+    pub connecting_to_auth: Option<String>,
     pub target_ssid: Option<String>,
     pub connection_start_time: Option<Instant>,
-    pub connection_result_rx: Option<Receiver<Result<()>>>,
     #[allow(dead_code)]
     pub wifi_listener: Option<WifiListener>,
     pub listener_init_rx: Option<Receiver<crate::error::WifiResult<WifiListener>>>,
     pub connection_event_tx: Option<UnboundedSender<ConnectionEvent>>,
     pub connection_event_rx: Option<UnboundedReceiver<ConnectionEvent>>,
+    /// Internet reachability of the connected network, keyed by its SSID
+    pub connectivity: Option<(String, Connectivity)>,
+    pub connectivity_rx: Option<Receiver<Connectivity>>,
+    pub connectivity_checked_at: Option<Instant>,
+    /// Addresses of the connected interface, fetched after each refresh
+    pub ip_info: Option<IpInfo>,
+    pub ip_info_rx: Option<Receiver<crate::error::WifiResult<IpInfo>>>,
+    /// Profile and its new MAC randomization setting, once a toggle finishes
+    pub random_mac_rx: Option<Receiver<crate::error::WifiResult<(String, bool)>>>,
+    /// Metered setting read for a profile
+    pub metered_rx: Option<Receiver<(String, crate::error::WifiResult<bool>)>>,
+    /// New metered setting of a profile, once a toggle finishes
+    pub metered_set_rx: Option<Receiver<(String, crate::error::WifiResult<bool>)>>,
+    /// Latest move to another access point of the connected network
+    pub last_roam: Option<Roam>,
+}
+
+/// A move of the connection to another access point of the same network
+#[derive(Debug, Clone)]
+pub struct Roam {
+    pub ssid: String,
+    /// Access point it left, if it was known
+    pub from: Option<[u8; 6]>,
+    pub to: [u8; 6],
+    pub at: Instant,
 }
 
 impl ConnectionState {
@@ -86,17 +547,161 @@ impl ConnectionState {
         Self {
             is_connecting: false,
             connecting_to_ssid: None,
+            connecting_to_auth: None,
             target_ssid: None,
             connection_start_time: None,
-            connection_result_rx: None,
             wifi_listener: None,
             listener_init_rx: None,
             connection_event_tx: Some(tx),
             connection_event_rx: Some(rx),
+            connectivity: None,
+            connectivity_rx: None,
+            connectivity_checked_at: None,
+            ip_info: None,
+            ip_info_rx: None,
+            random_mac_rx: None,
+            metered_rx: None,
+            metered_set_rx: None,
+            last_roam: None,
         }
     }
 }
 
+/// What a background WiFi operation does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Connect,
+    Disconnect,
+    Forget,
+    /// Reinstall the profile deleted by the last forget
+    Restore,
+    /// Turn auto-connect on or off
+    AutoConnect(bool),
+    /// Save changes from the profile editor
+    EditProfile,
+    /// Write the saved profile to an XML file
+    Export,
+    /// Install a profile from the XML file the operation's `ssid` names
+    Import,
+    /// Give the saved profile a new name
+    Rename,
+}
+
+impl OperationKind {
+    /// Only one operation that changes the connection runs at a time
+    fn changes_connection(self) -> bool {
+        matches!(self, Self::Connect | Self::Disconnect | Self::Forget)
+    }
+}
+
+/// What a finished operation hands back besides success
+#[derive(Debug)]
+pub enum OperationOutput {
+    Done,
+    /// Export of a forgotten profile, key included, kept for undo; `None` if
+    /// it couldn't be exported
+    Forgotten(Option<SecretString>),
+    /// Where an exported profile was written
+    Exported(PathBuf),
+    /// Name of an imported profile
+    Imported(String),
+    /// New name of a renamed profile
+    Renamed(String),
+    /// The old profile was removed without a passphrase to carry over, so
+    /// the credentials have to be typed again
+    NeedsCredentials,
+}
+
+impl From<()> for OperationOutput {
+    fn from(_: ()) -> Self {
+        OperationOutput::Done
+    }
+}
+
+/// A background WiFi operation on one network
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub id: u64,
+    pub kind: OperationKind,
+    pub ssid: String,
+}
+
+impl Operation {
+    /// What the operation is doing, e.g. "connecting to HomeWifi"
+    pub fn describe(&self) -> String {
+        match self.kind {
+            OperationKind::Connect => format!("connecting to {}", self.ssid),
+            OperationKind::Disconnect => format!("disconnecting from {}", self.ssid),
+            OperationKind::Forget => format!("forgetting {}", self.ssid),
+            OperationKind::Restore => format!("restoring {}", self.ssid),
+            OperationKind::AutoConnect(_) => format!("changing auto-connect of {}", self.ssid),
+            OperationKind::EditProfile => format!("saving the profile of {}", self.ssid),
+            OperationKind::Export => format!("exporting the profile of {}", self.ssid),
+            OperationKind::Import => format!("importing {}", self.ssid),
+            OperationKind::Rename => format!("renaming {}", self.ssid),
+        }
+    }
+}
+
+/// Background WiFi operations in flight; results come back on one channel,
+/// tagged with the operation they belong to
+#[derive(Debug)]
+pub struct TaskState {
+    pub running: Vec<Operation>,
+    next_id: u64,
+    pub result_tx: UnboundedSender<(Operation, crate::error::WifiResult<OperationOutput>)>,
+    pub result_rx:
+        Option<UnboundedReceiver<(Operation, crate::error::WifiResult<OperationOutput>)>>,
+}
+
+impl TaskState {
+    pub fn new() -> Self {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        Self {
+            running: Vec::new(),
+            next_id: 0,
+            result_tx: tx,
+            result_rx: Some(rx),
+        }
+    }
+
+    /// The running operation that `kind` on `ssid` would clash with: another
+    /// change to the connection, or anything on the same network
+    pub fn conflict(&self, kind: OperationKind, ssid: &str) -> Option<&Operation> {
+        self.running.iter().find(|op| {
+            (op.kind.changes_connection() && kind.changes_connection()) || op.ssid == ssid
+        })
+    }
+
+    /// Whether an operation that changes the connection is in flight
+    pub fn is_busy(&self) -> bool {
+        self.running.iter().any(|op| op.kind.changes_connection())
+    }
+
+    pub fn start(&mut self, kind: OperationKind, ssid: String) -> Operation {
+        self.next_id += 1;
+        let operation = Operation {
+            id: self.next_id,
+            kind,
+            ssid,
+        };
+        self.running.push(operation.clone());
+        operation
+    }
+
+    /// Stop tracking a finished operation; false if it was cancelled meanwhile
+    pub fn finish(&mut self, id: u64) -> bool {
+        let before = self.running.len();
+        self.running.retain(|op| op.id != id);
+        self.running.len() < before
+    }
+
+    /// Drop running operations of `kind`, ignoring their results
+    pub fn cancel(&mut self, kind: OperationKind) {
+        self.running.retain(|op| op.kind != kind);
+    }
+}
+
 /// Input field states
 #[derive(Debug, Default)]
 pub struct InputStates {
@@ -106,7 +711,18 @@ pub struct InputStates {
     pub manual_password_input: InputState,
     pub manual_security: String,
     pub manual_hidden: bool,
+    pub manual_random_mac: bool,
     pub manual_input_field: usize,
+    pub manual_eap_method: EapMethod,
+    pub manual_identity_input: InputState,
+    pub manual_validate_ca: bool,
+    pub survey_label_input: InputState,
+    pub share_password_input: InputState,
+    pub palette_input: InputState,
+    pub command_input: InputState,
+    pub hotspot_ssid_input: InputState,
+    pub hotspot_password_input: InputState,
+    pub profile_password_input: InputState,
 }
 
 impl InputStates {
@@ -118,15 +734,55 @@ impl InputStates {
             manual_password_input: InputState::new(),
             manual_security: "WPA2-Personal".to_string(),
             manual_hidden: false,
+            manual_random_mac: false,
             manual_input_field: 0,
+            manual_eap_method: EapMethod::default(),
+            manual_identity_input: InputState::new(),
+            manual_validate_ca: true,
+            survey_label_input: InputState::new(),
+            share_password_input: InputState::new(),
+            palette_input: InputState::new(),
+            command_input: InputState::new(),
+            hotspot_ssid_input: InputState::new(),
+            hotspot_password_input: InputState::new(),
+            profile_password_input: InputState::new(),
         }
     }
 
     pub fn clear_manual(&mut self) {
         self.manual_ssid_input.clear();
         self.manual_password_input.clear();
+        self.manual_identity_input.clear();
         self.manual_input_field = 0;
     }
+
+    /// Whether the manual add popup is set up for an 802.1X network
+    pub fn manual_is_enterprise(&self) -> bool {
+        self.manual_security.ends_with("-Enterprise")
+    }
+
+    /// Move focus to the next (or previous) field of the manual add popup.
+    ///
+    /// Fields: 0 SSID, 1 password, 2 security, 3 hidden, 9 random MAC,
+    /// 4 connect, 5 cancel, and for enterprise networks 6 EAP method,
+    /// 7 identity, 8 CA validation.
+    pub fn cycle_manual_field(&mut self, forward: bool) {
+        let order: &[usize] = if self.manual_is_enterprise() {
+            &[0, 2, 6, 7, 1, 8, 3, 9, 4, 5]
+        } else {
+            &[0, 1, 2, 3, 9, 4, 5]
+        };
+        let pos = order
+            .iter()
+            .position(|&f| f == self.manual_input_field)
+            .unwrap_or(0);
+        let next = if forward {
+            (pos + 1) % order.len()
+        } else {
+            (pos + order.len() - 1) % order.len()
+        };
+        self.manual_input_field = order[next];
+    }
 }
 
 /// Refresh and timing state
@@ -136,9 +792,15 @@ pub struct RefreshState {
     pub last_interaction: Instant,
     pub last_manual_refresh: Instant,
     pub is_refreshing_networks: bool,
-    pub network_update_rx: Option<Receiver<Result<(Vec<WifiInfo>, Option<String>)>>>,
+    pub network_update_rx: Option<Receiver<Result<NetworkUpdate>>>,
+    pub radio_rx: Option<Receiver<crate::error::WifiResult<RadioState>>>,
+    /// Wakes a manual refresh waiting for its scan to finish
+    pub scan_done_tx: Option<oneshot::Sender<()>>,
     pub refresh_burst: u8,
     pub is_initial_loading: bool,
+    /// The list is the previous run's snapshot until the first scan arrives
+    pub showing_snapshot: bool,
+    pub started_at: Option<Instant>,
 }
 
 impl RefreshState {
@@ -149,8 +811,330 @@ impl RefreshState {
             last_manual_refresh: Instant::now() - Duration::from_secs(15), // Allow immediate manual refresh
             is_refreshing_networks: false,
             network_update_rx: None,
+            radio_rx: None,
+            scan_done_tx: None,
             refresh_burst: config::STARTUP_REFRESH_BURST,
             is_initial_loading: true,
+            showing_snapshot: false,
+            started_at: None,
+        }
+    }
+}
+
+/// Band steering state
+#[derive(Debug, Default)]
+pub struct SteeringState {
+    pub suggestion: Option<SteeringSuggestion>,
+    pub last_attempt: Option<Instant>,
+}
+
+impl SteeringState {
+    /// Whether a steering reconnect was attempted recently
+    pub fn in_cooldown(&self) -> bool {
+        self.last_attempt
+            .is_some_and(|t| t.elapsed() < Duration::from_secs(config::BAND_STEERING_COOLDOWN_SECS))
+    }
+}
+
+/// Reconnect watchdog for a network that dropped
+#[derive(Debug, Default)]
+pub struct WatchdogState {
+    /// Network being reconnected to, `None` while idle
+    pub ssid: Option<String>,
+    /// Failed or running attempts so far
+    pub attempts: u32,
+    pub next_attempt: Option<Instant>,
+    /// The next disconnect was asked for by the user and shouldn't trigger a retry
+    pub expect_disconnect: bool,
+}
+
+impl WatchdogState {
+    /// Start reconnecting to a network that just dropped
+    pub fn start(&mut self, ssid: String) {
+        self.ssid = Some(ssid);
+        self.attempts = 0;
+        self.next_attempt =
+            Some(Instant::now() + Duration::from_secs(config::WATCHDOG_INITIAL_BACKOFF_SECS));
+    }
+
+    pub fn stop(&mut self) {
+        self.ssid = None;
+        self.attempts = 0;
+        self.next_attempt = None;
+    }
+
+    /// Count an attempt and schedule the next one, doubling the delay each time
+    pub fn record_attempt(&mut self) {
+        self.attempts += 1;
+        let backoff = config::WATCHDOG_INITIAL_BACKOFF_SECS
+            .saturating_mul(1 << self.attempts.min(16))
+            .min(config::WATCHDOG_MAX_BACKOFF_SECS);
+        self.next_attempt = Some(Instant::now() + Duration::from_secs(backoff));
+    }
+}
+
+/// Auto-connect rules, see [`crate::rules`]
+#[derive(Debug, Default)]
+pub struct RulesState {
+    /// What the profiles were last set to
+    pub applied: Plan,
+    /// Auto-connect of networks before a rule changed it
+    pub originals: BTreeMap<String, bool>,
+    /// Outcome of a check, `None` if the plan didn't change
+    pub apply_rx: Option<Receiver<crate::error::WifiResult<Option<rules::Checked>>>>,
+}
+
+/// Roaming between preferred networks
+#[derive(Debug, Default)]
+pub struct RoamingState {
+    /// Stronger preferred network and when it first became one
+    pub candidate: Option<(RoamCandidate, Instant)>,
+    pub last_switch: Option<Instant>,
+}
+
+impl RoamingState {
+    /// Time left before switching to the candidate, counting the hold time and the cooldown
+    pub fn wait(&self) -> Option<Duration> {
+        let (_, since) = self.candidate.as_ref()?;
+        let hold = Duration::from_secs(config::ROAM_HOLD_SECS).saturating_sub(since.elapsed());
+        let cooldown = self.last_switch.map_or(Duration::ZERO, |t| {
+            Duration::from_secs(config::ROAM_COOLDOWN_SECS).saturating_sub(t.elapsed())
+        });
+        Some(hold.max(cooldown))
+    }
+
+    /// The candidate to switch to now that it has stayed stronger long enough
+    pub fn due(&self) -> Option<&RoamCandidate> {
+        self.wait().filter(Duration::is_zero)?;
+        self.candidate.as_ref().map(|(candidate, _)| candidate)
+    }
+}
+
+/// Low-signal alert state for the connected network
+#[derive(Debug)]
+pub struct SignalAlertState {
+    pub threshold: Option<u8>,
+    pub duration: Duration,
+    pub below_since: Option<Instant>,
+    pub alerted: bool,
+}
+
+impl SignalAlertState {
+    pub fn new(threshold: Option<u8>, duration_secs: u64) -> Self {
+        Self {
+            threshold,
+            duration: Duration::from_secs(duration_secs),
+            below_since: None,
+            alerted: false,
+        }
+    }
+}
+
+/// Signal monitoring (CSV logging) state
+#[derive(Debug)]
+pub struct MonitorState {
+    pub csv_path: PathBuf,
+    pub logger: Option<SignalLogger>,
+    pub survey: Option<Survey>,
+    /// Recent signal samples per SSID, oldest first, one per refresh
+    pub signal_history: HashMap<String, VecDeque<SignalSample>>,
+    /// Frame rates of the connected interface
+    pub frame_rates: CounterHistory,
+    pub stats_rx: Option<Receiver<crate::error::WifiResult<InterfaceStats>>>,
+    pub stats_fetched_at: Option<Instant>,
+}
+
+impl MonitorState {
+    pub fn new(csv_path: PathBuf) -> Self {
+        Self {
+            csv_path,
+            logger: None,
+            survey: None,
+            signal_history: HashMap::new(),
+            frame_rates: CounterHistory::default(),
+            stats_rx: None,
+            stats_fetched_at: None,
+        }
+    }
+}
+
+/// One refresh of a network's signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalSample {
+    /// Quality in percent
+    pub signal: u8,
+    /// Live RSSI in dBm, only known while connected
+    pub rssi: Option<i32>,
+}
+
+/// Mobile hotspot popup state
+#[derive(Debug, Default)]
+pub struct HotspotState {
+    pub status: Option<HotspotStatus>,
+    pub status_rx: Option<Receiver<crate::error::WifiResult<HotspotStatus>>>,
+    pub last_fetch: Option<Instant>,
+    /// A start or stop request is in flight
+    pub is_busy: bool,
+    /// Focused row: 0 SSID, 1 passphrase, 2 start/stop button
+    pub field: usize,
+    /// Guest network the hotspot is running as, see [`crate::guest`]
+    pub guest: Option<GuestNetwork>,
+}
+
+/// Ping monitor for the connected network
+#[derive(Debug, Default)]
+pub struct LatencyState {
+    /// Network being monitored
+    pub ssid: Option<String>,
+    pub gateway: Option<Ipv4Addr>,
+    pub gateway_stats: LatencyStats,
+    pub internet_stats: LatencyStats,
+    pub updates_rx: Option<UnboundedReceiver<LatencyUpdate>>,
+    pub task: Option<JoinHandle<()>>,
+}
+
+/// Speed test popup state
+#[derive(Debug, Default)]
+pub struct SpeedTestState {
+    /// Network the current or last run measured
+    pub ssid: Option<String>,
+    /// Phase in progress, `None` when idle
+    pub phase: Option<SpeedTestPhase>,
+    pub fraction: f64,
+    pub latency_ms: Option<f64>,
+    pub download_mbps: Option<f64>,
+    pub upload_mbps: Option<f64>,
+    pub updates_rx: Option<UnboundedReceiver<SpeedTestUpdate>>,
+    /// Past results for `ssid`, oldest first
+    pub history: Vec<SpeedTestRecord>,
+}
+
+/// WPS push-button popup state
+#[derive(Debug, Default)]
+pub struct WpsState {
+    /// Network being joined
+    pub ssid: Option<String>,
+    pub result_rx: Option<Receiver<crate::error::WifiResult<()>>>,
+    /// Why the last attempt failed
+    pub error: Option<String>,
+}
+
+/// Saved profiles popup state
+#[derive(Debug, Default)]
+pub struct ProfilesState {
+    /// Profile names, most preferred first
+    pub list: Vec<String>,
+    pub list_state: ListState,
+    pub list_rx: Option<Receiver<crate::error::WifiResult<Vec<String>>>>,
+    /// A reorder is in flight
+    pub is_busy: bool,
+    /// The profile edit popup, open while set
+    pub editor: Option<ProfileEditor>,
+    /// The profile being read for the editor, with its SSID
+    pub editor_rx: Option<Receiver<(String, crate::error::WifiResult<wifi::ProfileXml>)>>,
+}
+
+/// Security a saved profile can be switched between in the editor, as
+/// reported by scans, with its `authentication` value in profile XML
+pub const EDITABLE_SECURITY: [(&str, &str); 3] = [
+    ("Open", "open"),
+    ("WPA2-PSK", "WPA2PSK"),
+    ("WPA3-SAE", "WPA3SAE"),
+];
+
+/// Fields of the profile edit popup, filled from the saved profile
+#[derive(Debug, Clone)]
+pub struct ProfileEditor {
+    pub ssid: String,
+    /// Index into [`EDITABLE_SECURITY`]; `None` for 802.1X and older
+    /// profiles, whose security can't be changed here
+    pub security: Option<usize>,
+    /// Security as saved, shown when it can't be changed
+    pub saved_security: String,
+    /// The profile stores a key, kept when the password is left empty
+    pub has_key: bool,
+    pub auto_connect: bool,
+    pub hidden: bool,
+    /// Focused field: security, password, auto-connect, hidden, cancel, save
+    pub field: usize,
+}
+
+impl ProfileEditor {
+    pub const FIELDS: usize = 6;
+
+    pub fn new(ssid: &str, profile: &wifi::ProfileXml) -> Self {
+        let security = (!profile.one_x)
+            .then(|| {
+                EDITABLE_SECURITY
+                    .iter()
+                    .position(|(_, xml)| *xml == profile.authentication)
+            })
+            .flatten();
+        Self {
+            ssid: ssid.to_string(),
+            security,
+            saved_security: profile.authentication.clone(),
+            has_key: profile.key.is_some(),
+            auto_connect: profile.auto_connect,
+            hidden: profile.hidden,
+            field: 0,
+        }
+    }
+
+    /// Security label for the popup
+    pub fn security_label(&self) -> &str {
+        match self.security {
+            Some(i) => EDITABLE_SECURITY[i].0,
+            None => &self.saved_security,
+        }
+    }
+
+    pub fn cycle_security(&mut self, step: isize) {
+        if let Some(i) = self.security {
+            let len = EDITABLE_SECURITY.len() as isize;
+            self.security = Some((i as isize + step).rem_euclid(len) as usize);
+        }
+    }
+
+    /// The chosen security needs a password that the profile doesn't have yet
+    pub fn needs_password(&self) -> bool {
+        self.security.is_some_and(|i| i != 0) && !self.has_key
+    }
+
+    /// Security to write, `None` if unchanged
+    pub fn changed_security(&self) -> Option<String> {
+        let i = self.security?;
+        let (scan, xml) = EDITABLE_SECURITY[i];
+        (xml != self.saved_security).then(|| scan.to_string())
+    }
+}
+
+/// Timings shown by the --perf overlay
+#[derive(Debug, Default)]
+pub struct PerfState {
+    pub enabled: bool,
+    pub last_frame: Duration,
+    pub last_refresh: Option<Duration>,
+}
+
+/// Loaded configuration and where it came from
+#[derive(Debug)]
+pub struct ConfigState {
+    pub path: PathBuf,
+    pub overrides: ConfigOverrides,
+    pub current: Config,
+    pub keymap: Keymap,
+    pub theme: Theme,
+}
+
+impl ConfigState {
+    pub fn new(path: PathBuf, overrides: ConfigOverrides, current: Config) -> Self {
+        Self {
+            path,
+            overrides,
+            keymap: current.keymap().unwrap_or_default(),
+            theme: current.theme().unwrap_or_default(),
+            current,
         }
     }
 }
@@ -161,45 +1145,72 @@ pub struct AppState {
     pub network: NetworkState,
     pub ui: UiState,
     pub connection: ConnectionState,
+    pub tasks: TaskState,
     pub inputs: InputStates,
     pub refresh: RefreshState,
+    pub steering: SteeringState,
+    pub watchdog: WatchdogState,
+    pub roaming: RoamingState,
+    pub rules: RulesState,
+    pub signal_alert: SignalAlertState,
+    pub monitor: MonitorState,
+    pub config: ConfigState,
+    pub perf: PerfState,
+    pub hotspot: HotspotState,
+    pub profiles: ProfilesState,
+    pub speedtest: SpeedTestState,
+    pub wps: WpsState,
+    pub latency: LatencyState,
+    pub clipboard: ClipboardState,
 }
 
 impl AppState {
-    pub fn new(wifi_list: Vec<WifiInfo>, show_key_logger: bool, use_ascii_icons: bool) -> AppState {
+    pub fn new(
+        wifi_list: Vec<WifiInfo>,
+        show_key_logger: bool,
+        monitor: MonitorState,
+        config: ConfigState,
+    ) -> AppState {
         let has_networks = !wifi_list.is_empty();
+        let signal_alert = SignalAlertState::new(
+            config.current.low_signal_threshold,
+            config.current.low_signal_secs,
+        );
         AppState {
-            network: NetworkState::new(wifi_list),
-            ui: UiState::new(show_key_logger, use_ascii_icons, has_networks),
+            network: NetworkState::new(wifi_list, config.current.sort),
+            ui: UiState::new(show_key_logger, config.current.ascii_icons, has_networks),
             connection: ConnectionState::new(),
+            tasks: TaskState::new(),
             inputs: InputStates::new(),
             refresh: RefreshState::new(),
+            steering: SteeringState::default(),
+            watchdog: WatchdogState::default(),
+            roaming: RoamingState::default(),
+            rules: RulesState::default(),
+            signal_alert,
+            monitor,
+            config,
+            perf: PerfState::default(),
+            hotspot: HotspotState::default(),
+            profiles: ProfilesState::default(),
+            speedtest: SpeedTestState::default(),
+            wps: WpsState::default(),
+            latency: LatencyState::default(),
+            clipboard: ClipboardState::default(),
         }
     }
 
-    pub fn next(&mut self) {
+    pub fn next(&mut self, step: usize) {
         let i = match self.ui.l_state.selected() {
-            Some(i) => {
-                if i >= self.network.filtered_wifi_list.len().saturating_sub(1) {
-                    i
-                } else {
-                    i + 1
-                }
-            }
+            Some(i) => (i + step).min(self.network.filtered_wifi_list.len().saturating_sub(1)),
             None => 0,
         };
         self.ui.l_state.select(Some(i));
     }
 
-    pub fn previous(&mut self) {
+    pub fn previous(&mut self, step: usize) {
         let i = match self.ui.l_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    0
-                } else {
-                    i - 1
-                }
-            }
+            Some(i) => i.saturating_sub(step),
             None => 0,
         };
         self.ui.l_state.select(Some(i));
@@ -211,6 +1222,28 @@ impl AppState {
         }
     }
 
+    /// Select the n-th network (1-based), or the last one if the list is shorter
+    pub fn go_to_row(&mut self, n: usize) {
+        if !self.network.filtered_wifi_list.is_empty() {
+            let last = self.network.filtered_wifi_list.len() - 1;
+            self.ui.l_state.select(Some(n.saturating_sub(1).min(last)));
+        }
+    }
+
+    /// Move to the next or previous search match, wrapping around the list
+    pub fn jump_to_match(&mut self, forward: bool) {
+        let len = self.network.filtered_wifi_list.len();
+        if len == 0 {
+            return;
+        }
+        let i = match self.ui.l_state.selected() {
+            Some(i) if forward => (i + 1) % len,
+            Some(i) => (i + len - 1) % len,
+            None => 0,
+        };
+        self.ui.l_state.select(Some(i));
+    }
+
     pub fn go_to_bottom(&mut self) {
         if !self.network.filtered_wifi_list.is_empty() {
             self.ui
@@ -220,43 +1253,969 @@ impl AppState {
     }
 
     pub fn update_filtered_list(&mut self) {
-        if self.inputs.search_input.value.is_empty() {
-            self.network.filtered_wifi_list = self.network.wifi_list.clone();
+        self.ui.search_due = None;
+        let filter = self.network.filter;
+        let query = Query::parse(&self.inputs.search_input.value);
+        self.network.filtered_wifi_list = self
+            .network
+            .wifi_list
+            .iter()
+            .filter(|w| filter.matches(w) && query.matches(w).is_some())
+            .cloned()
+            .collect();
+        sort::sort_networks(
+            &mut self.network.filtered_wifi_list,
+            self.network.sort,
+            &self.network.last_connected,
+            |w| self.network.presence.rank_signal(w),
+        );
+        // Best matches first; the sort order only breaks ties
+        if !query.is_empty() {
+            self.network
+                .filtered_wifi_list
+                .sort_by_cached_key(|w| std::cmp::Reverse(query.matches(w).map_or(0, |m| m.score)));
+        }
+        // Reset selection if out of bounds
+        if let Some(selected) = self.ui.l_state.selected()
+            && selected >= self.network.filtered_wifi_list.len()
+        {
+            self.ui.l_state.select(Some(0));
+        }
+        self.network.list_rows = None;
+    }
+
+    /// Switch to the next sort order, keeping the selected network selected
+    pub fn cycle_sort(&mut self) {
+        self.set_sort(self.network.sort.next());
+    }
+
+    pub fn set_sort(&mut self, mode: SortMode) {
+        let selected = self
+            .ui
+            .l_state
+            .selected()
+            .and_then(|i| self.network.filtered_wifi_list.get(i))
+            .map(|w| w.ssid.clone());
+        self.network.sort = mode;
+        self.update_filtered_list();
+        if let Some(ssid) = selected {
+            self.select_network(&ssid);
+        }
+        self.show_toast(format!("Sorted by {}", mode.label()));
+    }
+
+    /// Switch to the next filter preset
+    pub fn cycle_filter(&mut self) {
+        self.set_filter(self.network.filter.next());
+    }
+
+    /// Switch to the theme after the current one: the built-ins, then the
+    /// `[themes]` of the config file
+    pub fn cycle_theme(&mut self) {
+        let names: Vec<&str> = theme::BUILT_IN
+            .iter()
+            .map(|&(name, _)| name)
+            .chain(self.config.current.themes.keys().map(String::as_str))
+            .collect();
+        let current = &self.config.current.theme;
+        let pos = names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(current))
+            .map_or(0, |pos| pos + 1);
+        let next = names[pos % names.len()].to_string();
+        self.set_theme(&next);
+    }
+
+    /// Switch to a built-in or custom theme by name until the config is reloaded
+    pub fn set_theme(&mut self, name: &str) {
+        match Theme::from_config(name, &self.config.current.themes) {
+            Ok(theme) => {
+                self.config.theme = theme;
+                self.config.current.theme = name.to_string();
+                self.show_toast(format!("Theme: {}", name));
+            }
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Apply a filter preset, keeping the selection if it is still listed
+    pub fn set_filter(&mut self, filter: ListFilter) {
+        let selected = self.selected_entry();
+        self.network.filter = filter;
+        self.update_filtered_list();
+        let pos = selected.and_then(|(ssid, auth)| self.find_entry(&ssid, Some(&auth)));
+        self.ui.l_state.select(Some(pos.unwrap_or(0)));
+        self.show_toast(match filter {
+            ListFilter::All => "Showing all networks".to_string(),
+            _ => format!("Showing {} networks only", filter.label()),
+        });
+    }
+
+    /// Remember the current search query, most recent last
+    pub fn push_search_history(&mut self) {
+        self.ui.search_history_pos = None;
+        let query = self.inputs.search_input.value.trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+        self.ui.search_history.retain(|q| *q != query);
+        self.ui.search_history.push(query);
+        let excess = self
+            .ui
+            .search_history
+            .len()
+            .saturating_sub(config::SEARCH_HISTORY_LIMIT);
+        self.ui.search_history.drain(..excess);
+    }
+
+    /// Filter by the edited search after [`config::SEARCH_DEBOUNCE_MS`]
+    /// without another keystroke, instead of on every one
+    pub fn schedule_search(&mut self) {
+        self.ui.search_due =
+            Some(Instant::now() + Duration::from_millis(config::SEARCH_DEBOUNCE_MS));
+    }
+
+    /// Step through previous searches; stepping past the newest restores the draft
+    pub fn recall_search_history(&mut self, older: bool) {
+        let len = self.ui.search_history.len();
+        if len == 0 {
+            return;
+        }
+
+        let pos = match (self.ui.search_history_pos, older) {
+            (None, true) => {
+                self.ui.search_draft = self.inputs.search_input.value.clone();
+                Some(len - 1)
+            }
+            (None, false) => return,
+            (Some(pos), true) => Some(pos.saturating_sub(1)),
+            (Some(pos), false) if pos + 1 < len => Some(pos + 1),
+            (Some(_), false) => None,
+        };
+
+        self.ui.search_history_pos = pos;
+        self.inputs.search_input.value = match pos {
+            Some(pos) => self.ui.search_history[pos].clone(),
+            None => std::mem::take(&mut self.ui.search_draft),
+        };
+        self.inputs.search_input.move_end();
+        self.update_filtered_list();
+    }
+
+    /// Re-read the adapter list and switch to the next one
+    pub fn next_interface(&mut self) -> bool {
+        match wifi::list_interfaces() {
+            Ok(interfaces) => self.network.interfaces = interfaces,
+            Err(e) => {
+                self.show_error(e.to_string());
+                return false;
+            }
+        }
+        if self.network.interfaces.len() < 2 {
+            self.show_toast("No other WiFi adapter found");
+            return false;
+        }
+
+        let current = self
+            .network
+            .active_interface
+            .as_ref()
+            .and_then(|active| {
+                self.network
+                    .interfaces
+                    .iter()
+                    .position(|i| i.id == active.id)
+            })
+            .unwrap_or(0);
+        let next = self.network.interfaces[(current + 1) % self.network.interfaces.len()].clone();
+
+        wifi::set_active_interface(&next);
+        self.show_toast(format!("Switched to {}", next.description));
+        self.network.active_interface = Some(next);
+        self.network.marked.clear();
+        true
+    }
+
+    /// Whether the adapter in use is the first one, which a daemon serves
+    pub fn on_default_interface(&self) -> bool {
+        self.network.active_interface.as_ref().is_none_or(|active| {
+            self.network
+                .interfaces
+                .first()
+                .is_some_and(|first| first.id == active.id)
+        })
+    }
+
+    /// SSID and security of the selected network, which together tell apart
+    /// the entries of an SSID that is advertised with more than one security
+    pub fn selected_entry(&self) -> Option<(String, String)> {
+        self.ui
+            .l_state
+            .selected()
+            .and_then(|i| self.network.filtered_wifi_list.get(i))
+            .map(|w| (w.ssid.clone(), w.authentication.clone()))
+    }
+
+    /// Position of a network in the filtered list, preferring the entry with
+    /// the given security when the SSID is listed more than once
+    pub fn find_entry(&self, ssid: &str, auth: Option<&str>) -> Option<usize> {
+        let list = &self.network.filtered_wifi_list;
+        auth.and_then(|auth| {
+            list.iter()
+                .position(|w| w.ssid == ssid && w.authentication == auth)
+        })
+        .or_else(|| list.iter().position(|w| w.ssid == ssid))
+    }
+
+    /// Note that the connection moved from one access point of `ssid` to
+    /// another, unless the move was already seen
+    ///
+    /// Windows reports roams as they happen; elsewhere they only show up as a
+    /// different connected BSSID between scans.
+    pub fn record_roam(&mut self, ssid: &str, from: Option<[u8; 6]>, to: [u8; 6]) {
+        if from == Some(to)
+            || self
+                .connection
+                .last_roam
+                .as_ref()
+                .is_some_and(|roam| roam.ssid == ssid && roam.to == to)
+        {
+            return;
+        }
+        let channel = self
+            .network
+            .wifi_list
+            .iter()
+            .filter(|w| w.ssid == ssid)
+            .flat_map(|w| &w.bss_list)
+            .find(|bss| bss.bssid == to)
+            .map(|bss| format!(" (channel {})", bss.channel))
+            .unwrap_or_default();
+        self.show_toast(format!(
+            "{} roamed to {}{}",
+            ssid,
+            wifi::format_bssid(&to),
+            channel
+        ));
+        self.connection.last_roam = Some(Roam {
+            ssid: ssid.to_string(),
+            from,
+            to,
+            at: Instant::now(),
+        });
+    }
+
+    /// Select a network by SSID, clearing the search if it hides the network
+    ///
+    /// The selection stays put if it already is one of the SSID's entries, so
+    /// a command run on it after a confirmation acts on the same entry.
+    pub fn select_network(&mut self, ssid: &str) -> bool {
+        if self
+            .selected_entry()
+            .is_some_and(|(selected, _)| selected == ssid)
+        {
+            return true;
+        }
+        let find = |list: &[Arc<WifiInfo>]| {
+            list.iter()
+                .position(|w| w.ssid == ssid)
+                .or_else(|| list.iter().position(|w| w.ssid.eq_ignore_ascii_case(ssid)))
+        };
+
+        if find(&self.network.filtered_wifi_list).is_none()
+            && find(&self.network.wifi_list).is_some()
+        {
+            self.inputs.search_input.clear();
+            self.network.filter = ListFilter::All;
+            self.update_filtered_list();
+        }
+
+        match find(&self.network.filtered_wifi_list) {
+            Some(pos) => {
+                self.ui.l_state.select(Some(pos));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Rebuild the cached list rows if the list, connection, marks or icons changed
+    pub fn update_list_rows(&mut self) {
+        if self.network.list_rows.is_some() {
+            return;
+        }
+
+        let icons = &self.ui.icon_set;
+        let query = Query::parse(&self.inputs.search_input.value);
+        let rows = self
+            .network
+            .filtered_wifi_list
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                // Digits 1-9 quick-connect to the first nine rows
+                let index = if i < 9 {
+                    format!("{} ", i + 1)
+                } else {
+                    "  ".to_string()
+                };
+                let prefix = if w.is_saved {
+                    icons.saved()
+                } else if w.authentication == "Open" {
+                    icons.open()
+                } else {
+                    icons.locked()
+                };
+
+                let band = crate::wifi::Band::from_frequency(w.frequency)
+                    .map(|b| b.short_label())
+                    .unwrap_or("");
+                let generation = w
+                    .generation()
+                    .map(|g| format!("{}{}", icons.generation(), g.label()))
+                    .unwrap_or_default();
+                let mut text = format!(
+                    "{}{}{:<5}{:<4}{}",
+                    index,
+                    prefix,
+                    band,
+                    generation,
+                    w.display_ssid()
+                );
+                let name_start = text.chars().count() - w.display_ssid().chars().count();
+                let matched = query
+                    .matches(w)
+                    .map(|m| m.name.into_iter().map(|i| name_start + i).collect())
+                    .unwrap_or_default();
+
+                let ap_count = w.ap_count();
+                if ap_count > 1 {
+                    text = format!("{} {}{}", text, icons.ap_count(), ap_count);
+                }
+
+                let is_connected = self.network.connected_ssid.as_ref() == Some(&w.ssid);
+                if is_connected {
+                    text = format!("{}{}", text, icons.connected());
+                }
+
+                if w.is_saved {
+                    if w.auto_connect {
+                        text = format!("{} {}", text, icons.auto_on());
+                    } else {
+                        text = format!("{} {}", text, icons.auto_off());
+                    }
+                }
+
+                if w.profile_security.is_some() {
+                    text = format!("{} {}", text, icons.mismatch());
+                }
+
+                if w.authentication == "Open" && self.network.accepted_open.contains(&w.ssid) {
+                    text = format!("{} {}", text, icons.accepted_open());
+                }
+
+                let suspicious = self.suspicion(w).is_some();
+                if suspicious {
+                    text = format!("{} {}", text, icons.suspicious());
+                }
+
+                if self.is_marked(w) {
+                    text = format!("{} {}", text, icons.marked());
+                }
+
+                if let Some(priority) = roaming::priority(&self.config.current.preferred, &w.ssid) {
+                    text = format!("{} {}{}", text, icons.preferred(), priority + 1);
+                }
+
+                ListRow {
+                    text,
+                    signal: format!("{} {:>3}%", icons.signal_bars(w.signal), w.signal),
+                    security: w.security_badge().to_string(),
+                    is_saved: w.is_saved,
+                    is_connected,
+                    profile_mismatch: w.profile_security.is_some(),
+                    suspicious,
+                    fading: self.network.presence.is_fading(w),
+                    is_new: self.network.presence.is_new(w),
+                    matched,
+                }
+            })
+            .collect();
+        self.network.list_rows = Some(rows);
+    }
+
+    /// Re-evaluate the band steering suggestion for the connected network
+    pub fn update_band_steering(&mut self) {
+        self.steering.suggestion = if self.steering.in_cooldown() {
+            None
         } else {
-            let search_lower = self.inputs.search_input.value.to_lowercase();
-            self.network.filtered_wifi_list = self
-                .network
+            self.network
                 .wifi_list
                 .iter()
-                .filter(|w| {
-                    let ssid_lower = w.ssid.to_lowercase();
-                    let mut search_chars = search_lower.chars();
-                    let mut search_char = search_chars.next();
-
-                    for c in ssid_lower.chars() {
-                        if let Some(sc) = search_char {
-                            if c == sc {
-                                search_char = search_chars.next();
-                            }
-                        } else {
-                            break;
-                        }
-                    }
-                    search_char.is_none()
-                })
-                .cloned()
-                .collect();
+                .find(|w| w.is_connected)
+                .and_then(|w| steering::find_faster_band(w))
+        };
+    }
+
+    /// Track how long a preferred network has been clearly stronger than the connected one
+    pub fn update_roaming(&mut self) {
+        if !self.config.current.roaming {
+            self.roaming.candidate = None;
+            return;
         }
-        // Reset selection if out of bounds
-        if let Some(selected) = self.ui.l_state.selected() {
-            if selected >= self.network.filtered_wifi_list.len() {
-                self.ui.l_state.select(Some(0));
+        let found =
+            roaming::find_candidate(&self.network.in_range(), &self.config.current.preferred);
+        self.roaming.candidate = match (found, self.roaming.candidate.take()) {
+            // Same network still stronger: keep counting from when it started
+            (Some(found), Some((previous, since))) if found.ssid == previous.ssid => {
+                Some((found, since))
+            }
+            (Some(found), _) => Some((found, Instant::now())),
+            (None, _) => None,
+        };
+    }
+
+    /// What a background check of the auto-connect rules needs; `None` when
+    /// there are no rules and nothing left to restore
+    pub fn rules_check(&self) -> Option<rules::Check> {
+        let rules = &self.config.current.rules;
+        if rules.is_empty() && self.rules.applied == Plan::default() {
+            return None;
+        }
+        Some(rules::Check {
+            rules: rules.clone(),
+            applied: self.rules.applied.clone(),
+            networks: self.network.wifi_list.clone(),
+            originals: self.rules.originals.clone(),
+        })
+    }
+
+    /// Show a transient notification
+    pub fn show_toast(&mut self, message: impl Into<String>) {
+        self.push_toast(ToastKind::Info, message);
+    }
+
+    pub fn show_success(&mut self, message: impl Into<String>) {
+        self.push_toast(ToastKind::Success, message);
+    }
+
+    pub fn show_warning(&mut self, message: impl Into<String>) {
+        self.push_toast(ToastKind::Warning, message);
+    }
+
+    pub fn show_error(&mut self, message: impl Into<String>) {
+        self.push_toast(ToastKind::Error, message);
+    }
+
+    fn push_toast(&mut self, kind: ToastKind, message: impl Into<String>) {
+        let message = message.into();
+        // The same message again just restarts its timer
+        self.ui.toasts.retain(|t| t.message != message);
+        if self.ui.toasts.len() == config::MAX_TOASTS {
+            self.ui.toasts.pop_front();
+        }
+        self.ui.toasts.push_back(Toast {
+            kind,
+            message,
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// Replace the status line with the outcome of an action
+    pub fn set_status(&mut self, ok: bool, message: impl Into<String>) {
+        self.ui.status = Some(StatusLine {
+            message: message.into(),
+            ok,
+        });
+    }
+
+    /// Drop toasts that have been on screen long enough
+    pub fn expire_toasts(&mut self) {
+        let secs = self.config.current.toast_secs;
+        self.ui
+            .toasts
+            .retain(|t| t.shown_at.elapsed() < t.kind.duration(secs));
+    }
+
+    /// Show a desktop notification if the config allows it right now
+    pub fn notify(&self, title: &str, body: &str) {
+        let allowed = match self.config.current.notifications {
+            NotifyMode::Off => false,
+            NotifyMode::Background => !self.ui.has_focus,
+            NotifyMode::Always => true,
+        };
+        if allowed {
+            notify::send(title, body);
+        }
+    }
+
+    /// Track how long the connected network has been below the alert threshold
+    pub fn update_signal_alert(&mut self) {
+        let Some(threshold) = self.signal_alert.threshold else {
+            return;
+        };
+
+        let connected = self
+            .network
+            .wifi_list
+            .iter()
+            .find(|w| w.is_connected)
+            .map(|w| (w.ssid.clone(), w.signal));
+
+        match connected {
+            Some((ssid, signal)) if signal < threshold => {
+                let since = *self
+                    .signal_alert
+                    .below_since
+                    .get_or_insert_with(Instant::now);
+                if !self.signal_alert.alerted && since.elapsed() >= self.signal_alert.duration {
+                    self.signal_alert.alerted = true;
+                    self.show_warning(format!(
+                        "Weak signal on {}: {}% (below {}%)",
+                        ssid, signal, threshold
+                    ));
+                }
+            }
+            _ => {
+                self.signal_alert.below_since = None;
+                self.signal_alert.alerted = false;
             }
         }
     }
 
+    /// Start or stop appending signal samples to the CSV log
+    pub fn toggle_signal_logging(&mut self) {
+        if let Some(logger) = self.monitor.logger.take() {
+            self.show_toast(format!(
+                "Signal logging stopped ({})",
+                logger.path().display()
+            ));
+            return;
+        }
+
+        match SignalLogger::open(&self.monitor.csv_path) {
+            Ok(logger) => {
+                self.show_toast(format!("Logging signal to {}", logger.path().display()));
+                self.monitor.logger = Some(logger);
+                self.log_signal_sample();
+            }
+            Err(e) => {
+                self.show_error(format!("Failed to open signal log: {}", e));
+            }
+        }
+    }
+
+    /// Append a sample for the connected network if signal logging is active
+    pub fn log_signal_sample(&mut self) {
+        let Some(logger) = &mut self.monitor.logger else {
+            return;
+        };
+        let Some(wifi) = self.network.wifi_list.iter().find(|w| w.is_connected) else {
+            return;
+        };
+
+        if let Err(e) = logger.log_sample(wifi) {
+            self.monitor.logger = None;
+            self.show_error(format!("Signal logging stopped: {}", e));
+        }
+    }
+
+    /// Start a site survey, or finish it and export the per-label summary
+    pub fn toggle_survey(&mut self) {
+        let Some(survey) = self.monitor.survey.take() else {
+            let mut survey = Survey::new();
+            survey.record(&self.network.wifi_list);
+            self.monitor.survey = Some(survey);
+            self.show_toast("Survey started - press t to tag a location");
+            return;
+        };
+
+        if survey.label_count() == 0 {
+            self.show_toast("Survey ended - no locations were tagged");
+            return;
+        }
+
+        let stamp = format_timestamp(SystemTime::now()).replace(':', "-");
+        let path = config::data_dir().join(format!("survey-{}.csv", stamp));
+        match survey.export_summary(&path) {
+            Ok(()) => self.show_success(format!("Survey saved to {}", path.display())),
+            Err(e) => {
+                self.show_error(format!("Failed to export survey: {}", e));
+            }
+        }
+    }
+
+    /// Append the latest signal of every visible SSID to its history
+    pub fn record_signal_history(&mut self) {
+        let mut latest: HashMap<&str, SignalSample> = HashMap::new();
+        for wifi in &self.network.wifi_list {
+            let sample = latest.entry(&wifi.ssid).or_insert(SignalSample {
+                signal: 0,
+                rssi: None,
+            });
+            sample.signal = sample.signal.max(wifi.signal);
+            sample.rssi = sample.rssi.or(wifi.rssi);
+        }
+        for (ssid, sample) in latest {
+            let history = self
+                .monitor
+                .signal_history
+                .entry(ssid.to_string())
+                .or_default();
+            if history.len() >= config::SIGNAL_HISTORY_LEN {
+                history.pop_front();
+            }
+            history.push_back(sample);
+        }
+    }
+
+    /// Record the latest scan into the running survey
+    pub fn record_survey_sample(&mut self) {
+        if let Some(survey) = &mut self.monitor.survey {
+            survey.record(&self.network.wifi_list);
+        }
+    }
+
+    /// Tag the untagged survey samples with a location label
+    pub fn tag_survey_location(&mut self, label: &str) {
+        let Some(survey) = &mut self.monitor.survey else {
+            return;
+        };
+        let tagged = survey.tag(label);
+        self.show_toast(format!("Tagged {} access points as \"{}\"", tagged, label));
+    }
+
+    /// Mark or unmark the selected network for comparison (at most two are kept)
+    pub fn toggle_compare_mark(&mut self) {
+        let Some(wifi) = self
+            .ui
+            .l_state
+            .selected()
+            .and_then(|i| self.network.filtered_wifi_list.get(i))
+        else {
+            return;
+        };
+        let key = (wifi.ssid.clone(), wifi.authentication.clone());
+
+        if let Some(pos) = self.network.marked.iter().position(|m| *m == key) {
+            self.network.marked.remove(pos);
+        } else {
+            if self.network.marked.len() == 2 {
+                self.network.marked.remove(0);
+            }
+            self.network.marked.push(key);
+        }
+        self.network.list_rows = None;
+    }
+
+    /// Whether a network is marked for comparison
+    pub fn is_marked(&self, wifi: &WifiInfo) -> bool {
+        self.network
+            .marked
+            .iter()
+            .any(|(ssid, auth)| *ssid == wifi.ssid && *auth == wifi.authentication)
+    }
+
+    /// The two marked networks, if both are still in the scan results
+    pub fn compared_networks(&self) -> Option<(&WifiInfo, &WifiInfo)> {
+        let find = |(ssid, auth): &(String, String)| {
+            self.network
+                .wifi_list
+                .iter()
+                .find(|w| w.ssid == *ssid && w.authentication == *auth)
+                .map(|w| w.as_ref())
+        };
+        match self.network.marked.as_slice() {
+            [a, b] => Some((find(a)?, find(b)?)),
+            _ => None,
+        }
+    }
+
+    /// Why a network might be an evil twin, if it looks like one
+    pub fn suspicion(&self, wifi: &WifiInfo) -> Option<Suspicion> {
+        rogue::check(wifi, &self.network.wifi_list, &self.network.known_bssids)
+    }
+
+    /// Why joining a network would break `require_pmf`, if it would
+    pub fn pmf_refusal(&self, wifi: &WifiInfo) -> Option<String> {
+        let family = wifi::security_family(&wifi.authentication);
+        let pmf = wifi.strongest_bss()?.capabilities.pmf?;
+        (self.config.current.require_pmf
+            && (family.starts_with("WPA2-") || family.starts_with("WPA3-"))
+            && pmf == wifi::Pmf::Disabled)
+            .then(|| {
+                format!(
+                    "{} doesn't offer protected management frames (require_pmf is on)",
+                    wifi.ssid
+                )
+            })
+    }
+
+    /// Settings for a new profile, requiring PMF when `require_pmf` is on
+    pub fn profile_options(&self, hidden: bool, random_mac: bool) -> ProfileOptions {
+        ProfileOptions {
+            hidden,
+            random_mac,
+            require_pmf: self.config.current.require_pmf,
+        }
+    }
+
+    /// Hold `confirmation` for a yes or no unless confirmations are turned
+    /// off or it was just given; true means the caller should stop here
+    pub fn ask_confirmation(&mut self, confirmation: Confirmation) -> bool {
+        if !self.config.current.confirm || self.ui.confirmed {
+            return false;
+        }
+        self.ui.confirmation = Some(confirmation);
+        true
+    }
+
+    /// Drop the forgotten profile once it can no longer be restored
+    pub fn expire_forgotten_profile(&mut self) {
+        if self
+            .network
+            .forgotten
+            .as_ref()
+            .is_some_and(|f| f.remaining().is_zero())
+        {
+            self.network.forgotten = None;
+        }
+    }
+
+    /// Drop a revealed password once it expires or another network is selected
+    pub fn expire_revealed_password(&mut self) {
+        let selected_ssid = self
+            .ui
+            .l_state
+            .selected()
+            .and_then(|i| self.network.filtered_wifi_list.get(i))
+            .map(|w| w.ssid.as_str());
+        if let Some(revealed) = &self.ui.revealed_password
+            && (selected_ssid != Some(revealed.ssid.as_str())
+                || revealed.fetched_at.elapsed()
+                    >= Duration::from_secs(config::PASSWORD_REVEAL_SECS))
+        {
+            self.ui.revealed_password = None;
+        }
+    }
+
+    /// Close the share popup and drop the shared credentials
+    pub fn close_qr_popup(&mut self) {
+        self.ui.show_qr_popup = false;
+        self.ui.qr_code_lines.clear();
+        self.ui.qr_ssid.clear();
+        self.ui.qr_auth.clear();
+        self.ui.qr_password = None;
+        self.ui.show_qr_password = false;
+    }
+
+    /// Capture the UI state worth restoring on the next launch
+    pub fn session(&self) -> Session {
+        Session {
+            selected_ssid: self
+                .ui
+                .l_state
+                .selected()
+                .and_then(|i| self.network.filtered_wifi_list.get(i))
+                .map(|w| w.ssid.clone()),
+            search: self.inputs.search_input.value.clone(),
+            search_history: self.ui.search_history.clone(),
+            sort: (self.network.sort != self.config.current.sort).then_some(self.network.sort),
+            filter: self.network.filter,
+            details_tab: self.ui.details_tab,
+            last_connected: self.network.last_connected.clone(),
+            known_bssids: self.network.known_bssids.clone(),
+            accepted_open: self.network.accepted_open.clone(),
+        }
+    }
+
+    /// Apply a saved session; the selection is restored once the first scan arrives
+    pub fn restore_session(&mut self, session: Session) {
+        self.inputs.search_input.value = session.search;
+        self.inputs.search_input.move_end();
+        self.ui.restore_ssid = session.selected_ssid;
+        self.ui.search_history = session.search_history;
+        if let Some(sort) = session.sort {
+            self.network.sort = sort;
+        }
+        self.network.filter = session.filter;
+        self.ui.details_tab = session.details_tab;
+        self.network.last_connected = session.last_connected;
+        self.network.known_bssids = session.known_bssids;
+        self.network.accepted_open = session.accepted_open;
+        self.update_filtered_list();
+    }
+
+    /// Show the previous run's networks until the first scan arrives
+    pub fn show_snapshot(&mut self, mut networks: Vec<WifiInfo>) {
+        // Whatever was connected then may not be now
+        for wifi in &mut networks {
+            wifi.is_connected = false;
+            wifi.link_speed = None;
+        }
+        self.network.replace_networks(networks);
+        self.update_filtered_list();
+        let restored = self
+            .ui
+            .restore_ssid
+            .as_ref()
+            .and_then(|ssid| self.find_entry(ssid, None));
+        self.ui.l_state.select(Some(restored.unwrap_or(0)));
+        self.refresh.is_initial_loading = false;
+        self.refresh.showing_snapshot = true;
+    }
+
+    /// Re-read the config file and re-apply icons, intervals and alert settings
+    pub fn reload_config(&mut self) {
+        let config = match Config::load(&self.config.path) {
+            Ok(config) => config.with_overrides(&self.config.overrides),
+            Err(e) => {
+                self.show_error(e.to_string());
+                return;
+            }
+        };
+        // Bindings or a theme that fail to load keep the current ones, and the
+        // rest of the reload with them
+        let keymap = match config.keymap() {
+            Ok(keymap) => keymap,
+            Err(e) => {
+                self.show_error(e.to_string());
+                return;
+            }
+        };
+        let theme = match config.theme() {
+            Ok(theme) => theme,
+            Err(e) => {
+                self.show_error(e.to_string());
+                return;
+            }
+        };
+
+        self.ui.icon_set = if config.ascii_icons {
+            IconSet::Ascii
+        } else {
+            IconSet::Nerd
+        };
+        self.network.list_rows = None;
+        self.signal_alert =
+            SignalAlertState::new(config.low_signal_threshold, config.low_signal_secs);
+        self.config.keymap = keymap;
+        self.config.theme = theme;
+        if config.sort != self.config.current.sort {
+            self.network.sort = config.sort;
+            self.update_filtered_list();
+        }
+        self.config.current = config;
+        self.show_success(format!("Reloaded {}", self.config.path.display()));
+    }
+
+    /// Palette actions matching the current palette query, best match first
+    pub fn palette_actions(&self) -> Vec<Action> {
+        let query = self.inputs.palette_input.value.to_lowercase();
+        let mut matches: Vec<(Action, u32)> = Action::PALETTE
+            .iter()
+            .filter_map(|&action| {
+                let label = action.label().to_lowercase();
+                let indices = fuzzy_indices(&label, &query)?;
+                Some((action, fuzzy_score(&label, &indices)))
+            })
+            .collect();
+        // Best matches first; ties keep the palette order
+        matches.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        matches.into_iter().map(|(action, _)| action).collect()
+    }
+
+    pub fn close_command_palette(&mut self) {
+        self.ui.show_command_palette = false;
+        self.inputs.palette_input.clear();
+    }
+
     /// Check if any popup is open (for dimming the background)
     pub fn is_popup_open(&self) -> bool {
-        self.ui.show_manual_add_popup || self.ui.show_password_popup || self.ui.show_qr_popup
+        self.ui.show_manual_add_popup
+            || self.profiles.editor.is_some()
+            || self.ui.show_password_popup
+            || self.ui.show_qr_popup
+            || self.ui.show_share_password_popup
+            || self.ui.show_survey_label_popup
+            || self.ui.show_compare_popup
+            || self.ui.show_command_palette
+            || self.ui.show_command_line
+            || self.ui.show_hotspot_popup
+            || self.ui.show_profiles_popup
+            || self.ui.show_speedtest_popup
+            || self.ui.show_wps_popup
+            || self.ui.show_channel_graph
+            || self.ui.show_help
+            || self.ui.confirmation.is_some()
+    }
+
+    /// The text field keys are typed into, following the popup order of
+    /// `handle_key`
+    pub fn focused_input(&mut self) -> Option<&mut InputState> {
+        let is_popup_open = self.is_popup_open();
+        let inputs = &mut self.inputs;
+        if self.ui.show_qr_popup || self.ui.show_compare_popup {
+            None
+        } else if self.ui.show_manual_add_popup {
+            match inputs.manual_input_field {
+                0 => Some(&mut inputs.manual_ssid_input),
+                1 => Some(&mut inputs.manual_password_input),
+                7 => Some(&mut inputs.manual_identity_input),
+                _ => None,
+            }
+        } else if let Some(editor) = &self.profiles.editor {
+            (editor.field == 1).then_some(&mut inputs.profile_password_input)
+        } else if self.ui.show_password_popup {
+            Some(&mut inputs.password_input)
+        } else if self.ui.show_share_password_popup {
+            Some(&mut inputs.share_password_input)
+        } else if self.ui.show_survey_label_popup {
+            Some(&mut inputs.survey_label_input)
+        } else if self.ui.show_command_palette {
+            Some(&mut inputs.palette_input)
+        } else if self.ui.show_command_line {
+            Some(&mut inputs.command_input)
+        } else if self.ui.show_hotspot_popup {
+            match self.hotspot.field {
+                0 => Some(&mut inputs.hotspot_ssid_input),
+                1 => Some(&mut inputs.hotspot_password_input),
+                _ => None,
+            }
+        } else if self.ui.is_searching && !is_popup_open {
+            Some(&mut inputs.search_input)
+        } else {
+            None
+        }
+    }
+}
+
+/// Char positions in `text` where the characters of `query` matched, in order
+pub fn fuzzy_indices(text: &str, query: &str) -> Option<Vec<usize>> {
+    let mut query_chars = query.chars().peekable();
+    let mut indices = Vec::new();
+    for (i, c) in text.chars().enumerate() {
+        if query_chars.peek() == Some(&c) {
+            query_chars.next();
+            indices.push(i);
+        }
+    }
+    query_chars.peek().is_none().then_some(indices)
+}
+
+/// How well a fuzzy match reads: runs of adjacent characters and matches at
+/// the start of a word score higher
+pub fn fuzzy_score(text: &str, indices: &[usize]) -> u32 {
+    let chars: Vec<char> = text.chars().collect();
+    let mut score = 0;
+    for (n, &i) in indices.iter().enumerate() {
+        score += 1;
+        if i == 0 || !chars[i - 1].is_alphanumeric() {
+            score += 3;
+        }
+        if n > 0 && indices[n - 1] + 1 == i {
+            score += 2;
+        }
     }
+    score
 }
@@ -0,0 +1,96 @@
+//! Channel occupancy for the channel graph
+//!
+//! Groups the access points of every visible network by channel so the
+//! least congested channel of a band can be picked for one's own router.
+
+use crate::wifi::{Band, WifiInfo};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Bands in the order the graph cycles through them
+pub const BANDS: [Band; 3] = [Band::Ghz2_4, Band::Ghz5, Band::Ghz6];
+
+/// 2.4 GHz channels are 5 MHz apart but 20 MHz wide, so neighbours within
+/// this distance overlap
+const OVERLAP_2_4GHZ: u32 = 4;
+
+/// Networks seen on one channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelLoad {
+    pub channel: u32,
+    /// Distinct SSIDs with at least one access point on the channel
+    pub networks: usize,
+    /// Link quality (0-100) of the strongest access point, 0 when empty
+    pub strongest: u8,
+}
+
+/// Channels usable for a new network on each band
+fn standard_channels(band: Band) -> Vec<u32> {
+    match band {
+        Band::Ghz2_4 => (1..=13).collect(),
+        Band::Ghz5 => (36..=64)
+            .step_by(4)
+            .chain((100..=144).step_by(4))
+            .chain((149..=165).step_by(4))
+            .collect(),
+        // Preferred scanning channels; the full 6 GHz plan has 59 channels
+        Band::Ghz6 => (5..=229).step_by(16).collect(),
+    }
+}
+
+/// Occupancy of every standard channel of a band, plus any other occupied ones
+pub fn channel_loads(networks: &[Arc<WifiInfo>], band: Band) -> Vec<ChannelLoad> {
+    let mut channels: BTreeMap<u32, (Vec<&str>, u8)> = standard_channels(band)
+        .into_iter()
+        .map(|channel| (channel, (Vec::new(), 0)))
+        .collect();
+    for wifi in networks {
+        for bss in wifi.bss_list.iter().filter(|bss| bss.band() == Some(band)) {
+            let (ssids, strongest) = channels.entry(bss.channel).or_default();
+            if !ssids.contains(&wifi.ssid.as_str()) {
+                ssids.push(&wifi.ssid);
+            }
+            *strongest = (*strongest).max(bss.link_quality);
+        }
+    }
+    channels
+        .into_iter()
+        .map(|(channel, (ssids, strongest))| ChannelLoad {
+            channel,
+            networks: ssids.len(),
+            strongest,
+        })
+        .collect()
+}
+
+/// Networks interfering with a channel: those on it, plus overlapping
+/// neighbours on 2.4 GHz
+pub fn interference(loads: &[ChannelLoad], band: Band, channel: u32) -> usize {
+    let overlap = if band == Band::Ghz2_4 {
+        OVERLAP_2_4GHZ
+    } else {
+        0
+    };
+    loads
+        .iter()
+        .filter(|load| load.channel.abs_diff(channel) <= overlap)
+        .map(|load| load.networks)
+        .sum()
+}
+
+/// The standard channel with the fewest interfering networks, ties going to
+/// the one whose strongest neighbour is weakest
+pub fn least_congested(loads: &[ChannelLoad], band: Band) -> Option<u32> {
+    let candidates: Vec<u32> = match band {
+        // Only 1, 6 and 11 don't overlap each other
+        Band::Ghz2_4 => vec![1, 6, 11],
+        _ => standard_channels(band),
+    };
+    candidates.into_iter().min_by_key(|&channel| {
+        let strongest = loads
+            .iter()
+            .find(|load| load.channel == channel)
+            .map_or(0, |load| load.strongest);
+        (interference(loads, band, channel), strongest)
+    })
+}
@@ -0,0 +1,527 @@
+//! Headless subcommands
+//!
+//! `wifui list`, `wifui connect` and friends run a single WiFi operation and
+//! exit without starting the TUI, so scripts can reuse the same wifi module.
+
+use crate::config::{self, Config};
+use crate::daemon;
+use crate::monitor::format_timestamp;
+use crate::output::{self, Outcome};
+use crate::record::{Position, Recorder};
+use crate::scan_monitor::ScanMonitor;
+use crate::session::Session;
+use crate::share;
+use crate::sort;
+use crate::wifi::{self, Band, ProfileOptions, RadioState, WifiInfo};
+use clap::{Args, Subcommand, ValueEnum};
+use color_eyre::eyre::{Result, bail, eyre};
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    terminal::{Clear, ClearType},
+};
+use secrecy::SecretString;
+use serde_json::{Value, json};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Scan and list visible networks
+    List {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Connect to a network, using its saved profile unless a password is given
+    Connect {
+        ssid: String,
+
+        /// Passphrase for a secured network
+        #[arg(short, long)]
+        password: Option<String>,
+
+        /// The network does not broadcast its SSID
+        #[arg(long)]
+        hidden: bool,
+
+        /// Save the new profile with a randomized MAC address
+        #[arg(long)]
+        random_mac: bool,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Connect with the credentials of a `WIFI:` QR code URI, given directly or
+    /// as a text file containing one
+    Join {
+        #[arg(value_name = "URI_OR_FILE")]
+        source: String,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Disconnect from the current network
+    Disconnect {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Delete the saved profile of a network
+    Forget {
+        ssid: String,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Print a saved profile as WLAN profile XML, key included
+    Export {
+        ssid: String,
+
+        /// Write the XML to this file instead of stdout
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+
+    /// Validate and install a profile from a WLAN profile XML file
+    Import {
+        file: PathBuf,
+
+        /// Replace a saved profile of the same name
+        #[arg(long)]
+        overwrite: bool,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Show the connected network, e.g. for a shell prompt or status bar
+    Status {
+        /// Template with {ssid}, {signal}, {rssi}, {security}, {channel}, {band}
+        /// and {speed} placeholders, e.g. "{ssid} {signal}%"
+        #[arg(short, long)]
+        format: Option<String>,
+
+        /// Printed instead of the template while disconnected
+        #[arg(long, requires = "format")]
+        disconnected: Option<String>,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Show the WiFi radio state, or switch it on or off
+    Radio {
+        state: Option<Switch>,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Scan until interrupted, writing every access point seen to a CSV or KML
+    /// file, e.g. for wardriving or a site survey
+    Record {
+        /// Output file; `.kml` writes KML, anything else appends CSV
+        path: PathBuf,
+
+        /// Seconds between scans
+        #[arg(short, long, default_value_t = config::RECORD_INTERVAL_SECS)]
+        interval: u64,
+
+        /// Where the scans are taken, as LAT,LON; required for KML
+        #[arg(long, value_name = "LAT,LON", allow_hyphen_values = true)]
+        at: Option<Position>,
+    },
+
+    /// Rescan on a fixed interval and show the min, average and max signal of
+    /// every network seen, e.g. to find dead zones while walking around
+    Monitor {
+        /// Seconds between scans
+        #[arg(short, long, default_value_t = config::MONITOR_INTERVAL_SECS)]
+        interval: u64,
+    },
+
+    /// Keep scanning in the background and answer `list` and `status`
+    /// instantly over a local JSON-RPC socket
+    Daemon,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Switch {
+    On,
+    Off,
+}
+
+#[derive(Args, Debug)]
+pub struct OutputArgs {
+    /// Print the result as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// Run a subcommand to completion
+pub fn run(command: Command, config: &Config) -> Result<()> {
+    match command {
+        Command::List { output } => list(config, output.json),
+        Command::Connect {
+            ssid,
+            password,
+            hidden,
+            random_mac,
+            output,
+        } => {
+            let password = password.map(SecretString::from);
+            let status = connect(config, &ssid, password, hidden, random_mac)?;
+            let message = match status {
+                "already_connected" => format!("Already connected to {}", ssid),
+                _ => format!("Connected to {}", ssid),
+            };
+            report(output.json, status, Some(&ssid), message)
+        }
+        Command::Join { source, output } => {
+            let credentials = share::load_wifi_uri(&source).map_err(|e| eyre!(e))?;
+            let ssid = credentials.ssid;
+            let status = connect(
+                config,
+                &ssid,
+                credentials.password,
+                credentials.hidden,
+                false,
+            )?;
+            let message = match status {
+                "already_connected" => format!("Already connected to {}", ssid),
+                _ => format!("Connected to {}", ssid),
+            };
+            report(output.json, status, Some(&ssid), message)
+        }
+        Command::Disconnect { output } => {
+            daemon::request_or("disconnect", Value::Null, || Ok(wifi::disconnect()?))?;
+            report(
+                output.json,
+                "disconnected",
+                None,
+                "Disconnected".to_string(),
+            )
+        }
+        Command::Forget { ssid, output } => {
+            daemon::request_or("forget", json!({ "ssid": ssid }), || {
+                Ok(wifi::forget_network(&ssid)?)
+            })?;
+            report(
+                output.json,
+                "forgotten",
+                Some(&ssid),
+                format!("Forgot {}", ssid),
+            )
+        }
+        Command::Export { ssid, output } => {
+            let xml = wifi::export_profile(&ssid)?;
+            match output {
+                Some(path) => std::fs::write(&path, xml)?,
+                None => println!("{}", xml),
+            }
+            Ok(())
+        }
+        Command::Status {
+            format,
+            disconnected,
+            output,
+        } => status(
+            format.as_deref(),
+            disconnected.as_deref().unwrap_or_default(),
+            output.json,
+        ),
+        Command::Radio { state, output } => {
+            let on = state.map(|state| matches!(state, Switch::On));
+            let radio = match daemon::request("radio", json!({ "on": on })) {
+                Some(result) => daemon::parse_radio(result?)?,
+                None => {
+                    if let Some(on) = on {
+                        wifi::set_radio(on)?;
+                    }
+                    wifi::radio_state()?
+                }
+            };
+            let status = match radio {
+                RadioState::On => "radio_on",
+                RadioState::Off => "radio_off",
+                RadioState::HardwareOff => "radio_hardware_off",
+            };
+            report(output.json, status, None, radio.label().to_string())
+        }
+        Command::Record { path, interval, at } => record(config, &path, interval, at),
+        Command::Monitor { interval } => monitor(config, interval),
+        Command::Daemon => daemon::run(config),
+        Command::Import {
+            file,
+            overwrite,
+            output,
+        } => {
+            let name = wifi::import_profile(&std::fs::read_to_string(&file)?, overwrite)?;
+            report(
+                output.json,
+                "imported",
+                Some(&name),
+                format!("Imported {}", name),
+            )
+        }
+    }
+}
+
+/// Print the outcome of a state-changing subcommand
+fn report(json: bool, status: &str, ssid: Option<&str>, message: String) -> Result<()> {
+    if json {
+        output::print_json(&Outcome { status, ssid })
+    } else {
+        println!("{}", message);
+        Ok(())
+    }
+}
+
+/// Trigger a scan and wait for the results, like the TUI's refresh
+fn scan(config: &Config) -> Result<Vec<WifiInfo>> {
+    let _ = wifi::scan_networks();
+    std::thread::sleep(Duration::from_millis(config.scan_delay_ms));
+    Ok(wifi::get_wifi_networks()?)
+}
+
+fn list(config: &Config, json: bool) -> Result<()> {
+    // A running daemon already has fresh scan results
+    let mut networks = match daemon::request("list", Value::Null) {
+        Some(result) => serde_json::from_value(result?)?,
+        None => scan(config)?,
+    };
+    // Same order as the TUI, including a sort order cycled there
+    let session = Session::load(&config::data_dir().join(config::SESSION_FILE));
+    sort::sort_networks(
+        &mut networks,
+        session.sort.unwrap_or(config.sort),
+        &session.last_connected,
+        |w| w.signal,
+    );
+    if json {
+        return output::print_json(&networks);
+    }
+    println!(
+        "{:<32} {:>6} {:<10} {:>7} {:<6} STATUS",
+        "SSID", "SIGNAL", "SECURITY", "CHANNEL", "BAND"
+    );
+    for wifi in &networks {
+        let band = Band::from_frequency(wifi.frequency)
+            .map(|b| b.short_label())
+            .unwrap_or("?");
+        let status = if wifi.is_connected {
+            "connected"
+        } else if wifi.is_saved {
+            "saved"
+        } else {
+            ""
+        };
+        println!(
+            "{:<32} {:>5}% {:<10} {:>7} {:<6} {}",
+            wifi.display_ssid(),
+            wifi.signal,
+            wifi.authentication,
+            wifi.channel,
+            band,
+            status
+        );
+    }
+    Ok(())
+}
+
+/// Scan every `interval` seconds and write each scan until the process is
+/// interrupted
+fn record(config: &Config, path: &Path, interval: u64, at: Option<Position>) -> Result<()> {
+    let mut recorder =
+        Recorder::open(path, at).map_err(|e| eyre!("Can't record to {}: {}", path.display(), e))?;
+    eprintln!(
+        "Recording scans to {} every {}s, press Ctrl+C to stop",
+        recorder.path().display(),
+        interval
+    );
+    let interval = Duration::from_secs(interval.max(1));
+    loop {
+        let started = Instant::now();
+        let networks = scan(config)?;
+        let count = recorder.record(&networks)?;
+        println!(
+            "{}  {} networks, {} access points",
+            format_timestamp(SystemTime::now()),
+            networks.len(),
+            count
+        );
+        std::thread::sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
+
+/// Scan every `interval` seconds and redraw the signal table until the
+/// process is interrupted
+fn monitor(config: &Config, interval: u64) -> Result<()> {
+    let mut monitor = ScanMonitor::default();
+    let interval = Duration::from_secs(interval.max(1));
+    let mut stdout = std::io::stdout();
+    loop {
+        let started = Instant::now();
+        monitor.record(&scan(config)?);
+
+        let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+        queue!(stdout, MoveTo(0, 0), Clear(ClearType::All))?;
+        writeln!(
+            stdout,
+            "Scan {} every {}s, {}  (Ctrl+C to stop)",
+            monitor.scans(),
+            interval.as_secs(),
+            format_timestamp(SystemTime::now())
+        )?;
+        // The title line and a spare one for the cursor
+        for line in monitor.table(width as usize, (height as usize).saturating_sub(2)) {
+            writeln!(stdout, "{}", line)?;
+        }
+        stdout.flush()?;
+        std::thread::sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
+
+/// Print the connected network from the last scan results; no new scan is
+/// started so a prompt or status bar can call this often
+fn status(format: Option<&str>, disconnected: &str, json: bool) -> Result<()> {
+    let connected: Option<WifiInfo> = match daemon::request("status", Value::Null) {
+        Some(result) => serde_json::from_value(result?)?,
+        None => match wifi::get_connected_ssid()? {
+            Some(ssid) => wifi::get_wifi_networks()?
+                .into_iter()
+                .find(|w| w.ssid == ssid),
+            None => None,
+        },
+    };
+    if json {
+        return match &connected {
+            Some(network) => output::print_json(network),
+            None => output::print_json(&Outcome {
+                status: "disconnected",
+                ssid: None,
+            }),
+        };
+    }
+
+    match (format, connected) {
+        (Some(_), None) => println!("{}", disconnected),
+        (Some(template), Some(wifi)) => {
+            let line = output::render_template(template, |name| {
+                Some(match name {
+                    "ssid" => wifi.display_ssid().to_string(),
+                    "signal" => wifi.signal.to_string(),
+                    "rssi" => wifi.rssi.map(|dbm| dbm.to_string()).unwrap_or_default(),
+                    "security" => wifi.authentication.clone(),
+                    "channel" => wifi.channel.to_string(),
+                    "band" => Band::from_frequency(wifi.frequency)
+                        .map(|b| b.short_label().to_string())
+                        .unwrap_or_default(),
+                    "speed" => wifi
+                        .link_speed
+                        .map(|mbps| mbps.to_string())
+                        .unwrap_or_default(),
+                    _ => return None,
+                })
+            })
+            .map_err(|e| eyre!(e))?;
+            println!("{}", line);
+        }
+        (None, None) => println!("Not connected"),
+        (None, Some(wifi)) => {
+            let band = Band::from_frequency(wifi.frequency)
+                .map(|b| b.label())
+                .unwrap_or("?");
+            let rssi = wifi
+                .rssi
+                .map(|dbm| format!(" / {} dBm", dbm))
+                .unwrap_or_default();
+            println!(
+                "Connected to {} ({}%{}, {}, channel {})",
+                wifi.display_ssid(),
+                wifi.signal,
+                rssi,
+                band,
+                wifi.channel
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Connect and wait for it to complete, returning the outcome status
+fn connect(
+    config: &Config,
+    ssid: &str,
+    password: Option<SecretString>,
+    hidden: bool,
+    random_mac: bool,
+) -> Result<&'static str> {
+    let networks = match daemon::networks(true) {
+        Some(networks) => networks,
+        None => scan(config)?,
+    };
+    let network = networks.iter().find(|w| w.ssid == ssid);
+    if network.is_some_and(|w| w.is_connected) {
+        return Ok("already_connected");
+    }
+    if network.is_none() && !hidden {
+        bail!(
+            "Network {} not found (use --hidden for hidden networks)",
+            ssid
+        );
+    }
+
+    if wifi::get_connected_ssid()?.is_some() {
+        wifi::disconnect_and_wait()?;
+    }
+
+    let is_open = network.is_some_and(|w| w.authentication == "Open");
+    let options = ProfileOptions {
+        hidden,
+        random_mac,
+        require_pmf: config.require_pmf,
+    };
+    // A renamed profile no longer goes by its SSID
+    let profile = wifi::saved_profile_name(ssid)?;
+    match (password, network, profile.as_deref()) {
+        (Some(password), Some(info), _) => wifi::connect_with_password(
+            ssid,
+            &password,
+            &info.authentication,
+            &info.encryption,
+            options,
+        )?,
+        (Some(password), None, _) => {
+            wifi::connect_with_password(ssid, &password, "WPA2-PSK", "AES", options)?
+        }
+        // The daemon only connects saved profiles; new credentials are
+        // handled here
+        (None, _, Some(profile)) => {
+            daemon::request_or("connect", json!({ "ssid": profile }), || {
+                Ok(wifi::connect_profile(profile)?)
+            })?
+        }
+        (None, _, None) if is_open || hidden => wifi::connect_open(ssid, options)?,
+        (None, _, None) => bail!(
+            "{} is secured and has no saved profile; pass --password",
+            ssid
+        ),
+    }
+
+    // Connecting completes asynchronously, so wait for the adapter to report it
+    let timeout = Duration::from_secs(config.connection_timeout_secs);
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if wifi::get_connected_ssid()?.as_deref() == Some(ssid) {
+            return Ok("connected");
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+    bail!("Timed out connecting to {}", ssid)
+}
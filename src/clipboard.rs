@@ -0,0 +1,70 @@
+//! Clipboard support for copying SSIDs and saved passwords, and for pasting
+//! `WIFI:` URIs
+//!
+//! The clipboard handle is kept for the app's lifetime: on X11 copied text is
+//! only available while the program that owns it is still running.
+
+use arboard::Clipboard;
+use secrecy::{ExposeSecret, SecretString};
+use std::time::Instant;
+
+#[derive(Default)]
+pub struct ClipboardState {
+    clipboard: Option<Clipboard>,
+    /// What was copied and when to clear it, if auto-clear is on
+    pending_clear: Option<(SecretString, Instant)>,
+}
+
+impl std::fmt::Debug for ClipboardState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClipboardState")
+            .field("open", &self.clipboard.is_some())
+            .field("clear_at", &self.pending_clear.as_ref().map(|(_, at)| at))
+            .finish()
+    }
+}
+
+impl ClipboardState {
+    /// Put text on the clipboard, optionally clearing it again at `clear_at`
+    pub fn copy(
+        &mut self,
+        text: SecretString,
+        clear_at: Option<Instant>,
+    ) -> Result<(), arboard::Error> {
+        let clipboard = match &mut self.clipboard {
+            Some(clipboard) => clipboard,
+            None => self.clipboard.insert(Clipboard::new()?),
+        };
+        clipboard.set_text(text.expose_secret())?;
+        self.pending_clear = clear_at.map(|at| (text, at));
+        Ok(())
+    }
+
+    /// Text currently on the clipboard
+    pub fn paste(&mut self) -> Result<String, arboard::Error> {
+        let clipboard = match &mut self.clipboard {
+            Some(clipboard) => clipboard,
+            None => self.clipboard.insert(Clipboard::new()?),
+        };
+        clipboard.get_text()
+    }
+
+    /// Clear the clipboard once due, unless something else was copied since
+    pub fn clear_if_due(&mut self) {
+        if self
+            .pending_clear
+            .as_ref()
+            .is_none_or(|(_, at)| Instant::now() < *at)
+        {
+            return;
+        }
+        if let Some((text, _)) = self.pending_clear.take()
+            && let Some(clipboard) = &mut self.clipboard
+            && clipboard
+                .get_text()
+                .is_ok_and(|current| current == text.expose_secret())
+        {
+            let _ = clipboard.clear();
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Side-by-side network comparison
+//!
+//! Scores networks on a 0-100 scale from signal, band, PHY generation and
+//! security so two candidates can be weighed against each other.
+
+use crate::wifi::{Band, WifiInfo};
+
+/// Heuristic quality score (0-100) for choosing between networks
+pub fn quality_score(wifi: &WifiInfo) -> u8 {
+    // Signal dominates: up to 60 points
+    let signal = wifi.signal.min(100) as u32 * 60 / 100;
+
+    let band = match Band::from_frequency(wifi.frequency) {
+        Some(Band::Ghz6) => 15,
+        Some(Band::Ghz5) => 12,
+        Some(Band::Ghz2_4) => 5,
+        None => 0,
+    };
+
+    let phy = if wifi.phy_type.contains("Wi-Fi 7") {
+        15
+    } else if wifi.phy_type.contains("Wi-Fi 6") {
+        12
+    } else if wifi.phy_type.contains("Wi-Fi 5") {
+        9
+    } else if wifi.phy_type.contains("Wi-Fi 4") {
+        6
+    } else {
+        2
+    };
+
+    let security = match wifi.authentication.as_str() {
+        "WPA3-SAE" | "WPA3" => 10,
+        "WPA2-PSK" | "WPA2" => 8,
+        "WPA-PSK" | "WPA" => 4,
+        "Open" => 0,
+        _ => 2,
+    };
+
+    (signal + band + phy + security).min(100) as u8
+}
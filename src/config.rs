@@ -1,27 +1,354 @@
 /// Centralized configuration constants for WifUI
+use crate::error::ConfigError;
+use crate::keymap::Keymap;
+use crate::notify::NotifyMode;
+use crate::rules::Rule;
+use crate::sort::SortMode;
+use crate::theme::Theme;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 // UI Dimensions
-pub const MAIN_WINDOW_HEIGHT: u16 = 32;
-pub const MAIN_WINDOW_WIDTH: u16 = 77;
+/// The main window grows with the terminal up to this size
+pub const MAX_WINDOW_WIDTH: u16 = 110;
+pub const MAX_WINDOW_HEIGHT: u16 = 44;
+/// Below this size a "terminal too small" screen is shown; the tallest popup
+/// (the enterprise add-network form) needs 20 rows
+pub const MIN_WINDOW_WIDTH: u16 = 50;
+pub const MIN_WINDOW_HEIGHT: u16 = 20;
+/// Narrower windows get a one-line help bar
+pub const COMPACT_WINDOW_WIDTH: u16 = 72;
+/// The details pane is hidden rather than shrinking the list below this
+pub const MIN_LIST_ROWS: u16 = 5;
+/// Signal and security columns are dropped rather than squeezing names below this
+pub const MIN_LIST_NAME_WIDTH: usize = 24;
 
 // Timing
 pub const CONNECTION_TIMEOUT_SECS: u64 = 60;
-pub const PROFILE_REGISTRATION_DELAY_MS: u64 = 1500;
-pub const OPEN_PROFILE_REGISTRATION_DELAY_MS: u64 = 1000;
-pub const DISCONNECT_DELAY_MS: u64 = 500;
 pub const SCAN_DELAY_MS: u64 = 2000;
 pub const AUTO_REFRESH_INTERVAL_SECS: u64 = 10;
 pub const SEARCHING_REFRESH_INTERVAL_SECS: u64 = 15;
 pub const BURST_REFRESH_INTERVAL_SECS: u64 = 1;
 pub const INTERACTION_COOLDOWN_SECS: u64 = 1;
-pub const EVENT_POLL_MS: u64 = 100;
+/// Redraw interval while a spinner is animating
+pub const FRAME_INTERVAL_MS: u64 = 100;
+/// Wake-up interval for timers (toasts, auto-refresh) when nothing else happens
+pub const IDLE_TICK_MS: u64 = 1000;
 pub const MANUAL_REFRESH_DEBOUNCE_MS: u64 = 500;
+/// Two clicks on the same network within this window connect to it
+pub const DOUBLE_CLICK_MS: u64 = 400;
+/// A count or `g` with nothing after it for this long runs on its own
+pub const PENDING_KEYS_MS: u64 = 600;
+/// Typing pause after which the search filters the list
+pub const SEARCH_DEBOUNCE_MS: u64 = 80;
+pub const TOAST_DURATION_SECS: u64 = 5;
+/// Toasts on screen at once; older ones are dropped first
+pub const MAX_TOASTS: usize = 4;
+pub const HOTSPOT_STATUS_INTERVAL_SECS: u64 = 3;
+pub const HOTSPOT_MIN_PASSPHRASE_LEN: usize = 8;
+/// A password revealed in the details pane is dropped after this long
+pub const PASSWORD_REVEAL_SECS: u64 = 30;
+/// A forgotten profile can be restored with undo for this long
+pub const FORGET_UNDO_SECS: u64 = 15;
+pub const CONNECTIVITY_PROBE_TIMEOUT_SECS: u64 = 5;
+/// How often to re-probe while behind a captive portal, to notice the login
+pub const CAPTIVE_PORTAL_RECHECK_SECS: u64 = 10;
+
+// Latency monitor: ping every INTERVAL, stats over the last WINDOW samples
+pub const LATENCY_INTERVAL_SECS: u64 = 2;
+pub const LATENCY_TIMEOUT_SECS: u64 = 1;
+pub const LATENCY_WINDOW: usize = 30;
+/// Pinged alongside the gateway when `ping_internet` is set
+pub const INTERNET_PING_ADDR: std::net::Ipv4Addr = std::net::Ipv4Addr::new(1, 1, 1, 1);
+
+// Speed test: each transfer phase stops after PHASE_SECS even if unfinished
+pub const SPEEDTEST_DOWNLOAD_URL: &str = "http://speedtest.tele2.net/100MB.zip";
+pub const SPEEDTEST_UPLOAD_URL: &str = "http://speedtest.tele2.net/upload.php";
+pub const SPEEDTEST_TIMEOUT_SECS: u64 = 10;
+pub const SPEEDTEST_PHASE_SECS: u64 = 8;
+pub const SPEEDTEST_UPLOAD_BYTES: u64 = 25_000_000;
+/// Past results shown in the speed test popup
+pub const SPEEDTEST_HISTORY_SHOWN: usize = 3;
+
+// Navigation key repeat: presses closer than the window count as held, and
+// after the delay each repeat moves by the step
+pub const KEY_REPEAT_WINDOW_MS: u64 = 150;
+pub const KEY_REPEAT_ACCEL_DELAY_MS: u64 = 300;
+pub const KEY_REPEAT_ACCEL_STEP: usize = 4;
+
+// Debug log
+pub const DEBUG_PANE_HEIGHT: u16 = 10;
 
 // Refresh burst counts
 pub const STARTUP_REFRESH_BURST: u8 = 5;
 pub const CONNECTION_REFRESH_BURST: u8 = 15;
 pub const DISCONNECT_REFRESH_BURST: u8 = 5;
 
+// Data files
+pub const SIGNAL_LOG_FILE: &str = "signal_log.csv";
+pub const SHARE_DIR: &str = "shared";
+pub const SESSION_FILE: &str = "session.txt";
+pub const SNAPSHOT_FILE: &str = "networks.json";
+pub const DAEMON_SOCKET: &str = "wifui.sock";
+pub const SPEEDTEST_FILE: &str = "speedtest.csv";
+/// Default seconds between scans of `wifui record`
+pub const RECORD_INTERVAL_SECS: u64 = 5;
+/// Default seconds between scans of `wifui monitor`
+pub const MONITOR_INTERVAL_SECS: u64 = 3;
+/// Optional IEEE or Wireshark OUI listing for access point vendors
+pub const OUI_FILE: &str = "oui.txt";
+pub const SEARCH_HISTORY_LIMIT: usize = 20;
+/// Networks whose last connection time is remembered for the "recent" sort
+pub const LAST_CONNECTED_LIMIT: usize = 50;
+/// Access points remembered per saved network for evil twin detection
+pub const KNOWN_BSSIDS_LIMIT: usize = 32;
+/// Scans a network may be missing from before it leaves the list
+pub const FADE_SCANS: u8 = 3;
+/// How long networks that just appeared stay highlighted
+pub const NEW_NETWORK_HIGHLIGHT_SECS: u64 = 3;
+/// Signal change (percentage points) needed before a network moves in the list
+pub const RANK_SIGNAL_MARGIN: u8 = 8;
+
+/// Directory for files written by WifUI (logs, exports, state)
+pub fn data_dir() -> PathBuf {
+    if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+        PathBuf::from(local).join("wifui")
+    } else if let Some(home) = std::env::var_os("HOME") {
+        PathBuf::from(home)
+            .join(".local")
+            .join("share")
+            .join("wifui")
+    } else {
+        PathBuf::from(".")
+    }
+}
+
+/// Default location of the user config file
+pub fn config_path() -> PathBuf {
+    if let Some(appdata) = std::env::var_os("APPDATA") {
+        PathBuf::from(appdata).join("wifui").join("config.toml")
+    } else if let Some(home) = std::env::var_os("HOME") {
+        PathBuf::from(home)
+            .join(".config")
+            .join("wifui")
+            .join("config.toml")
+    } else {
+        PathBuf::from("config.toml")
+    }
+}
+
+/// User-tunable settings loaded from config.toml (missing keys use the defaults)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub ascii_icons: bool,
+    pub auto_refresh_secs: u64,
+    pub searching_refresh_secs: u64,
+    pub low_signal_threshold: Option<u8>,
+    pub low_signal_secs: u64,
+    pub burst_refresh_secs: u64,
+    /// Longest wait for a scan to finish before reading the results
+    pub scan_delay_ms: u64,
+    pub connection_timeout_secs: u64,
+    pub toast_secs: u64,
+    /// Clear copied SSIDs and passwords from the clipboard after this many seconds
+    pub clipboard_clear_secs: Option<u64>,
+    /// Also ping 1.1.1.1 in the latency monitor, not just the gateway
+    pub ping_internet: bool,
+    /// Plain HTTP file downloaded by the speed test
+    pub speedtest_download_url: String,
+    /// Plain HTTP endpoint accepting the speed test's POST upload
+    pub speedtest_upload_url: String,
+    /// Key overrides by action name, see [`Keymap`]
+    pub keys: BTreeMap<String, KeySpec>,
+    /// Built-in theme or an entry of `themes`
+    pub theme: String,
+    /// Custom themes by name, see [`Theme`]
+    pub themes: BTreeMap<String, ThemeSpec>,
+    /// Network list order until it is cycled with `O`
+    pub sort: SortMode,
+    /// Desktop notifications when a connection completes, fails or drops
+    pub notifications: NotifyMode,
+    /// Reconnect with backoff when the connected network drops
+    pub watchdog: bool,
+    /// Reconnect to a faster band of the connected network on its own
+    pub auto_band_steer: bool,
+    /// Saved networks to roam between, most preferred first
+    pub preferred: Vec<String>,
+    /// Switch to a clearly stronger preferred network on its own
+    pub roaming: bool,
+    /// Stop and remove a guest network this many minutes after it starts
+    pub guest_ttl_mins: u64,
+    /// Ask before forgetting a network, disconnecting during a speed test
+    /// or overwriting a saved profile
+    pub confirm: bool,
+    /// Warn before joining an unencrypted network
+    pub warn_open: bool,
+    /// Only join WPA2/WPA3 networks with protected management frames (802.11w)
+    pub require_pmf: bool,
+    /// Auto-connect rules by time and power source, see [`crate::rules`]
+    pub rules: Vec<Rule>,
+}
+
+/// One key or a list of keys bound to an action
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// A custom theme; colors left out come from the `base` built-in theme
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeSpec {
+    pub base: Option<String>,
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+    pub red: Option<String>,
+    pub green: Option<String>,
+    pub yellow: Option<String>,
+    pub blue: Option<String>,
+    pub purple: Option<String>,
+    pub cyan: Option<String>,
+    pub bright_purple: Option<String>,
+    pub dimmed: Option<String>,
+    pub selection_bg: Option<String>,
+}
+
+impl KeySpec {
+    pub fn keys(&self) -> &[String] {
+        match self {
+            KeySpec::One(key) => std::slice::from_ref(key),
+            KeySpec::Many(keys) => keys,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            ascii_icons: false,
+            auto_refresh_secs: AUTO_REFRESH_INTERVAL_SECS,
+            searching_refresh_secs: SEARCHING_REFRESH_INTERVAL_SECS,
+            low_signal_threshold: None,
+            low_signal_secs: DEFAULT_LOW_SIGNAL_SECS,
+            burst_refresh_secs: BURST_REFRESH_INTERVAL_SECS,
+            scan_delay_ms: SCAN_DELAY_MS,
+            connection_timeout_secs: CONNECTION_TIMEOUT_SECS,
+            toast_secs: TOAST_DURATION_SECS,
+            clipboard_clear_secs: None,
+            ping_internet: false,
+            speedtest_download_url: SPEEDTEST_DOWNLOAD_URL.to_string(),
+            speedtest_upload_url: SPEEDTEST_UPLOAD_URL.to_string(),
+            keys: BTreeMap::new(),
+            theme: DEFAULT_THEME.to_string(),
+            themes: BTreeMap::new(),
+            sort: SortMode::default(),
+            notifications: NotifyMode::default(),
+            watchdog: false,
+            auto_band_steer: false,
+            preferred: Vec::new(),
+            roaming: false,
+            guest_ttl_mins: DEFAULT_GUEST_TTL_MINS,
+            confirm: true,
+            warn_open: true,
+            require_pmf: false,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Load the config file, using defaults when it does not exist
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(ConfigError::Read(e)),
+        };
+        let config: Self = toml::from_str(&contents).map_err(ConfigError::Parse)?;
+        config.keymap()?;
+        config.theme()?;
+        Ok(config)
+    }
+
+    /// Key bindings with the `[keys]` overrides applied
+    pub fn keymap(&self) -> Result<Keymap, ConfigError> {
+        Keymap::from_config(&self.keys).map_err(ConfigError::Keys)
+    }
+
+    /// The selected color theme
+    pub fn theme(&self) -> Result<Theme, ConfigError> {
+        Theme::from_config(&self.theme, &self.themes).map_err(ConfigError::Theme)
+    }
+
+    /// Apply command line flags, which take precedence over the file
+    pub fn with_overrides(mut self, overrides: &ConfigOverrides) -> Self {
+        self.ascii_icons |= overrides.ascii_icons;
+        self.watchdog |= overrides.watchdog;
+        self.auto_band_steer |= overrides.auto_band_steer;
+        self.roaming |= overrides.roaming;
+        if overrides.low_signal_threshold.is_some() {
+            self.low_signal_threshold = overrides.low_signal_threshold;
+        }
+        if let Some(secs) = overrides.low_signal_secs {
+            self.low_signal_secs = secs;
+        }
+        if let Some(theme) = &overrides.theme {
+            self.theme = theme.clone();
+        }
+        self
+    }
+}
+
+/// Settings given on the command line, re-applied on every config reload
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    pub ascii_icons: bool,
+    pub low_signal_threshold: Option<u8>,
+    pub low_signal_secs: Option<u64>,
+    pub theme: Option<String>,
+    pub watchdog: bool,
+    pub auto_band_steer: bool,
+    pub roaming: bool,
+}
+
+pub const DEFAULT_THEME: &str = "dark";
+
+// Low-signal alert
+pub const DEFAULT_LOW_SIGNAL_SECS: u64 = 30;
+
+// Band steering
+pub const BAND_STEERING_MIN_SIGNAL: u8 = 60;
+pub const BAND_STEERING_COOLDOWN_SECS: u64 = 120;
+
+// Reconnect watchdog: the delay doubles after every failed attempt
+pub const WATCHDOG_INITIAL_BACKOFF_SECS: u64 = 2;
+pub const WATCHDOG_MAX_BACKOFF_SECS: u64 = 120;
+pub const WATCHDOG_MAX_ATTEMPTS: u32 = 10;
+
+// Roaming: a preferred network must beat the connected one by MARGIN points
+// for HOLD seconds, and switches are at least COOLDOWN seconds apart
+pub const ROAM_SIGNAL_MARGIN: u8 = 20;
+pub const ROAM_HOLD_SECS: u64 = 20;
+pub const ROAM_COOLDOWN_SECS: u64 = 120;
+
+// Guest network: the hotspot under a throwaway SSID and passphrase
+pub const DEFAULT_GUEST_TTL_MINS: u64 = 60;
+pub const GUEST_SSID_PREFIX: &str = "wifui-guest";
+pub const GUEST_PASSPHRASE_LEN: usize = 12;
+
+// Interface statistics: frame counters polled every INTERVAL, charted over
+// the last HISTORY_LEN polls
+pub const STATS_INTERVAL_SECS: u64 = 1;
+pub const FRAME_HISTORY_LEN: usize = 40;
+
+// Signal history sparkline
+pub const SIGNAL_HISTORY_LEN: usize = 40;
+pub const SPARKLINE_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
 // Loading animation frames
 pub const LOADING_CHARS: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
@@ -34,7 +361,18 @@ pub mod icons {
         pub const CONNECTED: &str = " 󰖩"; // nf-md-wifi_check
         pub const AUTO_ON: &str = "󰁪"; // nf-md-bell
         pub const AUTO_OFF: &str = "󱧧"; // nf-md-bell_off
+        pub const MARKED: &str = "󰃀"; // nf-md-bookmark
+        pub const PREFERRED: &str = ""; // nf-fa-star
+        pub const MISMATCH: &str = ""; // nf-fa-warning
+        pub const AP_COUNT: &str = "×";
+        pub const SUSPICIOUS: &str = "󰗹"; // nf-md-incognito
+        pub const ACCEPTED_OPEN: &str = ""; // nf-fa-check
+        pub const SUCCESS: &str = "✓";
+        pub const FAILURE: &str = "✗";
+        pub const GENERATION: &str = "󰖩"; // nf-md-wifi
         pub const HIGHLIGHT: &str = "  "; // Two spaces for alignment
+        pub const SIGNAL_BARS: [char; 4] = ['▂', '▄', '▆', '█'];
+        pub const SIGNAL_EMPTY: char = ' ';
         // UI symbols for help bar and popups
         pub const ENTER: &str = "󰌑"; // nf-md-keyboard_return
         pub const TAB_NEXT: &str = "⇥ / ↓";
@@ -55,7 +393,18 @@ pub mod icons {
         pub const CONNECTED: &str = " <-";
         pub const AUTO_ON: &str = "(A)";
         pub const AUTO_OFF: &str = "(M)";
+        pub const MARKED: &str = "[+]";
+        pub const PREFERRED: &str = "#";
+        pub const MISMATCH: &str = "(!)";
+        pub const SUSPICIOUS: &str = "(?)";
+        pub const ACCEPTED_OPEN: &str = "(ok)";
+        pub const SUCCESS: &str = "OK";
+        pub const FAILURE: &str = "ERR";
+        pub const AP_COUNT: &str = "x";
+        pub const GENERATION: &str = "W";
         pub const HIGHLIGHT: &str = "> ";
+        pub const SIGNAL_BARS: [char; 4] = ['|'; 4];
+        pub const SIGNAL_EMPTY: char = '.';
         // UI symbols for help bar and popups
         pub const ENTER: &str = "Enter";
         pub const TAB_NEXT: &str = "Tab/Down";
@@ -71,18 +420,13 @@ pub mod icons {
 }
 
 /// Icon set to use based on configuration
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum IconSet {
+    #[default]
     Nerd,
     Ascii,
 }
 
-impl Default for IconSet {
-    fn default() -> Self {
-        IconSet::Nerd
-    }
-}
-
 impl IconSet {
     pub fn saved(&self) -> &'static str {
         match self {
@@ -126,6 +470,27 @@ impl IconSet {
         }
     }
 
+    pub fn marked(&self) -> &'static str {
+        match self {
+            IconSet::Nerd => icons::nerd::MARKED,
+            IconSet::Ascii => icons::ascii::MARKED,
+        }
+    }
+
+    pub fn ap_count(&self) -> &'static str {
+        match self {
+            IconSet::Nerd => icons::nerd::AP_COUNT,
+            IconSet::Ascii => icons::ascii::AP_COUNT,
+        }
+    }
+
+    pub fn generation(&self) -> &'static str {
+        match self {
+            IconSet::Nerd => icons::nerd::GENERATION,
+            IconSet::Ascii => icons::ascii::GENERATION,
+        }
+    }
+
     pub fn highlight(&self) -> &'static str {
         match self {
             IconSet::Nerd => icons::nerd::HIGHLIGHT,
@@ -133,6 +498,60 @@ impl IconSet {
         }
     }
 
+    pub fn preferred(&self) -> &'static str {
+        match self {
+            IconSet::Nerd => icons::nerd::PREFERRED,
+            IconSet::Ascii => icons::ascii::PREFERRED,
+        }
+    }
+
+    /// Saved profile no longer matches the network's security
+    pub fn mismatch(&self) -> &'static str {
+        match self {
+            IconSet::Nerd => icons::nerd::MISMATCH,
+            IconSet::Ascii => icons::ascii::MISMATCH,
+        }
+    }
+
+    /// Network might be an evil twin
+    pub fn suspicious(&self) -> &'static str {
+        match self {
+            IconSet::Nerd => icons::nerd::SUSPICIOUS,
+            IconSet::Ascii => icons::ascii::SUSPICIOUS,
+        }
+    }
+
+    /// Open network joined without asking again
+    pub fn accepted_open(&self) -> &'static str {
+        match self {
+            IconSet::Nerd => icons::nerd::ACCEPTED_OPEN,
+            IconSet::Ascii => icons::ascii::ACCEPTED_OPEN,
+        }
+    }
+
+    /// Outcome mark of the status line
+    pub fn outcome(&self, ok: bool) -> &'static str {
+        match (self, ok) {
+            (IconSet::Nerd, true) => icons::nerd::SUCCESS,
+            (IconSet::Nerd, false) => icons::nerd::FAILURE,
+            (IconSet::Ascii, true) => icons::ascii::SUCCESS,
+            (IconSet::Ascii, false) => icons::ascii::FAILURE,
+        }
+    }
+
+    /// Four signal bars, filled up to the signal level
+    pub fn signal_bars(&self, signal: u8) -> String {
+        let (bars, empty) = match self {
+            IconSet::Nerd => (icons::nerd::SIGNAL_BARS, icons::nerd::SIGNAL_EMPTY),
+            IconSet::Ascii => (icons::ascii::SIGNAL_BARS, icons::ascii::SIGNAL_EMPTY),
+        };
+        let level = (signal as usize).div_ceil(25).min(bars.len());
+        bars.iter()
+            .enumerate()
+            .map(|(i, &bar)| if i < level { bar } else { empty })
+            .collect()
+    }
+
     pub fn enter(&self) -> &'static str {
         match self {
             IconSet::Nerd => icons::nerd::ENTER,
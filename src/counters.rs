@@ -0,0 +1,78 @@
+//! Frame rates of the connected interface
+//!
+//! The adapter only keeps running totals, so they are polled every second
+//! and the differences between polls give the TX/RX rates, retries and
+//! failures charted in the IP tab of the details pane.
+
+use crate::config;
+use crate::wifi::InterfaceStats;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Frames per second between two polls
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameRates {
+    pub tx: f64,
+    pub rx: f64,
+    pub retries: f64,
+    pub failed: f64,
+}
+
+impl FrameRates {
+    /// Share of transmitted frames that needed a retry
+    pub fn retry_percent(&self) -> Option<f64> {
+        (self.tx > 0.0).then(|| (self.retries / self.tx * 100.0).min(100.0))
+    }
+}
+
+/// Rates of the recent polls, oldest first
+#[derive(Debug, Default)]
+pub struct CounterHistory {
+    last: Option<(InterfaceStats, Instant)>,
+    rates: VecDeque<FrameRates>,
+}
+
+impl CounterHistory {
+    /// Add a poll; the first one only sets the baseline
+    pub fn record(&mut self, stats: InterfaceStats) {
+        let now = Instant::now();
+        if let Some((last, at)) = self.last.replace((stats, now)) {
+            let secs = now.duration_since(at).as_secs_f64();
+            // Counters restart when the adapter is reset or reconnects
+            let rate = |new: u64, old: u64| match new.checked_sub(old) {
+                Some(delta) if secs > 0.0 => Some(delta as f64 / secs),
+                _ => None,
+            };
+            let (Some(tx), Some(rx), Some(retries), Some(failed)) = (
+                rate(stats.tx_frames, last.tx_frames),
+                rate(stats.rx_frames, last.rx_frames),
+                rate(stats.retries, last.retries),
+                rate(stats.failed, last.failed),
+            ) else {
+                return;
+            };
+            if self.rates.len() >= config::FRAME_HISTORY_LEN {
+                self.rates.pop_front();
+            }
+            self.rates.push_back(FrameRates {
+                tx,
+                rx,
+                retries,
+                failed,
+            });
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn latest(&self) -> Option<&FrameRates> {
+        self.rates.back()
+    }
+
+    /// One series of the history, e.g. `|rates| rates.tx`
+    pub fn series(&self, field: impl Fn(&FrameRates) -> f64) -> Vec<f64> {
+        self.rates.iter().map(field).collect()
+    }
+}
@@ -0,0 +1,391 @@
+//! `wifui daemon`: a long-running WiFi service for thin clients
+//!
+//! The daemon keeps the WLAN handle and connection listener open and rescans
+//! on the auto-refresh interval, so clients read cached scan results instead
+//! of waiting for a scan. Requests are JSON-RPC 2.0 objects, one per line,
+//! over a unix socket on Linux and the `\\.\pipe\wifui` named pipe on Windows.
+//!
+//! Methods: `list`, `status`, `scan`, `connect` (`{"ssid": ...}`, saved
+//! profiles only), `disconnect`, `forget` (`{"ssid": ...}`) and `radio`
+//! (optional `{"on": bool}`).
+//!
+//! The CLI's verbs and the TUI's refreshes go through a running daemon and
+//! fall back to the WiFi API when there is none.
+
+use crate::config::Config;
+use crate::error::WifiError;
+use crate::wifi::{self, RadioState, WifiInfo};
+use color_eyre::eyre::{Result, bail, eyre};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// JSON-RPC 2.0 error codes; -32000 is the first of the server-defined range
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const WIFI_ERROR: i64 = -32000;
+
+/// Latest scan results, shared by the refresh threads and the clients
+type Cache = Arc<Mutex<Vec<WifiInfo>>>;
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl From<WifiError> for RpcError {
+    fn from(e: WifiError) -> Self {
+        Self {
+            code: WIFI_ERROR,
+            message: e.to_string(),
+        }
+    }
+}
+
+/// Serve clients until the process is killed
+pub fn run(config: &Config) -> Result<()> {
+    let listener = transport::Listener::bind()
+        .map_err(|e| eyre!("Can't listen on {}: {}", transport::endpoint(), e))?;
+    let scan_delay = Duration::from_millis(config.scan_delay_ms);
+    let cache: Cache = Arc::default();
+    refresh(&cache, Some(scan_delay));
+
+    // Connection changes and finished scans update the cache right away
+    let mut events = wifi::subscribe()?;
+    let events_cache = Arc::clone(&cache);
+    std::thread::spawn(move || {
+        while events.blocking_recv().is_some() {
+            refresh(&events_cache, None);
+        }
+    });
+
+    let interval = Duration::from_secs(config.auto_refresh_secs.max(1));
+    let scan_cache = Arc::clone(&cache);
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(interval);
+            refresh(&scan_cache, Some(scan_delay));
+        }
+    });
+
+    eprintln!("wifui daemon listening on {}", transport::endpoint());
+    loop {
+        match listener.accept() {
+            Ok(stream) => {
+                let cache = Arc::clone(&cache);
+                std::thread::spawn(move || serve(stream, &cache, scan_delay));
+            }
+            Err(e) => eprintln!("Accepting a client failed: {}", e),
+        }
+    }
+}
+
+/// Re-read the network list, scanning first when a scan delay is given
+fn refresh(cache: &Mutex<Vec<WifiInfo>>, scan_delay: Option<Duration>) {
+    if let Some(delay) = scan_delay {
+        let _ = wifi::scan_networks();
+        std::thread::sleep(delay);
+    }
+    match wifi::get_wifi_networks() {
+        Ok(networks) => {
+            if let Ok(mut cached) = cache.lock() {
+                *cached = networks;
+            }
+        }
+        Err(e) => eprintln!("Refreshing networks failed: {}", e),
+    }
+}
+
+/// Answer one client's requests until it hangs up
+fn serve(stream: transport::Stream, cache: &Mutex<Vec<WifiInfo>>, scan_delay: Duration) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (id, result) = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => (
+                request.id,
+                call(&request.method, &request.params, cache, scan_delay),
+            ),
+            Err(e) => (
+                Value::Null,
+                Err(RpcError {
+                    code: PARSE_ERROR,
+                    message: e.to_string(),
+                }),
+            ),
+        };
+        let response = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": e.code, "message": e.message },
+            }),
+        };
+        if writeln!(writer, "{}", response).is_err() {
+            break;
+        }
+    }
+}
+
+fn call(
+    method: &str,
+    params: &Value,
+    cache: &Mutex<Vec<WifiInfo>>,
+    scan_delay: Duration,
+) -> Result<Value, RpcError> {
+    let ssid = || {
+        params
+            .get("ssid")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError {
+                code: INVALID_PARAMS,
+                message: "Missing \"ssid\"".to_string(),
+            })
+    };
+    let networks = || cache.lock().map(|n| n.clone()).unwrap_or_default();
+
+    match method {
+        "list" => to_value(&networks()),
+        "status" => to_value(&networks().into_iter().find(|w| w.is_connected)),
+        "scan" => {
+            refresh(cache, Some(scan_delay));
+            to_value(&networks())
+        }
+        "connect" => {
+            wifi::connect_profile(ssid()?)?;
+            Ok(Value::Null)
+        }
+        "disconnect" => {
+            wifi::disconnect()?;
+            Ok(Value::Null)
+        }
+        "forget" => {
+            wifi::forget_network(ssid()?)?;
+            refresh(cache, None);
+            Ok(Value::Null)
+        }
+        "radio" => {
+            if let Some(on) = params.get("on").and_then(Value::as_bool) {
+                wifi::set_radio(on)?;
+            }
+            Ok(json!(radio_name(wifi::radio_state()?)))
+        }
+        _ => Err(RpcError {
+            code: METHOD_NOT_FOUND,
+            message: format!("Unknown method {}", method),
+        }),
+    }
+}
+
+fn radio_name(radio: RadioState) -> &'static str {
+    match radio {
+        RadioState::On => "on",
+        RadioState::Off => "off",
+        RadioState::HardwareOff => "hardware_off",
+    }
+}
+
+/// Read back the state `radio` answers with
+pub fn parse_radio(value: Value) -> Result<RadioState> {
+    [RadioState::On, RadioState::Off, RadioState::HardwareOff]
+        .into_iter()
+        .find(|&radio| value.as_str() == Some(radio_name(radio)))
+        .ok_or_else(|| eyre!("Unexpected radio state {}", value))
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError {
+        code: WIFI_ERROR,
+        message: e.to_string(),
+    })
+}
+
+/// Call a daemon method; `None` when no daemon is running
+pub fn request(method: &str, params: Value) -> Option<Result<Value>> {
+    let stream = transport::connect().ok()?;
+    Some(send(stream, method, params))
+}
+
+/// Call a daemon method, or run `local` when no daemon is running
+pub fn request_or<T: DeserializeOwned>(
+    method: &str,
+    params: Value,
+    local: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    match request(method, params) {
+        Some(result) => Ok(serde_json::from_value(result?)?),
+        None => local(),
+    }
+}
+
+/// The daemon's network list, rescanning first if `scan`; `None` when no
+/// daemon is running or it can't answer
+pub fn networks(scan: bool) -> Option<Vec<WifiInfo>> {
+    let result = request(if scan { "scan" } else { "list" }, Value::Null)?;
+    serde_json::from_value(result.ok()?).ok()
+}
+
+fn send(mut stream: transport::Stream, method: &str, params: Value) -> Result<Value> {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    writeln!(stream, "{}", request)?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    let mut response: Value = serde_json::from_str(&line)?;
+    if let Some(error) = response.get("error") {
+        bail!(
+            "{}",
+            error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("daemon request failed")
+        );
+    }
+    Ok(response["result"].take())
+}
+
+#[cfg(unix)]
+mod transport {
+    use crate::config;
+    use std::io;
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::PathBuf;
+
+    pub type Stream = UnixStream;
+
+    pub fn endpoint() -> String {
+        socket_path().display().to_string()
+    }
+
+    fn socket_path() -> PathBuf {
+        std::env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(config::data_dir)
+            .join(config::DAEMON_SOCKET)
+    }
+
+    pub struct Listener(UnixListener);
+
+    impl Listener {
+        pub fn bind() -> io::Result<Self> {
+            let path = socket_path();
+            if UnixStream::connect(&path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "another daemon is running",
+                ));
+            }
+            // Left behind by a daemon that was killed
+            let _ = std::fs::remove_file(&path);
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let listener = UnixListener::bind(&path)?;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+            Ok(Self(listener))
+        }
+
+        pub fn accept(&self) -> io::Result<Stream> {
+            self.0.accept().map(|(stream, _)| stream)
+        }
+    }
+
+    pub fn connect() -> io::Result<Stream> {
+        UnixStream::connect(socket_path())
+    }
+}
+
+#[cfg(windows)]
+mod transport {
+    use std::fs::{File, OpenOptions};
+    use std::io;
+    use std::os::windows::io::FromRawHandle;
+    use windows::{
+        Win32::{
+            Foundation::{CloseHandle, ERROR_PIPE_CONNECTED},
+            Storage::FileSystem::PIPE_ACCESS_DUPLEX,
+            System::Pipes::{
+                ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+            },
+        },
+        core::HSTRING,
+    };
+
+    pub type Stream = File;
+
+    const PIPE_NAME: &str = r"\\.\pipe\wifui";
+
+    const PIPE_BUFFER_SIZE: u32 = 64 * 1024;
+
+    pub fn endpoint() -> String {
+        PIPE_NAME.to_string()
+    }
+
+    /// Named pipes have no listening socket; each client gets a new instance
+    pub struct Listener;
+
+    impl Listener {
+        pub fn bind() -> io::Result<Self> {
+            if connect().is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "another daemon is running",
+                ));
+            }
+            Ok(Self)
+        }
+
+        pub fn accept(&self) -> io::Result<Stream> {
+            let handle = unsafe {
+                CreateNamedPipeW(
+                    &HSTRING::from(PIPE_NAME),
+                    PIPE_ACCESS_DUPLEX,
+                    PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                    PIPE_UNLIMITED_INSTANCES,
+                    PIPE_BUFFER_SIZE,
+                    PIPE_BUFFER_SIZE,
+                    0,
+                    None,
+                )
+            };
+            if handle.is_invalid() {
+                return Err(io::Error::last_os_error());
+            }
+            // A client that opened the pipe before this call is already connected
+            if let Err(e) = unsafe { ConnectNamedPipe(handle, None) }
+                && e.code() != ERROR_PIPE_CONNECTED.to_hresult()
+            {
+                unsafe {
+                    let _ = CloseHandle(handle);
+                }
+                return Err(io::Error::other(e.message()));
+            }
+            Ok(unsafe { File::from_raw_handle(handle.0) })
+        }
+    }
+
+    pub fn connect() -> io::Result<Stream> {
+        OpenOptions::new().read(true).write(true).open(PIPE_NAME)
+    }
+}
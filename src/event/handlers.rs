@@ -1,29 +1,167 @@
-use crate::app::AppState;
+use crate::action::{Action, Command};
+use crate::app::{
+    AppState, Confirmation, DetailsTab, OperationKind, OperationOutput, PasswordUse, PendingKeys,
+    ProfileEditor,
+};
 use crate::config;
 use crate::error::WifiError;
-use crate::wifi::{disconnect, get_connected_ssid, get_wifi_networks};
+use crate::event::tasks::{
+    can_start, leave_current_network, spawn_blocking_op, spawn_wifi_op, start_connection,
+};
+use crate::filter::ListFilter;
+use crate::guest::GuestNetwork;
+use crate::latency::{LatencyUpdate, PingTarget};
+use crate::logging;
+use crate::net::Connectivity;
+use crate::sort::SortMode;
+use crate::speedtest::{SpeedTestResult, SpeedTestUpdate};
+use crate::wifi::{
+    Band, EnterpriseCredentials, ProfileEdit, disconnect, edit_profile_xml, get_connected_ssid,
+    get_wifi_networks, security_family,
+};
 use color_eyre::eyre::eyre;
-use crossterm::event::{self, KeyEvent, KeyModifiers};
-use secrecy::SecretString;
-use std::time::Instant;
-use tokio::sync::mpsc;
+use crossterm::event::{self, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
+use secrecy::{ExposeSecret, SecretString};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
 
 /// Handle keyboard events for the QR code popup
 pub fn handle_qr_popup(key: KeyEvent, state: &mut AppState) -> bool {
     match key.code {
         event::KeyCode::Esc | event::KeyCode::Char('q') | event::KeyCode::Enter => {
-            state.ui.show_qr_popup = false;
-            state.ui.qr_code_lines.clear();
+            state.close_qr_popup();
         }
         event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            state.ui.show_qr_popup = false;
-            state.ui.qr_code_lines.clear();
+            state.close_qr_popup();
+        }
+        event::KeyCode::Char('p') if state.ui.qr_password.is_some() => {
+            state.ui.show_qr_password = !state.ui.show_qr_password;
+        }
+        event::KeyCode::Char('w') => {
+            let uri = crate::share::wifi_uri(
+                &state.ui.qr_ssid,
+                &state.ui.qr_auth,
+                state.ui.qr_password.as_ref(),
+            );
+            match crate::share::save_qr_files(&state.ui.share_dir, &state.ui.qr_ssid, &uri) {
+                Ok(path) => state.show_success(format!("Saved QR to {}", path.display())),
+                Err(e) => {
+                    state.show_error(format!("Failed to save QR code: {}", e));
+                }
+            }
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Handle keyboard events for the network comparison popup
+pub fn handle_compare_popup(key: KeyEvent, state: &mut AppState) -> bool {
+    match key.code {
+        event::KeyCode::Esc | event::KeyCode::Char('q') | event::KeyCode::Enter => {
+            state.ui.show_compare_popup = false;
+        }
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            state.ui.show_compare_popup = false;
         }
         _ => {}
     }
     false
 }
 
+/// Security types offered by the manual add popup
+const MANUAL_SECURITY_OPTIONS: [&str; 7] = [
+    "WPA2-Personal",
+    "WPA3-Personal",
+    "WPA2-Enterprise",
+    "WPA3-Enterprise",
+    "Open",
+    "WPA-Personal",
+    "WEP",
+];
+
+/// Open the manual add popup for a hidden network, so only its SSID and
+/// password need typing
+fn open_hidden_network_popup(state: &mut AppState, wifi: &crate::wifi::WifiInfo) {
+    state.inputs.clear_manual();
+    state.inputs.manual_security = manual_security(&wifi.authentication).to_string();
+    state.inputs.manual_hidden = true;
+    state.ui.show_manual_add_popup = true;
+}
+
+/// The manual add popup's security option for a scanned authentication
+fn manual_security(authentication: &str) -> &'static str {
+    match authentication {
+        "Open" => "Open",
+        "WPA-PSK" => "WPA-Personal",
+        "WPA3-SAE" => "WPA3-Personal",
+        "WPA" | "WPA2" => "WPA2-Enterprise",
+        "WPA3" | "WPA3ENT" | "WPA3ENT192" => "WPA3-Enterprise",
+        "Shared" | "WEP" => "WEP",
+        _ => "WPA2-Personal",
+    }
+}
+
+/// Replace a saved profile whose security the network no longer offers with
+/// one for its current security, keeping the saved passphrase when possible
+fn recreate_profile(state: &mut AppState, wifi: &crate::wifi::WifiInfo) {
+    let is_open = wifi.authentication == "Open";
+    let is_enterprise = security_family(&wifi.authentication).ends_with("Enterprise");
+    let ssid = wifi.ssid.clone();
+    let authentication = wifi.authentication.clone();
+    let encryption = wifi.encryption.clone();
+    let options = state.profile_options(false, false);
+    start_connection(state, wifi.ssid.clone(), move || {
+        let password = crate::wifi::get_wifi_password(&ssid).unwrap_or(None);
+        // Nothing to carry over: drop the profile and ask for the credentials
+        if !is_open && (password.is_none() || is_enterprise) {
+            crate::wifi::forget_network(&ssid)?;
+            return Ok(OperationOutput::NeedsCredentials);
+        }
+        leave_current_network();
+        crate::wifi::forget_network(&ssid)?;
+        match password {
+            Some(password) if !is_open => crate::wifi::connect_with_password(
+                &ssid,
+                &password,
+                &authentication,
+                &encryption,
+                options,
+            ),
+            _ => crate::wifi::connect_open(&ssid, options),
+        }
+        .map(|()| OperationOutput::Done)
+    });
+}
+
+/// Open the manual add popup for a network whose profile was dropped, with
+/// its SSID and security filled in
+pub fn open_credentials_popup(state: &mut AppState, ssid: &str, authentication: &str) {
+    state.inputs.clear_manual();
+    state.inputs.manual_security = manual_security(authentication).to_string();
+    state.inputs.manual_ssid_input.value = ssid.to_string();
+    state.inputs.manual_ssid_input.move_end();
+    state.inputs.manual_input_field = 1;
+    state.ui.show_manual_add_popup = true;
+}
+
+/// Open the manual add popup filled in from `WIFI:` URI credentials, with
+/// the Connect button focused
+fn open_join_popup(state: &mut AppState, credentials: crate::share::WifiCredentials) {
+    state.inputs.clear_manual();
+    state.inputs.manual_security = credentials.security().to_string();
+    state.inputs.manual_hidden = credentials.hidden;
+    state.inputs.manual_ssid_input.value = credentials.ssid;
+    state.inputs.manual_ssid_input.move_end();
+    if let Some(password) = credentials.password {
+        state.inputs.manual_password_input.value = password.expose_secret().to_string();
+        state.inputs.manual_password_input.move_end();
+    }
+    state.inputs.manual_input_field = 4;
+    state.ui.show_manual_add_popup = true;
+}
+
 /// Handle keyboard events for the manual add network popup
 pub fn handle_manual_add_popup(key: KeyEvent, state: &mut AppState) -> bool {
     match key.code {
@@ -36,65 +174,67 @@ pub fn handle_manual_add_popup(key: KeyEvent, state: &mut AppState) -> bool {
             state.ui.show_manual_add_popup = false;
             state.inputs.clear_manual();
         }
-        event::KeyCode::Tab | event::KeyCode::Down => {
-            state.inputs.manual_input_field = (state.inputs.manual_input_field + 1) % 6;
-        }
-        event::KeyCode::BackTab | event::KeyCode::Up => {
-            if state.inputs.manual_input_field == 0 {
-                state.inputs.manual_input_field = 5;
-            } else {
-                state.inputs.manual_input_field -= 1;
-            }
-        }
+        event::KeyCode::Tab | event::KeyCode::Down => state.inputs.cycle_manual_field(true),
+        event::KeyCode::BackTab | event::KeyCode::Up => state.inputs.cycle_manual_field(false),
         event::KeyCode::Enter => {
             match state.inputs.manual_input_field {
                 3 => state.inputs.manual_hidden = !state.inputs.manual_hidden,
-                4 => {
+                9 => state.inputs.manual_random_mac = !state.inputs.manual_random_mac,
+                6 => state.inputs.manual_eap_method = state.inputs.manual_eap_method.toggle(),
+                8 => state.inputs.manual_validate_ca = !state.inputs.manual_validate_ca,
+                4 if state.inputs.manual_is_enterprise()
+                    && state.inputs.manual_identity_input.value.is_empty() =>
+                {
+                    state.show_error("Enter an identity (username)".to_string());
+                    state.inputs.manual_input_field = 7;
+                }
+                4
                     // Connect
-                    if !state.inputs.manual_ssid_input.value.is_empty() {
-                        state.connection.is_connecting = true;
-                        state.connection.target_ssid =
-                            Some(state.inputs.manual_ssid_input.value.clone());
-                        state.connection.connection_start_time = Some(Instant::now());
+                    if !state.inputs.manual_ssid_input.value.is_empty() => {
                         let ssid = state.inputs.manual_ssid_input.value.clone();
                         let password =
                             SecretString::from(state.inputs.manual_password_input.value.clone());
                         let security = state.inputs.manual_security.clone();
-                        let hidden = state.inputs.manual_hidden;
+                        let options = state.profile_options(
+                            state.inputs.manual_hidden,
+                            state.inputs.manual_random_mac,
+                        );
+                        let enterprise = EnterpriseCredentials {
+                            method: state.inputs.manual_eap_method,
+                            identity: state.inputs.manual_identity_input.value.clone(),
+                            password: password.clone(),
+                            validate_ca: state.inputs.manual_validate_ca,
+                        };
 
-                        let (tx, rx) = mpsc::channel(1);
-                        state.connection.connection_result_rx = Some(rx);
-
-                        tokio::spawn(async move {
-                            if get_connected_ssid().unwrap_or(None).is_some() {
-                                let _ = tokio::task::spawn_blocking(crate::wifi::disconnect_and_wait).await;
-                            }
-                            let result = tokio::task::spawn_blocking(move || {
-                                if security == "Open" {
-                                    crate::wifi::connect_open(&ssid, hidden)
+                        start_connection(state, ssid.clone(), move || {
+                            leave_current_network();
+                            if security == "Open" {
+                                crate::wifi::connect_open(&ssid, options)
+                            } else if security.ends_with("-Enterprise") {
+                                let auth = if security == "WPA3-Enterprise" {
+                                    "WPA3ENT"
                                 } else {
-                                    // Map security string to auth/cipher
-                                    let (auth, cipher) = match security.as_str() {
-                                        "WPA3-Personal" => ("WPA3-SAE", "AES"),
-                                        "WPA2-Personal" => ("WPA2-PSK", "AES"),
-                                        "WPA-Personal" => ("WPA-PSK", "AES"),
-                                        "WEP" => ("Shared", "WEP"),
-                                        _ => ("WPA2-PSK", "AES"),
-                                    };
-                                    crate::wifi::connect_with_password(
-                                        &ssid, &password, auth, cipher, hidden,
-                                    )
-                                }
-                            })
-                            .await
-                            .unwrap_or_else(|e| Err(WifiError::Internal(e.to_string())));
-                            let _ = tx.send(result.map_err(|e: WifiError| e.into())).await;
+                                    "WPA2"
+                                };
+                                crate::wifi::connect_enterprise(&ssid, auth, &enterprise, options)
+                            } else {
+                                // Map security string to auth/cipher
+                                let (auth, cipher) = match security.as_str() {
+                                    "WPA3-Personal" => ("WPA3-SAE", "AES"),
+                                    "WPA2-Personal" => ("WPA2-PSK", "AES"),
+                                    "WPA-Personal" => ("WPA-PSK", "AES"),
+                                    "WEP" => ("Shared", "WEP"),
+                                    _ => ("WPA2-PSK", "AES"),
+                                };
+                                crate::wifi::connect_with_password(
+                                    &ssid, &password, auth, cipher, options,
+                                )
+                            }
                         });
 
                         state.ui.show_manual_add_popup = false;
                         state.inputs.clear_manual();
                     }
-                }
                 5 => {
                     // Cancel
                     state.ui.show_manual_add_popup = false;
@@ -106,19 +246,29 @@ pub fn handle_manual_add_popup(key: KeyEvent, state: &mut AppState) -> bool {
         event::KeyCode::Char(' ') if state.inputs.manual_input_field == 3 => {
             state.inputs.manual_hidden = !state.inputs.manual_hidden;
         }
+        event::KeyCode::Char(' ') if state.inputs.manual_input_field == 9 => {
+            state.inputs.manual_random_mac = !state.inputs.manual_random_mac;
+        }
+        event::KeyCode::Char(' ') if state.inputs.manual_input_field == 8 => {
+            state.inputs.manual_validate_ca = !state.inputs.manual_validate_ca;
+        }
+        event::KeyCode::Char('h' | 'j' | 'k' | 'l')
+        | event::KeyCode::Left
+        | event::KeyCode::Right
+            if state.inputs.manual_input_field == 6 =>
+        {
+            state.inputs.manual_eap_method = state.inputs.manual_eap_method.toggle();
+        }
+        _ if state.inputs.manual_input_field == 7 => {
+            state.inputs.manual_identity_input.handle_key(&key);
+        }
         event::KeyCode::Char(c) => {
             match state.inputs.manual_input_field {
                 0 => state.inputs.manual_ssid_input.insert(c),
                 1 => state.inputs.manual_password_input.insert(c),
                 2 => {
                     // Handle h/j/k/l for Security field
-                    let options = [
-                        "WPA2-Personal",
-                        "WPA3-Personal",
-                        "Open",
-                        "WPA-Personal",
-                        "WEP",
-                    ];
+                    let options = MANUAL_SECURITY_OPTIONS;
                     let current_idx = options
                         .iter()
                         .position(|&s| s == state.inputs.manual_security)
@@ -173,13 +323,7 @@ pub fn handle_manual_add_popup(key: KeyEvent, state: &mut AppState) -> bool {
             0 => state.inputs.manual_ssid_input.move_left(),
             1 => state.inputs.manual_password_input.move_left(),
             2 => {
-                let options = [
-                    "WPA2-Personal",
-                    "WPA3-Personal",
-                    "Open",
-                    "WPA-Personal",
-                    "WEP",
-                ];
+                let options = MANUAL_SECURITY_OPTIONS;
                 let current_idx = options
                     .iter()
                     .position(|&s| s == state.inputs.manual_security)
@@ -208,13 +352,7 @@ pub fn handle_manual_add_popup(key: KeyEvent, state: &mut AppState) -> bool {
             0 => state.inputs.manual_ssid_input.move_right(),
             1 => state.inputs.manual_password_input.move_right(),
             2 => {
-                let options = [
-                    "WPA2-Personal",
-                    "WPA3-Personal",
-                    "Open",
-                    "WPA-Personal",
-                    "WEP",
-                ];
+                let options = MANUAL_SECURITY_OPTIONS;
                 let current_idx = options
                     .iter()
                     .position(|&s| s == state.inputs.manual_security)
@@ -244,55 +382,54 @@ pub fn handle_password_popup(key: KeyEvent, state: &mut AppState) -> bool {
     match key.code {
         event::KeyCode::Enter => {
             if let Some(ssid) = state.connection.connecting_to_ssid.take() {
-                state.connection.is_connecting = true;
-                state.connection.target_ssid = Some(ssid.clone());
-                state.connection.connection_start_time = Some(Instant::now());
                 let password = SecretString::from(state.inputs.password_input.value.clone());
-                let (tx, rx) = mpsc::channel(1);
-                state.connection.connection_result_rx = Some(rx);
-
+                let auth = state.connection.connecting_to_auth.clone();
                 let wifi_info = state
                     .network
                     .wifi_list
                     .iter()
-                    .find(|w| w.ssid == ssid)
+                    .find(|w| w.ssid == ssid && Some(&w.authentication) == auth.as_ref())
+                    .or_else(|| state.network.wifi_list.iter().find(|w| w.ssid == ssid))
                     .cloned();
+                let options = state.profile_options(false, false);
 
-                tokio::spawn(async move {
-                    if get_connected_ssid().unwrap_or(None).is_some() {
-                        let _ = tokio::task::spawn_blocking(crate::wifi::disconnect_and_wait).await;
+                start_connection(state, ssid.clone(), move || {
+                    leave_current_network();
+                    if let Some(info) = wifi_info {
+                        crate::wifi::connect_with_password(
+                            &ssid,
+                            &password,
+                            &info.authentication,
+                            &info.encryption,
+                            options,
+                        )
+                    } else {
+                        crate::wifi::connect_with_password(
+                            &ssid, &password, "WPA2-PSK", "AES", options,
+                        )
                     }
-                    let result = tokio::task::spawn_blocking(move || {
-                        if let Some(info) = wifi_info {
-                            crate::wifi::connect_with_password(
-                                &ssid,
-                                &password,
-                                &info.authentication,
-                                &info.encryption,
-                                false,
-                            )
-                        } else {
-                            crate::wifi::connect_with_password(
-                                &ssid, &password, "WPA2-PSK", "AES", false,
-                            )
-                        }
-                    })
-                    .await
-                    .unwrap_or_else(|e| Err(WifiError::Internal(e.to_string())));
-                    let _ = tx.send(result.map_err(|e: WifiError| e.into())).await;
                 });
             }
             state.ui.show_password_popup = false;
+            state.ui.password_error = None;
+            state.ui.show_password_text = false;
             state.inputs.password_input.clear();
         }
         event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
             state.ui.show_password_popup = false;
+            state.ui.password_error = None;
+            state.ui.show_password_text = false;
             state.inputs.password_input.clear();
         }
         event::KeyCode::Esc => {
             state.ui.show_password_popup = false;
+            state.ui.password_error = None;
+            state.ui.show_password_text = false;
             state.inputs.password_input.clear();
         }
+        event::KeyCode::F(2) => {
+            state.ui.show_password_text = !state.ui.show_password_text;
+        }
         _ => {
             // Use the input helper for common key handling
             state.inputs.password_input.handle_key(&key);
@@ -301,287 +438,1807 @@ pub fn handle_password_popup(key: KeyEvent, state: &mut AppState) -> bool {
     false
 }
 
-/// Handle keyboard events for the search mode
-pub fn handle_search_mode(key: KeyEvent, state: &mut AppState) -> bool {
+/// Handle keyboard events for the confirm popup
+pub fn handle_confirm_popup(key: KeyEvent, state: &mut AppState) -> bool {
     match key.code {
-        event::KeyCode::Esc => {
-            state.ui.is_searching = false;
+        event::KeyCode::Char('y') | event::KeyCode::Enter => {
+            if let Some(confirmation) = state.ui.confirmation.take() {
+                state.ui.confirmed = true;
+                let quit = execute_command(confirmation.command, state);
+                state.ui.confirmed = false;
+                return quit;
+            }
+        }
+        event::KeyCode::Char('a')
+            if state
+                .ui
+                .confirmation
+                .as_ref()
+                .is_some_and(|c| c.accept_open.is_some()) =>
+        {
+            if let Some(confirmation) = state.ui.confirmation.take() {
+                if let Some(ssid) = confirmation.accept_open {
+                    state.network.accepted_open.insert(ssid);
+                    state.network.list_rows = None;
+                }
+                state.ui.confirmed = true;
+                let quit = execute_command(confirmation.command, state);
+                state.ui.confirmed = false;
+                return quit;
+            }
+        }
+        event::KeyCode::Char('n') | event::KeyCode::Char('q') | event::KeyCode::Esc => {
+            state.ui.confirmation = None;
         }
         event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            state.ui.is_searching = false;
+            state.ui.confirmation = None;
         }
-        event::KeyCode::Enter => {
-            state.ui.is_searching = false;
-            if !state.network.filtered_wifi_list.is_empty() {
-                state.ui.l_state.select(Some(0));
+        _ => {}
+    }
+    false
+}
+
+/// Delete a network's saved profile, keeping an export of it so undo can
+/// restore it
+fn forget_network(state: &mut AppState, wifi: &crate::wifi::WifiInfo) {
+    let ssid = wifi.ssid.clone();
+    // The profile of this entry, not another one for the same SSID
+    let profile = wifi.profile().to_string();
+    if !can_start(state, OperationKind::Forget, &ssid) {
+        return;
+    }
+    // Without its profile the network can't be reconnected to
+    state.watchdog.expect_disconnect |= state.network.connected_ssid.as_ref() == Some(&ssid);
+    spawn_wifi_op(state, OperationKind::Forget, ssid.clone(), move || {
+        let xml = match crate::wifi::export_profile(&profile) {
+            Ok(xml) => Some(SecretString::from(xml)),
+            Err(e) => {
+                // Enterprise profiles, for one, can't be exported on Linux
+                logging::warn(format!("Can't keep {} for undo: {}", ssid, e));
+                None
             }
+        };
+        crate::wifi::forget_network(&profile)?;
+        Ok(OperationOutput::Forgotten(xml))
+    });
+}
+
+/// Handle keyboard events for the share passphrase popup (unsaved networks)
+pub fn handle_share_password_popup(key: KeyEvent, state: &mut AppState) -> bool {
+    match key.code {
+        event::KeyCode::Enter => {
+            let value = std::mem::take(&mut state.inputs.share_password_input.value);
+            state.inputs.share_password_input.clear();
+            state.ui.show_share_password_popup = false;
+            let password = (!value.is_empty()).then(|| SecretString::from(value));
+            let ssid = std::mem::take(&mut state.ui.qr_ssid);
+            let auth = std::mem::take(&mut state.ui.qr_auth);
+            open_qr_popup(state, ssid, auth, password);
         }
-        event::KeyCode::Char(c) => {
-            state.inputs.search_input.insert(c);
-            state.update_filtered_list();
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            state.ui.show_share_password_popup = false;
+            state.inputs.share_password_input.clear();
+        }
+        event::KeyCode::Esc => {
+            state.ui.show_share_password_popup = false;
+            state.inputs.share_password_input.clear();
         }
         _ => {
-            if state.inputs.search_input.handle_key(&key) {
-                state.update_filtered_list();
-            }
+            state.inputs.share_password_input.handle_key(&key);
         }
     }
     false
 }
 
-/// Handle keyboard events for the main view (network list)
-pub fn handle_main_view(key: KeyEvent, state: &mut AppState) -> bool {
-    use std::time::Duration;
-
+/// Handle keyboard events for the survey location label popup
+pub fn handle_survey_label_popup(key: KeyEvent, state: &mut AppState) -> bool {
     match key.code {
-        event::KeyCode::Char('/') => {
-            state.ui.is_searching = true;
+        event::KeyCode::Enter => {
+            let label = state.inputs.survey_label_input.value.trim().to_string();
+            if !label.is_empty() {
+                state.tag_survey_location(&label);
+            }
+            state.ui.show_survey_label_popup = false;
+            state.inputs.survey_label_input.clear();
         }
-        event::KeyCode::Char('n') => {
-            state.ui.show_manual_add_popup = true;
-            state.inputs.manual_input_field = 0;
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            state.ui.show_survey_label_popup = false;
+            state.inputs.survey_label_input.clear();
         }
         event::KeyCode::Esc => {
-            if state.connection.is_connecting {
-                state.connection.is_connecting = false;
-                state.connection.target_ssid = None;
-                state.connection.connection_result_rx = None;
-            } else if !state.inputs.search_input.value.is_empty() {
-                state.inputs.search_input.clear();
-                state.update_filtered_list();
-            }
+            state.ui.show_survey_label_popup = false;
+            state.inputs.survey_label_input.clear();
+        }
+        _ => {
+            state.inputs.survey_label_input.handle_key(&key);
         }
-        event::KeyCode::Char('q') => return true,
+    }
+    false
+}
+
+/// Handle keyboard events for the saved profiles popup
+pub fn handle_profiles_popup(key: KeyEvent, state: &mut AppState) -> bool {
+    let last = state.profiles.list.len().saturating_sub(1);
+    let selected = state.profiles.list_state.selected().unwrap_or(0);
+    match key.code {
+        event::KeyCode::Esc | event::KeyCode::Char('q') => state.ui.show_profiles_popup = false,
         event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            if !state.inputs.search_input.value.is_empty() {
-                state.inputs.search_input.clear();
-                state.update_filtered_list();
-            }
+            state.ui.show_profiles_popup = false;
         }
-        event::KeyCode::Char('j') | event::KeyCode::Down => state.next(),
-        event::KeyCode::Char('k') | event::KeyCode::Up => state.previous(),
-        event::KeyCode::Char('g') | event::KeyCode::Home => state.go_to_top(),
-        event::KeyCode::Char('G') | event::KeyCode::End => state.go_to_bottom(),
-        event::KeyCode::Enter => {
-            if let Some(selected) = state.ui.l_state.selected() {
-                if let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned() {
-                    let is_connected = if let Some(connected_ssid) = &state.network.connected_ssid {
-                        wifi.ssid == *connected_ssid
-                    } else {
-                        false
-                    };
+        event::KeyCode::Char('J') => move_profile(state, selected, selected + 1),
+        event::KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => {
+            move_profile(state, selected, selected + 1);
+        }
+        event::KeyCode::Char('K') if selected > 0 => move_profile(state, selected, selected - 1),
+        event::KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) && selected > 0 => {
+            move_profile(state, selected, selected - 1);
+        }
+        event::KeyCode::Char('j') | event::KeyCode::Down => {
+            state
+                .profiles
+                .list_state
+                .select(Some((selected + 1).min(last)));
+        }
+        event::KeyCode::Char('k') | event::KeyCode::Up => {
+            state
+                .profiles
+                .list_state
+                .select(Some(selected.saturating_sub(1)));
+        }
+        event::KeyCode::Char('g') | event::KeyCode::Home => {
+            state.profiles.list_state.select(Some(0));
+        }
+        event::KeyCode::Char('G') | event::KeyCode::End => {
+            state.profiles.list_state.select(Some(last));
+        }
+        _ => {}
+    }
+    false
+}
 
-                    if is_connected {
-                        let (tx, rx) = mpsc::channel(1);
-                        state.connection.connection_result_rx = Some(rx);
-                        tokio::spawn(async move {
-                            let result = tokio::task::spawn_blocking(disconnect).await;
-                            let result = match result {
-                                Ok(inner) => inner.map_err(|e: WifiError| e.into()),
-                                Err(e) => Err(eyre!(e.to_string())),
-                            };
-                            let _ = tx.send(result).await;
-                        });
-                    } else if wifi.authentication != "Open" {
-                        // Check if profile exists
-                        let saved_profiles = crate::wifi::get_saved_profiles().unwrap_or_default();
-                        if saved_profiles.contains(&wifi.ssid) {
-                            state.connection.is_connecting = true;
-                            state.connection.target_ssid = Some(wifi.ssid.clone());
-                            state.connection.connection_start_time = Some(Instant::now());
-                            let ssid = wifi.ssid.clone();
-                            let (tx, rx) = mpsc::channel(1);
-                            state.connection.connection_result_rx = Some(rx);
-
-                            tokio::spawn(async move {
-                                if get_connected_ssid().unwrap_or(None).is_some() {
-                                    let _ = tokio::task::spawn_blocking(crate::wifi::disconnect_and_wait).await;
-                                }
-                                let result = tokio::task::spawn_blocking(move || {
-                                    crate::wifi::connect_profile(&ssid)
-                                })
-                                .await;
-                                let result = match result {
-                                    Ok(inner) => inner.map_err(|e: WifiError| e.into()),
-                                    Err(e) => Err(eyre!(e.to_string())),
-                                };
-                                let _ = tx.send(result).await;
-                            });
-                        } else {
-                            state.ui.show_password_popup = true;
-                            state.inputs.password_input.cursor = 0;
-                            state.connection.connecting_to_ssid = Some(wifi.ssid.clone());
-                        }
-                    } else {
-                        state.connection.is_connecting = true;
-                        state.connection.target_ssid = Some(wifi.ssid.clone());
-                        state.connection.connection_start_time = Some(Instant::now());
-                        let ssid = wifi.ssid.clone();
-                        let (tx, rx) = mpsc::channel(1);
-                        state.connection.connection_result_rx = Some(rx);
-
-                        tokio::spawn(async move {
-                            if get_connected_ssid().unwrap_or(None).is_some() {
-                                let _ = tokio::task::spawn_blocking(crate::wifi::disconnect_and_wait).await;
-                            }
-                            let result = tokio::task::spawn_blocking(move || {
-                                crate::wifi::connect_open(&ssid, false)
-                            })
-                            .await;
-                            let result = match result {
-                                Ok(inner) => inner.map_err(|e: WifiError| e.into()),
-                                Err(e) => Err(eyre!(e.to_string())),
-                            };
-                            let _ = tx.send(result).await;
-                        });
-                    }
-                }
-            }
+/// Copy text to the clipboard and confirm with a toast naming `what` was copied
+pub fn copy_to_clipboard(state: &mut AppState, text: SecretString, what: String) {
+    let clear_secs = state.config.current.clipboard_clear_secs;
+    let clear_at = clear_secs.map(|secs| Instant::now() + std::time::Duration::from_secs(secs));
+    match state.clipboard.copy(text, clear_at) {
+        Ok(()) => match clear_secs {
+            Some(secs) => state.show_success(format!("Copied {} (clears in {}s)", what, secs)),
+            None => state.show_success(format!("Copied {}", what)),
+        },
+        Err(e) => state.show_error(format!("Clipboard unavailable: {}", e)),
+    }
+}
+
+/// Move a profile in the preference order, then reload the order the system reports
+fn move_profile(state: &mut AppState, from: usize, to: usize) {
+    if state.profiles.is_busy
+        || from >= state.profiles.list.len()
+        || to >= state.profiles.list.len()
+    {
+        return;
+    }
+
+    // Show the move right away; the reloaded list replaces it when the call returns
+    let ssid = state.profiles.list[from].clone();
+    state.profiles.list.swap(from, to);
+    state.profiles.list_state.select(Some(to));
+    state.profiles.is_busy = true;
+    spawn_profiles_task(state, move || {
+        crate::wifi::set_profile_position(&ssid, to as u32)?;
+        crate::wifi::get_saved_profiles()
+    });
+}
+
+fn spawn_profiles_task(
+    state: &mut AppState,
+    task: impl FnOnce() -> crate::error::WifiResult<Vec<String>> + Send + 'static,
+) {
+    state.profiles.list_rx = Some(spawn_blocking_op(task));
+}
+
+/// Handle keyboard events for the mobile hotspot popup
+pub fn handle_hotspot_popup(key: KeyEvent, state: &mut AppState) -> bool {
+    match key.code {
+        event::KeyCode::Esc => close_hotspot_popup(state),
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            close_hotspot_popup(state);
         }
-        event::KeyCode::Char('r') => {
-            // Debounce rapid 'r' key presses
-            if state.refresh.last_manual_refresh.elapsed()
-                < Duration::from_millis(config::MANUAL_REFRESH_DEBOUNCE_MS)
-            {
-                return false;
-            }
-            state.refresh.last_manual_refresh = Instant::now();
-            state.refresh.is_refreshing_networks = true;
-            let (tx, rx) = mpsc::channel(1);
-            state.refresh.network_update_rx = Some(rx);
-
-            tokio::spawn(async move {
-                let result = tokio::task::spawn_blocking(|| {
-                    let _ = crate::wifi::scan_networks();
-                    std::thread::sleep(Duration::from_millis(config::SCAN_DELAY_MS));
-                    let networks = get_wifi_networks()?;
-                    let connected = get_connected_ssid()?;
-                    Ok((networks, connected))
-                })
-                .await;
-                let result = match result {
-                    Ok(inner) => inner.map_err(|e: WifiError| e.into()),
-                    Err(e) => Err(eyre!(e.to_string())),
-                };
-                let _ = tx.send(result).await;
-            });
+        event::KeyCode::Tab | event::KeyCode::Down => {
+            state.hotspot.field = (state.hotspot.field + 1) % 3;
         }
-        event::KeyCode::Char('a') => {
-            if let Some(selected) = state.ui.l_state.selected() {
-                if let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned() {
-                    if wifi.is_saved {
-                        let ssid = wifi.ssid.clone();
-                        let auto_connect = !wifi.auto_connect;
-                        let (tx, rx) = mpsc::channel(1);
-                        state.connection.connection_result_rx = Some(rx);
-
-                        tokio::spawn(async move {
-                            let result = tokio::task::spawn_blocking(move || {
-                                crate::wifi::set_auto_connect(&ssid, auto_connect)
-                            })
-                            .await;
-                            let result = match result {
-                                Ok(inner) => inner.map_err(|e: WifiError| e.into()),
-                                Err(e) => Err(eyre!(e.to_string())),
-                            };
-                            let _ = tx.send(result).await;
-                        });
-                    }
-                }
-            }
+        event::KeyCode::BackTab | event::KeyCode::Up => {
+            state.hotspot.field = (state.hotspot.field + 2) % 3;
         }
-        event::KeyCode::Char('f') => {
-            if let Some(selected) = state.ui.l_state.selected() {
-                if let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned() {
-                    if wifi.is_saved {
-                        let ssid = wifi.ssid.clone();
-                        let (tx, rx) = mpsc::channel(1);
-                        state.connection.connection_result_rx = Some(rx);
-
-                        tokio::spawn(async move {
-                            let result = tokio::task::spawn_blocking(move || {
-                                crate::wifi::forget_network(&ssid)
-                            })
-                            .await;
-                            let result = match result {
-                                Ok(inner) => inner.map_err(|e: WifiError| e.into()),
-                                Err(e) => Err(eyre!(e.to_string())),
-                            };
-                            let _ = tx.send(result).await;
-                        });
-                    }
-                }
+        event::KeyCode::Enter => toggle_hotspot(state),
+        _ => match state.hotspot.field {
+            0 => {
+                state.inputs.hotspot_ssid_input.handle_key(&key);
             }
-        }
-        event::KeyCode::Char('s') => {
-            if let Some(selected) = state.ui.l_state.selected() {
-                if let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned() {
-                    if wifi.is_saved {
-                        let ssid = wifi.ssid.clone();
-                        let auth = wifi.authentication.clone();
-                        let password_result = crate::wifi::get_wifi_password(&ssid);
-
-                        match password_result {
-                            Ok(password_opt) => {
-                                let qr_lines =
-                                    generate_wifi_qr(&ssid, &auth, password_opt.as_ref());
-                                state.ui.qr_code_lines = qr_lines;
-                                state.ui.show_qr_popup = true;
-                            }
-                            Err(_) => {
-                                let qr_lines = generate_wifi_qr(&ssid, &auth, None);
-                                state.ui.qr_code_lines = qr_lines;
-                                state.ui.show_qr_popup = true;
-                            }
-                        }
-                    }
-                }
+            1 => {
+                state.inputs.hotspot_password_input.handle_key(&key);
             }
+            _ => {}
+        },
+    }
+    false
+}
+
+fn close_hotspot_popup(state: &mut AppState) {
+    state.ui.show_hotspot_popup = false;
+    state.inputs.hotspot_password_input.clear();
+}
+
+/// Handle keyboard events for the profile edit popup
+pub fn handle_profile_editor(key: KeyEvent, state: &mut AppState) -> bool {
+    let Some(editor) = state.profiles.editor.as_mut() else {
+        return false;
+    };
+    match key.code {
+        event::KeyCode::Esc => close_profile_editor(state),
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            close_profile_editor(state);
+        }
+        event::KeyCode::Tab | event::KeyCode::Down => {
+            editor.field = (editor.field + 1) % ProfileEditor::FIELDS;
+        }
+        event::KeyCode::BackTab | event::KeyCode::Up => {
+            editor.field = (editor.field + ProfileEditor::FIELDS - 1) % ProfileEditor::FIELDS;
+        }
+        event::KeyCode::Enter | event::KeyCode::Char(' ') if editor.field == 2 => {
+            editor.auto_connect = !editor.auto_connect;
+        }
+        event::KeyCode::Enter | event::KeyCode::Char(' ') if editor.field == 3 => {
+            editor.hidden = !editor.hidden;
+        }
+        event::KeyCode::Enter if editor.field == 4 => close_profile_editor(state),
+        event::KeyCode::Enter => save_profile_edit(state),
+        event::KeyCode::Char('h' | 'k') | event::KeyCode::Left if editor.field == 0 => {
+            editor.cycle_security(-1);
+        }
+        event::KeyCode::Char('l' | 'j') | event::KeyCode::Right if editor.field == 0 => {
+            editor.cycle_security(1);
+        }
+        _ if editor.field == 1 => {
+            state.inputs.profile_password_input.handle_key(&key);
         }
         _ => {}
     }
     false
 }
 
-/// Generate WiFi QR code in standard format: WIFI:S:ssid;T:auth;P:password;;
-fn generate_wifi_qr(ssid: &str, auth: &str, password: Option<&SecretString>) -> Vec<String> {
-    use qrcode::QrCode;
-    use qrcode::render::unicode;
-    use secrecy::ExposeSecret;
+fn close_profile_editor(state: &mut AppState) {
+    state.profiles.editor = None;
+    state.inputs.profile_password_input.clear();
+}
 
-    let auth_type = match auth {
-        "WPA3-SAE" | "WPA3" => "WPA",
-        "WPA2-PSK" | "WPA2" | "WPA-PSK" | "WPA" => "WPA",
-        "Open" | "open" => "nopass",
-        _ => "WPA",
+/// Write the profile editor's changes back to the saved profile
+fn save_profile_edit(state: &mut AppState) {
+    let Some(editor) = state.profiles.editor.as_mut() else {
+        return;
     };
+    let password = state.inputs.profile_password_input.value.clone();
+    if password.is_empty() && editor.needs_password() {
+        editor.field = 1;
+        let message = format!("Enter a password for {}", editor.security_label());
+        state.show_error(message);
+        return;
+    }
 
-    let qr_string = if auth_type == "nopass" {
-        format!("WIFI:S:{};T:nopass;;", escape_special_chars(ssid))
-    } else if let Some(pwd) = password {
-        format!(
-            "WIFI:S:{};T:{};P:{};;",
-            escape_special_chars(ssid),
-            auth_type,
-            escape_special_chars(pwd.expose_secret())
-        )
-    } else {
-        format!("WIFI:S:{};T:{};;", escape_special_chars(ssid), auth_type)
+    let edit = ProfileEdit {
+        auto_connect: editor.auto_connect,
+        hidden: editor.hidden,
+        authentication: editor.changed_security(),
+        password: (!password.is_empty()).then(|| SecretString::from(password)),
     };
+    let ssid = editor.ssid.clone();
+    let started = spawn_wifi_op(state, OperationKind::EditProfile, ssid.clone(), move || {
+        // Re-read the profile so nothing changed since the popup opened is lost
+        let xml = crate::wifi::export_profile(&ssid)?;
+        crate::wifi::import_profile(&edit_profile_xml(&xml, &edit)?, true).map(|_| ())
+    });
+    if started {
+        close_profile_editor(state);
+    }
+}
 
-    match QrCode::new(&qr_string) {
-        Ok(code) => {
-            let string = code.render::<unicode::Dense1x2>().build();
-            string.lines().map(|s| s.to_string()).collect()
+/// Start the hotspot with the entered SSID and passphrase, or stop it if it is on
+fn toggle_hotspot(state: &mut AppState) {
+    if state.hotspot.is_busy {
+        return;
+    }
+
+    if state.hotspot.status.as_ref().is_some_and(|s| s.active) {
+        state.hotspot.is_busy = true;
+        spawn_hotspot_task(state, || {
+            crate::wifi::stop_hotspot()?;
+            crate::wifi::hotspot_status()
+        });
+        return;
+    }
+
+    let ssid = state.inputs.hotspot_ssid_input.value.trim().to_string();
+    let password = state.inputs.hotspot_password_input.value.clone();
+    if ssid.is_empty() {
+        state.show_error("Enter a name for the hotspot".to_string());
+        state.hotspot.field = 0;
+        return;
+    }
+    if password.chars().count() < config::HOTSPOT_MIN_PASSPHRASE_LEN {
+        state.show_error(format!(
+            "Passphrase must be at least {} characters",
+            config::HOTSPOT_MIN_PASSPHRASE_LEN
+        ));
+        state.hotspot.field = 1;
+        return;
+    }
+
+    state.hotspot.is_busy = true;
+    let password = SecretString::from(password);
+    spawn_hotspot_task(state, move || {
+        crate::wifi::start_hotspot(&ssid, &password)?;
+        crate::wifi::hotspot_status()
+    });
+}
+
+/// Start the hotspot as a guest network with a random passphrase, or show the
+/// QR code of the one already running
+fn start_guest_network(state: &mut AppState) {
+    if state
+        .hotspot
+        .guest
+        .as_ref()
+        .is_some_and(|g| g.expires_at.is_some())
+    {
+        show_guest_qr(state);
+        return;
+    }
+    if state.hotspot.is_busy {
+        return;
+    }
+
+    let guest = GuestNetwork::generate();
+    let ssid = guest.ssid.clone();
+    let passphrase = SecretString::from(guest.passphrase.expose_secret().to_string());
+    state.show_toast(format!("Starting guest network {}…", ssid));
+    state.hotspot.guest = Some(guest);
+    state.hotspot.is_busy = true;
+    spawn_hotspot_task(state, move || {
+        crate::wifi::start_hotspot(&ssid, &passphrase)?;
+        crate::wifi::hotspot_status()
+    });
+}
+
+/// QR code for joining the running guest network
+pub fn show_guest_qr(state: &mut AppState) {
+    let Some(guest) = &state.hotspot.guest else {
+        return;
+    };
+    let ssid = guest.ssid.clone();
+    let passphrase = SecretString::from(guest.passphrase.expose_secret().to_string());
+    open_qr_popup(state, ssid, "WPA2-PSK".to_string(), Some(passphrase));
+}
+
+/// Take the guest network down and remove its hotspot profile
+pub fn remove_guest_network(state: &mut AppState) {
+    let Some(guest) = state.hotspot.guest.take() else {
+        return;
+    };
+    state.show_toast(format!(
+        "Guest network {} expired and was removed",
+        guest.ssid
+    ));
+    state.hotspot.is_busy = true;
+    spawn_hotspot_task(state, || {
+        crate::wifi::remove_hotspot()?;
+        crate::wifi::hotspot_status()
+    });
+}
+
+/// Handle keyboard events for the speed test popup
+pub fn handle_speedtest_popup(key: KeyEvent, state: &mut AppState) -> bool {
+    match key.code {
+        event::KeyCode::Esc | event::KeyCode::Char('q') => {
+            state.ui.show_speedtest_popup = false;
         }
-        Err(_) => vec!["Error generating QR code".to_string()],
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            state.ui.show_speedtest_popup = false;
+        }
+        event::KeyCode::Enter | event::KeyCode::Char('r')
+            if state.speedtest.updates_rx.is_none() =>
+        {
+            start_speedtest(state);
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Handle keyboard events for the WPS popup
+pub fn handle_wps_popup(key: KeyEvent, state: &mut AppState) -> bool {
+    match key.code {
+        event::KeyCode::Esc | event::KeyCode::Char('q') => {
+            state.ui.show_wps_popup = false;
+        }
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            state.ui.show_wps_popup = false;
+        }
+        event::KeyCode::Enter | event::KeyCode::Char('r') if state.wps.result_rx.is_none() => {
+            if let Some(ssid) = state.wps.ssid.clone() {
+                start_wps(state, ssid);
+            }
+        }
+        _ => {}
     }
+    false
 }
 
-/// Escape special characters for WiFi QR code format
-fn escape_special_chars(s: &str) -> String {
-    s.replace('\\', "\\\\")
-        .replace(';', "\\;")
-        .replace(',', "\\,")
-        .replace(':', "\\:")
+/// Handle keyboard events for the channel graph
+pub fn handle_channel_graph(key: KeyEvent, state: &mut AppState) -> bool {
+    let bands = crate::channels::BANDS;
+    let current = bands
+        .iter()
+        .position(|&band| band == state.ui.channel_graph_band)
+        .unwrap_or(0);
+    match key.code {
+        event::KeyCode::Esc | event::KeyCode::Char('q') => {
+            state.ui.show_channel_graph = false;
+        }
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            state.ui.show_channel_graph = false;
+        }
+        event::KeyCode::Tab | event::KeyCode::Right | event::KeyCode::Char('l') => {
+            state.ui.channel_graph_band = bands[(current + 1) % bands.len()];
+        }
+        event::KeyCode::BackTab | event::KeyCode::Left | event::KeyCode::Char('h') => {
+            state.ui.channel_graph_band = bands[(current + bands.len() - 1) % bands.len()];
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Handle keyboard events for the key binding overlay
+pub fn handle_help_overlay(key: KeyEvent, state: &mut AppState) -> bool {
+    let scroll = &mut state.ui.help_scroll;
+    match key.code {
+        event::KeyCode::Esc | event::KeyCode::Char('q') | event::KeyCode::Char('?') => {
+            state.ui.show_help = false;
+        }
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            state.ui.show_help = false;
+        }
+        // The renderer clamps the scroll to the end of the list
+        event::KeyCode::Down | event::KeyCode::Char('j') => *scroll = scroll.saturating_add(1),
+        event::KeyCode::Up | event::KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+        event::KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+        event::KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+        event::KeyCode::Home | event::KeyCode::Char('g') => *scroll = 0,
+        event::KeyCode::End | event::KeyCode::Char('G') => *scroll = u16::MAX,
+        _ => {}
+    }
+    false
+}
+
+/// Run a speed test on the connected network in the background
+fn start_speedtest(state: &mut AppState) {
+    let Some(ssid) = state.network.connected_ssid.clone() else {
+        state.show_error("Connect to a network to run a speed test".to_string());
+        state.ui.show_speedtest_popup = false;
+        return;
+    };
+
+    let speedtest = &mut state.speedtest;
+    speedtest.history =
+        crate::speedtest::load_history(&config::data_dir().join(config::SPEEDTEST_FILE), &ssid);
+    speedtest.ssid = Some(ssid);
+    speedtest.phase = Some(crate::speedtest::SpeedTestPhase::Latency);
+    speedtest.fraction = 0.0;
+    speedtest.latency_ms = None;
+    speedtest.download_mbps = None;
+    speedtest.upload_mbps = None;
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    speedtest.updates_rx = Some(rx);
+    let download_url = state.config.current.speedtest_download_url.clone();
+    let upload_url = state.config.current.speedtest_upload_url.clone();
+    tokio::task::spawn_blocking(move || {
+        let update = match crate::speedtest::run(&download_url, &upload_url, &tx) {
+            Ok(result) => SpeedTestUpdate::Done(result),
+            Err(e) => SpeedTestUpdate::Failed(e),
+        };
+        let _ = tx.send(update);
+    });
+}
+
+/// Join a network through WPS push-button in the background; the attempt
+/// waits for the router's button, so it can take up to a couple of minutes
+fn start_wps(state: &mut AppState, ssid: String) {
+    state.watchdog.stop();
+    state.watchdog.expect_disconnect = state.network.connected_ssid.is_some();
+    state.wps.ssid = Some(ssid.clone());
+    state.wps.error = None;
+
+    state.wps.result_rx = Some(spawn_blocking_op(move || crate::wifi::connect_wps(&ssid)));
+}
+
+/// Record a completed speed test in the history
+pub fn finish_speedtest(state: &mut AppState, result: SpeedTestResult) {
+    let speedtest = &mut state.speedtest;
+    speedtest.updates_rx = None;
+    speedtest.phase = None;
+    speedtest.latency_ms = Some(result.latency_ms);
+    speedtest.download_mbps = Some(result.download_mbps);
+    speedtest.upload_mbps = Some(result.upload_mbps);
+
+    let Some(ssid) = speedtest.ssid.clone() else {
+        return;
+    };
+    let path = config::data_dir().join(config::SPEEDTEST_FILE);
+    if let Err(e) = crate::speedtest::save_result(&path, &ssid, &result) {
+        state.show_error(format!("Failed to save speed test result: {}", e));
+    }
+    state.speedtest.history = crate::speedtest::load_history(&path, &ssid);
+    if !state.ui.show_speedtest_popup {
+        state.show_success(format!(
+            "Speed test: {:.1} down / {:.1} up Mbps",
+            result.download_mbps, result.upload_mbps
+        ));
+    }
+}
+
+/// Start pinging the gateway of a newly connected network
+pub fn start_latency_monitor(state: &mut AppState, ssid: &str) {
+    if state.latency.ssid.as_deref() == Some(ssid) {
+        return;
+    }
+    stop_latency_monitor(state);
+    state.latency.ssid = Some(ssid.to_string());
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    state.latency.updates_rx = Some(rx);
+    let ping_internet = state.config.current.ping_internet;
+    state.latency.task = Some(tokio::spawn(async move {
+        let gateway = tokio::task::spawn_blocking(crate::wifi::default_gateway)
+            .await
+            .ok()
+            .and_then(Result::ok)
+            .flatten();
+        let _ = tx.send(LatencyUpdate::Gateway(gateway));
+
+        let mut targets = Vec::new();
+        if let Some(gateway) = gateway {
+            targets.push((PingTarget::Gateway, gateway));
+        }
+        if ping_internet {
+            targets.push((PingTarget::Internet, config::INTERNET_PING_ADDR));
+        }
+        if targets.is_empty() {
+            return;
+        }
+
+        let timeout = Duration::from_secs(config::LATENCY_TIMEOUT_SECS);
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(config::LATENCY_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            for &(target, addr) in &targets {
+                let update =
+                    match tokio::task::spawn_blocking(move || crate::wifi::ping(addr, timeout))
+                        .await
+                    {
+                        Ok(Ok(rtt)) => LatencyUpdate::Sample(target, rtt),
+                        Ok(Err(e)) => LatencyUpdate::Failed(e.to_string()),
+                        Err(e) => LatencyUpdate::Failed(e.to_string()),
+                    };
+                let failed = matches!(update, LatencyUpdate::Failed(_));
+                if tx.send(update).is_err() || failed {
+                    return;
+                }
+            }
+        }
+    }));
+}
+
+/// Stop the latency monitor and forget its samples
+pub fn stop_latency_monitor(state: &mut AppState) {
+    if let Some(task) = state.latency.task.take() {
+        task.abort();
+    }
+    state.latency = Default::default();
+}
+
+/// Probe internet reachability of a newly connected network
+pub fn start_connectivity_probe(state: &mut AppState, ssid: &str) {
+    if state
+        .connection
+        .connectivity
+        .as_ref()
+        .is_some_and(|(probed, _)| probed == ssid)
+    {
+        return;
+    }
+    state.connection.connectivity = Some((ssid.to_string(), Connectivity::Checking));
+    recheck_connectivity(state);
+}
+
+/// Probe the connected network again, keeping its last status until the result arrives
+pub fn recheck_connectivity(state: &mut AppState) {
+    let (tx, rx) = mpsc::channel(1);
+    state.connection.connectivity_rx = Some(rx);
+    state.connection.connectivity_checked_at = Some(Instant::now());
+    tokio::spawn(async move {
+        let result = tokio::task::spawn_blocking(crate::net::probe).await;
+        let _ = tx.send(result.unwrap_or(Connectivity::NoInternet)).await;
+    });
+}
+
+/// List the WiFi adapters in the background
+pub fn fetch_interfaces(state: &mut AppState) {
+    if state.network.interfaces_rx.is_none() {
+        state.network.interfaces_rx = Some(spawn_blocking_op(crate::wifi::list_interfaces));
+    }
+}
+
+/// Query the radio state, unless a query or switch is already running
+pub fn fetch_radio_state(state: &mut AppState) {
+    if state.refresh.radio_rx.is_none() {
+        spawn_radio_task(state, crate::wifi::radio_state);
+    }
+}
+
+fn spawn_radio_task(
+    state: &mut AppState,
+    task: impl FnOnce() -> crate::error::WifiResult<crate::wifi::RadioState> + Send + 'static,
+) {
+    state.refresh.radio_rx = Some(spawn_blocking_op(task));
+}
+
+/// Read the connected interface's addresses, unless a read is already running
+pub fn fetch_ip_info(state: &mut AppState) {
+    if state.connection.ip_info_rx.is_some() {
+        return;
+    }
+    state.connection.ip_info_rx = Some(spawn_blocking_op(crate::wifi::ip_info));
+}
+
+/// Poll the frame counters of the connected interface
+pub fn fetch_interface_stats(state: &mut AppState) {
+    state.monitor.stats_rx = Some(spawn_blocking_op(crate::wifi::interface_stats));
+    state.monitor.stats_fetched_at = Some(Instant::now());
+}
+
+/// Read the metered setting of a saved profile in the background
+pub fn fetch_metered(state: &mut AppState, ssid: String) {
+    let (tx, rx) = mpsc::channel(1);
+    state.connection.metered_rx = Some(rx);
+    tokio::spawn(async move {
+        let result = {
+            let ssid = ssid.clone();
+            tokio::task::spawn_blocking(move || crate::wifi::is_metered(&ssid))
+                .await
+                .unwrap_or_else(|e| Err(WifiError::Internal(e.to_string())))
+        };
+        let _ = tx.send((ssid, result)).await;
+    });
+}
+
+/// Read the saved password of a network in the background, for `purpose`
+fn fetch_password(state: &mut AppState, ssid: String, purpose: PasswordUse) {
+    let (tx, rx) = mpsc::channel(1);
+    state.ui.password_rx = Some(rx);
+    tokio::spawn(async move {
+        let result = {
+            let ssid = ssid.clone();
+            tokio::task::spawn_blocking(move || crate::wifi::get_wifi_password(&ssid))
+                .await
+                .unwrap_or_else(|e| Err(WifiError::Internal(e.to_string())))
+        };
+        let _ = tx.send((purpose, ssid, result)).await;
+    });
+}
+
+/// Read a saved profile in the background; the editor opens once it arrives
+fn fetch_profile_for_editor(state: &mut AppState, ssid: String) {
+    let (tx, rx) = mpsc::channel(1);
+    state.profiles.editor_rx = Some(rx);
+    tokio::spawn(async move {
+        let result = {
+            let ssid = ssid.clone();
+            tokio::task::spawn_blocking(move || {
+                crate::wifi::export_profile(&ssid)
+                    .and_then(|xml| crate::wifi::parse_profile_xml(&xml))
+            })
+            .await
+            .unwrap_or_else(|e| Err(WifiError::Internal(e.to_string())))
+        };
+        let _ = tx.send((ssid, result)).await;
+    });
+}
+
+/// Refresh the hotspot state and client count in the background
+pub fn fetch_hotspot_status(state: &mut AppState) {
+    spawn_hotspot_task(state, crate::wifi::hotspot_status);
+}
+
+fn spawn_hotspot_task(
+    state: &mut AppState,
+    task: impl FnOnce() -> crate::error::WifiResult<crate::wifi::HotspotStatus> + Send + 'static,
+) {
+    state.hotspot.status_rx = Some(spawn_blocking_op(task));
+    state.hotspot.last_fetch = Some(Instant::now());
+}
+
+/// Handle keyboard events for the search mode
+pub fn handle_search_mode(key: KeyEvent, state: &mut AppState) -> bool {
+    match key.code {
+        event::KeyCode::Esc => {
+            state.ui.is_searching = false;
+            state.ui.search_history_pos = None;
+        }
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            state.ui.is_searching = false;
+            state.ui.search_history_pos = None;
+        }
+        event::KeyCode::Enter => {
+            state.ui.is_searching = false;
+            if state.ui.search_due.is_some() {
+                state.update_filtered_list();
+            }
+            state.push_search_history();
+            if !state.network.filtered_wifi_list.is_empty() {
+                state.ui.l_state.select(Some(0));
+            }
+        }
+        event::KeyCode::Up => state.recall_search_history(true),
+        event::KeyCode::Down => state.recall_search_history(false),
+        event::KeyCode::Char(c) => {
+            state.inputs.search_input.insert(c);
+            state.ui.search_history_pos = None;
+            state.schedule_search();
+        }
+        _ => {
+            if state.inputs.search_input.handle_key(&key) {
+                state.schedule_search();
+            }
+        }
+    }
+    false
+}
+
+/// Handle keyboard events for the main view (network list)
+pub fn handle_main_view(key: KeyEvent, state: &mut AppState) -> bool {
+    let searching = !state.inputs.search_input.value.is_empty();
+    let action = state.config.keymap.action(&key, searching);
+    let pending = state.ui.pending_keys.take();
+    let count = pending.and_then(|p| p.count);
+    let plain_char = matches!(key.code, event::KeyCode::Char(_))
+        && !key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+
+    // Digits build a count like the 5 of `5j`; zero only continues one
+    let digit = match (action, key.code) {
+        (Some(Action::QuickConnect(n)), _) => Some(usize::from(n)),
+        (_, event::KeyCode::Char('0')) if plain_char && count.is_some() => Some(0),
+        _ => None,
+    };
+    if let Some(digit) = digit
+        && !pending.is_some_and(|p| p.g)
+    {
+        state.ui.pending_keys = Some(PendingKeys {
+            count: Some(count.unwrap_or(0).saturating_mul(10).saturating_add(digit)),
+            g: false,
+            since: Instant::now(),
+        });
+        return false;
+    }
+
+    match (action, count) {
+        (Some(Action::Down), Some(n)) => state.next(n),
+        (Some(Action::Up), Some(n)) => state.previous(n),
+        (Some(Action::Bottom), Some(n)) => state.go_to_row(n),
+        // `gg`, or `5gg` for the fifth network
+        (Some(Action::Top), _) if plain_char => {
+            if pending.is_some_and(|p| p.g) {
+                match count {
+                    Some(n) => state.go_to_row(n),
+                    None => state.go_to_top(),
+                }
+            } else {
+                state.ui.pending_keys = Some(PendingKeys {
+                    count,
+                    g: true,
+                    since: Instant::now(),
+                });
+            }
+        }
+        _ => {
+            // A sequence cut short still does what its keys do on their own
+            if let Some(pending) = pending {
+                finish_pending_keys(state, pending);
+            }
+            return match action {
+                Some(action) => perform_action(action, state),
+                None => false,
+            };
+        }
+    }
+    false
+}
+
+/// Run a key sequence nothing completed: a lone digit quick-connects and a
+/// lone `g` goes to the top, as they do without sequences
+pub fn finish_pending_keys(state: &mut AppState, pending: PendingKeys) {
+    match (pending.count, pending.g) {
+        (Some(n), true) => state.go_to_row(n),
+        (None, true) => state.go_to_top(),
+        (Some(n @ 1..=9), false) => {
+            perform_action(Action::QuickConnect(n as u8), state);
+        }
+        _ => {}
+    }
+}
+
+/// Handle clicks and scrolling; returns true if the app should quit
+pub fn handle_mouse(mouse: MouseEvent, state: &mut AppState) -> bool {
+    let position = Position::new(mouse.column, mouse.row);
+    if state.ui.show_manual_add_popup {
+        return match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => click_manual_add_popup(position, state),
+            _ => false,
+        };
+    }
+    if state.is_popup_open() {
+        return false;
+    }
+
+    match mouse.kind {
+        MouseEventKind::ScrollDown => state.next(1),
+        MouseEventKind::ScrollUp => state.previous(1),
+        MouseEventKind::Down(MouseButton::Left) => {
+            let list = state.ui.click_areas.list;
+            if !list.contains(position) {
+                return false;
+            }
+            let index = state.ui.l_state.offset() + (position.y - list.y) as usize;
+            if index >= state.network.filtered_wifi_list.len() {
+                return false;
+            }
+            state.ui.l_state.select(Some(index));
+
+            let is_double_click = state.ui.last_click.take().is_some_and(|(row, at)| {
+                row == index && at.elapsed() < Duration::from_millis(config::DOUBLE_CLICK_MS)
+            });
+            if is_double_click {
+                return perform_action(Action::Connect, state);
+            }
+            state.ui.last_click = Some((index, Instant::now()));
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Focus the clicked field of the manual add popup; buttons and toggles act
+/// as if Enter was pressed on them
+fn click_manual_add_popup(position: Position, state: &mut AppState) -> bool {
+    let areas = &state.ui.click_areas;
+    if !areas.manual_popup.contains(position) {
+        // Clicking outside dismisses the popup, like Esc
+        return handle_manual_add_popup(KeyEvent::from(event::KeyCode::Esc), state);
+    }
+    let Some(field) = areas
+        .manual_fields
+        .iter()
+        .find(|(_, area)| area.contains(position))
+        .map(|&(field, _)| field)
+    else {
+        return false;
+    };
+
+    state.inputs.manual_input_field = field;
+    match field {
+        3..=6 | 8 | 9 => handle_manual_add_popup(KeyEvent::from(event::KeyCode::Enter), state),
+        _ => false,
+    }
+}
+
+/// Run a main view action; returns true if the app should quit
+pub fn perform_action(action: Action, state: &mut AppState) -> bool {
+    use std::time::Duration;
+
+    match action {
+        Action::Search => {
+            state.ui.is_searching = true;
+        }
+        Action::AddNetwork => {
+            state.ui.show_manual_add_popup = true;
+            state.inputs.manual_input_field = 0;
+        }
+        Action::Back => {
+            if state.connection.is_connecting {
+                state.connection.is_connecting = false;
+                state.connection.target_ssid = None;
+                state.tasks.cancel(OperationKind::Connect);
+            } else if !state.inputs.search_input.value.is_empty() {
+                state.inputs.search_input.clear();
+                state.update_filtered_list();
+            }
+        }
+        Action::Quit => return true,
+        Action::ClearSearch if !state.inputs.search_input.value.is_empty() => {
+            state.inputs.search_input.clear();
+            state.update_filtered_list();
+        }
+        Action::Down => {
+            let step = state.ui.nav_repeat.step(1);
+            state.next(step);
+        }
+        Action::Up => {
+            let step = state.ui.nav_repeat.step(-1);
+            state.previous(step);
+        }
+        Action::Top => state.go_to_top(),
+        Action::Bottom => state.go_to_bottom(),
+        Action::Connect => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+            {
+                if wifi.is_hidden() {
+                    open_hidden_network_popup(state, &wifi);
+                    return false;
+                }
+
+                let is_connected = if let Some(connected_ssid) = &state.network.connected_ssid {
+                    wifi.ssid == *connected_ssid
+                } else {
+                    false
+                };
+
+                if is_connected
+                    && state.speedtest.updates_rx.is_some()
+                    && state.ask_confirmation(Confirmation {
+                        title: "Disconnect",
+                        message: format!(
+                            "A speed test is running on {}. Disconnect anyway?",
+                            wifi.ssid
+                        ),
+                        detail: None,
+                        command: Command {
+                            action: Action::Connect,
+                            argument: Some(wifi.ssid.clone()),
+                        },
+                        accept_open: None,
+                    })
+                {
+                    return false;
+                }
+
+                if !is_connected && let Some(refusal) = state.pmf_refusal(&wifi) {
+                    state.show_error(refusal);
+                    return false;
+                }
+
+                if !is_connected
+                    && let Some(suspicion) = state.suspicion(&wifi)
+                    && state.ask_confirmation(Confirmation {
+                        title: "Possible Evil Twin",
+                        message: format!(
+                            "{} looks spoofed: {}. Connect anyway?",
+                            wifi.ssid,
+                            suspicion.describe()
+                        ),
+                        detail: Some(
+                            "Impostors copy a network's name to capture passwords".to_string(),
+                        ),
+                        command: Command {
+                            action: Action::Connect,
+                            argument: Some(wifi.ssid.clone()),
+                        },
+                        accept_open: None,
+                    })
+                {
+                    return false;
+                }
+
+                // Asked even with `confirm` off; `warn_open` turns it off instead
+                if !is_connected
+                    && wifi.authentication == "Open"
+                    && state.config.current.warn_open
+                    && !state.ui.confirmed
+                    && !state.network.accepted_open.contains(&wifi.ssid)
+                {
+                    state.ui.confirmation = Some(Confirmation {
+                        title: "Unencrypted Network",
+                        message: format!(
+                            "{} is unencrypted; your traffic may be visible to others nearby. Connect?",
+                            wifi.ssid
+                        ),
+                        detail: Some("a connects and stops asking for this network".to_string()),
+                        command: Command {
+                            action: Action::Connect,
+                            argument: Some(wifi.ssid.clone()),
+                        },
+                        accept_open: Some(wifi.ssid.clone()),
+                    });
+                    return false;
+                }
+
+                if is_connected {
+                    if spawn_wifi_op(
+                        state,
+                        OperationKind::Disconnect,
+                        wifi.ssid.clone(),
+                        disconnect,
+                    ) {
+                        state.watchdog.expect_disconnect = true;
+                    }
+                } else if wifi.is_saved {
+                    // The profile of this entry, not another one for the same SSID
+                    let profile = wifi.profile().to_string();
+                    let bssid = state
+                        .network
+                        .wifi_list
+                        .iter()
+                        .any(|w| w.ssid == wifi.ssid && w.authentication != wifi.authentication)
+                        .then(|| wifi.strongest_bss().map(|bss| bss.bssid))
+                        .flatten();
+                    start_connection(state, wifi.ssid.clone(), move || {
+                        leave_current_network();
+                        match bssid {
+                            Some(bssid) => crate::wifi::connect_profile_bssid(&profile, bssid),
+                            None => crate::wifi::connect_profile(&profile),
+                        }
+                    });
+                } else if wifi.authentication != "Open" {
+                    state.ui.show_password_popup = true;
+                    state.inputs.password_input.cursor = 0;
+                    state.connection.connecting_to_ssid = Some(wifi.ssid.clone());
+                    state.connection.connecting_to_auth = Some(wifi.authentication.clone());
+                } else {
+                    let ssid = wifi.ssid.clone();
+                    let options = state.profile_options(false, false);
+                    start_connection(state, wifi.ssid.clone(), move || {
+                        leave_current_network();
+                        crate::wifi::connect_open(&ssid, options)
+                    });
+                }
+            }
+        }
+        Action::WpsConnect => {
+            let Some(wifi) = state
+                .ui
+                .l_state
+                .selected()
+                .and_then(|i| state.network.filtered_wifi_list.get(i).cloned())
+            else {
+                return false;
+            };
+            if wifi.authentication == "Open" || wifi.is_hidden() {
+                state.show_error("WPS needs a visible, password-protected network".to_string());
+            } else if state.network.connected_ssid.as_ref() == Some(&wifi.ssid) {
+                state.show_toast(format!("Already connected to {}", wifi.ssid));
+            } else {
+                state.ui.show_wps_popup = true;
+                if state.wps.result_rx.is_none() {
+                    start_wps(state, wifi.ssid.clone());
+                }
+            }
+        }
+        Action::JoinUri => {
+            let credentials = state
+                .clipboard
+                .paste()
+                .map_err(|e| format!("Failed to read the clipboard: {}", e))
+                .and_then(|text| crate::share::parse_wifi_uri(&text));
+            match credentials {
+                Ok(credentials) => open_join_popup(state, credentials),
+                Err(e) => state.show_error(e),
+            }
+        }
+        Action::QuickConnect(n) => {
+            let index = n as usize - 1;
+            let Some(wifi) = state.network.filtered_wifi_list.get(index) else {
+                return false;
+            };
+            if state.network.connected_ssid.as_ref() == Some(&wifi.ssid) {
+                let message = format!("Already connected to {}", wifi.ssid);
+                state.show_toast(message);
+                return false;
+            }
+            state.ui.l_state.select(Some(index));
+            return perform_action(Action::Connect, state);
+        }
+        Action::Refresh => {
+            // Debounce rapid 'r' key presses
+            if state.refresh.last_manual_refresh.elapsed()
+                < Duration::from_millis(config::MANUAL_REFRESH_DEBOUNCE_MS)
+            {
+                return false;
+            }
+            state.refresh.last_manual_refresh = Instant::now();
+            start_network_refresh(state);
+        }
+        Action::ToggleAutoConnect => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+                && wifi.is_saved
+            {
+                let ssid = wifi.ssid.clone();
+                let auto_connect = !wifi.auto_connect;
+                let kind = OperationKind::AutoConnect(auto_connect);
+                spawn_wifi_op(state, kind, ssid.clone(), move || {
+                    crate::wifi::set_auto_connect(&ssid, auto_connect)
+                });
+            }
+        }
+        Action::ToggleRandomMac => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+                && wifi.is_saved
+            {
+                let ssid = wifi.ssid.clone();
+                state.connection.random_mac_rx = Some(spawn_blocking_op(move || {
+                    let enable = !crate::wifi::mac_randomization(&ssid)?;
+                    crate::wifi::set_mac_randomization(&ssid, enable)?;
+                    Ok((ssid, enable))
+                }));
+            }
+        }
+        Action::ToggleMetered => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+                && wifi.is_saved
+            {
+                let ssid = wifi.ssid.clone();
+                let metered = !state
+                    .network
+                    .metered
+                    .get(&ssid)
+                    .copied()
+                    .flatten()
+                    .unwrap_or(false);
+                let (tx, rx) = mpsc::channel(1);
+                state.connection.metered_set_rx = Some(rx);
+
+                tokio::spawn(async move {
+                    let result = {
+                        let ssid = ssid.clone();
+                        tokio::task::spawn_blocking(move || {
+                            crate::wifi::set_metered(&ssid, metered).map(|()| metered)
+                        })
+                        .await
+                        .unwrap_or_else(|e| Err(WifiError::Internal(e.to_string())))
+                    };
+                    let _ = tx.send((ssid, result)).await;
+                });
+            }
+        }
+        Action::Forget => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+                && wifi.is_saved
+            {
+                let asked = state.ask_confirmation(Confirmation {
+                    title: "Forget Network",
+                    message: format!("Delete the saved profile of {}?", wifi.ssid),
+                    detail: Some(format!(
+                        "{} restores it within {}s",
+                        state.config.keymap.hint(Action::UndoForget),
+                        config::FORGET_UNDO_SECS
+                    )),
+                    command: Command {
+                        action: Action::Forget,
+                        argument: Some(wifi.ssid.clone()),
+                    },
+                    accept_open: None,
+                });
+                if !asked {
+                    forget_network(state, &wifi);
+                }
+            }
+        }
+        Action::UndoForget => match state.network.forgotten.take() {
+            Some(forgotten) => {
+                let xml = forgotten.xml;
+                spawn_wifi_op(state, OperationKind::Restore, forgotten.ssid, move || {
+                    crate::wifi::import_profile(xml.expose_secret(), true).map(|_| ())
+                });
+            }
+            None => state.show_toast("Nothing to undo"),
+        },
+        Action::RecreateProfile => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+            {
+                if let Some(saved_as) = wifi.profile_security {
+                    let asked = state.ask_confirmation(Confirmation {
+                        title: "Recreate Profile",
+                        message: format!(
+                            "Replace the {} profile of {} with a {} one?",
+                            saved_as,
+                            wifi.ssid,
+                            security_family(&wifi.authentication)
+                        ),
+                        detail: None,
+                        command: Command {
+                            action: Action::RecreateProfile,
+                            argument: Some(wifi.ssid.clone()),
+                        },
+                        accept_open: None,
+                    });
+                    if !asked {
+                        recreate_profile(state, &wifi);
+                    }
+                } else {
+                    state.show_toast(format!("{} has no outdated profile", wifi.ssid));
+                }
+            }
+        }
+        Action::ToggleMark => state.toggle_compare_mark(),
+        Action::Compare => {
+            if state.compared_networks().is_some() {
+                state.ui.show_compare_popup = true;
+            } else {
+                state.show_toast("Mark two visible networks with m to compare");
+            }
+        }
+        Action::CycleSort => state.cycle_sort(),
+        Action::CycleFilter => state.cycle_filter(),
+        Action::NextMatch => state.jump_to_match(true),
+        Action::PrevMatch => state.jump_to_match(false),
+        Action::CycleTheme => state.cycle_theme(),
+        Action::ReloadConfig => state.reload_config(),
+        Action::ToggleDebugLog => state.ui.show_debug_pane = !state.ui.show_debug_pane,
+        Action::ToggleDebugPane if state.ui.show_debug_pane => {
+            state.ui.debug_pane_expanded = !state.ui.debug_pane_expanded;
+        }
+        Action::NextDetailsTab => state.ui.details_tab = state.ui.details_tab.cycle(1),
+        Action::PrevDetailsTab => state.ui.details_tab = state.ui.details_tab.cycle(-1),
+        Action::DetailsTab(n) => {
+            if let Some(&tab) = DetailsTab::ALL.get(usize::from(n).saturating_sub(1)) {
+                state.ui.details_tab = tab;
+            }
+        }
+        Action::ToggleAccessPoints => {
+            state.ui.details_tab = if state.ui.details_tab == DetailsTab::Radio {
+                DetailsTab::Overview
+            } else {
+                DetailsTab::Radio
+            };
+        }
+        Action::ToggleSignalLog => state.toggle_signal_logging(),
+        Action::ToggleSurvey => state.toggle_survey(),
+        Action::TagSurvey if state.monitor.survey.is_some() => {
+            state.ui.show_survey_label_popup = true;
+            state.inputs.survey_label_input.clear();
+        }
+        Action::SwitchBand if state.steering.suggestion.is_some() => {
+            start_band_steering(state);
+        }
+        Action::RoamNow => {
+            let Some(wifi) = state
+                .network
+                .wifi_list
+                .iter()
+                .find(|w| w.is_connected)
+                .cloned()
+            else {
+                state.show_toast("Not connected to a network");
+                return false;
+            };
+            let Some(strongest) = wifi.strongest_bss().map(|bss| bss.bssid) else {
+                return false;
+            };
+            if wifi.connected_bssid == Some(strongest) {
+                state.show_toast(format!(
+                    "Already on the strongest access point of {}",
+                    wifi.ssid
+                ));
+                return false;
+            }
+            let profile = wifi.profile().to_string();
+            if start_connection(state, wifi.ssid.clone(), move || {
+                leave_current_network();
+                crate::wifi::connect_profile_bssid(&profile, strongest)
+            }) {
+                state.refresh.refresh_burst = config::CONNECTION_REFRESH_BURST;
+            }
+        }
+        Action::Share => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+            {
+                if wifi.is_saved {
+                    fetch_password(
+                        state,
+                        wifi.ssid.clone(),
+                        PasswordUse::Share(wifi.authentication.clone()),
+                    );
+                } else if wifi.authentication == "Open" {
+                    open_qr_popup(state, wifi.ssid.clone(), wifi.authentication.clone(), None);
+                } else {
+                    // Ask for the passphrase without creating a profile
+                    state.ui.qr_ssid = wifi.ssid.clone();
+                    state.ui.qr_auth = wifi.authentication.clone();
+                    state.inputs.share_password_input.clear();
+                    state.ui.show_share_password_popup = true;
+                }
+            }
+        }
+        Action::OpenPortal => {
+            match state
+                .connection
+                .connectivity
+                .as_ref()
+                .and_then(|(_, connectivity)| connectivity.portal_url())
+            {
+                Some(url) => match crate::net::open_in_browser(url) {
+                    Ok(()) => state.show_toast("Opened the portal login page"),
+                    Err(e) => {
+                        state.show_error(format!("Failed to open browser: {}", e));
+                    }
+                },
+                None => state.show_toast("No captive portal detected"),
+            }
+        }
+        Action::RevealPassword => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+            {
+                match &mut state.ui.revealed_password {
+                    // Masked -> shown -> hidden (and zeroized)
+                    Some(revealed) if revealed.ssid == wifi.ssid && !revealed.visible => {
+                        revealed.visible = true;
+                    }
+                    Some(revealed) if revealed.ssid == wifi.ssid => {
+                        state.ui.revealed_password = None;
+                    }
+                    _ if !wifi.is_saved => {
+                        state.show_toast(format!("{} has no saved password", wifi.ssid));
+                    }
+                    _ => fetch_password(state, wifi.ssid.clone(), PasswordUse::Reveal),
+                }
+            }
+        }
+        Action::CopyPassword => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+            {
+                if !wifi.is_saved {
+                    state.show_toast(format!("{} has no saved password", wifi.ssid));
+                    return false;
+                }
+                fetch_password(state, wifi.ssid.clone(), PasswordUse::Copy);
+            }
+        }
+        Action::CopySsid => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+            {
+                copy_to_clipboard(
+                    state,
+                    SecretString::from(wifi.ssid.clone()),
+                    format!("SSID {}", wifi.ssid),
+                );
+            }
+        }
+        Action::CommandPalette => {
+            state.inputs.palette_input.clear();
+            state.ui.palette_state.select(Some(0));
+            state.ui.show_command_palette = true;
+        }
+        Action::NextInterface if state.next_interface() => {
+            start_network_refresh(state);
+        }
+        Action::SavedProfiles => {
+            state.ui.show_profiles_popup = true;
+            state.profiles.list_state.select(Some(0));
+            spawn_profiles_task(state, crate::wifi::get_saved_profiles);
+        }
+        Action::Hotspot => {
+            state.ui.show_hotspot_popup = true;
+            state.hotspot.field = 0;
+            fetch_hotspot_status(state);
+        }
+        Action::GuestNetwork => start_guest_network(state),
+        Action::SpeedTest => {
+            state.ui.show_speedtest_popup = true;
+            if state.speedtest.updates_rx.is_none() {
+                start_speedtest(state);
+            }
+        }
+        Action::ToggleRadio => {
+            let on = !state.network.radio.is_some_and(|radio| radio.is_on());
+            if !on {
+                state.watchdog.stop();
+                state.watchdog.expect_disconnect = state.network.connected_ssid.is_some();
+            }
+            spawn_radio_task(state, move || {
+                crate::wifi::set_radio(on)?;
+                crate::wifi::radio_state()
+            });
+        }
+        Action::ChannelGraph => {
+            // Start on the band of the connected network, where the router lives
+            if let Some(band) = state
+                .network
+                .wifi_list
+                .iter()
+                .find(|w| w.is_connected)
+                .and_then(|w| Band::from_frequency(w.frequency))
+            {
+                state.ui.channel_graph_band = band;
+            }
+            state.ui.show_channel_graph = true;
+        }
+        Action::CommandLine => {
+            state.inputs.command_input.clear();
+            state.ui.show_command_line = true;
+        }
+        Action::Help => {
+            state.ui.help_scroll = 0;
+            state.ui.show_help = true;
+        }
+        Action::ExportProfile => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+            {
+                if !wifi.is_saved {
+                    state.show_toast(format!("{} has no saved profile to export", wifi.ssid));
+                    return false;
+                }
+                let dir = state.ui.share_dir.clone();
+                let ssid = wifi.ssid.clone();
+                spawn_wifi_op(state, OperationKind::Export, wifi.ssid.clone(), move || {
+                    let xml = crate::wifi::export_profile(&ssid)?;
+                    crate::share::save_profile_xml(&dir, &ssid, &xml)
+                        .map(OperationOutput::Exported)
+                        .map_err(|e| WifiError::Internal(e.to_string()))
+                });
+            }
+        }
+        Action::EditProfile => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+            {
+                if !wifi.is_saved {
+                    state.show_toast(format!("{} has no saved profile to edit", wifi.ssid));
+                    return false;
+                }
+                fetch_profile_for_editor(state, wifi.ssid.clone());
+            }
+        }
+        Action::RenameProfile => {
+            if let Some(selected) = state.ui.l_state.selected()
+                && let Some(wifi) = state.network.filtered_wifi_list.get(selected).cloned()
+            {
+                if !wifi.is_saved {
+                    state.show_toast(format!("{} has no saved profile to rename", wifi.ssid));
+                    return false;
+                }
+                // Needs the new name, so prompt for it with the current one to edit
+                state.inputs.command_input.clear();
+                for c in format!("rename {}", wifi.profile()).chars() {
+                    state.inputs.command_input.insert(c);
+                }
+                state.ui.show_command_line = true;
+            }
+        }
+        Action::ImportProfile => {
+            // Needs a file path, so prompt for it on the command line
+            state.inputs.command_input.clear();
+            for c in "import ".chars() {
+                state.inputs.command_input.insert(c);
+            }
+            state.ui.show_command_line = true;
+        }
+        _ => {}
+    }
+    false
+}
+
+/// Handle keyboard events for the `:` command line
+pub fn handle_command_line(key: KeyEvent, state: &mut AppState) -> bool {
+    match key.code {
+        event::KeyCode::Esc => {
+            state.ui.show_command_line = false;
+            state.inputs.command_input.clear();
+        }
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            state.ui.show_command_line = false;
+            state.inputs.command_input.clear();
+        }
+        event::KeyCode::Backspace if state.inputs.command_input.value.is_empty() => {
+            state.ui.show_command_line = false;
+        }
+        event::KeyCode::Enter => {
+            let line = std::mem::take(&mut state.inputs.command_input.value);
+            state.inputs.command_input.clear();
+            state.ui.show_command_line = false;
+            return run_command(&line, state);
+        }
+        _ => {
+            state.inputs.command_input.handle_key(&key);
+        }
+    }
+    false
+}
+
+/// Run a `:` command; returns true if the app should quit
+fn run_command(line: &str, state: &mut AppState) -> bool {
+    match Command::parse(line) {
+        Ok(command) => execute_command(command, state),
+        Err(e) => {
+            state.show_error(e);
+            false
+        }
+    }
+}
+
+/// Run a parsed command; returns true if the app should quit
+fn execute_command(command: Command, state: &mut AppState) -> bool {
+    match (command.action, command.argument) {
+        (Action::Search, Some(query)) => {
+            state.inputs.search_input.value = query;
+            state.inputs.search_input.move_end();
+            state.update_filtered_list();
+            state.push_search_history();
+            false
+        }
+        (Action::ImportProfile, Some(path)) => {
+            let xml = match std::fs::read_to_string(&path) {
+                Ok(xml) => xml,
+                Err(e) => {
+                    state.show_error(format!("Failed to import {}: {}", path, e));
+                    return false;
+                }
+            };
+            // A profile it would replace is only found in the background,
+            // which then asks; once confirmed it is overwritten
+            let overwrite = state.ui.confirmed || !state.config.current.confirm;
+            spawn_wifi_op(state, OperationKind::Import, path, move || {
+                crate::wifi::import_profile(&xml, overwrite).map(OperationOutput::Imported)
+            });
+            false
+        }
+        (Action::RenameProfile, Some(new_name)) => {
+            let Some(wifi) = state
+                .ui
+                .l_state
+                .selected()
+                .and_then(|i| state.network.filtered_wifi_list.get(i).cloned())
+                .filter(|wifi| wifi.is_saved)
+            else {
+                state.show_error("Select a saved network to rename its profile".to_string());
+                return false;
+            };
+            let name = wifi.profile().to_string();
+            if new_name == name {
+                return false;
+            }
+            spawn_wifi_op(state, OperationKind::Rename, name.clone(), move || {
+                if crate::wifi::get_saved_profiles()
+                    .unwrap_or_default()
+                    .contains(&new_name)
+                {
+                    return Err(WifiError::ProfileExists(new_name));
+                }
+                crate::wifi::rename_profile(&name, &new_name)
+                    .map(|()| OperationOutput::Renamed(new_name))
+            });
+            false
+        }
+        (Action::JoinUri, Some(source)) => {
+            match crate::share::load_wifi_uri(&source) {
+                Ok(credentials) => open_join_popup(state, credentials),
+                Err(e) => state.show_error(e),
+            }
+            false
+        }
+        (Action::TagSurvey, Some(label)) => {
+            if state.monitor.survey.is_some() {
+                state.tag_survey_location(&label);
+            } else {
+                state.show_error("No survey running - start one with V".to_string());
+            }
+            false
+        }
+        (Action::CycleSort, Some(name)) => {
+            match SortMode::from_name(&name) {
+                Some(mode) => state.set_sort(mode),
+                None => state.show_error(format!("Unknown sort order: {}", name)),
+            }
+            false
+        }
+        (Action::CycleFilter, Some(name)) => {
+            match ListFilter::from_name(&name) {
+                Some(filter) => state.set_filter(filter),
+                None => state.show_error(format!("Unknown filter: {}", name)),
+            }
+            false
+        }
+        (Action::CycleTheme, Some(name)) => {
+            state.set_theme(&name);
+            false
+        }
+        (action, Some(ssid)) => {
+            if !state.select_network(&ssid) {
+                state.show_error(format!("Network not found: {}", ssid));
+                return false;
+            }
+            perform_action(action, state)
+        }
+        (action, None) => perform_action(action, state),
+    }
+}
+
+/// Handle keyboard events for the command palette
+pub fn handle_command_palette(key: KeyEvent, state: &mut AppState) -> bool {
+    match key.code {
+        event::KeyCode::Esc => state.close_command_palette(),
+        event::KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            state.close_command_palette();
+        }
+        event::KeyCode::Enter => {
+            let action = state
+                .ui
+                .palette_state
+                .selected()
+                .and_then(|i| state.palette_actions().get(i).copied());
+            state.close_command_palette();
+            if let Some(action) = action {
+                return perform_action(action, state);
+            }
+        }
+        event::KeyCode::Down => {
+            let len = state.palette_actions().len();
+            let i = state.ui.palette_state.selected().map_or(0, |i| i + 1);
+            state
+                .ui
+                .palette_state
+                .select(Some(i.min(len.saturating_sub(1))));
+        }
+        event::KeyCode::Up => {
+            let i = state.ui.palette_state.selected().unwrap_or(0);
+            state.ui.palette_state.select(Some(i.saturating_sub(1)));
+        }
+        event::KeyCode::Char(c) => {
+            state.inputs.palette_input.insert(c);
+            state.ui.palette_state.select(Some(0));
+        }
+        _ => {
+            if state.inputs.palette_input.handle_key(&key) {
+                state.ui.palette_state.select(Some(0));
+            }
+        }
+    }
+    false
+}
+
+/// Scan and fetch the network list in the background
+pub fn start_network_refresh(state: &mut AppState) {
+    use std::time::Duration;
+
+    state.refresh.is_refreshing_networks = true;
+    state.refresh.started_at = Some(Instant::now());
+    let (tx, rx) = mpsc::channel(1);
+    state.refresh.network_update_rx = Some(rx);
+    let scan_delay = Duration::from_millis(state.config.current.scan_delay_ms);
+    let (scan_done_tx, scan_done_rx) = oneshot::channel();
+    state.refresh.scan_done_tx = Some(scan_done_tx);
+    let use_daemon = state.on_default_interface();
+
+    tokio::spawn(async move {
+        // A running daemon scans for us
+        let cached = if use_daemon {
+            tokio::task::spawn_blocking(|| crate::daemon::networks(true))
+                .await
+                .ok()
+                .flatten()
+        } else {
+            None
+        };
+        if cached.is_none() {
+            let scan = tokio::task::spawn_blocking(crate::wifi::scan_networks).await;
+            if let Ok(Ok(())) = scan {
+                // The listener reports when the scan finishes; without one, wait the full delay
+                let _ = tokio::time::timeout(scan_delay, scan_done_rx).await;
+            }
+        }
+        let result = tokio::task::spawn_blocking(move || {
+            let networks = match cached {
+                Some(networks) => networks,
+                None => get_wifi_networks()?,
+            };
+            let connected = get_connected_ssid()?;
+            Ok((networks, connected))
+        })
+        .await;
+        let result = match result {
+            Ok(inner) => inner.map_err(|e: WifiError| e.into()),
+            Err(e) => Err(eyre!(e.to_string())),
+        };
+        let _ = tx.send(result).await;
+    });
+}
+
+/// Reconnect to the faster-band access point from the current steering suggestion
+pub fn start_band_steering(state: &mut AppState) {
+    let Some(suggestion) = state.steering.suggestion.take() else {
+        return;
+    };
+    state.steering.last_attempt = Some(Instant::now());
+    let ssid = suggestion.ssid.clone();
+    if !spawn_wifi_op(state, OperationKind::Connect, ssid, move || {
+        crate::wifi::connect_profile_bssid(&suggestion.ssid, suggestion.bssid)
+    }) {
+        return;
+    }
+    state.refresh.refresh_burst = config::CONNECTION_REFRESH_BURST;
+}
+
+/// Connect to a saved network's profile for the watchdog or roaming
+pub fn start_profile_connection(state: &mut AppState, ssid: String) {
+    // Only a person gets to decide on joining a possible evil twin
+    if let Some(suspicion) = state
+        .network
+        .wifi_list
+        .iter()
+        .filter(|w| w.ssid == ssid)
+        .find_map(|w| state.suspicion(w))
+    {
+        state.show_warning(format!(
+            "Not auto-connecting to {}: {}",
+            ssid,
+            suspicion.describe()
+        ));
+        return;
+    }
+    if let Some(refusal) = state
+        .network
+        .wifi_list
+        .iter()
+        .filter(|w| w.ssid == ssid)
+        .find_map(|w| state.pmf_refusal(w))
+    {
+        state.show_warning(refusal);
+        return;
+    }
+    if start_connection(state, ssid.clone(), move || {
+        crate::wifi::connect_profile(&ssid)
+    }) {
+        state.refresh.refresh_burst = config::CONNECTION_REFRESH_BURST;
+    }
+}
+
+/// Show the share popup with a QR code for the given credentials
+pub fn open_qr_popup(
+    state: &mut AppState,
+    ssid: String,
+    auth: String,
+    password: Option<SecretString>,
+) {
+    state.ui.qr_code_lines = generate_wifi_qr(&ssid, &auth, password.as_ref());
+    state.ui.qr_ssid = ssid;
+    state.ui.qr_auth = auth;
+    state.ui.qr_password = password;
+    state.ui.show_qr_password = false;
+    state.ui.show_qr_popup = true;
+}
+
+/// Generate WiFi QR code in standard format: WIFI:S:ssid;T:auth;P:password;;
+fn generate_wifi_qr(ssid: &str, auth: &str, password: Option<&SecretString>) -> Vec<String> {
+    use qrcode::QrCode;
+    use qrcode::render::unicode;
+
+    let qr_string = crate::share::wifi_uri(ssid, auth, password);
+
+    match QrCode::new(&qr_string) {
+        Ok(code) => {
+            let string = code.render::<unicode::Dense1x2>().build();
+            string.lines().map(|s| s.to_string()).collect()
+        }
+        Err(_) => vec!["Error generating QR code".to_string()],
+    }
 }
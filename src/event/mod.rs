@@ -1,40 +1,100 @@
 //! Event handling module for WifUI
 //!
 //! This module handles keyboard input, connection events, and the main event loop.
+//! The loop sleeps in `tokio::select!` until a key press, a background result or a
+//! timer arrives, and redraws only then.
 
 mod handlers;
+mod tasks;
 
 use crate::{
-    app::AppState,
+    action::{Action, Command},
+    app::{
+        AppState, Confirmation, ForgottenProfile, NetworkUpdate, Operation, OperationKind,
+        OperationOutput, PasswordUse, ProfileEditor, RevealedPassword,
+    },
     config,
-    error::WifiError,
+    error::{WifiError, WifiResult, is_wrong_password},
+    latency::{LatencyUpdate, PingTarget},
+    logging,
+    net::Connectivity,
+    rules,
+    speedtest::{SpeedTestPhase, SpeedTestUpdate},
     ui::render,
-    wifi::{ConnectionEvent, get_connected_ssid, get_wifi_networks, start_wifi_listener},
+    wifi::{
+        ConnectionEvent, HotspotStatus, InterfaceInfo, InterfaceStats, IpInfo, ProfileXml,
+        RadioState, WifiListener, get_connected_ssid, get_wifi_networks, start_wifi_listener,
+    },
 };
 use color_eyre::eyre::{Result, eyre};
 use crossterm::{
     cursor::SetCursorStyle,
-    event::{self, Event, KeyModifiers},
+    event::{self, Event, EventStream, KeyEvent, KeyModifiers, MouseEventKind},
 };
+use futures_core::Stream;
 use handlers::{
-    handle_main_view, handle_manual_add_popup, handle_password_popup, handle_qr_popup,
-    handle_search_mode,
+    copy_to_clipboard, fetch_hotspot_status, fetch_interface_stats, fetch_interfaces,
+    fetch_ip_info, fetch_metered, fetch_radio_state, finish_pending_keys, finish_speedtest,
+    handle_channel_graph, handle_command_line, handle_command_palette, handle_compare_popup,
+    handle_confirm_popup, handle_help_overlay, handle_hotspot_popup, handle_main_view,
+    handle_manual_add_popup, handle_mouse, handle_password_popup, handle_profile_editor,
+    handle_profiles_popup, handle_qr_popup, handle_search_mode, handle_share_password_popup,
+    handle_speedtest_popup, handle_survey_label_popup, handle_wps_popup, open_credentials_popup,
+    open_qr_popup, recheck_connectivity, remove_guest_network, show_guest_qr, start_band_steering,
+    start_connectivity_probe, start_latency_monitor, start_network_refresh,
+    start_profile_connection, stop_latency_monitor,
 };
 use ratatui::DefaultTerminal;
+use secrecy::SecretString;
+use std::pin::Pin;
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
+use tasks::spawn_blocking_op;
+use tokio::sync::mpsc::{self, Receiver, UnboundedReceiver};
 
 struct CursorStyleGuard;
 
 impl Drop for CursorStyleGuard {
     fn drop(&mut self) {
-        let _ = crossterm::execute!(
-            std::io::stdout(),
-            SetCursorStyle::DefaultUserShape
-        );
+        let _ = crossterm::execute!(std::io::stdout(), SetCursorStyle::DefaultUserShape);
     }
 }
 
+/// A channel end the event loop can wait on
+trait Channel<T> {
+    async fn next(&mut self) -> Option<T>;
+}
+
+impl<T> Channel<T> for Receiver<T> {
+    async fn next(&mut self) -> Option<T> {
+        self.recv().await
+    }
+}
+
+impl<T> Channel<T> for UnboundedReceiver<T> {
+    async fn next(&mut self) -> Option<T> {
+        self.recv().await
+    }
+}
+
+impl Channel<std::io::Result<Event>> for EventStream {
+    async fn next(&mut self) -> Option<std::io::Result<Event>> {
+        std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+/// Wait for the next message of an optional channel, forever if there is none.
+/// A closed channel is dropped so the loop stops waiting on it.
+async fn recv<T>(rx: &mut Option<impl Channel<T>>) -> Option<T> {
+    let Some(channel) = rx else {
+        return std::future::pending().await;
+    };
+    let message = channel.next().await;
+    if message.is_none() {
+        *rx = None;
+    }
+    message
+}
+
 pub async fn run(mut terminal: DefaultTerminal, state: &mut AppState) -> Result<()> {
     // Ensure the terminal cursor shape is restored on exit.
     let _cursor_style_guard = CursorStyleGuard;
@@ -42,22 +102,29 @@ pub async fn run(mut terminal: DefaultTerminal, state: &mut AppState) -> Result<
     // Set cursor style to blinking block while the app is active.
     crossterm::execute!(std::io::stdout(), SetCursorStyle::BlinkingBlock)?;
 
-    let mut listener_init_started = false;
+    let mut first_frame_drawn = false;
+    let mut terminal_events = EventStream::new();
 
     loop {
+        let frame_start = Instant::now();
         terminal.draw(|frame| render(frame, state))?;
+        state.perf.last_frame = frame_start.elapsed();
 
-        // Start WiFi event listener only after the first frame is rendered.
-        if !listener_init_started {
-            listener_init_started = true;
+        // Start the WiFi event listener, the first scan and the adapter query
+        // only after the first frame is rendered.
+        if !first_frame_drawn {
+            first_frame_drawn = true;
+            start_network_refresh(state);
+            fetch_interfaces(state);
             if let Some(connection_event_tx) = state.connection.connection_event_tx.take() {
                 let (init_tx, init_rx) = mpsc::channel(1);
                 state.connection.listener_init_rx = Some(init_rx);
 
                 tokio::spawn(async move {
-                    let result =
-                        tokio::task::spawn_blocking(move || start_wifi_listener(connection_event_tx))
-                            .await;
+                    let result = tokio::task::spawn_blocking(move || {
+                        start_wifi_listener(connection_event_tx)
+                    })
+                    .await;
                     let result = match result {
                         Ok(inner) => inner,
                         Err(e) => Err(WifiError::Internal(e.to_string())),
@@ -67,281 +134,1092 @@ pub async fn run(mut terminal: DefaultTerminal, state: &mut AppState) -> Result<
             }
         }
 
-        if let Some(rx) = &mut state.connection.listener_init_rx {
-            if let Ok(result) = rx.try_recv() {
-                state.connection.listener_init_rx = None;
-                match result {
-                    Ok(listener) => {
-                        state.connection.wifi_listener = Some(listener);
+        // Spinners need frames; otherwise only timers like toasts and auto-refresh do
+        let animating = state.connection.is_connecting
+            || state.refresh.is_initial_loading
+            || state.wps.result_rx.is_some()
+            // Lone digits and `g` need their timeout noticed promptly, and so does a search edit
+            || state.ui.pending_keys.is_some()
+            || state.ui.search_due.is_some();
+        let tick = Duration::from_millis(if animating {
+            config::FRAME_INTERVAL_MS
+        } else {
+            config::IDLE_TICK_MS
+        });
+
+        tokio::select! {
+            Some(event) = terminal_events.next() => match event? {
+                Event::Key(key) => {
+                    state.refresh.last_interaction = Instant::now();
+                    if key.kind == event::KeyEventKind::Press && handle_key(key, state) {
+                        break;
                     }
-                    Err(e) => {
-                        state.ui.error_message =
-                            Some(format!("WiFi event listener unavailable: {}", e));
+                }
+                // Plain pointer movement is not an interaction
+                Event::Mouse(mouse) if mouse.kind != MouseEventKind::Moved => {
+                    state.refresh.last_interaction = Instant::now();
+                    if handle_mouse(mouse, state) {
+                        break;
                     }
                 }
+                Event::Paste(text) => {
+                    state.refresh.last_interaction = Instant::now();
+                    handle_paste(&text, state);
+                }
+                Event::FocusGained => state.ui.has_focus = true,
+                Event::FocusLost => state.ui.has_focus = false,
+                _ => {}
+            },
+            Some(result) = recv(&mut state.refresh.network_update_rx) => {
+                on_network_update(state, result)
+            }
+            Some(event) = recv(&mut state.connection.connection_event_rx) => {
+                on_connection_event(state, event)
+            }
+            Some((operation, result)) = recv(&mut state.tasks.result_rx) => {
+                on_operation_result(state, operation, result)
+            }
+            Some(result) = recv(&mut state.connection.listener_init_rx) => {
+                on_listener_init(state, result)
+            }
+            Some(connectivity) = recv(&mut state.connection.connectivity_rx) => {
+                on_connectivity(state, connectivity)
+            }
+            Some(result) = recv(&mut state.connection.ip_info_rx) => on_ip_info(state, result),
+            Some(result) = recv(&mut state.monitor.stats_rx) => {
+                on_interface_stats(state, result)
+            }
+            Some(result) = recv(&mut state.refresh.radio_rx) => on_radio_state(state, result),
+            Some(result) = recv(&mut state.network.interfaces_rx) => {
+                on_interfaces(state, result)
+            }
+            Some(result) = recv(&mut state.connection.random_mac_rx) => {
+                on_random_mac(state, result)
+            }
+            Some((ssid, result)) = recv(&mut state.connection.metered_rx) => {
+                on_metered(state, ssid, result, false)
+            }
+            Some((ssid, result)) = recv(&mut state.connection.metered_set_rx) => {
+                on_metered(state, ssid, result, true)
+            }
+            Some(update) = recv(&mut state.speedtest.updates_rx) => {
+                on_speedtest_update(state, update)
+            }
+            Some(update) = recv(&mut state.latency.updates_rx) => {
+                on_latency_update(state, update)
+            }
+            Some(result) = recv(&mut state.hotspot.status_rx) => {
+                on_hotspot_status(state, result)
+            }
+            Some(result) = recv(&mut state.profiles.list_rx) => on_profiles(state, result),
+            Some((ssid, result)) = recv(&mut state.profiles.editor_rx) => {
+                on_profile_read(state, ssid, result)
+            }
+            Some((purpose, ssid, result)) = recv(&mut state.ui.password_rx) => {
+                on_password_read(state, purpose, ssid, result)
+            }
+            Some(result) = recv(&mut state.wps.result_rx) => on_wps_result(state, result),
+            Some(result) = recv(&mut state.rules.apply_rx) => on_rules_applied(state, result),
+            _ = tokio::time::sleep(tick) => {
+                if animating {
+                    state.ui.loading_frame = state.ui.loading_frame.wrapping_add(1);
+                }
             }
         }
 
-        // Check for connection result
-        if let Some(rx) = &mut state.connection.connection_result_rx {
-            if let Ok(result) = rx.try_recv() {
-                state.connection.connection_result_rx = None;
-                if let Err(e) = result {
-                    state.connection.is_connecting = false;
-                    state.connection.target_ssid = None;
-                    state.connection.connection_start_time = None;
-                    state.ui.error_message = Some(format!("Failed to connect: {}", e));
-                } else {
-                    // Connection initiated successfully, now wait for it to actually connect
-                    state.refresh.refresh_burst = config::CONNECTION_REFRESH_BURST;
-                }
-                // Trigger background refresh instead of blocking
-                state.refresh.is_refreshing_networks = true;
-                let (tx, rx) = mpsc::channel(1);
-                state.refresh.network_update_rx = Some(rx);
-                tokio::spawn(async move {
-                    let result = tokio::task::spawn_blocking(|| {
-                        let networks = get_wifi_networks()?;
-                        let connected = get_connected_ssid()?;
-                        Ok((networks, connected))
-                    })
-                    .await;
-                    let result = match result {
-                        Ok(inner) => inner,
-                        Err(e) => Err(eyre!(e.to_string())),
-                    };
-                    let _ = tx.send(result).await;
-                });
+        run_timers(state);
+    }
+    Ok(())
+}
+
+/// Handle a key press; returns true to quit
+fn handle_key(key: KeyEvent, state: &mut AppState) -> bool {
+    // Log key press if enabled
+    if state.ui.show_key_logger
+        && !state.ui.show_password_popup
+        && !state.ui.show_manual_add_popup
+        && state.profiles.editor.is_none()
+        && !state.ui.show_share_password_popup
+        && !state.ui.show_hotspot_popup
+    {
+        let mut key_str = String::new();
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            key_str.push_str("Ctrl+");
+        }
+        if key.modifiers.contains(KeyModifiers::ALT) {
+            key_str.push_str("Alt+");
+        }
+        if key.modifiers.contains(KeyModifiers::SHIFT)
+            && !matches!(key.code, event::KeyCode::Char(_))
+        {
+            key_str.push_str("Shift+");
+        }
+
+        let code_str = match key.code {
+            event::KeyCode::Char(c) => c.to_string(),
+            event::KeyCode::Enter => "Enter".to_string(),
+            event::KeyCode::Backspace => "Backspace".to_string(),
+            event::KeyCode::Left => "Left".to_string(),
+            event::KeyCode::Right => "Right".to_string(),
+            event::KeyCode::Up => "Up".to_string(),
+            event::KeyCode::Down => "Down".to_string(),
+            event::KeyCode::Tab => "Tab".to_string(),
+            event::KeyCode::Delete => "Delete".to_string(),
+            event::KeyCode::Home => "Home".to_string(),
+            event::KeyCode::End => "End".to_string(),
+            event::KeyCode::PageUp => "PageUp".to_string(),
+            event::KeyCode::PageDown => "PageDown".to_string(),
+            event::KeyCode::Esc => "Esc".to_string(),
+            event::KeyCode::F(n) => format!("F{}", n),
+            _ => format!("{:?}", key.code),
+        };
+        key_str.push_str(&code_str);
+        state.ui.last_key_press = Some((key_str, Instant::now()));
+    }
+
+    // Global shortcuts
+    if key.code == event::KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        return true;
+    }
+
+    // Terminals without bracketed paste send Ctrl+V as a key
+    if key.code == event::KeyCode::Char('v')
+        && key.modifiers.contains(KeyModifiers::CONTROL)
+        && state.focused_input().is_some()
+    {
+        match state.clipboard.paste() {
+            Ok(text) => handle_paste(&text, state),
+            Err(e) => state.show_error(format!("Failed to read the clipboard: {}", e)),
+        }
+        return false;
+    }
+
+    // Route to appropriate handler
+    if state.ui.confirmation.is_some() {
+        handle_confirm_popup(key, state)
+    } else if state.ui.show_qr_popup {
+        handle_qr_popup(key, state)
+    } else if state.ui.show_compare_popup {
+        handle_compare_popup(key, state)
+    } else if state.ui.show_manual_add_popup {
+        handle_manual_add_popup(key, state)
+    } else if state.profiles.editor.is_some() {
+        handle_profile_editor(key, state)
+    } else if state.ui.show_password_popup {
+        handle_password_popup(key, state)
+    } else if state.ui.show_share_password_popup {
+        handle_share_password_popup(key, state)
+    } else if state.ui.show_survey_label_popup {
+        handle_survey_label_popup(key, state)
+    } else if state.ui.show_command_palette {
+        handle_command_palette(key, state)
+    } else if state.ui.show_command_line {
+        handle_command_line(key, state)
+    } else if state.ui.show_hotspot_popup {
+        handle_hotspot_popup(key, state)
+    } else if state.ui.show_profiles_popup {
+        handle_profiles_popup(key, state)
+    } else if state.ui.show_speedtest_popup {
+        handle_speedtest_popup(key, state)
+    } else if state.ui.show_wps_popup {
+        handle_wps_popup(key, state)
+    } else if state.ui.show_channel_graph {
+        handle_channel_graph(key, state)
+    } else if state.ui.show_help {
+        handle_help_overlay(key, state)
+    } else if state.ui.is_searching {
+        handle_search_mode(key, state)
+    } else {
+        handle_main_view(key, state)
+    }
+}
+
+/// Insert pasted text into the focused text field
+fn handle_paste(text: &str, state: &mut AppState) {
+    let Some(input) = state.focused_input() else {
+        return;
+    };
+    input.insert_str(text);
+    if state.ui.show_command_palette {
+        state.ui.palette_state.select(Some(0));
+    } else if state.ui.is_searching && !state.is_popup_open() {
+        state.update_filtered_list();
+    }
+}
+
+/// Refresh the network list in the background, without a fresh scan
+fn spawn_network_update(state: &mut AppState) {
+    state.refresh.is_refreshing_networks = true;
+    state.refresh.started_at = Some(Instant::now());
+    let (tx, rx) = mpsc::channel(1);
+    state.refresh.network_update_rx = Some(rx);
+    let use_daemon = state.on_default_interface();
+    tokio::spawn(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            // A running daemon has its cache ready
+            let networks = match use_daemon.then(|| crate::daemon::networks(false)).flatten() {
+                Some(networks) => networks,
+                None => get_wifi_networks()?,
+            };
+            let connected = get_connected_ssid()?;
+            Ok((networks, connected))
+        })
+        .await;
+        let result = match result {
+            Ok(inner) => inner,
+            Err(e) => Err(eyre!(e.to_string())),
+        };
+        let _ = tx.send(result).await;
+    });
+}
+
+fn on_listener_init(state: &mut AppState, result: WifiResult<WifiListener>) {
+    state.connection.listener_init_rx = None;
+    match result {
+        Ok(listener) => {
+            state.connection.wifi_listener = Some(listener);
+        }
+        Err(e) => {
+            state.show_error(format!("WiFi event listener unavailable: {}", e));
+        }
+    }
+}
+
+fn on_operation_result(
+    state: &mut AppState,
+    operation: Operation,
+    result: WifiResult<OperationOutput>,
+) {
+    // Cancelled while it ran
+    if !state.tasks.finish(operation.id) {
+        return;
+    }
+    let ssid = &operation.ssid;
+    match (operation.kind, result) {
+        (OperationKind::Connect, Err(e)) => {
+            logging::warn(format!("Connecting to {} failed: {}", ssid, e));
+            state.set_status(false, format!("Connecting to {} failed", ssid));
+            state.connection.is_connecting = false;
+            state.connection.target_ssid = None;
+            state.connection.connection_start_time = None;
+            state.notify(
+                "WiFi connection failed",
+                &format!("Failed to connect: {}", e),
+            );
+            if matches!(e, WifiError::WrongPassword) && !state.is_popup_open() {
+                ask_password_again(state, ssid.clone(), e.to_string());
+            } else {
+                state.show_error(format!("Failed to connect: {}", e));
             }
         }
+        (OperationKind::Connect, Ok(OperationOutput::NeedsCredentials)) => {
+            state.connection.is_connecting = false;
+            state.connection.target_ssid = None;
+            state.connection.connection_start_time = None;
+            let authentication = state
+                .network
+                .wifi_list
+                .iter()
+                .find(|w| w.ssid == *ssid)
+                .map(|w| w.authentication.clone())
+                .unwrap_or_default();
+            open_credentials_popup(state, ssid, &authentication);
+        }
+        (OperationKind::Connect, Ok(_)) => {
+            logging::info(format!("Connecting to {}, waiting for the adapter", ssid));
+            // Connection initiated successfully, now wait for it to actually connect
+            state.refresh.refresh_burst = config::CONNECTION_REFRESH_BURST;
+        }
+        (OperationKind::Disconnect, Err(e)) => {
+            logging::warn(format!("Disconnecting from {} failed: {}", ssid, e));
+            state.watchdog.expect_disconnect = false;
+            state.set_status(false, format!("Disconnecting from {} failed", ssid));
+            state.show_error(format!("Failed to disconnect: {}", e));
+        }
+        (OperationKind::Forget, Err(e)) => {
+            logging::warn(format!("Forgetting {} failed: {}", ssid, e));
+            state.set_status(false, format!("Forgetting {} failed", ssid));
+            state.show_error(format!("Failed to forget {}: {}", ssid, e));
+        }
+        (OperationKind::EditProfile, Err(e)) => {
+            logging::warn(format!("Saving the profile of {} failed: {}", ssid, e));
+            state.set_status(false, format!("Saving the profile of {} failed", ssid));
+            state.show_error(format!("Failed to save the profile of {}: {}", ssid, e));
+        }
+        (OperationKind::EditProfile, Ok(_)) => {
+            state.set_status(true, format!("Saved the profile of {}", ssid));
+        }
+        (OperationKind::AutoConnect(_), Err(e)) => {
+            state.set_status(false, format!("Changing auto-connect of {} failed", ssid));
+            state.show_error(format!("Failed to change auto-connect of {}: {}", ssid, e));
+        }
+        (OperationKind::Disconnect, Ok(_)) => {
+            state.set_status(true, format!("Disconnected from {}", ssid));
+        }
+        (OperationKind::Forget, Ok(output)) => {
+            state.network.forgotten = match output {
+                OperationOutput::Forgotten(Some(xml)) => Some(ForgottenProfile {
+                    ssid: ssid.clone(),
+                    xml,
+                    forgotten_at: Instant::now(),
+                }),
+                _ => None,
+            };
+            state.set_status(true, format!("Forgot {}", ssid));
+        }
+        (OperationKind::Export, Ok(OperationOutput::Exported(path))) => {
+            state.show_success(format!("Exported to {}", path.display()));
+        }
+        (OperationKind::Export, Ok(_)) => {}
+        (OperationKind::Export, Err(e)) => {
+            state.show_error(format!("Failed to export profile: {}", e));
+        }
+        (OperationKind::Import, Err(WifiError::ProfileExists(name))) => {
+            state.ask_confirmation(Confirmation {
+                title: "Overwrite Profile",
+                message: format!("Replace the saved profile {} with {}?", name, ssid),
+                detail: None,
+                command: Command {
+                    action: Action::ImportProfile,
+                    argument: Some(ssid.clone()),
+                },
+                accept_open: None,
+            });
+        }
+        (OperationKind::Import, Err(e)) => {
+            state.show_error(format!("Failed to import {}: {}", ssid, e));
+        }
+        (OperationKind::Import, Ok(OperationOutput::Imported(name))) => {
+            state.show_success(format!("Imported profile {}", name));
+        }
+        (OperationKind::Import, Ok(_)) => {}
+        (OperationKind::Rename, Ok(OperationOutput::Renamed(new_name))) => {
+            state.show_success(format!("Renamed profile {} to {}", ssid, new_name));
+        }
+        (OperationKind::Rename, Ok(_)) => {}
+        (OperationKind::Rename, Err(e)) => {
+            state.show_error(format!("Failed to rename {}: {}", ssid, e));
+        }
+        (OperationKind::Restore, Err(e)) => {
+            state.show_error(format!("Failed to restore {}: {}", ssid, e));
+        }
+        (OperationKind::Restore, Ok(_)) => {
+            state.set_status(true, format!("Restored {}", ssid));
+            state.show_success(format!("Restored {}", ssid));
+        }
+        (OperationKind::AutoConnect(enabled), Ok(_)) => {
+            let setting = if enabled { "enabled" } else { "disabled" };
+            state.set_status(true, format!("Auto-connect {} for {}", setting, ssid));
+        }
+    }
+    // Trigger background refresh instead of blocking
+    spawn_network_update(state);
+}
 
-        // Check for network updates
-        if let Some(rx) = &mut state.refresh.network_update_rx {
-            if let Ok(result) = rx.try_recv() {
-                if let Ok((new_list, connected_ssid)) = result {
-                    let connection_changed = state.network.connected_ssid != connected_ssid;
-
-                    // Try to preserve selection
-                    let selected_ssid = state
-                        .ui
-                        .l_state
-                        .selected()
-                        .and_then(|i| state.network.wifi_list.get(i))
-                        .map(|w| w.ssid.clone());
-
-                    state.network.wifi_list = new_list;
-                    state.network.connected_ssid = connected_ssid;
-                    state.update_filtered_list();
-
-                    if connection_changed && state.network.connected_ssid.is_some() {
-                        state.ui.l_state.select(Some(0));
-                    } else if let Some(ssid) = selected_ssid {
-                        if let Some(pos) = state
-                            .network
-                            .filtered_wifi_list
-                            .iter()
-                            .position(|w| w.ssid == ssid)
-                        {
-                            state.ui.l_state.select(Some(pos));
-                        } else {
-                            state.ui.l_state.select(Some(0));
-                        }
-                    } else {
-                        // No previous selection, select first item
-                        state.ui.l_state.select(Some(0));
-                    }
-                }
-                state.refresh.is_refreshing_networks = false;
-                state.refresh.is_initial_loading = false;
-                state.refresh.network_update_rx = None;
-                state.refresh.last_refresh = Instant::now();
-            }
-        }
-
-        // Check for connection events
-        if let Some(rx) = &mut state.connection.connection_event_rx {
-            while let Ok(event) = rx.try_recv() {
-                match event {
-                    ConnectionEvent::Connected(ssid) => {
-                        if let Some(target) = &state.connection.target_ssid {
-                            if *target == ssid {
-                                state.connection.is_connecting = false;
-                                state.connection.target_ssid = None;
-                                state.connection.connection_start_time = None;
-                                state.refresh.refresh_burst = config::DISCONNECT_REFRESH_BURST;
-                            }
-                        }
-                    }
-                    ConnectionEvent::Disconnected => {
-                        state.refresh.refresh_burst = config::DISCONNECT_REFRESH_BURST;
-                    }
-                    ConnectionEvent::Failed {
-                        ssid, reason_str, ..
-                    } => {
-                        if let Some(target) = &state.connection.target_ssid {
-                            if *target == ssid {
-                                state.connection.is_connecting = false;
-                                state.connection.target_ssid = None;
-                                state.connection.connection_start_time = None;
-                                state.ui.error_message =
-                                    Some(format!("Connection failed: {}", reason_str));
-                            }
-                        }
-                    }
-                }
+fn on_rules_applied(state: &mut AppState, result: WifiResult<Option<rules::Checked>>) {
+    state.rules.apply_rx = None;
+    let Ok(Some(checked)) = result else {
+        return;
+    };
+    state.rules.applied = checked.plan;
+    state.rules.originals = checked.originals;
+    if !checked.done.is_empty() {
+        state.show_toast(format!("Rules: {}", checked.done.join(", ")));
+        spawn_network_update(state);
+    }
+}
+
+fn on_wps_result(state: &mut AppState, result: WifiResult<()>) {
+    state.wps.result_rx = None;
+    let ssid = state.wps.ssid.clone().unwrap_or_default();
+    match result {
+        Ok(()) => {
+            state.ui.show_wps_popup = false;
+            state.show_success(format!("Joined {} with WPS", ssid));
+            state.refresh.refresh_burst = config::CONNECTION_REFRESH_BURST;
+            spawn_network_update(state);
+        }
+        Err(e) if state.ui.show_wps_popup => state.wps.error = Some(e.to_string()),
+        Err(e) => {
+            state.show_error(format!("WPS connect to {} failed: {}", ssid, e));
+        }
+    }
+}
+
+fn on_connectivity(state: &mut AppState, connectivity: Connectivity) {
+    state.connection.connectivity_rx = None;
+    if let Some((ssid, status)) = &mut state.connection.connectivity
+        && *status != connectivity
+    {
+        logging::info(format!("{}: {}", ssid, connectivity.label()));
+        *status = connectivity;
+    }
+}
+
+fn on_ip_info(state: &mut AppState, result: WifiResult<IpInfo>) {
+    state.connection.ip_info_rx = None;
+    match result {
+        // A disconnect may have landed while the fetch was running
+        Ok(info) if state.network.connected_ssid.is_some() => state.connection.ip_info = Some(info),
+        Ok(_) => {}
+        Err(e) => logging::warn(format!("Failed to read IP configuration: {}", e)),
+    }
+}
+
+fn on_interface_stats(state: &mut AppState, result: WifiResult<InterfaceStats>) {
+    state.monitor.stats_rx = None;
+    match result {
+        Ok(stats) if state.network.connected_ssid.is_some() => {
+            state.monitor.frame_rates.record(stats)
+        }
+        Ok(_) => {}
+        // Polled every second, so a driver without counters would flood the log
+        Err(e) => {
+            logging::debug(format!("Failed to read interface statistics: {}", e));
+            state.monitor.frame_rates.clear();
+        }
+    }
+}
+
+fn on_speedtest_update(state: &mut AppState, update: SpeedTestUpdate) {
+    let speedtest = &mut state.speedtest;
+    match update {
+        SpeedTestUpdate::Progress {
+            phase,
+            fraction,
+            mbps,
+        } => {
+            speedtest.phase = Some(phase);
+            speedtest.fraction = fraction.min(1.0);
+            match phase {
+                SpeedTestPhase::Latency => {}
+                SpeedTestPhase::Download => speedtest.download_mbps = Some(mbps),
+                SpeedTestPhase::Upload => speedtest.upload_mbps = Some(mbps),
             }
         }
+        SpeedTestUpdate::Latency(ms) => speedtest.latency_ms = Some(ms),
+        SpeedTestUpdate::Done(result) => finish_speedtest(state, result),
+        SpeedTestUpdate::Failed(e) => {
+            speedtest.updates_rx = None;
+            speedtest.phase = None;
+            state.show_error(e);
+        }
+    }
+}
 
-        // Check if connected to target SSID
-        if state.connection.is_connecting {
-            state.ui.loading_frame = state.ui.loading_frame.wrapping_add(1);
+fn on_latency_update(state: &mut AppState, update: LatencyUpdate) {
+    match update {
+        LatencyUpdate::Gateway(gateway) => state.latency.gateway = gateway,
+        LatencyUpdate::Sample(PingTarget::Gateway, rtt) => state.latency.gateway_stats.push(rtt),
+        LatencyUpdate::Sample(PingTarget::Internet, rtt) => state.latency.internet_stats.push(rtt),
+        LatencyUpdate::Failed(e) => logging::warn(format!("Ping stopped: {}", e)),
+    }
+}
 
-            if let Some(target) = &state.connection.target_ssid {
-                if let Some(connected) = &state.network.connected_ssid {
-                    if connected == target {
-                        state.connection.is_connecting = false;
-                        state.connection.target_ssid = None;
-                        state.connection.connection_start_time = None;
-                    }
+fn on_hotspot_status(state: &mut AppState, result: WifiResult<HotspotStatus>) {
+    state.hotspot.status_rx = None;
+    state.hotspot.is_busy = false;
+    match result {
+        Ok(status) => {
+            let ssid_input = &mut state.inputs.hotspot_ssid_input;
+            if ssid_input.value.is_empty() {
+                ssid_input.value = status.ssid.clone();
+                ssid_input.cursor = ssid_input.value.chars().count();
+            }
+            if let Some(guest) = &mut state.hotspot.guest {
+                let running = status.active && status.ssid == guest.ssid;
+                if running && guest.expires_at.is_none() {
+                    let ttl = Duration::from_secs(state.config.current.guest_ttl_mins * 60);
+                    guest.expires_at = Some(Instant::now() + ttl);
+                    show_guest_qr(state);
+                } else if !running {
+                    // Failed to start, or stopped from the hotspot popup
+                    state.hotspot.guest = None;
                 }
+            }
+            state.hotspot.status = Some(status);
+        }
+        Err(e) => {
+            if state
+                .hotspot
+                .guest
+                .as_ref()
+                .is_some_and(|g| g.expires_at.is_none())
+            {
+                state.hotspot.guest = None;
+            }
+            state.show_error(e.to_string());
+        }
+    }
+}
 
-                // Check for timeout
-                if let Some(start_time) = state.connection.connection_start_time {
-                    if start_time.elapsed() > Duration::from_secs(config::CONNECTION_TIMEOUT_SECS) {
-                        state.connection.is_connecting = false;
-                        state.connection.target_ssid = None;
-                        state.connection.connection_start_time = None;
-                        state.ui.error_message =
-                            Some("Connection timed out (No response from OS)".to_string());
-                    }
+fn on_profiles(state: &mut AppState, result: WifiResult<Vec<String>>) {
+    state.profiles.list_rx = None;
+    state.profiles.is_busy = false;
+    match result {
+        Ok(list) => {
+            let last = list.len().saturating_sub(1);
+            let selected = state.profiles.list_state.selected().unwrap_or(0);
+            state.profiles.list_state.select(Some(selected.min(last)));
+            state.profiles.list = list;
+        }
+        Err(e) => state.show_error(e.to_string()),
+    }
+}
+
+fn on_profile_read(state: &mut AppState, ssid: String, result: WifiResult<ProfileXml>) {
+    state.profiles.editor_rx = None;
+    match result {
+        // Something else took over the screen while the profile was read
+        Ok(_) if state.is_popup_open() => {}
+        Ok(profile) => {
+            state.inputs.profile_password_input.clear();
+            state.profiles.editor = Some(ProfileEditor::new(&ssid, &profile));
+        }
+        Err(e) => state.show_error(format!("Failed to read the profile of {}: {}", ssid, e)),
+    }
+}
+
+fn on_password_read(
+    state: &mut AppState,
+    purpose: PasswordUse,
+    ssid: String,
+    result: WifiResult<Option<SecretString>>,
+) {
+    state.ui.password_rx = None;
+    match (purpose, result) {
+        (PasswordUse::Reveal, Ok(Some(password))) => {
+            state.ui.revealed_password = Some(RevealedPassword {
+                ssid,
+                password,
+                visible: false,
+                fetched_at: Instant::now(),
+            });
+        }
+        (PasswordUse::Copy, Ok(Some(password))) => {
+            let what = format!("password for {}", ssid);
+            copy_to_clipboard(state, password, what);
+        }
+        // The QR code is still useful without the passphrase
+        (PasswordUse::Share(authentication), result) => {
+            open_qr_popup(state, ssid, authentication, result.unwrap_or(None));
+        }
+        (_, Ok(None)) => state.show_toast(format!("{} has no saved password", ssid)),
+        (_, Err(e)) => state.show_error(format!("Failed to read password: {}", e)),
+    }
+}
+
+fn on_network_update(state: &mut AppState, result: Result<NetworkUpdate>) {
+    if let Ok((new_list, connected_ssid)) = result {
+        let connection_changed = state.network.connected_ssid != connected_ssid;
+
+        // Try to preserve selection
+        let selected = state.selected_entry();
+        let restore_ssid = state.ui.restore_ssid.take();
+        let connected_bss = state
+            .network
+            .wifi_list
+            .iter()
+            .find(|w| w.is_connected)
+            .and_then(|w| Some((w.ssid.clone(), w.connected_bssid?)));
+
+        // The snapshot is replaced, not faded out like a scan
+        if state.refresh.showing_snapshot {
+            state.network.replace_networks(new_list);
+        } else {
+            state.network.set_networks(new_list);
+        }
+        state.refresh.showing_snapshot = false;
+        state.network.connected_ssid = connected_ssid;
+        state.network.touch_last_connected();
+        state.network.learn_access_points();
+        state.update_filtered_list();
+        if connection_changed {
+            state.connection.ip_info = None;
+            state.monitor.frame_rates.clear();
+            match state.network.connected_ssid.clone() {
+                Some(ssid) => {
+                    start_connectivity_probe(state, &ssid);
+                    start_latency_monitor(state, &ssid);
                 }
-            } else {
-                // If no target SSID is set but is_connecting is true, check connection result
-                if state.connection.connection_result_rx.is_none() {
-                    state.connection.is_connecting = false;
+                None => {
+                    state.connection.connectivity = None;
+                    stop_latency_monitor(state);
                 }
             }
         }
+        if state.network.connected_ssid.is_some() {
+            fetch_ip_info(state);
+        }
+        // A roam nobody reported shows up as another connected access point
+        if let Some((ssid, from)) = connected_bss
+            && let Some(to) = state
+                .network
+                .wifi_list
+                .iter()
+                .find(|w| w.is_connected && w.ssid == ssid)
+                .and_then(|w| w.connected_bssid)
+        {
+            state.record_roam(&ssid, Some(from), to);
+        }
 
-        // Auto-refresh logic
-        let refresh_interval = if state.refresh.refresh_burst > 0 {
-            Duration::from_secs(config::BURST_REFRESH_INTERVAL_SECS)
-        } else if state.ui.is_searching || !state.inputs.search_input.value.is_empty() {
-            Duration::from_secs(config::SEARCHING_REFRESH_INTERVAL_SECS)
+        if let Some(pos) = restore_ssid.and_then(|ssid| state.find_entry(&ssid, None)) {
+            // Selection remembered from the previous session
+            state.ui.l_state.select(Some(pos));
+        } else if connection_changed && state.network.connected_ssid.is_some() {
+            state.ui.l_state.select(Some(0));
+        } else if let Some((ssid, auth)) = selected {
+            if let Some(pos) = state.find_entry(&ssid, Some(&auth)) {
+                state.ui.l_state.select(Some(pos));
+            } else {
+                state.ui.l_state.select(Some(0));
+            }
         } else {
-            Duration::from_secs(config::AUTO_REFRESH_INTERVAL_SECS)
-        };
+            // No previous selection, select first item
+            state.ui.l_state.select(Some(0));
+        }
 
-        if !state.refresh.is_refreshing_networks
-            && !state.ui.show_manual_add_popup
-            && !state.ui.show_password_popup
-            && !state.ui.show_qr_popup
-            && state.refresh.last_refresh.elapsed() >= refresh_interval
-            && state.refresh.last_interaction.elapsed()
-                >= Duration::from_secs(config::INTERACTION_COOLDOWN_SECS)
+        state.log_signal_sample();
+        state.record_signal_history();
+        state.record_survey_sample();
+        state.update_signal_alert();
+        state.update_band_steering();
+        if state.config.current.auto_band_steer
+            && state.steering.suggestion.is_some()
+            && !state.connection.is_connecting
         {
-            if state.refresh.refresh_burst > 0 {
-                state.refresh.refresh_burst -= 1;
-            }
-            state.refresh.is_refreshing_networks = true;
-            let (tx, rx) = mpsc::channel(1);
-            state.refresh.network_update_rx = Some(rx);
-
-            tokio::spawn(async move {
-                let result = tokio::task::spawn_blocking(|| {
-                    let networks = get_wifi_networks()?;
-                    let connected = get_connected_ssid()?;
-                    Ok((networks, connected))
-                })
-                .await;
-                let result = match result {
-                    Ok(inner) => inner,
-                    Err(e) => Err(eyre!(e.to_string())),
-                };
-                let _ = tx.send(result).await;
-            });
+            start_band_steering(state);
+        }
+        state.update_roaming();
+        if let Some(candidate) = state.roaming.due().cloned()
+            && !state.connection.is_connecting
+            && !state.tasks.is_busy()
+        {
+            let message = format!(
+                "Roaming to {} ({}% vs {}%)",
+                candidate.ssid, candidate.signal, candidate.current_signal
+            );
+            logging::info(message.clone());
+            state.show_toast(message);
+            state.roaming.candidate = None;
+            state.roaming.last_switch = Some(Instant::now());
+            start_profile_connection(state, candidate.ssid);
+        }
+        // Reading the time and power source blocks, so the whole check runs
+        // in the background
+        if state.rules.apply_rx.is_none()
+            && let Some(check) = state.rules_check()
+        {
+            state.rules.apply_rx = Some(spawn_blocking_op(move || Ok(check.run())));
         }
+    }
+    if let Some(started_at) = state.refresh.started_at.take() {
+        state.perf.last_refresh = Some(started_at.elapsed());
+    }
+    state.refresh.is_refreshing_networks = false;
+    state.refresh.is_initial_loading = false;
+    state.refresh.network_update_rx = None;
+    state.refresh.last_refresh = Instant::now();
+    fetch_radio_state(state);
+}
 
-        if event::poll(Duration::from_millis(config::EVENT_POLL_MS))? {
-            if let Event::Key(key) = event::read()? {
-                state.refresh.last_interaction = Instant::now();
-                if key.kind == event::KeyEventKind::Press {
-                    // Log key press if enabled
-                    if state.ui.show_key_logger
-                        && !state.ui.show_password_popup
-                        && !state.ui.show_manual_add_popup
-                    {
-                        let mut key_str = String::new();
-                        if key.modifiers.contains(KeyModifiers::CONTROL) {
-                            key_str.push_str("Ctrl+");
-                        }
-                        if key.modifiers.contains(KeyModifiers::ALT) {
-                            key_str.push_str("Alt+");
-                        }
-                        if key.modifiers.contains(KeyModifiers::SHIFT)
-                            && !matches!(key.code, event::KeyCode::Char(_))
-                        {
-                            key_str.push_str("Shift+");
-                        }
-
-                        let code_str = match key.code {
-                            event::KeyCode::Char(c) => c.to_string(),
-                            event::KeyCode::Enter => "Enter".to_string(),
-                            event::KeyCode::Backspace => "Backspace".to_string(),
-                            event::KeyCode::Left => "Left".to_string(),
-                            event::KeyCode::Right => "Right".to_string(),
-                            event::KeyCode::Up => "Up".to_string(),
-                            event::KeyCode::Down => "Down".to_string(),
-                            event::KeyCode::Tab => "Tab".to_string(),
-                            event::KeyCode::Delete => "Delete".to_string(),
-                            event::KeyCode::Home => "Home".to_string(),
-                            event::KeyCode::End => "End".to_string(),
-                            event::KeyCode::PageUp => "PageUp".to_string(),
-                            event::KeyCode::PageDown => "PageDown".to_string(),
-                            event::KeyCode::Esc => "Esc".to_string(),
-                            event::KeyCode::F(n) => format!("F{}", n),
-                            _ => format!("{:?}", key.code),
-                        };
-                        key_str.push_str(&code_str);
-                        state.ui.last_key_press = Some((key_str, Instant::now()));
-                    }
+fn on_interfaces(state: &mut AppState, result: WifiResult<Vec<InterfaceInfo>>) {
+    state.network.interfaces_rx = None;
+    if let Ok(interfaces) = result {
+        // The backend uses the first adapter until another one is picked
+        if state.network.active_interface.is_none() {
+            state.network.active_interface = interfaces.first().cloned();
+        }
+        state.network.interfaces = interfaces;
+    }
+}
 
-                    // Clear error message on any key press
-                    if state.ui.error_message.is_some() {
-                        state.ui.error_message = None;
-                    }
+fn on_radio_state(state: &mut AppState, result: WifiResult<RadioState>) {
+    state.refresh.radio_rx = None;
+    match result {
+        Ok(radio) if state.network.radio != Some(radio) => {
+            let was_known = state.network.radio.is_some();
+            state.network.radio = Some(radio);
+            logging::info(radio.label());
+            if !radio.is_on() {
+                // Scans fail or return stale results while the radio is off
+                state.network.replace_networks(Vec::new());
+                state.network.connected_ssid = None;
+                state.update_filtered_list();
+            } else if was_known && !state.refresh.is_refreshing_networks {
+                start_network_refresh(state);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => state.show_error(format!("WiFi radio: {}", e)),
+    }
+}
 
-                    // Global shortcuts
-                    if key.code == event::KeyCode::Char('c')
-                        && key.modifiers.contains(KeyModifiers::CONTROL)
-                    {
-                        break;
-                    }
+fn on_random_mac(state: &mut AppState, result: WifiResult<(String, bool)>) {
+    state.connection.random_mac_rx = None;
+    match result {
+        Ok((ssid, enabled)) => {
+            let setting = if enabled { "on" } else { "off" };
+            state.set_status(true, format!("Random MAC {} for {}", setting, ssid));
+            let mut message = format!("Random MAC {} for {}", setting, ssid);
+            if state.network.connected_ssid.as_ref() == Some(&ssid) {
+                message.push_str(" (applies on reconnect)");
+            }
+            state.show_success(message);
+        }
+        Err(e) => {
+            state.set_status(false, "Changing the MAC setting failed");
+            state.show_error(format!("Failed to change MAC setting: {}", e));
+        }
+    }
+}
 
-                    // Route to appropriate handler
-                    let should_quit = if state.ui.show_qr_popup {
-                        handle_qr_popup(key, state)
-                    } else if state.ui.show_manual_add_popup {
-                        handle_manual_add_popup(key, state)
-                    } else if state.ui.show_password_popup {
-                        handle_password_popup(key, state)
-                    } else if state.ui.is_searching {
-                        handle_search_mode(key, state)
-                    } else {
-                        handle_main_view(key, state)
-                    };
+/// Record a profile's metered setting, read in the background or just changed
+fn on_metered(state: &mut AppState, ssid: String, result: WifiResult<bool>, changed: bool) {
+    if changed {
+        state.connection.metered_set_rx = None;
+    } else {
+        state.connection.metered_rx = None;
+    }
+    match result {
+        Ok(metered) => {
+            if changed {
+                let message = if metered {
+                    format!("{} is now a metered connection", ssid)
+                } else {
+                    format!("{} is no longer metered", ssid)
+                };
+                state.set_status(true, message.clone());
+                state.show_success(message);
+            }
+            state.network.metered.insert(ssid, Some(metered));
+        }
+        Err(e) if changed => {
+            state.set_status(
+                false,
+                format!("Changing the metered setting of {} failed", ssid),
+            );
+            state.show_error(format!("Failed to change metered setting: {}", e));
+        }
+        Err(e) => {
+            logging::warn(format!("Failed to read metered setting of {}: {}", ssid, e));
+            state.network.metered.insert(ssid, None);
+        }
+    }
+}
 
-                    if should_quit {
-                        break;
-                    }
+fn on_connection_event(state: &mut AppState, event: ConnectionEvent) {
+    match &event {
+        ConnectionEvent::Connected(ssid) => logging::info(format!("Connected to {}", ssid)),
+        ConnectionEvent::Disconnected => logging::info("Disconnected"),
+        ConnectionEvent::ScanComplete => {}
+        ConnectionEvent::Roamed { ssid, bssid } => logging::info(format!(
+            "Roamed to {} on {}",
+            crate::wifi::format_bssid(bssid),
+            ssid
+        )),
+        ConnectionEvent::Failed {
+            ssid,
+            reason_code,
+            reason_str,
+        } => logging::warn(format!(
+            "Connection to {} failed: {} (0x{:X})",
+            ssid, reason_str, reason_code
+        )),
+    }
+    match event {
+        ConnectionEvent::Connected(ssid) => {
+            state.notify("WiFi connected", &format!("Connected to {}", ssid));
+            state.set_status(true, format!("Connected to {}", ssid));
+            if state.watchdog.ssid.as_ref() == Some(&ssid) {
+                state.show_success(format!("Reconnected to {}", ssid));
+            }
+            // Any connection ends the watchdog, including one the user picked
+            state.watchdog.stop();
+            state.watchdog.expect_disconnect = false;
+            start_connectivity_probe(state, &ssid);
+            start_latency_monitor(state, &ssid);
+            if let Some(target) = &state.connection.target_ssid
+                && *target == ssid
+            {
+                state.connection.is_connecting = false;
+                state.connection.target_ssid = None;
+                state.connection.connection_start_time = None;
+                state.refresh.refresh_burst = config::DISCONNECT_REFRESH_BURST;
+            }
+        }
+        ConnectionEvent::Disconnected => {
+            if let Some(ssid) = &state.network.connected_ssid {
+                state.notify("WiFi disconnected", &format!("Disconnected from {}", ssid));
+            }
+            // Switching networks also disconnects first, so only a drop nobody asked for counts
+            let expected = std::mem::take(&mut state.watchdog.expect_disconnect);
+            if state.config.current.watchdog
+                && !expected
+                && !state.connection.is_connecting
+                && state.watchdog.ssid.is_none()
+                && let Some(ssid) = state.network.connected_ssid.clone()
+            {
+                logging::info(format!("Watchdog: {} dropped, reconnecting", ssid));
+                state.watchdog.start(ssid);
+            }
+            state.connection.connectivity = None;
+            state.connection.ip_info = None;
+            state.monitor.frame_rates.clear();
+            stop_latency_monitor(state);
+            state.refresh.refresh_burst = config::DISCONNECT_REFRESH_BURST;
+        }
+        ConnectionEvent::Failed {
+            ssid,
+            reason_code,
+            reason_str,
+        } => {
+            state.notify(
+                "WiFi connection failed",
+                &format!("Could not connect to {}: {}", ssid, reason_str),
+            );
+            if let Some(target) = &state.connection.target_ssid
+                && *target == ssid
+            {
+                state.set_status(false, format!("Connecting to {} failed", ssid));
+                state.connection.is_connecting = false;
+                state.connection.target_ssid = None;
+                state.connection.connection_start_time = None;
+                if is_wrong_password(reason_code) && !state.is_popup_open() {
+                    ask_password_again(state, ssid, reason_str);
+                } else {
+                    state.show_error(format!("Connection failed: {}", reason_str));
                 }
             }
-        } else {
+        }
+        ConnectionEvent::ScanComplete => {
+            if let Some(scan_done_tx) = state.refresh.scan_done_tx.take() {
+                let _ = scan_done_tx.send(());
+            }
+        }
+        ConnectionEvent::Roamed { ssid, bssid } => {
+            let from = state
+                .network
+                .wifi_list
+                .iter()
+                .find(|w| w.is_connected && w.ssid == ssid)
+                .and_then(|w| w.connected_bssid);
+            state.record_roam(&ssid, from, bssid);
+            // The list still shows the access point it left
+            state.refresh.refresh_burst = config::DISCONNECT_REFRESH_BURST;
+        }
+    }
+}
 
-            if state.connection.is_connecting || state.refresh.is_initial_loading {
-                state.ui.loading_frame = (state.ui.loading_frame + 1) % 10;
+/// Reopen the password popup for a network that rejected its password;
+/// connecting with the new one replaces the profile holding the wrong one
+fn ask_password_again(state: &mut AppState, ssid: String, error: String) {
+    if let Some(saved) = state
+        .network
+        .wifi_list
+        .iter()
+        .find(|w| w.ssid == ssid && w.is_saved)
+    {
+        state.connection.connecting_to_auth = Some(saved.authentication.clone());
+    }
+    state.inputs.password_input.clear();
+    state.connection.connecting_to_ssid = Some(ssid);
+    state.ui.password_error = Some(error);
+    state.ui.show_password_popup = true;
+}
+
+/// Retry a dropped network once its backoff has passed and it shows up in scans
+fn run_watchdog(state: &mut AppState) {
+    let Some(ssid) = state.watchdog.ssid.clone() else {
+        return;
+    };
+    if !state.config.current.watchdog
+        || state
+            .network
+            .connected_ssid
+            .as_ref()
+            .is_some_and(|connected| *connected != ssid)
+    {
+        state.watchdog.stop();
+        return;
+    }
+    if state
+        .watchdog
+        .next_attempt
+        .is_some_and(|t| t > Instant::now())
+        || state.connection.is_connecting
+        || state.tasks.is_busy()
+    {
+        return;
+    }
+
+    if state.watchdog.attempts >= config::WATCHDOG_MAX_ATTEMPTS {
+        state.watchdog.stop();
+        state.show_error(format!(
+            "Gave up reconnecting to {} after {} attempts",
+            ssid,
+            config::WATCHDOG_MAX_ATTEMPTS
+        ));
+    } else if state.network.in_range().iter().any(|w| w.ssid == ssid) {
+        state.watchdog.record_attempt();
+        logging::info(format!(
+            "Watchdog: reconnecting to {} (attempt {})",
+            ssid, state.watchdog.attempts
+        ));
+        start_profile_connection(state, ssid);
+    }
+}
+
+/// Deadlines checked after every wake-up of the loop
+fn run_timers(state: &mut AppState) {
+    state.expire_revealed_password();
+    state.expire_forgotten_profile();
+    state.expire_toasts();
+    if state.network.presence.expire_arrivals() {
+        state.network.list_rows = None;
+    }
+    if let Some(pending) = state.ui.pending_keys
+        && pending.is_due()
+    {
+        state.ui.pending_keys = None;
+        finish_pending_keys(state, pending);
+    }
+    if state.ui.search_due.is_some_and(|due| due <= Instant::now()) {
+        state.update_filtered_list();
+    }
+    state.clipboard.clear_if_due();
+
+    // Take the guest network down once its time is up
+    if !state.hotspot.is_busy
+        && state
+            .hotspot
+            .guest
+            .as_ref()
+            .and_then(|g| g.remaining())
+            .is_some_and(|left| left.is_zero())
+    {
+        remove_guest_network(state);
+    }
+
+    // Keep the client count current while the hotspot popup is open
+    if state.ui.show_hotspot_popup
+        && state.hotspot.status_rx.is_none()
+        && state.hotspot.last_fetch.is_none_or(|t| {
+            t.elapsed() >= Duration::from_secs(config::HOTSPOT_STATUS_INTERVAL_SECS)
+        })
+    {
+        fetch_hotspot_status(state);
+    }
+
+    // Frame counters only change while connected
+    if state.network.connected_ssid.is_some()
+        && state.monitor.stats_rx.is_none()
+        && state
+            .monitor
+            .stats_fetched_at
+            .is_none_or(|t| t.elapsed() >= Duration::from_secs(config::STATS_INTERVAL_SECS))
+    {
+        fetch_interface_stats(state);
+    }
+
+    // Keep probing while behind a captive portal so the banner clears after logging in
+    if state.connection.connectivity_rx.is_none()
+        && state
+            .connection
+            .connectivity
+            .as_ref()
+            .is_some_and(|(_, connectivity)| connectivity.portal_url().is_some())
+        && state
+            .connection
+            .connectivity_checked_at
+            .is_none_or(|t| t.elapsed() >= Duration::from_secs(config::CAPTIVE_PORTAL_RECHECK_SECS))
+    {
+        recheck_connectivity(state);
+    }
+
+    run_watchdog(state);
+
+    // Read the metered setting of the selected saved network for the details pane
+    if state.connection.metered_rx.is_none()
+        && let Some(wifi) = state
+            .ui
+            .l_state
+            .selected()
+            .and_then(|i| state.network.filtered_wifi_list.get(i))
+        && wifi.is_saved
+        && !state.network.metered.contains_key(&wifi.ssid)
+    {
+        let ssid = wifi.ssid.clone();
+        fetch_metered(state, ssid);
+    }
+
+    // Check if connected to target SSID
+    if state.connection.is_connecting {
+        if let Some(target) = &state.connection.target_ssid {
+            if let Some(connected) = &state.network.connected_ssid
+                && connected == target
+            {
+                state.connection.is_connecting = false;
+                state.connection.target_ssid = None;
+                state.connection.connection_start_time = None;
+            }
+
+            // Check for timeout
+            if let Some(start_time) = state.connection.connection_start_time
+                && start_time.elapsed()
+                    > Duration::from_secs(state.config.current.connection_timeout_secs)
+            {
+                logging::warn(format!(
+                    "Connecting to {} timed out after {}s",
+                    state.connection.target_ssid.as_deref().unwrap_or_default(),
+                    state.config.current.connection_timeout_secs
+                ));
+                state.connection.is_connecting = false;
+                state.connection.target_ssid = None;
+                state.connection.connection_start_time = None;
+                state.show_error("Connection timed out (No response from OS)".to_string());
+                state.notify("WiFi connection failed", "Connection timed out");
+            }
+        } else {
+            // If no target SSID is set but is_connecting is true, check connection result
+            if !state.tasks.is_busy() {
+                state.connection.is_connecting = false;
             }
         }
     }
-    Ok(())
+
+    // Auto-refresh logic
+    let refresh_interval = if state.refresh.refresh_burst > 0 {
+        Duration::from_secs(state.config.current.burst_refresh_secs)
+    } else if state.ui.is_searching || !state.inputs.search_input.value.is_empty() {
+        Duration::from_secs(state.config.current.searching_refresh_secs)
+    } else {
+        Duration::from_secs(state.config.current.auto_refresh_secs)
+    };
+
+    if !state.refresh.is_refreshing_networks
+        && !state.ui.show_manual_add_popup
+        && !state.ui.show_password_popup
+        && !state.ui.show_qr_popup
+        && !state.ui.show_share_password_popup
+        && !state.ui.show_survey_label_popup
+        && !state.ui.show_compare_popup
+        && !state.ui.show_command_palette
+        && !state.ui.show_command_line
+        && !state.ui.show_hotspot_popup
+        && !state.ui.show_profiles_popup
+        && !state.ui.show_speedtest_popup
+        && !state.ui.show_wps_popup
+        && state.ui.confirmation.is_none()
+        // Scanning while the test runs would skew its results
+        && state.speedtest.updates_rx.is_none()
+        && state.refresh.last_refresh.elapsed() >= refresh_interval
+        && state.refresh.last_interaction.elapsed()
+            >= Duration::from_secs(config::INTERACTION_COOLDOWN_SECS)
+    {
+        if state.refresh.refresh_burst > 0 {
+            state.refresh.refresh_burst -= 1;
+        }
+        spawn_network_update(state);
+    }
 }
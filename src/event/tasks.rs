@@ -0,0 +1,80 @@
+//! Background WiFi operations
+//!
+//! WiFi calls block, so they run on the blocking pool and hand their result
+//! to the event loop on a channel that one of its `on_*` handlers picks up.
+//! Actions decide what to run; these helpers do the spawning.
+
+use crate::app::{AppState, OperationKind, OperationOutput};
+use crate::error::{WifiError, WifiResult};
+use crate::wifi::{disconnect_and_wait, get_connected_ssid};
+use std::time::Instant;
+use tokio::sync::mpsc::{self, Receiver};
+
+/// Run a blocking WiFi call; its result arrives on the returned channel
+pub fn spawn_blocking_op<T, F>(op: F) -> Receiver<WifiResult<T>>
+where
+    T: Send + 'static,
+    F: FnOnce() -> WifiResult<T> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(1);
+    tokio::spawn(async move {
+        let result = tokio::task::spawn_blocking(op)
+            .await
+            .unwrap_or_else(|e| Err(WifiError::Internal(e.to_string())));
+        let _ = tx.send(result).await;
+    });
+    rx
+}
+
+/// Whether `kind` on `ssid` may start now; says what it waits for if not
+pub fn can_start(state: &mut AppState, kind: OperationKind, ssid: &str) -> bool {
+    if let Some(running) = state.tasks.conflict(kind, ssid) {
+        state.show_error(format!("Busy {}, try again shortly", running.describe()));
+        return false;
+    }
+    true
+}
+
+/// Run a WiFi operation on `ssid` unless it clashes with one already in
+/// flight; its result reaches `on_operation_result`. Returns whether it started
+pub fn spawn_wifi_op<F, T>(state: &mut AppState, kind: OperationKind, ssid: String, op: F) -> bool
+where
+    F: FnOnce() -> WifiResult<T> + Send + 'static,
+    T: Into<OperationOutput>,
+{
+    if !can_start(state, kind, &ssid) {
+        return false;
+    }
+    let operation = state.tasks.start(kind, ssid);
+    let tx = state.tasks.result_tx.clone();
+    tokio::spawn(async move {
+        let result = tokio::task::spawn_blocking(move || op().map(Into::into))
+            .await
+            .unwrap_or_else(|e| Err(WifiError::Internal(e.to_string())));
+        let _ = tx.send((operation, result));
+    });
+    true
+}
+
+/// Start joining `ssid` with `connect`, showing the spinner until the
+/// listener reports the outcome or the attempt times out
+pub fn start_connection<F, T>(state: &mut AppState, ssid: String, connect: F) -> bool
+where
+    F: FnOnce() -> WifiResult<T> + Send + 'static,
+    T: Into<OperationOutput>,
+{
+    if !spawn_wifi_op(state, OperationKind::Connect, ssid.clone(), connect) {
+        return false;
+    }
+    state.connection.is_connecting = true;
+    state.connection.target_ssid = Some(ssid);
+    state.connection.connection_start_time = Some(Instant::now());
+    true
+}
+
+/// Disconnect from the current network, if any, before joining another one
+pub fn leave_current_network() {
+    if get_connected_ssid().unwrap_or(None).is_some() {
+        let _ = disconnect_and_wait();
+    }
+}
@@ -0,0 +1,79 @@
+//! Quick filters for the network list
+//!
+//! Presets cycled with `F` that narrow the list on top of the fuzzy search.
+
+use crate::wifi::{Band, WifiInfo};
+
+/// Which networks the list shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListFilter {
+    #[default]
+    All,
+    /// Networks with a saved profile
+    Saved,
+    /// Networks without a password
+    Open,
+    /// Networks with at least one 5 GHz access point
+    Ghz5,
+}
+
+impl ListFilter {
+    pub const ALL: [ListFilter; 4] = [
+        ListFilter::All,
+        ListFilter::Saved,
+        ListFilter::Open,
+        ListFilter::Ghz5,
+    ];
+
+    /// Short label for the list title and toasts
+    pub fn label(self) -> &'static str {
+        match self {
+            ListFilter::All => "all",
+            ListFilter::Saved => "saved",
+            ListFilter::Open => "open",
+            ListFilter::Ghz5 => "5 GHz",
+        }
+    }
+
+    /// Name used in the session file
+    pub fn name(self) -> &'static str {
+        match self {
+            ListFilter::All => "all",
+            ListFilter::Saved => "saved",
+            ListFilter::Open => "open",
+            ListFilter::Ghz5 => "5ghz",
+        }
+    }
+
+    /// Parse a `:filter` argument, e.g. `saved` or `5ghz`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace(' ', "").as_str() {
+            "all" | "none" | "off" => Some(ListFilter::All),
+            "saved" => Some(ListFilter::Saved),
+            "open" => Some(ListFilter::Open),
+            "5" | "5ghz" => Some(ListFilter::Ghz5),
+            _ => None,
+        }
+    }
+
+    /// The next preset in the cycle
+    pub fn next(self) -> Self {
+        let pos = Self::ALL.iter().position(|&f| f == self).unwrap_or(0);
+        Self::ALL[(pos + 1) % Self::ALL.len()]
+    }
+
+    pub fn matches(self, wifi: &WifiInfo) -> bool {
+        match self {
+            ListFilter::All => true,
+            ListFilter::Saved => wifi.is_saved,
+            ListFilter::Open => wifi.authentication == "Open",
+            ListFilter::Ghz5 => {
+                Band::from_frequency(wifi.frequency) == Some(Band::Ghz5)
+                    || wifi
+                        .bss_list
+                        .iter()
+                        .any(|bss| bss.band() == Some(Band::Ghz5))
+            }
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Temporary guest networks
+//!
+//! A guest network is the mobile hotspot started under a throwaway SSID with
+//! a random passphrase. Its QR code is shown as soon as it is up, and the
+//! hotspot is stopped and its profile removed once `guest_ttl_mins` is over.
+
+use crate::config;
+use secrecy::SecretString;
+use std::time::{Duration, Instant};
+use wifui_core::random::random_string;
+
+#[derive(Debug)]
+pub struct GuestNetwork {
+    pub ssid: String,
+    pub passphrase: SecretString,
+    /// Set once the hotspot is up; `None` while it is starting
+    pub expires_at: Option<Instant>,
+}
+
+impl GuestNetwork {
+    pub fn generate() -> Self {
+        Self {
+            ssid: format!("{}-{}", config::GUEST_SSID_PREFIX, random_string(4)),
+            passphrase: SecretString::from(random_string(config::GUEST_PASSPHRASE_LEN)),
+            expires_at: None,
+        }
+    }
+
+    /// Time left before the guest network is taken down
+    pub fn remaining(&self) -> Option<Duration> {
+        self.expires_at
+            .map(|t| t.saturating_duration_since(Instant::now()))
+    }
+}
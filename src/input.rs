@@ -25,6 +25,14 @@ impl InputState {
         self.cursor += 1;
     }
 
+    /// Insert pasted text at the cursor; line breaks and other control
+    /// characters are dropped since every field is a single line
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.chars().filter(|c| !c.is_control()) {
+            self.insert(c);
+        }
+    }
+
     pub fn backspace(&mut self) {
         if self.cursor > 0 {
             let byte_idx = self
@@ -0,0 +1,261 @@
+//! Key bindings for the main view
+//!
+//! Maps key presses to [`Action`]s. The defaults can be replaced per action
+//! from the `[keys]` table of the config file, e.g. `forget = "F"` or
+//! `down = ["down"]`; an empty list unbinds the action.
+
+use crate::action::Action;
+use crate::config::KeySpec;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+
+/// Built-in bindings; the first key of each action is shown as its hint
+const DEFAULT_BINDINGS: &[(&str, Action)] = &[
+    ("ctrl+p", Action::CommandPalette),
+    ("ctrl+[", Action::ClearSearch),
+    ("/", Action::Search),
+    // Only while searching, see [`Action::needs_search`]
+    ("n", Action::NextMatch),
+    ("N", Action::PrevMatch),
+    ("n", Action::AddNetwork),
+    ("J", Action::JoinUri),
+    ("esc", Action::Back),
+    ("q", Action::Quit),
+    ("j", Action::Down),
+    ("down", Action::Down),
+    ("k", Action::Up),
+    ("up", Action::Up),
+    ("g", Action::Top),
+    ("home", Action::Top),
+    ("G", Action::Bottom),
+    ("end", Action::Bottom),
+    ("enter", Action::Connect),
+    ("1", Action::QuickConnect(1)),
+    ("2", Action::QuickConnect(2)),
+    ("3", Action::QuickConnect(3)),
+    ("4", Action::QuickConnect(4)),
+    ("5", Action::QuickConnect(5)),
+    ("6", Action::QuickConnect(6)),
+    ("7", Action::QuickConnect(7)),
+    ("8", Action::QuickConnect(8)),
+    ("9", Action::QuickConnect(9)),
+    ("r", Action::Refresh),
+    ("a", Action::ToggleAutoConnect),
+    ("M", Action::ToggleRandomMac),
+    ("$", Action::ToggleMetered),
+    ("f", Action::Forget),
+    ("u", Action::UndoForget),
+    ("U", Action::RecreateProfile),
+    ("w", Action::WpsConnect),
+    ("m", Action::ToggleMark),
+    ("c", Action::Compare),
+    ("b", Action::ToggleAccessPoints),
+    ("]", Action::NextDetailsTab),
+    ("alt+l", Action::NextDetailsTab),
+    ("[", Action::PrevDetailsTab),
+    ("alt+h", Action::PrevDetailsTab),
+    ("alt+1", Action::DetailsTab(1)),
+    ("alt+2", Action::DetailsTab(2)),
+    ("alt+3", Action::DetailsTab(3)),
+    ("alt+4", Action::DetailsTab(4)),
+    ("s", Action::Share),
+    ("p", Action::RevealPassword),
+    ("y", Action::CopyPassword),
+    ("Y", Action::CopySsid),
+    ("o", Action::OpenPortal),
+    ("O", Action::CycleSort),
+    ("F", Action::CycleFilter),
+    ("S", Action::SpeedTest),
+    ("C", Action::ChannelGraph),
+    ("B", Action::SwitchBand),
+    ("A", Action::RoamNow),
+    ("tab", Action::NextInterface),
+    ("W", Action::ToggleRadio),
+    ("h", Action::Hotspot),
+    ("N", Action::GuestNetwork),
+    ("P", Action::SavedProfiles),
+    ("E", Action::EditProfile),
+    ("ctrl+r", Action::RenameProfile),
+    ("e", Action::ExportProfile),
+    ("R", Action::ReloadConfig),
+    ("f12", Action::ToggleDebugLog),
+    ("D", Action::ToggleDebugPane),
+    ("L", Action::ToggleSignalLog),
+    ("V", Action::ToggleSurvey),
+    ("t", Action::TagSurvey),
+    (":", Action::CommandLine),
+    ("?", Action::Help),
+];
+
+/// A key with its required modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Parse a key like `f`, `G`, `enter`, `ctrl+p` or `alt+down`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+        loop {
+            let lower = rest.to_lowercase();
+            if lower.starts_with("ctrl+") && rest.len() > 5 {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if lower.starts_with("alt+") && rest.len() > 4 {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("Unknown key: {}", text)),
+                },
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    /// Whether a key press triggers this binding
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        // Shift is already reflected in the character ('G' vs 'g')
+        let pressed = match key.code {
+            KeyCode::Char(_) => key.modifiers - KeyModifiers::SHIFT,
+            _ => key.modifiers,
+        };
+        self.code == key.code && self.modifiers == pressed
+    }
+
+    /// Short label for the help bar and command palette
+    pub fn label(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "enter".to_string(),
+            KeyCode::Esc => "esc".to_string(),
+            KeyCode::Tab => "tab".to_string(),
+            KeyCode::BackTab => "shift+tab".to_string(),
+            KeyCode::Backspace => "backspace".to_string(),
+            KeyCode::Delete => "del".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Home => "home".to_string(),
+            KeyCode::End => "end".to_string(),
+            KeyCode::PageUp => "pgup".to_string(),
+            KeyCode::PageDown => "pgdn".to_string(),
+            KeyCode::F(n) => format!("f{}", n),
+            _ => "?".to_string(),
+        };
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("alt+");
+        }
+        label + &key
+    }
+}
+
+/// Active key bindings, checked in order
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&BTreeMap::new()).expect("default key bindings are valid")
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from the `[keys]` config table on top of the defaults
+    pub fn from_config(keys: &BTreeMap<String, KeySpec>) -> Result<Self, String> {
+        let mut bindings = Vec::new();
+        let mut overridden = Vec::new();
+
+        // Configured keys come first so they win over any default using the same key
+        for (name, spec) in keys {
+            let action =
+                Action::from_name(name).ok_or_else(|| format!("Unknown action: {}", name))?;
+            for key in spec.keys() {
+                bindings.push((KeyBinding::parse(key)?, action));
+            }
+            overridden.push(action);
+        }
+        for &(key, action) in DEFAULT_BINDINGS {
+            if !overridden.contains(&action) {
+                bindings.push((KeyBinding::parse(key)?, action));
+            }
+        }
+
+        Ok(Self { bindings })
+    }
+
+    /// The action bound to a key press, if any; search-only actions are
+    /// skipped unless `searching`
+    pub fn action(&self, key: &KeyEvent, searching: bool) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|&&(binding, action)| {
+                binding.matches(key) && (searching || !action.needs_search())
+            })
+            .map(|&(_, action)| action)
+    }
+
+    /// Labels of all keys bound to an action, in the order they are checked
+    pub fn keys(&self, action: Action) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|&&(_, bound)| bound == action)
+            .map(|(binding, _)| binding.label())
+            .collect()
+    }
+
+    /// Every action with at least one key, in binding order
+    pub fn bound_actions(&self) -> Vec<Action> {
+        let mut actions: Vec<Action> = Vec::new();
+        for &(_, action) in &self.bindings {
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+        actions
+    }
+
+    /// Label of the first key bound to an action, or an empty string if unbound
+    pub fn hint(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|&&(_, bound)| bound == action)
+            .map(|(binding, _)| binding.label())
+            .unwrap_or_default()
+    }
+}
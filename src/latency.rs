@@ -0,0 +1,78 @@
+//! Latency monitor for the active connection
+//!
+//! While connected, a background task pings the gateway (and optionally a
+//! public resolver) every couple of seconds. The recent samples give the
+//! latency, jitter and packet loss shown in the details pane.
+
+use crate::config;
+use std::collections::VecDeque;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+/// Host pinged by the monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PingTarget {
+    Gateway,
+    Internet,
+}
+
+/// Messages from the monitor task
+#[derive(Debug, Clone)]
+pub enum LatencyUpdate {
+    /// The gateway was looked up; `None` if the connection has none
+    Gateway(Option<Ipv4Addr>),
+    /// One echo request; `None` if it went unanswered
+    Sample(PingTarget, Option<Duration>),
+    /// Pinging is not possible on this system
+    Failed(String),
+}
+
+/// Recent ping results for one host, oldest first
+#[derive(Debug, Default)]
+pub struct LatencyStats {
+    samples: VecDeque<Option<Duration>>,
+}
+
+impl LatencyStats {
+    pub fn push(&mut self, rtt: Option<Duration>) {
+        if self.samples.len() >= config::LATENCY_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(rtt);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    fn replies_ms(&self) -> impl Iterator<Item = f64> + '_ {
+        self.samples
+            .iter()
+            .flatten()
+            .map(|rtt| rtt.as_secs_f64() * 1000.0)
+    }
+
+    /// Round trip time of the latest reply
+    pub fn latest_ms(&self) -> Option<f64> {
+        self.replies_ms().last()
+    }
+
+    /// Mean difference between consecutive replies
+    pub fn jitter_ms(&self) -> Option<f64> {
+        let replies: Vec<f64> = self.replies_ms().collect();
+        if replies.len() < 2 {
+            return None;
+        }
+        let total: f64 = replies.windows(2).map(|w| (w[1] - w[0]).abs()).sum();
+        Some(total / (replies.len() - 1) as f64)
+    }
+
+    /// Share of unanswered requests, 0 to 100
+    pub fn loss_percent(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let lost = self.samples.iter().filter(|rtt| rtt.is_none()).count();
+        lost as f64 * 100.0 / self.samples.len() as f64
+    }
+}
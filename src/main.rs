@@ -1,77 +1,275 @@
+mod action;
 mod app;
+mod channels;
+mod cli;
+mod clipboard;
+mod compare;
 mod config;
+mod counters;
+mod daemon;
 mod error;
 mod event;
+mod filter;
+mod guest;
 mod input;
+mod keymap;
+mod latency;
+mod monitor;
+mod net;
+mod notify;
+mod oui;
+mod output;
+mod presence;
+mod query;
+mod record;
+mod roaming;
+mod rogue;
+mod rules;
+mod scan_monitor;
+mod session;
+mod share;
+mod snapshot;
+mod sort;
+mod speedtest;
+mod steering;
+mod survey;
 mod theme;
 mod ui;
-mod wifi;
 
 use clap::Parser;
 use color_eyre::eyre::Result;
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use crossterm::{
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture,
+    },
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+use std::path::{Path, PathBuf};
+use tracing_subscriber::{
+    Layer, filter::LevelFilter, layer::SubscriberExt, util::SubscriberInitExt,
+};
+// The WiFi layer lives in the wifui-core library; keep the `crate::` paths
+use wifui_core::{logging, perf, wifi};
 
 use crate::{
-    app::AppState,
+    app::{AppState, ConfigState, MonitorState},
+    config::{Config, ConfigOverrides},
     event::run,
-    wifi::{get_connected_ssid, get_wifi_networks, scan_networks},
+    session::Session,
+    wifi::remove_hotspot,
 };
 
-/// A lightweight, keyboard-driven TUI for managing Wi-Fi connections on Windows
+/// A lightweight, keyboard-driven TUI for managing Wi-Fi connections on Windows and Linux
 #[derive(Parser, Debug)]
 #[command(
     name = "wifui",
     author = "Soham Waghmare",
-    about = "A lightweight, keyboard-driven TUI for managing Wi-Fi connections on Windows.\n\nAuthor: Soham Waghmare",
+    about = "A lightweight, keyboard-driven TUI for managing Wi-Fi connections on Windows and Linux.\n\nAuthor: Soham Waghmare",
     long_about = None,
     version = env!("CARGO_PKG_VERSION"),
     disable_version_flag = true
 )]
 struct Args {
+    #[command(subcommand)]
+    command: Option<cli::Command>,
+
+    /// Path to the config file (reload at runtime with R)
+    #[arg(short = 'c', long = "config", value_name = "PATH")]
+    config: Option<PathBuf>,
+
     /// Print version information
     #[arg(short = 'v', long = "version", action = clap::ArgAction::Version)]
     version: (),
 
+    /// Show a debug pane with WiFi API calls, durations and failure reasons (toggle with D)
+    #[arg(long)]
+    debug: bool,
+
+    /// Append the debug log to this file, e.g. to attach to a bug report
+    #[arg(long = "log-file", value_name = "PATH")]
+    log_file: Option<PathBuf>,
+
+    /// Also log the timing of every successful WiFi call to the log file
+    #[arg(long, requires = "log_file")]
+    verbose: bool,
+
+    /// Show an overlay with frame, refresh and WiFi call timings
+    #[arg(long)]
+    perf: bool,
+
     /// Use ASCII icons (no Nerd Fonts required)
     #[arg(long)]
     ascii: bool,
 
+    /// Color theme: dark, light, gruvbox, catppuccin or one from the config's [themes] table
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
+
     /// Show key logger for debugging
     #[arg(long = "show-keys")]
     show_keys: bool,
+
+    /// Automatically reconnect to a faster band of the connected network
+    #[arg(long = "auto-band-steer")]
+    auto_band_steer: bool,
+
+    /// Keep reconnecting with backoff when the connected network drops
+    #[arg(long)]
+    watchdog: bool,
+
+    /// Switch to a clearly stronger network from the config's preferred list
+    #[arg(long)]
+    roam: bool,
+
+    /// Alert when the connected network's signal stays below this percentage
+    #[arg(
+        long = "low-signal-threshold",
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(1..=100)
+    )]
+    low_signal_threshold: Option<u8>,
+
+    /// Seconds the signal must stay below the threshold before alerting [default: 30]
+    #[arg(long = "low-signal-secs", value_name = "SECS")]
+    low_signal_secs: Option<u64>,
+
+    /// CSV file for signal logging (toggled with L)
+    #[arg(long = "signal-log", value_name = "PATH")]
+    signal_log: Option<PathBuf>,
+
+    /// Directory where the share popup saves QR codes (w) and profiles are exported (e)
+    #[arg(long = "share-dir", value_name = "DIR")]
+    share_dir: Option<PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
+    let config_path = args.config.unwrap_or_else(config::config_path);
+    init_logging(
+        args.log_file.as_deref(),
+        args.verbose,
+        args.command.is_none(),
+    )?;
+    if let Some(command) = args.command {
+        let config = Config::load(&config_path)?;
+        return cli::run(command, &config);
+    }
 
-    let mut state = AppState::new(Vec::new(), args.show_keys, args.ascii);
-    state.refresh.is_initial_loading = true;
+    let overrides = ConfigOverrides {
+        ascii_icons: args.ascii,
+        low_signal_threshold: args.low_signal_threshold,
+        low_signal_secs: args.low_signal_secs,
+        theme: args.theme,
+        watchdog: args.watchdog,
+        auto_band_steer: args.auto_band_steer,
+        roaming: args.roam,
+    };
+    let (loaded_config, config_error) = match Config::load(&config_path) {
+        Ok(config) => (config, None),
+        Err(e) => (Config::default(), Some(e.to_string())),
+    };
+    let loaded_config = loaded_config.with_overrides(&overrides);
+    // The file was validated without --theme, so check the flag's name here
+    let config_error = config_error.or_else(|| loaded_config.theme().err().map(|e| e.to_string()));
 
-    let (tx, rx) = tokio::sync::mpsc::channel(1);
-    state.refresh.is_refreshing_networks = true;
-    state.refresh.network_update_rx = Some(rx);
-    tokio::spawn(async move {
-        let result = tokio::task::spawn_blocking(|| {
-            let _ = scan_networks();
-            let networks = get_wifi_networks()?;
-            let connected = get_connected_ssid()?;
-            Ok((networks, connected))
-        })
-        .await;
-        let result = match result {
-            Ok(inner) => inner,
-            Err(e) => Err(color_eyre::eyre::eyre!(e.to_string())),
-        };
-        let _ = tx.send(result).await;
-    });
+    let mut state = AppState::new(
+        Vec::new(),
+        args.show_keys,
+        MonitorState::new(
+            args.signal_log
+                .unwrap_or_else(|| config::data_dir().join(config::SIGNAL_LOG_FILE)),
+        ),
+        ConfigState::new(config_path, overrides.clone(), loaded_config),
+    );
+    if let Some(error) = config_error {
+        state.show_error(error);
+    }
+    state.ui.show_debug_pane = args.debug;
+    if args.perf {
+        perf::enable();
+        state.perf.enabled = true;
+    }
+    state.refresh.is_initial_loading = true;
+    if let Some(dir) = args.share_dir {
+        state.ui.share_dir = dir;
+    }
+    let session_path = config::data_dir().join(config::SESSION_FILE);
+    state.restore_session(Session::load(&session_path));
+    let snapshot_path = config::data_dir().join(config::SNAPSHOT_FILE);
+    if let Some(networks) = snapshot::load(&snapshot_path).filter(|n| !n.is_empty()) {
+        state.show_snapshot(networks);
+    }
 
     color_eyre::install()?;
     let terminal = ratatui::init();
     enable_raw_mode()?;
+    crossterm::execute!(
+        std::io::stdout(),
+        EnableMouseCapture,
+        EnableFocusChange,
+        EnableBracketedPaste
+    )?;
     let result = run(terminal, &mut state).await;
+    let _ = crossterm::execute!(
+        std::io::stdout(),
+        DisableMouseCapture,
+        DisableFocusChange,
+        DisableBracketedPaste
+    );
     disable_raw_mode()?;
+    let _ = state.session().save(&session_path);
+    if !state.refresh.showing_snapshot {
+        let _ = snapshot::save(&snapshot_path, &state.network.wifi_list);
+    }
+    // Nothing would take a guest network down once wifui is closed
+    if state.hotspot.guest.is_some() {
+        let _ = remove_hotspot();
+    }
 
     ratatui::restore();
     result
 }
+
+/// Install the `tracing` subscriber: the TUI always records into the debug
+/// pane's buffer, so it has history when it is opened, and `log_file` gets
+/// every entry at info level, or debug with `verbose`
+fn init_logging(log_file: Option<&Path>, verbose: bool, tui: bool) -> Result<()> {
+    let file = log_file
+        .map(|path| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| color_eyre::eyre::eyre!("Can't open {}: {}", path.display(), e))
+        })
+        .transpose()?;
+    let level = if verbose {
+        LevelFilter::DEBUG
+    } else {
+        LevelFilter::INFO
+    };
+    let file_layer = file.map(|file| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::sync::Mutex::new(file))
+            .with_ansi(false)
+            .with_target(false)
+            .with_filter(level)
+    });
+    tracing_subscriber::registry()
+        .with(file_layer)
+        .with(tui.then_some(logging::BufferLayer))
+        .init();
+    if tui || log_file.is_some() {
+        logging::enable();
+    }
+    if log_file.is_some() {
+        logging::info(format!(
+            "wifui {} on {}",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! Signal monitoring
+//!
+//! Appends timestamped samples of the connected network to a CSV file for
+//! long-running signal quality investigations.
+
+use crate::wifi::{WifiInfo, format_bssid};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub use crate::logging::format_timestamp;
+
+const CSV_HEADER: &str = "timestamp,ssid,bssid,signal_percent,rssi_dbm,link_speed_mbps,channel";
+
+/// CSV logger for connected-network signal samples
+#[derive(Debug)]
+pub struct SignalLogger {
+    path: PathBuf,
+    file: File,
+}
+
+impl SignalLogger {
+    /// Open (or create) the CSV file in append mode, writing a header for new files
+    pub fn open(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if is_new {
+            writeln!(file, "{}", CSV_HEADER)?;
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one sample for the connected network
+    pub fn log_sample(&mut self, wifi: &WifiInfo) -> io::Result<()> {
+        let bss = wifi.connected_bss();
+        writeln!(
+            self.file,
+            "{},{},{},{},{},{},{}",
+            format_timestamp(SystemTime::now()),
+            csv_escape(&wifi.ssid),
+            wifi.connected_bssid
+                .as_ref()
+                .map(format_bssid)
+                .unwrap_or_default(),
+            wifi.signal,
+            wifi.rssi
+                .or(bss.map(|b| b.rssi))
+                .map(|rssi| rssi.to_string())
+                .unwrap_or_default(),
+            wifi.link_speed.map(|s| s.to_string()).unwrap_or_default(),
+            bss.map_or(wifi.channel, |b| b.channel),
+        )
+    }
+}
+
+/// Quote a CSV field if it contains separators, quotes or newlines
+pub fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Split one CSV line into fields, undoing [`csv_escape`]
+pub fn csv_split(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
@@ -0,0 +1,193 @@
+//! Internet connectivity probe and captive portal detection
+//!
+//! Being associated with an access point doesn't mean the internet works. After
+//! connecting we resolve a well-known host and fetch a URL that answers with an
+//! empty `204 No Content`; anything else means the traffic was intercepted,
+//! usually by a hotel or airport login page.
+
+use crate::config;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Host answering `GET /generate_204` with an empty 204 response
+const PROBE_HOST: &str = "connectivitycheck.gstatic.com";
+const PROBE_PATH: &str = "/generate_204";
+
+/// Result of a connectivity probe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Connectivity {
+    Checking,
+    Online,
+    NoInternet,
+    /// The probe was answered by a login page, reachable at this URL
+    CaptivePortal(String),
+}
+
+impl Connectivity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Connectivity::Checking => "Checking...",
+            Connectivity::Online => "Online",
+            Connectivity::NoInternet => "No Internet",
+            Connectivity::CaptivePortal(_) => "Captive Portal",
+        }
+    }
+
+    /// Login page URL if the network is behind a captive portal
+    pub fn portal_url(&self) -> Option<&str> {
+        match self {
+            Connectivity::CaptivePortal(url) => Some(url),
+            _ => None,
+        }
+    }
+}
+
+/// A plain `http://` URL split into the parts needed to send a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpUrl {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl HttpUrl {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("Only http:// URLs are supported: {}", url))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .map_err(|_| format!("Invalid port in URL: {}", url))?,
+            ),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(format!("Missing host in URL: {}", url));
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    /// Resolve the host and open a connection with read and write timeouts set
+    pub fn connect(&self, timeout: Duration) -> io::Result<TcpStream> {
+        let addr = (self.host.as_str(), self.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Host not found"))?;
+        let stream = TcpStream::connect_timeout(&addr, timeout)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        Ok(stream)
+    }
+}
+
+/// Check whether the internet is reachable through the current connection
+pub fn probe() -> Connectivity {
+    let timeout = Duration::from_secs(config::CONNECTIVITY_PROBE_TIMEOUT_SECS);
+    // DNS failing is the usual symptom of having no upstream at all
+    let Some(addr) = (PROBE_HOST, 80)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+    else {
+        return Connectivity::NoInternet;
+    };
+    match fetch_probe(addr, timeout) {
+        Some((204, _)) => Connectivity::Online,
+        // Portals that serve their page in place of the probe show it again
+        // when the probe URL is opened in a browser
+        Some((_, location)) => Connectivity::CaptivePortal(
+            location.unwrap_or_else(|| format!("http://{}{}", PROBE_HOST, PROBE_PATH)),
+        ),
+        None => Connectivity::NoInternet,
+    }
+}
+
+/// Send the probe request and return the status code and redirect target
+fn fetch_probe(addr: SocketAddr, timeout: Duration) -> Option<(u16, Option<String>)> {
+    let mut stream = TcpStream::connect_timeout(&addr, timeout).ok()?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        PROBE_PATH, PROBE_HOST
+    )
+    .ok()?;
+
+    // Only the status line and headers are needed
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while buf.len() < 8192 && !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        match stream.read(&mut chunk) {
+            Ok(0) | Err(_) => break,
+            Ok(len) => buf.extend_from_slice(&chunk[..len]),
+        }
+    }
+    let response = String::from_utf8_lossy(&buf);
+    let mut lines = response.lines();
+
+    // e.g. "HTTP/1.1 302 Found"
+    let status = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+    let location = lines
+        .take_while(|line| !line.is_empty())
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim()
+                .eq_ignore_ascii_case("location")
+                .then(|| value.trim().to_string())
+        })
+        .map(|location| {
+            if location.starts_with('/') {
+                format!("http://{}{}", PROBE_HOST, location)
+            } else {
+                location
+            }
+        });
+    Some((status, location))
+}
+
+/// Open a captive portal page in the default browser
+pub fn open_in_browser(url: &str) -> io::Result<()> {
+    // The URL comes from whoever answered the probe, so never hand anything
+    // but a web page to the OS launcher
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Refusing to open non-web URL: {}", url),
+        ));
+    }
+
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("rundll32");
+        command.args(["url.dll,FileProtocolHandler", url]);
+        command
+    };
+    #[cfg(not(windows))]
+    let mut command = {
+        let mut command = Command::new("xdg-open");
+        command.arg(url);
+        command
+    };
+
+    // Keep the launcher's output from drawing over the TUI
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! Desktop notifications for connection events
+//!
+//! Windows shows a toast through WinRT, Linux hands the message to
+//! `notify-send`. Failures are only logged: a missing notification daemon
+//! shouldn't disturb the TUI.
+
+use crate::logging;
+use serde::Deserialize;
+
+/// When to show desktop notifications, set with `notifications` in the config
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyMode {
+    Off,
+    /// Only while the terminal doesn't have focus
+    #[default]
+    Background,
+    Always,
+}
+
+/// Show a notification without blocking the caller
+pub fn send(title: &str, body: &str) {
+    let (title, body) = (title.to_string(), body.to_string());
+    std::thread::spawn(move || {
+        if let Err(e) = show(&title, &body) {
+            logging::warn(format!("Desktop notification failed: {}", e));
+        }
+    });
+}
+
+#[cfg(windows)]
+fn show(title: &str, body: &str) -> windows::core::Result<()> {
+    use quick_xml::escape::escape;
+    use windows::{
+        Data::Xml::Dom::XmlDocument,
+        UI::Notifications::{ToastNotification, ToastNotificationManager},
+        core::HSTRING,
+    };
+
+    // Unpackaged apps have no AppUserModelID of their own; borrow PowerShell's
+    // registered one so the toast is accepted
+    const APP_ID: &str =
+        "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe";
+
+    let xml = format!(
+        "<toast><visual><binding template=\"ToastGeneric\"><text>{}</text><text>{}</text></binding></visual></toast>",
+        escape(title),
+        escape(body)
+    );
+    let document = XmlDocument::new()?;
+    document.LoadXml(&HSTRING::from(xml))?;
+    let toast = ToastNotification::CreateToastNotification(&document)?;
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(APP_ID))?.Show(&toast)
+}
+
+#[cfg(not(windows))]
+fn show(title: &str, body: &str) -> std::io::Result<()> {
+    use std::process::{Command, Stdio};
+
+    let status = Command::new("notify-send")
+        .args(["--app-name=wifui", "--", title, body])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "notify-send exited with {}",
+            status
+        )))
+    }
+}
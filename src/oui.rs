@@ -0,0 +1,188 @@
+//! Access point vendors by OUI
+//!
+//! The first three bytes of a BSSID identify the manufacturer. A short table
+//! of common router, enterprise AP and IoT vendors is bundled; dropping the
+//! IEEE `oui.txt` or Wireshark's `manuf` file into the data directory as
+//! `oui.txt` extends it to every registered vendor.
+
+use crate::config;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Bundled OUIs, as 24-bit prefixes
+const BUILTIN: &[(&str, &[u32])] = &[
+    (
+        "ASUS",
+        &[
+            0x000C6E, 0x00112F, 0x0015F2, 0x001A92, 0x001D60, 0x001FC6, 0x002354, 0x002618,
+            0x04D4C4, 0x08606E, 0x107B44, 0x14DAE9, 0x2C56DC, 0x305A3A, 0x50465D, 0x6045CB,
+            0xAC220B, 0xBCEE7B, 0xF832E4,
+        ],
+    ),
+    (
+        "AVM (FRITZ!Box)",
+        &[
+            0x00040E, 0x00150C, 0x001C4A, 0x001F3F, 0x0024FE, 0x246511, 0x3810D5, 0x3CA62F,
+            0x444E6D, 0x5C4979, 0x7CFF4D, 0x989BCB, 0xBC0543, 0xC02506, 0xC80E14, 0xDC396F,
+            0xE0286D,
+        ],
+    ),
+    (
+        "Apple",
+        &[
+            0x000393, 0x000A95, 0x001EC2, 0x002500, 0x28CFE9, 0x3C0754, 0x406C8F, 0x7CD1C3,
+            0xA45E60, 0xACBC32, 0xF01898,
+        ],
+    ),
+    (
+        "Aruba",
+        &[0x000B86, 0x001A1E, 0x24DEC6, 0x6CF37F, 0x94B40F, 0xD8C7C8],
+    ),
+    ("Cisco", &[0x00000C, 0x004096]),
+    (
+        "Cisco Meraki",
+        &[
+            0x00180A, 0x0C8DDB, 0x3456FE, 0x881544, 0xAC17C8, 0xE0553D, 0xE0CBBC,
+        ],
+    ),
+    (
+        "D-Link",
+        &[
+            0x00055D, 0x000D88, 0x000F3D, 0x001195, 0x001346, 0x0015E9, 0x00179A, 0x00195B,
+            0x001B11, 0x001CF0, 0x001E58, 0x002191, 0x0022B0, 0x002401, 0x00265A, 0x14D64D,
+            0x1C7EE5, 0x28107B, 0xC8BE19,
+        ],
+    ),
+    (
+        "Espressif (ESP32/ESP8266)",
+        &[
+            0x18FE34, 0x240AC4, 0x246F28, 0x30AEA4, 0x3C71BF, 0x5CCF7F, 0x600194, 0x84F3EB,
+            0xA4CF12, 0xBCDDC2, 0xCC50E3, 0xECFABC,
+        ],
+    ),
+    (
+        "Fortinet",
+        &[0x00090F, 0x085B0E, 0x704CA5, 0x906CAC, 0xE81CBA],
+    ),
+    (
+        "Google",
+        &[0x001A11, 0x3C5AB4, 0x546009, 0xF4F5D8, 0xF4F5E8],
+    ),
+    (
+        "Huawei",
+        &[
+            0x00E0FC, 0x001882, 0x001E10, 0x00259E, 0x286ED4, 0x4846FB, 0x70723C, 0x80B686,
+            0x8853D4, 0xACE215, 0xE0247F,
+        ],
+    ),
+    ("Juniper Mist", &[0x5C5B35]),
+    (
+        "Linksys",
+        &[
+            0x000625, 0x000C41, 0x000F66, 0x001217, 0x001310, 0x0014BF, 0x0016B6, 0x001839,
+            0x0018F8, 0x001A70, 0x001C10, 0x001D7E, 0x001EE5, 0x002129, 0x00226B, 0x002369,
+            0x00259C, 0x20AA4B, 0xC0C1C0,
+        ],
+    ),
+    (
+        "MikroTik",
+        &[
+            0x000C42, 0x2CC81B, 0x488F5A, 0x4C5E0C, 0x64D154, 0x6C3B6B, 0x744D28, 0xB869F4,
+            0xCC2DE0, 0xD4CA6D, 0xDC2C6E, 0xE48D8C,
+        ],
+    ),
+    (
+        "Netgear",
+        &[
+            0x00095B, 0x000FB5, 0x00146C, 0x00184D, 0x001B2F, 0x001E2A, 0x001F33, 0x00223F,
+            0x0024B2, 0x0026F2, 0x204E7F, 0x28C68E, 0x2CB05D, 0x30469A, 0x841B5E, 0xA040A0,
+            0xC03F0E, 0xC40415, 0xE091F5,
+        ],
+    ),
+    (
+        "Raspberry Pi",
+        &[0xB827EB, 0xDCA632, 0xE45F01, 0x28CDC1, 0xD83ADD],
+    ),
+    (
+        "Ruckus",
+        &[
+            0x00227F, 0x002482, 0x2C5D93, 0x58B633, 0x74911A, 0xC4017C, 0xEC58EA,
+        ],
+    ),
+    (
+        "TP-Link",
+        &[
+            0x14CC20, 0x50C7BF, 0x60E327, 0x647002, 0x98DED0, 0xC04A00, 0xEC086B, 0xF4F26D,
+        ],
+    ),
+    (
+        "Ubiquiti",
+        &[
+            0x00156D, 0x002722, 0x0418D6, 0x24A43C, 0x44D9E7, 0x687251, 0x788A20, 0x802AA8,
+            0xB4FBE4, 0xDC9FDB, 0xF09FC2, 0xFCECDA,
+        ],
+    ),
+    (
+        "Xiaomi",
+        &[
+            0x286C07, 0x34CE00, 0x50642B, 0x640980, 0x7811DC, 0x8CBEBE, 0xF8A45F,
+        ],
+    ),
+    (
+        "Zyxel",
+        &[
+            0x001349, 0x0019CB, 0x0023F8, 0x00A0C5, 0x404A03, 0x5CF4AB, 0xB0B2DC, 0xC86C87,
+            0xE4186B,
+        ],
+    ),
+];
+
+/// Vendor of the access point behind a BSSID, if known
+pub fn vendor(bssid: &[u8; 6]) -> Option<&'static str> {
+    // Locally administered addresses are made up by the AP itself, typically
+    // for its extra SSIDs, so they don't name a manufacturer
+    if bssid[0] & 0x02 != 0 {
+        return Some("Locally administered");
+    }
+    let oui = u32::from_be_bytes([0, bssid[0], bssid[1], bssid[2]]);
+    if let Some(name) = oui_file().get(&oui) {
+        return Some(name);
+    }
+    BUILTIN
+        .iter()
+        .find(|(_, ouis)| ouis.contains(&oui))
+        .map(|(name, _)| *name)
+}
+
+/// Vendors from the user's `oui.txt`, read on first lookup
+fn oui_file() -> &'static HashMap<u32, String> {
+    static TABLE: OnceLock<HashMap<u32, String>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let path = config::data_dir().join(config::OUI_FILE);
+        std::fs::read_to_string(path)
+            .map(|text| text.lines().filter_map(parse_line).collect())
+            .unwrap_or_default()
+    })
+}
+
+/// One entry of the IEEE listing (`00-00-0C   (hex)   Cisco Systems, Inc`)
+/// or Wireshark's manuf file (`00:00:0C<TAB>Cisco<TAB>Cisco Systems, Inc`)
+fn parse_line(line: &str) -> Option<(u32, String)> {
+    let (prefix, rest) = line.split_once(char::is_whitespace)?;
+    let hex: String = prefix.chars().filter(|c| *c != '-' && *c != ':').collect();
+    // Longer MA-M/MA-S prefixes (`00:1B:C5:00:00/36`) are skipped
+    if hex.len() != 6 {
+        return None;
+    }
+    let oui = u32::from_str_radix(&hex, 16).ok()?;
+    let rest = rest.trim_start();
+    // The IEEE listing repeats each entry as `00000C  (base 16)  ...`
+    if rest.starts_with("(base 16)") {
+        return None;
+    }
+    let name = match rest.strip_prefix("(hex)") {
+        Some(name) => name.trim(),
+        None => rest.split('\t').next()?.trim(),
+    };
+    (!name.is_empty()).then(|| (oui, name.to_string()))
+}
@@ -0,0 +1,57 @@
+//! Output formatting for the headless subcommands
+
+use color_eyre::eyre::Result;
+use serde::Serialize;
+
+/// Result of a subcommand that changes state, e.g. `{"status":"connected","ssid":"Home"}`
+#[derive(Debug, Serialize)]
+pub struct Outcome<'a> {
+    pub status: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssid: Option<&'a str>,
+}
+
+/// Print a value as pretty-printed JSON on stdout
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Fill the `{name}` placeholders of a `--format` template; `{{` and `}}`
+/// are literal braces
+pub fn render_template(
+    template: &str,
+    value: impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(format!("Unclosed placeholder {{{}", name)),
+                    }
+                }
+                match value(name.trim()) {
+                    Some(v) => out.push_str(&v),
+                    None => return Err(format!("Unknown placeholder {{{}}}", name)),
+                }
+            }
+            '}' => return Err("Unmatched } in format (use }} for a literal brace)".to_string()),
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,122 @@
+//! Network presence across scans
+//!
+//! Scans are merged into the list rather than replacing it: a network that
+//! drops out of a scan lingers, dimmed, for a few more before it is removed,
+//! one that wasn't there before is highlighted for a moment, and the order
+//! only follows the signal once it has clearly changed, so rows don't swap
+//! places on every refresh.
+
+use crate::config;
+use crate::wifi::{WifiInfo, format_bssid};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Networks are told apart by SSID and security, like the scan itself
+pub type NetworkKey = (String, String);
+
+pub fn key(wifi: &WifiInfo) -> NetworkKey {
+    // Hidden networks share the empty SSID, so their access point names them
+    let name = match wifi.bss_list.first() {
+        Some(bss) if wifi.is_hidden() => format_bssid(&bss.bssid),
+        _ => wifi.ssid.clone(),
+    };
+    (name, wifi.authentication.clone())
+}
+
+#[derive(Debug, Default)]
+pub struct Presence {
+    /// Networks missing from the latest scans, with how many they missed
+    fading: HashMap<NetworkKey, u8>,
+    /// When networks absent from the previous scan showed up
+    arrivals: HashMap<NetworkKey, Instant>,
+    /// Signal each network is ordered by
+    rank_signal: HashMap<NetworkKey, u8>,
+}
+
+impl Presence {
+    /// Merge a scan into the previous list; networks missing from it are kept
+    /// at the end until they have missed [`config::FADE_SCANS`] scans
+    pub fn merge(&mut self, previous: &[Arc<WifiInfo>], scan: Vec<WifiInfo>) -> Vec<Arc<WifiInfo>> {
+        let seen: HashSet<NetworkKey> = scan.iter().map(key).collect();
+        let known: HashSet<NetworkKey> = previous.iter().map(|w| key(w)).collect();
+
+        // Everything is new on the first scan, which isn't worth pointing out
+        if !previous.is_empty() {
+            let now = Instant::now();
+            for network_key in seen.difference(&known) {
+                self.arrivals.insert(network_key.clone(), now);
+            }
+        }
+        self.arrivals.retain(|k, _| seen.contains(k));
+        self.fading.retain(|k, _| !seen.contains(k));
+
+        let mut merged: Vec<Arc<WifiInfo>> = Vec::with_capacity(scan.len());
+        for wifi in scan {
+            let network_key = key(&wifi);
+            let rank = self.rank_signal.entry(network_key).or_insert(wifi.signal);
+            if rank.abs_diff(wifi.signal) >= config::RANK_SIGNAL_MARGIN {
+                *rank = wifi.signal;
+            }
+            merged.push(Arc::new(wifi));
+        }
+
+        for old in previous {
+            let network_key = key(old);
+            if seen.contains(&network_key) {
+                continue;
+            }
+            let missed = self.fading.entry(network_key.clone()).or_insert(0);
+            *missed += 1;
+            if *missed > config::FADE_SCANS {
+                self.fading.remove(&network_key);
+                self.rank_signal.remove(&network_key);
+                continue;
+            }
+            // A network that is gone can't still be connected
+            merged.push(if old.is_connected {
+                Arc::new(WifiInfo {
+                    is_connected: false,
+                    link_speed: None,
+                    rssi: None,
+                    ..WifiInfo::clone(old)
+                })
+            } else {
+                Arc::clone(old)
+            });
+        }
+        merged
+    }
+
+    /// Forget everything, e.g. before the first scan replaces a snapshot
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Missing from the latest scan and about to be removed
+    pub fn is_fading(&self, wifi: &WifiInfo) -> bool {
+        self.fading.contains_key(&key(wifi))
+    }
+
+    /// Appeared within the last [`config::NEW_NETWORK_HIGHLIGHT_SECS`]
+    pub fn is_new(&self, wifi: &WifiInfo) -> bool {
+        self.arrivals.contains_key(&key(wifi))
+    }
+
+    /// Signal to order by, which only follows changes of at least
+    /// [`config::RANK_SIGNAL_MARGIN`]
+    pub fn rank_signal(&self, wifi: &WifiInfo) -> u8 {
+        self.rank_signal
+            .get(&key(wifi))
+            .copied()
+            .unwrap_or(wifi.signal)
+    }
+
+    /// Stop highlighting arrivals that are no longer new; true if any were
+    pub fn expire_arrivals(&mut self) -> bool {
+        let before = self.arrivals.len();
+        let highlight = Duration::from_secs(config::NEW_NETWORK_HIGHLIGHT_SECS);
+        self.arrivals.retain(|_, at| at.elapsed() < highlight);
+        self.arrivals.len() != before
+    }
+}
@@ -0,0 +1,170 @@
+//! Search queries
+//!
+//! A search is split into words that must all match. A plain word matches
+//! the network name fuzzily, or else its security (`wpa3`), band (`5ghz`),
+//! channel (`36`) or a BSSID fragment (`a4:2b`). A prefixed word only looks
+//! at one of them: `sec:`, `band:`, `ch:` or `bssid:`.
+
+use crate::app::{fuzzy_indices, fuzzy_score};
+use crate::wifi::{Band, WifiInfo, format_bssid};
+
+/// One word of a search
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    /// A plain word, lowercased
+    Any(String),
+    Security(String),
+    /// `None` for a band that doesn't exist, which matches nothing
+    Band(Option<Band>),
+    Channel(u32),
+    /// Hex digits of a BSSID fragment, without separators
+    Bssid(String),
+}
+
+/// A parsed search
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+/// How a network matched a query
+#[derive(Debug, Clone, Default)]
+pub struct QueryMatch {
+    /// Char positions in the network's name matched by plain words
+    pub name: Vec<usize>,
+    /// How well the name matched, see [`fuzzy_score`]
+    pub score: u32,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Self {
+        let terms = input
+            .split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                let Some((field, value)) = word.split_once(':') else {
+                    return Term::Any(word);
+                };
+                match field {
+                    "sec" | "security" => Term::Security(value.to_string()),
+                    "band" => Term::Band(parse_band(value)),
+                    "ch" | "channel" => match value.parse() {
+                        Ok(channel) => Term::Channel(channel),
+                        Err(_) => Term::Any(word),
+                    },
+                    "bssid" | "mac" => Term::Bssid(hex_digits(value)),
+                    // Likely part of a BSSID like `a4:2b`
+                    _ => Term::Any(word),
+                }
+            })
+            .collect();
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Whether every word matches the network, and where in its name
+    pub fn matches(&self, wifi: &WifiInfo) -> Option<QueryMatch> {
+        let name = wifi.display_ssid().to_lowercase();
+        let mut found = QueryMatch::default();
+        for term in &self.terms {
+            match term {
+                Term::Any(word) => {
+                    if let Some(indices) = fuzzy_indices(&name, word) {
+                        found.score += fuzzy_score(&name, &indices);
+                        found.name.extend(indices);
+                    } else if !matches_field(wifi, word) {
+                        return None;
+                    }
+                }
+                Term::Security(value) => {
+                    if !matches_security(wifi, value) {
+                        return None;
+                    }
+                }
+                Term::Band(band) => {
+                    if band.is_none_or(|band| !on_band(wifi, band)) {
+                        return None;
+                    }
+                }
+                Term::Channel(channel) => {
+                    if !on_channel(wifi, *channel) {
+                        return None;
+                    }
+                }
+                Term::Bssid(hex) => {
+                    if !matches_bssid(wifi, hex) {
+                        return None;
+                    }
+                }
+            }
+        }
+        found.name.sort_unstable();
+        found.name.dedup();
+        Some(found)
+    }
+}
+
+/// A plain word that isn't in the name: security, a band like `5ghz`, a
+/// channel number, or a BSSID fragment written with separators
+fn matches_field(wifi: &WifiInfo, word: &str) -> bool {
+    if matches_security(wifi, word) {
+        return true;
+    }
+    if let Some(number) = word.strip_suffix("ghz").or_else(|| word.strip_suffix('g'))
+        && let Some(band) = parse_band(number)
+    {
+        return on_band(wifi, band);
+    }
+    if let Ok(channel) = word.parse() {
+        return on_channel(wifi, channel);
+    }
+    word.contains([':', '-']) && matches_bssid(wifi, &hex_digits(word))
+}
+
+fn matches_security(wifi: &WifiInfo, value: &str) -> bool {
+    wifi.authentication.to_lowercase().contains(value)
+        || wifi.security_badge().to_lowercase().contains(value)
+}
+
+/// `2.4`, `24`, `5` or `6`, optionally followed by `ghz` or `g`
+fn parse_band(value: &str) -> Option<Band> {
+    let value = value
+        .strip_suffix("ghz")
+        .or_else(|| value.strip_suffix('g'))
+        .unwrap_or(value);
+    match value {
+        "2.4" | "24" | "2" => Some(Band::Ghz2_4),
+        "5" => Some(Band::Ghz5),
+        "6" | "6e" => Some(Band::Ghz6),
+        _ => None,
+    }
+}
+
+fn on_band(wifi: &WifiInfo, band: Band) -> bool {
+    Band::from_frequency(wifi.frequency) == Some(band)
+        || wifi.bss_list.iter().any(|bss| bss.band() == Some(band))
+}
+
+fn on_channel(wifi: &WifiInfo, channel: u32) -> bool {
+    wifi.channel == channel || wifi.bss_list.iter().any(|bss| bss.channel == channel)
+}
+
+fn matches_bssid(wifi: &WifiInfo, hex: &str) -> bool {
+    !hex.is_empty()
+        && wifi
+            .bss_list
+            .iter()
+            .any(|bss| hex_digits(&format_bssid(&bss.bssid)).contains(hex))
+}
+
+/// Lowercase hex digits of a MAC address or fragment, dropping separators
+fn hex_digits(value: &str) -> String {
+    value
+        .chars()
+        .filter(char::is_ascii_hexdigit)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
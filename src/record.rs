@@ -0,0 +1,192 @@
+//! Scan recorder
+//!
+//! `wifui record` scans on an interval and writes every access point it sees
+//! to a file for other tools: CSV gets a row per access point per scan, KML a
+//! placemark per access point with its strongest reading. There is no GPS, so
+//! the position comes from `--at` and stays the same for the whole run, e.g.
+//! one run per room of a survey.
+
+use crate::monitor::{csv_escape, format_timestamp};
+use crate::wifi::{WifiInfo, format_bssid};
+use quick_xml::escape::escape;
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+const CSV_HEADER: &str = "timestamp,ssid,bssid,channel,frequency_mhz,rssi_dbm,signal_percent,security,latitude,longitude";
+
+/// Where the samples were taken, in decimal degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl FromStr for Position {
+    type Err = String;
+
+    /// `LAT,LON`, e.g. `52.5200,13.4050`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (lat, lon) = s
+            .split_once(',')
+            .ok_or_else(|| "expected LAT,LON".to_string())?;
+        let lat: f64 = lat.trim().parse().map_err(|_| "invalid latitude")?;
+        let lon: f64 = lon.trim().parse().map_err(|_| "invalid longitude")?;
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return Err("position out of range".to_string());
+        }
+        Ok(Self { lat, lon })
+    }
+}
+
+/// Strongest sighting of one access point
+#[derive(Debug, Clone)]
+struct Placemark {
+    ssid: String,
+    channel: u32,
+    frequency: u32,
+    security: String,
+    rssi: i32,
+    seen: SystemTime,
+}
+
+#[derive(Debug)]
+enum Format {
+    Csv(File),
+    /// KML is a single document, so it is rewritten after every scan
+    Kml(BTreeMap<[u8; 6], Placemark>),
+}
+
+/// Writes scans to a CSV or KML file, chosen by its extension
+#[derive(Debug)]
+pub struct Recorder {
+    path: PathBuf,
+    position: Option<Position>,
+    format: Format,
+}
+
+impl Recorder {
+    /// Open the file; CSV is appended to, KML needs a position
+    pub fn open(path: &Path, position: Option<Position>) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let is_kml = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("kml"));
+        let format = if is_kml {
+            if position.is_none() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "KML needs a position, pass --at LAT,LON",
+                ));
+            }
+            Format::Kml(BTreeMap::new())
+        } else {
+            let is_new = !path.exists();
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            if is_new {
+                writeln!(file, "{}", CSV_HEADER)?;
+            }
+            Format::Csv(file)
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            position,
+            format,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write every access point of a scan; returns how many there were
+    pub fn record(&mut self, networks: &[WifiInfo]) -> io::Result<usize> {
+        let now = SystemTime::now();
+        let access_points = networks
+            .iter()
+            .flat_map(|wifi| wifi.bss_list.iter().map(move |bss| (wifi, bss)));
+        let mut count = 0;
+        match &mut self.format {
+            Format::Csv(file) => {
+                let timestamp = format_timestamp(now);
+                let (lat, lon) = self
+                    .position
+                    .map(|p| (p.lat.to_string(), p.lon.to_string()))
+                    .unwrap_or_default();
+                for (wifi, bss) in access_points {
+                    writeln!(
+                        file,
+                        "{},{},{},{},{},{},{},{},{},{}",
+                        timestamp,
+                        csv_escape(&wifi.ssid),
+                        format_bssid(&bss.bssid),
+                        bss.channel,
+                        bss.frequency / 1000,
+                        bss.rssi,
+                        bss.link_quality,
+                        csv_escape(&wifi.authentication),
+                        lat,
+                        lon,
+                    )?;
+                    count += 1;
+                }
+            }
+            Format::Kml(placemarks) => {
+                for (wifi, bss) in access_points {
+                    let sighting = Placemark {
+                        ssid: wifi.display_ssid().to_string(),
+                        channel: bss.channel,
+                        frequency: bss.frequency,
+                        security: wifi.authentication.clone(),
+                        rssi: bss.rssi,
+                        seen: now,
+                    };
+                    placemarks
+                        .entry(bss.bssid)
+                        .and_modify(|best| {
+                            if sighting.rssi > best.rssi {
+                                *best = sighting.clone();
+                            }
+                        })
+                        .or_insert(sighting);
+                    count += 1;
+                }
+                self.write_kml()?;
+            }
+        }
+        Ok(count)
+    }
+
+    fn write_kml(&self) -> io::Result<()> {
+        let (Format::Kml(placemarks), Some(position)) = (&self.format, self.position) else {
+            return Ok(());
+        };
+        let mut kml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n<name>wifui scan</name>\n",
+        );
+        for (bssid, mark) in placemarks {
+            kml.push_str(&format!(
+                "<Placemark>\n<name>{}</name>\n<description>{} · channel {} ({} MHz) · {} dBm · {} · {}</description>\n\
+                 <Point><coordinates>{},{}</coordinates></Point>\n</Placemark>\n",
+                escape(&mark.ssid),
+                format_bssid(bssid),
+                mark.channel,
+                mark.frequency / 1000,
+                mark.rssi,
+                escape(&mark.security),
+                format_timestamp(mark.seen),
+                // KML puts longitude first
+                position.lon,
+                position.lat,
+            ));
+        }
+        kml.push_str("</Document>\n</kml>\n");
+        std::fs::write(&self.path, kml)
+    }
+}
@@ -0,0 +1,42 @@
+//! Roaming between preferred networks
+//!
+//! With roaming on, the connected network is compared against the other
+//! preferred networks in range after every scan. A candidate must be clearly
+//! stronger, and stay that way for a while, before wifui switches to it, so
+//! two networks of similar strength don't make it flap back and forth.
+
+use crate::config;
+use crate::wifi::WifiInfo;
+use std::sync::Arc;
+
+/// A preferred network worth switching to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoamCandidate {
+    pub ssid: String,
+    pub signal: u8,
+    /// Signal of the network it would replace
+    pub current_signal: u8,
+}
+
+/// Rank of an SSID in the `preferred` list, 0 being the most preferred
+pub fn priority(preferred: &[String], ssid: &str) -> Option<usize> {
+    preferred.iter().position(|p| p == ssid)
+}
+
+/// The preferred network to switch to from the connected one, if any is
+/// stronger by at least [`config::ROAM_SIGNAL_MARGIN`]; higher priority wins,
+/// then the stronger signal
+pub fn find_candidate(networks: &[Arc<WifiInfo>], preferred: &[String]) -> Option<RoamCandidate> {
+    let current = networks.iter().find(|w| w.is_connected)?;
+    networks
+        .iter()
+        .filter(|w| w.is_saved && w.ssid != current.ssid)
+        .filter(|w| w.signal >= current.signal.saturating_add(config::ROAM_SIGNAL_MARGIN))
+        .filter_map(|w| Some((priority(preferred, &w.ssid)?, w)))
+        .min_by_key(|&(priority, w)| (priority, u8::MAX - w.signal))
+        .map(|(_, w)| RoamCandidate {
+            ssid: w.ssid.clone(),
+            signal: w.signal,
+            current_signal: current.signal,
+        })
+}
@@ -0,0 +1,88 @@
+//! Evil twin heuristics
+//!
+//! An attacker impersonating a network copies its SSID, but usually not its
+//! security or its access points. A network is flagged when the same SSID is
+//! also advertised with different security, or when a saved network shows up
+//! with access points that were never seen while connected to it.
+
+use crate::config;
+use crate::wifi::{WifiInfo, security_family};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+/// BSSIDs seen while connected, per saved SSID
+pub type KnownBssids = BTreeMap<String, BTreeSet<[u8; 6]>>;
+
+/// Why a network might not be what it claims
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Suspicion {
+    /// The SSID is also advertised with this other security
+    MixedSecurity(&'static str),
+    /// Access points of a saved network never seen before
+    UnknownAccessPoints(usize),
+}
+
+impl Suspicion {
+    pub fn describe(&self) -> String {
+        match self {
+            Suspicion::MixedSecurity(other) => format!("also advertised as {}", other),
+            Suspicion::UnknownAccessPoints(1) => "1 access point never seen before".to_string(),
+            Suspicion::UnknownAccessPoints(n) => format!("{} access points never seen before", n),
+        }
+    }
+}
+
+/// Check one network against the rest of the scan and the known access points
+pub fn check(
+    wifi: &WifiInfo,
+    networks: &[Arc<WifiInfo>],
+    known: &KnownBssids,
+) -> Option<Suspicion> {
+    if wifi.is_hidden() {
+        return None;
+    }
+    let family = security_family(&wifi.authentication);
+    if let Some(other) = networks
+        .iter()
+        .filter(|w| w.ssid == wifi.ssid)
+        .map(|w| security_family(&w.authentication))
+        .find(|&other| other != family && !same_generation_pair(family, other))
+    {
+        return Some(Suspicion::MixedSecurity(other));
+    }
+
+    // Only saved networks have a history, and only once it has been learned
+    let known = known.get(&wifi.ssid).filter(|_| wifi.is_saved)?;
+    let unknown = wifi
+        .bss_list
+        .iter()
+        .filter(|bss| !known.contains(&bss.bssid))
+        .count();
+    (unknown > 0 && !wifi.is_connected).then_some(Suspicion::UnknownAccessPoints(unknown))
+}
+
+/// Remember the access points of the connected network
+///
+/// Everything advertising it while connected is trusted, so a mesh network's
+/// other nodes aren't flagged later.
+pub fn learn(networks: &[Arc<WifiInfo>], known: &mut KnownBssids) {
+    for wifi in networks.iter().filter(|w| w.is_connected && w.is_saved) {
+        let bssids = known.entry(wifi.ssid.clone()).or_default();
+        for bss in &wifi.bss_list {
+            if bssids.len() >= config::KNOWN_BSSIDS_LIMIT {
+                break;
+            }
+            bssids.insert(bss.bssid);
+        }
+    }
+}
+
+/// WPA2/WPA3 transition mode advertises one SSID with both, which is fine
+fn same_generation_pair(a: &'static str, b: &'static str) -> bool {
+    let kind = |family: &'static str| {
+        family
+            .strip_prefix("WPA2-")
+            .or_else(|| family.strip_prefix("WPA3-"))
+    };
+    kind(a).is_some() && kind(a) == kind(b)
+}
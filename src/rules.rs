@@ -0,0 +1,572 @@
+//! Scheduled auto-connect rules
+//!
+//! `[[rules]]` in the config turn auto-connect of saved networks on or off
+//! depending on the time of day, the weekday and whether the machine runs on
+//! battery. They are checked after every refresh; the first rule that applies
+//! to an SSID decides, `prefer` rules also move their networks to the top of
+//! the profile order, and a network no rule applies to anymore gets its own
+//! auto-connect setting back.
+
+use crate::error::WifiResult;
+use crate::logging;
+use crate::wifi::{self, WifiInfo};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// One entry of `[[rules]]`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub ssid: String,
+    pub action: RuleAction,
+    /// Local time the rule applies, e.g. `"9-17"` or `"22:30-6"`
+    #[serde(default)]
+    pub hours: Option<TimeRange>,
+    /// Weekdays the rule applies, every day if empty
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    /// Only on battery (`true`) or only on AC power (`false`)
+    #[serde(default)]
+    pub on_battery: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    /// Auto-connect, ahead of every other saved network
+    Prefer,
+    /// Never auto-connect
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Weekday {
+    /// From days since Monday
+    fn from_index(index: u32) -> Self {
+        match index % 7 {
+            0 => Weekday::Mon,
+            1 => Weekday::Tue,
+            2 => Weekday::Wed,
+            3 => Weekday::Thu,
+            4 => Weekday::Fri,
+            5 => Weekday::Sat,
+            _ => Weekday::Sun,
+        }
+    }
+}
+
+/// Start (inclusive) and end (exclusive) in minutes after midnight; a range
+/// whose end comes first wraps past midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct TimeRange {
+    start: u16,
+    end: u16,
+}
+
+impl TimeRange {
+    fn contains(&self, minute: u16) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+impl TryFrom<String> for TimeRange {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("invalid hours \"{}\", expected e.g. \"9-17\"", value);
+        // `H` or `H:MM`
+        let parse = |time: &str| -> Option<u16> {
+            let (hour, minute) = time.trim().split_once(':').unwrap_or((time.trim(), "0"));
+            let (hour, minute): (u16, u16) = (hour.parse().ok()?, minute.parse().ok()?);
+            (hour <= 24 && minute < 60 && hour * 60 + minute <= 24 * 60)
+                .then_some(hour * 60 + minute)
+        };
+        let (start, end) = value.split_once('-').ok_or_else(invalid)?;
+        match (parse(start), parse(end)) {
+            (Some(start), Some(end)) if start != end => Ok(Self { start, end }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// What the rules look at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conditions {
+    /// Weekday and minutes after midnight; `None` if the local time can't be
+    /// read, which keeps rules with `hours` or `days` from applying
+    pub local_time: Option<(Weekday, u16)>,
+    pub on_battery: bool,
+}
+
+impl Conditions {
+    /// The local time and power source right now
+    pub fn current() -> Self {
+        Self {
+            local_time: local_time(),
+            on_battery: on_battery(),
+        }
+    }
+}
+
+impl Rule {
+    pub fn applies(&self, now: &Conditions) -> bool {
+        let in_hours = self.hours.is_none_or(|hours| {
+            now.local_time
+                .is_some_and(|(_, minute)| hours.contains(minute))
+        });
+        let on_day = self.days.is_empty()
+            || now
+                .local_time
+                .is_some_and(|(weekday, _)| self.days.contains(&weekday));
+        in_hours
+            && on_day
+            && self
+                .on_battery
+                .is_none_or(|battery| battery == now.on_battery)
+    }
+}
+
+/// Settings the rules ask for right now
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    /// Auto-connect of every SSID a rule applies to
+    pub auto_connect: BTreeMap<String, bool>,
+    /// SSIDs of the `prefer` rules that apply, in rule order
+    pub preferred: Vec<String>,
+}
+
+/// The first rule that applies to an SSID decides its setting
+pub fn plan(rules: &[Rule], now: &Conditions) -> Plan {
+    let mut plan = Plan::default();
+    for rule in rules.iter().filter(|rule| rule.applies(now)) {
+        if plan.auto_connect.contains_key(&rule.ssid) {
+            continue;
+        }
+        plan.auto_connect
+            .insert(rule.ssid.clone(), rule.action == RuleAction::Prefer);
+        if rule.action == RuleAction::Prefer {
+            plan.preferred.push(rule.ssid.clone());
+        }
+    }
+    plan
+}
+
+/// A profile change the rules ask for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleChange {
+    AutoConnect(String, bool),
+    /// Move a profile to this place in the connection order
+    Position(String, u32),
+}
+
+/// Work out what changes between two plans; `originals` keeps the
+/// auto-connect setting networks had before a rule first changed it
+pub fn changes(
+    previous: &Plan,
+    plan: &Plan,
+    networks: &[Arc<WifiInfo>],
+    originals: &mut BTreeMap<String, bool>,
+) -> Vec<RuleChange> {
+    let mut changes = Vec::new();
+    // Networks no rule applies to anymore get their own setting back
+    for ssid in previous.auto_connect.keys() {
+        if !plan.auto_connect.contains_key(ssid)
+            && let Some(original) = originals.remove(ssid)
+        {
+            changes.push(RuleChange::AutoConnect(ssid.clone(), original));
+        }
+    }
+    for (ssid, &enable) in &plan.auto_connect {
+        if previous.auto_connect.get(ssid) == Some(&enable) {
+            continue;
+        }
+        let current = networks
+            .iter()
+            .find(|w| w.is_saved && w.ssid == *ssid)
+            .map(|w| w.auto_connect);
+        // A network out of range is assumed to have the default, on
+        originals
+            .entry(ssid.clone())
+            .or_insert(current.unwrap_or(true));
+        if current != Some(enable) {
+            changes.push(RuleChange::AutoConnect(ssid.clone(), enable));
+        }
+    }
+    if plan.preferred != previous.preferred {
+        changes.extend(
+            (0..)
+                .zip(&plan.preferred)
+                .map(|(position, ssid)| RuleChange::Position(ssid.clone(), position)),
+        );
+    }
+    changes
+}
+
+/// Everything a check of the rules needs, so it can run off the UI thread
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub rules: Vec<Rule>,
+    /// What the profiles were last set to
+    pub applied: Plan,
+    pub networks: Vec<Arc<WifiInfo>>,
+    pub originals: BTreeMap<String, bool>,
+}
+
+/// A check whose plan changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checked {
+    pub plan: Plan,
+    pub originals: BTreeMap<String, bool>,
+    /// Descriptions of the changes made
+    pub done: Vec<String>,
+}
+
+impl Check {
+    /// Read the conditions and make the changes the rules ask for; `None` if
+    /// the plan is the same as before
+    pub fn run(self) -> Option<Checked> {
+        let plan = plan(&self.rules, &Conditions::current());
+        self.settle(plan, make_change)
+    }
+
+    /// Make the changes from the applied plan to `plan` with `make`
+    fn settle(
+        mut self,
+        plan: Plan,
+        make: impl Fn(&RuleChange) -> WifiResult<()>,
+    ) -> Option<Checked> {
+        if plan == self.applied {
+            return None;
+        }
+        let changes = changes(&self.applied, &plan, &self.networks, &mut self.originals);
+        let (done, failed) = apply(&changes, make);
+        Some(Checked {
+            plan: settled(&self.applied, plan, &failed, &mut self.originals),
+            originals: self.originals,
+            done,
+        })
+    }
+}
+
+/// The plan as far as the changes got: a failed one keeps what was applied
+/// before, so the next check tries it again
+fn settled(
+    previous: &Plan,
+    mut plan: Plan,
+    failed: &[&RuleChange],
+    originals: &mut BTreeMap<String, bool>,
+) -> Plan {
+    for change in failed {
+        match change {
+            RuleChange::AutoConnect(ssid, enable) => match previous.auto_connect.get(ssid) {
+                Some(&before) => {
+                    // A setting that failed to come back still needs its original
+                    if plan.auto_connect.insert(ssid.clone(), before).is_none() {
+                        originals.insert(ssid.clone(), *enable);
+                    }
+                }
+                None => {
+                    plan.auto_connect.remove(ssid);
+                }
+            },
+            RuleChange::Position(..) => plan.preferred = previous.preferred.clone(),
+        }
+    }
+    plan
+}
+
+fn make_change(change: &RuleChange) -> WifiResult<()> {
+    match change {
+        RuleChange::AutoConnect(ssid, enable) => wifi::set_auto_connect(ssid, *enable),
+        RuleChange::Position(ssid, position) => wifi::set_profile_position(ssid, *position),
+    }
+}
+
+/// Make the changes with `make`, logging the ones that fail; returns what was
+/// done and the changes that failed
+pub fn apply(
+    changes: &[RuleChange],
+    make: impl Fn(&RuleChange) -> WifiResult<()>,
+) -> (Vec<String>, Vec<&RuleChange>) {
+    let mut done = Vec::new();
+    let mut failed = Vec::new();
+    for change in changes {
+        let description = match change {
+            RuleChange::AutoConnect(ssid, enable) => format!(
+                "auto-connect {} for {}",
+                if *enable { "on" } else { "off" },
+                ssid
+            ),
+            RuleChange::Position(ssid, position) => {
+                format!("{} moved to #{}", ssid, position + 1)
+            }
+        };
+        match make(change) {
+            Ok(()) => {
+                logging::info(format!("Rules: {}", description));
+                done.push(description);
+            }
+            Err(e) => {
+                logging::warn(format!("Rules: {} failed: {}", description, e));
+                failed.push(change);
+            }
+        }
+    }
+    (done, failed)
+}
+
+#[cfg(windows)]
+fn local_time() -> Option<(Weekday, u16)> {
+    use windows::Win32::System::SystemInformation::GetLocalTime;
+
+    let time = unsafe { GetLocalTime() };
+    // wDayOfWeek counts from Sunday
+    let weekday = Weekday::from_index((time.wDayOfWeek as u32 + 6) % 7);
+    Some((weekday, time.wHour * 60 + time.wMinute))
+}
+
+/// std has no time zones, so ask `date`, e.g. "1 09:30" for Monday morning
+#[cfg(not(windows))]
+fn local_time() -> Option<(Weekday, u16)> {
+    let output = std::process::Command::new("date")
+        .arg("+%u %H:%M")
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let (day, time) = text.trim().split_once(' ')?;
+    let (hour, minute) = time.split_once(':')?;
+    let day: u32 = day.parse().ok()?;
+    let (hour, minute): (u16, u16) = (hour.parse().ok()?, minute.parse().ok()?);
+    Some((Weekday::from_index(day.checked_sub(1)?), hour * 60 + minute))
+}
+
+#[cfg(windows)]
+fn on_battery() -> bool {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    // 0 is offline; 255, unknown, counts as AC
+    unsafe { GetSystemPowerStatus(&mut status) }.is_ok() && status.ACLineStatus == 0
+}
+
+/// On battery when a battery is discharging and no charger is online
+#[cfg(not(windows))]
+fn on_battery() -> bool {
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+    let mut discharging = false;
+    for supply in supplies.flatten() {
+        let read = |name: &str| {
+            std::fs::read_to_string(supply.path().join(name))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        match read("type").as_str() {
+            "Mains" | "USB" if read("online") == "1" => return false,
+            "Battery" if read("status") == "Discharging" => discharging = true,
+            _ => {}
+        }
+    }
+    discharging
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::WifiError;
+
+    fn range(value: &str) -> Result<TimeRange, String> {
+        TimeRange::try_from(value.to_string())
+    }
+
+    fn saved(ssid: &str, auto_connect: bool) -> Arc<WifiInfo> {
+        Arc::new(WifiInfo {
+            ssid: ssid.to_string(),
+            is_saved: true,
+            auto_connect,
+            ..Default::default()
+        })
+    }
+
+    fn plan_of(auto_connect: &[(&str, bool)], preferred: &[&str]) -> Plan {
+        Plan {
+            auto_connect: auto_connect
+                .iter()
+                .map(|&(ssid, enable)| (ssid.to_string(), enable))
+                .collect(),
+            preferred: preferred.iter().map(|ssid| ssid.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn time_range_parses_hours_and_minutes() {
+        let hours = range("9-17:30").unwrap();
+        assert!(hours.contains(9 * 60));
+        assert!(hours.contains(17 * 60 + 29));
+        assert!(!hours.contains(17 * 60 + 30));
+        assert!(!hours.contains(8 * 60 + 59));
+    }
+
+    #[test]
+    fn time_range_wraps_past_midnight() {
+        let night = range("22:30-6").unwrap();
+        assert!(night.contains(22 * 60 + 30));
+        assert!(night.contains(0));
+        assert!(night.contains(5 * 60 + 59));
+        assert!(!night.contains(6 * 60));
+        assert!(!night.contains(22 * 60 + 29));
+    }
+
+    #[test]
+    fn time_range_accepts_24_as_end_of_day() {
+        let evening = range("18-24:00").unwrap();
+        assert!(evening.contains(23 * 60 + 59));
+        assert!(!evening.contains(17 * 60 + 59));
+        assert!(range("0-24").unwrap().contains(12 * 60));
+        assert!(range("9-24:30").is_err());
+        assert!(range("9-25").is_err());
+    }
+
+    #[test]
+    fn time_range_rejects_empty_and_malformed() {
+        assert!(range("9-9").is_err());
+        assert!(range("9:00-9").is_err());
+        assert!(range("9").is_err());
+        assert!(range("9-17:60").is_err());
+        assert!(range("nine-five").is_err());
+    }
+
+    #[test]
+    fn changes_remember_and_restore_original_setting() {
+        let networks = [saved("Home", true), saved("Cafe", false)];
+        let mut originals = BTreeMap::new();
+
+        let never = plan_of(&[("Home", false)], &[]);
+        let changes_on = changes(&Plan::default(), &never, &networks, &mut originals);
+        assert_eq!(
+            changes_on,
+            [RuleChange::AutoConnect("Home".to_string(), false)]
+        );
+        assert_eq!(originals.get("Home"), Some(&true));
+
+        let changes_off = changes(&never, &Plan::default(), &networks, &mut originals);
+        assert_eq!(
+            changes_off,
+            [RuleChange::AutoConnect("Home".to_string(), true)]
+        );
+        assert!(originals.is_empty());
+    }
+
+    #[test]
+    fn changes_skip_networks_already_set() {
+        let networks = [saved("Cafe", false)];
+        let mut originals = BTreeMap::new();
+        let never = plan_of(&[("Cafe", false)], &[]);
+        assert!(changes(&Plan::default(), &never, &networks, &mut originals).is_empty());
+        assert_eq!(originals.get("Cafe"), Some(&false));
+    }
+
+    #[test]
+    fn changes_assume_default_for_networks_out_of_range() {
+        let mut originals = BTreeMap::new();
+        let never = plan_of(&[("Office", false)], &[]);
+        assert_eq!(
+            changes(&Plan::default(), &never, &[], &mut originals),
+            [RuleChange::AutoConnect("Office".to_string(), false)]
+        );
+        assert_eq!(originals.get("Office"), Some(&true));
+    }
+
+    #[test]
+    fn changes_reorder_preferred_networks() {
+        let networks = [saved("Home", true), saved("Phone", true)];
+        let mut originals = BTreeMap::new();
+        let previous = plan_of(&[("Home", true)], &["Home"]);
+        let plan = plan_of(&[("Home", true), ("Phone", true)], &["Phone", "Home"]);
+        assert_eq!(
+            changes(&previous, &plan, &networks, &mut originals),
+            [
+                RuleChange::Position("Phone".to_string(), 0),
+                RuleChange::Position("Home".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_retries_failed_changes() {
+        let check = Check {
+            rules: Vec::new(),
+            applied: Plan::default(),
+            networks: vec![saved("Home", true), saved("Cafe", true)],
+            originals: BTreeMap::new(),
+        };
+        let never = plan_of(&[("Home", false), ("Cafe", false)], &[]);
+        let checked = check
+            .clone()
+            .settle(never.clone(), |change| match change {
+                RuleChange::AutoConnect(ssid, _) if ssid == "Cafe" => {
+                    Err(WifiError::Internal("busy".to_string()))
+                }
+                _ => Ok(()),
+            })
+            .unwrap();
+        assert_eq!(checked.plan, plan_of(&[("Home", false)], &[]));
+        assert_eq!(checked.done, ["auto-connect off for Home"]);
+
+        let retry = Check {
+            applied: checked.plan,
+            networks: vec![saved("Home", false), saved("Cafe", true)],
+            originals: checked.originals,
+            ..check
+        };
+        let made = std::cell::RefCell::new(Vec::new());
+        let checked = retry
+            .settle(never.clone(), |change| {
+                made.borrow_mut().push(change.clone());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(
+            made.into_inner(),
+            [RuleChange::AutoConnect("Cafe".to_string(), false)]
+        );
+        assert_eq!(checked.plan, never);
+    }
+
+    #[test]
+    fn check_restores_again_after_failed_restore() {
+        let mut originals = BTreeMap::new();
+        originals.insert("Home".to_string(), true);
+        let check = Check {
+            rules: Vec::new(),
+            applied: plan_of(&[("Home", false)], &[]),
+            networks: vec![saved("Home", false)],
+            originals,
+        };
+        let checked = check
+            .settle(Plan::default(), |_| {
+                Err(WifiError::Internal("busy".to_string()))
+            })
+            .unwrap();
+        assert_eq!(checked.plan, plan_of(&[("Home", false)], &[]));
+        assert_eq!(checked.originals.get("Home"), Some(&true));
+    }
+}
@@ -0,0 +1,118 @@
+//! Continuous scan monitor
+//!
+//! `wifui monitor` rescans on a fixed interval, whatever else is going on,
+//! and keeps the weakest, average and strongest signal of every SSID it has
+//! seen, redrawn as a dense table. Walking around with it running shows where
+//! each network fades or drops out.
+
+use crate::wifi::WifiInfo;
+use std::collections::HashMap;
+
+/// Signal of one SSID over the scans it showed up in
+#[derive(Debug, Clone)]
+struct SignalStats {
+    /// `None` when the latest scan missed it
+    current: Option<u8>,
+    /// Strongest access point in the latest scan, in dBm
+    rssi: Option<i32>,
+    min: u8,
+    max: u8,
+    sum: u64,
+    /// Scans it showed up in
+    seen: u32,
+}
+
+impl SignalStats {
+    fn average(&self) -> f64 {
+        self.sum as f64 / self.seen.max(1) as f64
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ScanMonitor {
+    scans: u32,
+    networks: HashMap<String, SignalStats>,
+}
+
+impl ScanMonitor {
+    /// Add one scan; networks missing from it keep their stats
+    pub fn record(&mut self, networks: &[WifiInfo]) {
+        self.scans += 1;
+        let mut latest: HashMap<String, (u8, Option<i32>)> = HashMap::new();
+        for wifi in networks {
+            // The live reading of the connected access point beats the scan's
+            let rssi = wifi.rssi.or(wifi.bss_list.iter().map(|bss| bss.rssi).max());
+            let entry = latest
+                .entry(wifi.display_ssid().into_owned())
+                .or_insert((0, None));
+            entry.0 = entry.0.max(wifi.signal);
+            entry.1 = entry.1.max(rssi);
+        }
+
+        for stats in self.networks.values_mut() {
+            stats.current = None;
+            stats.rssi = None;
+        }
+        for (ssid, (signal, rssi)) in latest {
+            let stats = self.networks.entry(ssid).or_insert(SignalStats {
+                current: None,
+                rssi: None,
+                min: signal,
+                max: signal,
+                sum: 0,
+                seen: 0,
+            });
+            stats.current = Some(signal);
+            stats.rssi = rssi;
+            stats.min = stats.min.min(signal);
+            stats.max = stats.max.max(signal);
+            stats.sum += signal as u64;
+            stats.seen += 1;
+        }
+    }
+
+    pub fn scans(&self) -> u32 {
+        self.scans
+    }
+
+    /// Table of every SSID seen, strongest right now first, fitted to
+    /// `width` columns and at most `rows` lines
+    pub fn table(&self, width: usize, rows: usize) -> Vec<String> {
+        let mut networks: Vec<(&String, &SignalStats)> = self.networks.iter().collect();
+        networks.sort_by(|(a_ssid, a), (b_ssid, b)| {
+            b.current
+                .cmp(&a.current)
+                .then(b.average().total_cmp(&a.average()))
+                .then(a_ssid.cmp(b_ssid))
+        });
+
+        // Everything but the SSID column takes 36 characters
+        let name_width = width.saturating_sub(36).clamp(8, 32);
+        let mut lines = vec![format!(
+            "{:<name_width$} {:>4} {:>5} {:>4} {:>4} {:>4} {:>9}",
+            "SSID", "NOW", "DBM", "MIN", "AVG", "MAX", "SEEN"
+        )];
+        for (ssid, stats) in networks.into_iter().take(rows.saturating_sub(1)) {
+            let name: String = ssid.chars().take(name_width).collect();
+            let now = stats
+                .current
+                .map(|signal| format!("{}%", signal))
+                .unwrap_or_else(|| "--".to_string());
+            let rssi = stats
+                .rssi
+                .map(|rssi| rssi.to_string())
+                .unwrap_or_else(|| "--".to_string());
+            lines.push(format!(
+                "{:<name_width$} {:>4} {:>5} {:>3}% {:>3.0}% {:>3}% {:>9}",
+                name,
+                now,
+                rssi,
+                stats.min,
+                stats.average(),
+                stats.max,
+                format!("{}/{}", stats.seen, self.scans)
+            ));
+        }
+        lines
+    }
+}
@@ -0,0 +1,149 @@
+//! Session persistence
+//!
+//! Remembers a few pieces of UI state (selected network, search query and
+//! history, sort order, list filter, details tab, when each network was last
+//! connected, the access points seen while connected and open networks
+//! accepted without encryption) between launches in a small `key=value` file
+//! under the data directory.
+
+use crate::app::DetailsTab;
+use crate::filter::ListFilter;
+use crate::rogue::KnownBssids;
+use crate::sort::SortMode;
+use crate::wifi::{format_bssid, parse_bssid};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// UI state restored on the next launch
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub selected_ssid: Option<String>,
+    pub search: String,
+    /// Recent search queries, oldest first
+    pub search_history: Vec<String>,
+    /// Set once the sort order has been cycled, overriding the config default
+    pub sort: Option<SortMode>,
+    pub filter: ListFilter,
+    pub details_tab: DetailsTab,
+    /// Unix seconds each SSID was last seen connected
+    pub last_connected: BTreeMap<String, u64>,
+    pub known_bssids: KnownBssids,
+    /// Open networks the unencrypted warning is no longer shown for
+    pub accepted_open: BTreeSet<String>,
+}
+
+impl Session {
+    /// Load a session file, falling back to defaults if it is missing or unreadable
+    pub fn load(path: &Path) -> Self {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+
+        let mut session = Self::default();
+        for line in contents.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = unescape(value);
+            match key {
+                "selected_ssid" if !value.is_empty() => session.selected_ssid = Some(value),
+                "search" => session.search = value,
+                "history" if !value.is_empty() => session.search_history.push(value),
+                "sort" => session.sort = SortMode::from_name(&value),
+                "filter" => session.filter = ListFilter::from_name(&value).unwrap_or_default(),
+                "tab" => session.details_tab = DetailsTab::from_label(&value).unwrap_or_default(),
+                "last_connected" => {
+                    if let Some((secs, ssid)) = value.split_once(' ')
+                        && let Ok(secs) = secs.parse()
+                    {
+                        session.last_connected.insert(ssid.to_string(), secs);
+                    }
+                }
+                "accepted_open" if !value.is_empty() => {
+                    session.accepted_open.insert(value);
+                }
+                "known_bssid" => {
+                    if let Some((bssid, ssid)) = value.split_once(' ')
+                        && let Some(bssid) = parse_bssid(bssid)
+                    {
+                        session
+                            .known_bssids
+                            .entry(ssid.to_string())
+                            .or_default()
+                            .insert(bssid);
+                    }
+                }
+                _ => {}
+            }
+        }
+        session
+    }
+
+    /// Write the session file, creating the data directory if needed
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        if let Some(ssid) = &self.selected_ssid {
+            contents.push_str(&format!("selected_ssid={}\n", escape(ssid)));
+        }
+        contents.push_str(&format!("search={}\n", escape(&self.search)));
+        for query in &self.search_history {
+            contents.push_str(&format!("history={}\n", escape(query)));
+        }
+        if let Some(sort) = self.sort {
+            contents.push_str(&format!("sort={}\n", sort.name()));
+        }
+        if self.filter != ListFilter::All {
+            contents.push_str(&format!("filter={}\n", self.filter.name()));
+        }
+        if self.details_tab != DetailsTab::Overview {
+            contents.push_str(&format!("tab={}\n", self.details_tab.label()));
+        }
+        for (ssid, secs) in &self.last_connected {
+            contents.push_str(&format!("last_connected={} {}\n", secs, escape(ssid)));
+        }
+        for ssid in &self.accepted_open {
+            contents.push_str(&format!("accepted_open={}\n", escape(ssid)));
+        }
+        for (ssid, bssids) in &self.known_bssids {
+            for bssid in bssids {
+                contents.push_str(&format!(
+                    "known_bssid={} {}\n",
+                    format_bssid(bssid),
+                    escape(ssid)
+                ));
+            }
+        }
+        fs::write(path, contents)
+    }
+}
+
+/// Escape backslashes and line breaks so every value stays on one line
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
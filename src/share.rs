@@ -0,0 +1,233 @@
+//! Wi-Fi credential sharing
+//!
+//! Builds the standard `WIFI:` URI used by phone camera apps and writes the
+//! QR code to disk as a PNG plus a companion text file. Exported profile XML
+//! is saved alongside. Going the other way, a `WIFI:` URI pasted or read from
+//! such a text file is parsed back into credentials.
+
+use qrcode::{Color, QrCode};
+use secrecy::{ExposeSecret, SecretString};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Pixels per QR module in the exported PNG
+const PNG_SCALE: usize = 8;
+/// Light modules around the code, as required by the QR spec
+const QUIET_ZONE: usize = 4;
+
+/// Build a WiFi URI in standard format: WIFI:S:ssid;T:auth;P:password;;
+pub fn wifi_uri(ssid: &str, auth: &str, password: Option<&SecretString>) -> String {
+    let auth_type = match auth {
+        "WPA3-SAE" | "WPA3" => "WPA",
+        "WPA2-PSK" | "WPA2" | "WPA-PSK" | "WPA" => "WPA",
+        "Open" | "open" => "nopass",
+        _ => "WPA",
+    };
+
+    if auth_type == "nopass" {
+        format!("WIFI:S:{};T:nopass;;", escape_special_chars(ssid))
+    } else if let Some(pwd) = password {
+        format!(
+            "WIFI:S:{};T:{};P:{};;",
+            escape_special_chars(ssid),
+            auth_type,
+            escape_special_chars(pwd.expose_secret())
+        )
+    } else {
+        format!("WIFI:S:{};T:{};;", escape_special_chars(ssid), auth_type)
+    }
+}
+
+/// Network credentials read from a `WIFI:` URI
+#[derive(Debug, Clone)]
+pub struct WifiCredentials {
+    pub ssid: String,
+    /// The `T:` field: `WPA`, `WEP`, `SAE` or `nopass`
+    pub auth: String,
+    pub password: Option<SecretString>,
+    pub hidden: bool,
+}
+
+impl WifiCredentials {
+    /// Matching entry of the add-network popup's security options
+    pub fn security(&self) -> &'static str {
+        match self.auth.to_uppercase().as_str() {
+            "NOPASS" => "Open",
+            "" if self.password.is_none() => "Open",
+            "WEP" => "WEP",
+            "SAE" | "WPA3" => "WPA3-Personal",
+            _ => "WPA2-Personal",
+        }
+    }
+}
+
+/// Parse a `WIFI:S:ssid;T:auth;P:password;H:true;;` URI; fields may come in
+/// any order and unknown ones are ignored
+pub fn parse_wifi_uri(uri: &str) -> Result<WifiCredentials, String> {
+    let uri = uri.trim();
+    let body = uri
+        .get(..5)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("WIFI:"))
+        .map(|_| &uri[5..])
+        .ok_or_else(|| "Not a WIFI: URI".to_string())?;
+
+    let mut credentials = WifiCredentials {
+        ssid: String::new(),
+        auth: String::new(),
+        password: None,
+        hidden: false,
+    };
+    for field in split_unescaped(body) {
+        let Some((key, value)) = field.split_once(':') else {
+            continue;
+        };
+        let value = unescape_special_chars(value);
+        match key.to_uppercase().as_str() {
+            "S" => credentials.ssid = value,
+            "T" => credentials.auth = value,
+            "P" if !value.is_empty() => credentials.password = Some(SecretString::from(value)),
+            "H" => credentials.hidden = value.eq_ignore_ascii_case("true"),
+            _ => {}
+        }
+    }
+    if credentials.ssid.is_empty() {
+        return Err("The WIFI: URI has no SSID".to_string());
+    }
+    Ok(credentials)
+}
+
+/// Read credentials from a `WIFI:` URI or a text file holding one, like the
+/// `.txt` written next to a shared QR code
+pub fn load_wifi_uri(source: &str) -> Result<WifiCredentials, String> {
+    if source.trim_start().to_uppercase().starts_with("WIFI:") {
+        return parse_wifi_uri(source);
+    }
+    let bytes = fs::read(source).map_err(|e| format!("Failed to read {}: {}", source, e))?;
+    if is_image(&bytes) {
+        return Err(format!(
+            "{} is an image; QR images can't be decoded yet, pass the WIFI: text instead",
+            source
+        ));
+    }
+    let text = String::from_utf8(bytes).map_err(|_| format!("{} is not a text file", source))?;
+    let uri = text
+        .lines()
+        .find(|line| line.trim_start().to_uppercase().starts_with("WIFI:"))
+        .ok_or_else(|| format!("No WIFI: URI in {}", source))?;
+    parse_wifi_uri(uri)
+}
+
+/// Whether the data starts with a PNG, JPEG, GIF or BMP signature
+fn is_image(bytes: &[u8]) -> bool {
+    [&b"\x89PNG"[..], b"\xFF\xD8\xFF", b"GIF8", b"BM"]
+        .iter()
+        .any(|magic| bytes.starts_with(magic))
+}
+
+/// Split on `;` except where it is escaped with a backslash
+fn split_unescaped(s: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ';' => {
+                fields.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    fields.push(&s[start..]);
+    fields.into_iter().filter(|f| !f.is_empty()).collect()
+}
+
+/// Undo [`escape_special_chars`]
+fn unescape_special_chars(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escape special characters for WiFi QR code format
+fn escape_special_chars(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace(':', "\\:")
+}
+
+/// Write `<ssid>.png` and `<ssid>.txt` into `dir`, returning the PNG path
+pub fn save_qr_files(dir: &Path, ssid: &str, uri: &str) -> io::Result<PathBuf> {
+    let code = QrCode::new(uri).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    fs::create_dir_all(dir)?;
+
+    let stem = file_stem(ssid);
+    let png_path = dir.join(format!("{}.png", stem));
+    fs::write(&png_path, encode_png(&code)?)?;
+    fs::write(dir.join(format!("{}.txt", stem)), format!("{}\n", uri))?;
+    Ok(png_path)
+}
+
+/// Write `<ssid>.xml` into `dir`, returning its path
+pub fn save_profile_xml(dir: &Path, ssid: &str, xml: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.xml", file_stem(ssid)));
+    fs::write(&path, xml)?;
+    Ok(path)
+}
+
+/// Turn an SSID into a safe file name
+fn file_stem(ssid: &str) -> String {
+    let stem: String = ssid
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("wifi-{}", stem)
+}
+
+/// Render the QR code as an 8-bit grayscale PNG
+fn encode_png(code: &QrCode) -> io::Result<Vec<u8>> {
+    let modules = code.width();
+    let colors = code.to_colors();
+    let size = (modules + QUIET_ZONE * 2) * PNG_SCALE;
+
+    let mut pixels = Vec::with_capacity(size * size);
+    for y in 0..size {
+        let my = (y / PNG_SCALE).checked_sub(QUIET_ZONE);
+        for x in 0..size {
+            let mx = (x / PNG_SCALE).checked_sub(QUIET_ZONE);
+            let is_dark = match (mx, my) {
+                (Some(mx), Some(my)) if mx < modules && my < modules => {
+                    colors[my * modules + mx] == Color::Dark
+                }
+                _ => false,
+            };
+            pixels.push(if is_dark { 0x00 } else { 0xFF });
+        }
+    }
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels)?;
+    writer.finish()?;
+    Ok(png)
+}
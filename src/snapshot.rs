@@ -0,0 +1,27 @@
+//! Network list snapshot
+//!
+//! The list of the last scan is written to the data directory on exit and
+//! shown, dimmed, on the next launch until the first scan replaces it, so the
+//! window isn't empty while the adapter scans.
+
+use crate::wifi::WifiInfo;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Networks of the last run, `None` if there is no readable snapshot
+pub fn load(path: &Path) -> Option<Vec<WifiInfo>> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Write the current networks, creating the data directory if needed
+pub fn save(path: &Path, networks: &[Arc<WifiInfo>]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let networks: Vec<&WifiInfo> = networks.iter().map(Arc::as_ref).collect();
+    let contents = serde_json::to_string(&networks).map_err(io::Error::other)?;
+    fs::write(path, contents)
+}
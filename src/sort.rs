@@ -0,0 +1,119 @@
+//! Sort orders for the network list
+//!
+//! The backends return networks in scan order; the list is sorted here so the
+//! order can be cycled at runtime. The connected network always stays on top.
+
+use crate::wifi::WifiInfo;
+use serde::Deserialize;
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// How the network list is ordered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortMode {
+    /// Saved networks first, then strongest signal
+    #[default]
+    Signal,
+    /// Alphabetical by SSID, hidden networks last
+    Name,
+    Channel,
+    /// Strongest security first
+    Security,
+    /// Most recently connected first, then strongest signal
+    LastConnected,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 5] = [
+        SortMode::Signal,
+        SortMode::Name,
+        SortMode::Channel,
+        SortMode::Security,
+        SortMode::LastConnected,
+    ];
+
+    /// Short label for the list title
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Signal => "signal",
+            SortMode::Name => "name",
+            SortMode::Channel => "channel",
+            SortMode::Security => "security",
+            SortMode::LastConnected => "recent",
+        }
+    }
+
+    /// Name used in the config and session files
+    pub fn name(self) -> &'static str {
+        match self {
+            SortMode::Signal => "signal",
+            SortMode::Name => "name",
+            SortMode::Channel => "channel",
+            SortMode::Security => "security",
+            SortMode::LastConnected => "last_connected",
+        }
+    }
+
+    /// Parse a config name or list label, e.g. `last_connected` or `recent`
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name() == name || mode.label() == name)
+    }
+
+    /// The next mode in the cycle
+    pub fn next(self) -> Self {
+        let pos = Self::ALL.iter().position(|&mode| mode == self).unwrap_or(0);
+        Self::ALL[(pos + 1) % Self::ALL.len()]
+    }
+}
+
+/// Rank of an authentication algorithm, higher is stronger
+fn security_rank(authentication: &str) -> u8 {
+    let auth = authentication.to_uppercase();
+    if auth.contains("WPA3") || auth.contains("SAE") {
+        5
+    } else if auth.contains("WPA2") || auth.contains("RSNA") {
+        4
+    } else if auth.contains("WPA") {
+        3
+    } else if auth.contains("WEP") || auth.contains("SHARED") {
+        2
+    } else if auth.contains("OPEN") || auth.is_empty() {
+        0
+    } else {
+        1
+    }
+}
+
+/// Sort networks in place; `last_connected` maps SSIDs to unix seconds and
+/// `signal` gives the signal to order by
+pub fn sort_networks<T: Borrow<WifiInfo>>(
+    list: &mut [T],
+    mode: SortMode,
+    last_connected: &BTreeMap<String, u64>,
+    signal: impl Fn(&WifiInfo) -> u8,
+) {
+    list.sort_by(|a, b| {
+        let (a, b) = (a.borrow(), b.borrow());
+        let by_mode = match mode {
+            SortMode::Signal => b.is_saved.cmp(&a.is_saved),
+            SortMode::Name => (a.ssid.is_empty(), a.ssid.to_lowercase())
+                .cmp(&(b.ssid.is_empty(), b.ssid.to_lowercase())),
+            SortMode::Channel => a.channel.cmp(&b.channel),
+            SortMode::Security => {
+                security_rank(&b.authentication).cmp(&security_rank(&a.authentication))
+            }
+            SortMode::LastConnected => {
+                let when = |w: &WifiInfo| Reverse(last_connected.get(&w.ssid).copied());
+                when(a).cmp(&when(b))
+            }
+        };
+        b.is_connected
+            .cmp(&a.is_connected)
+            .then(by_mode)
+            .then_with(|| signal(b).cmp(&signal(a)))
+    });
+}
@@ -0,0 +1,240 @@
+//! Speed test for the current connection
+//!
+//! Measures latency, then download and upload throughput against the plain
+//! HTTP endpoints from the config. Results are appended to a CSV history so
+//! each network's past runs can be compared.
+
+use crate::config;
+use crate::monitor::{csv_escape, csv_split, format_timestamp};
+use crate::net::HttpUrl;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+const CSV_HEADER: &str = "timestamp,ssid,latency_ms,download_mbps,upload_mbps";
+
+/// Connections opened to measure latency
+const LATENCY_SAMPLES: usize = 5;
+/// Bytes sent per write during the upload phase
+const UPLOAD_CHUNK: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeedTestPhase {
+    Latency,
+    Download,
+    Upload,
+}
+
+impl SpeedTestPhase {
+    pub fn label(self) -> &'static str {
+        match self {
+            SpeedTestPhase::Latency => "Latency",
+            SpeedTestPhase::Download => "Download",
+            SpeedTestPhase::Upload => "Upload",
+        }
+    }
+}
+
+/// Measurements of one complete run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeedTestResult {
+    pub latency_ms: f64,
+    pub download_mbps: f64,
+    pub upload_mbps: f64,
+}
+
+/// Live updates sent while a test runs
+#[derive(Debug, Clone)]
+pub enum SpeedTestUpdate {
+    /// Share of the phase done (0.0 to 1.0) and its throughput so far
+    Progress {
+        phase: SpeedTestPhase,
+        fraction: f64,
+        mbps: f64,
+    },
+    Latency(f64),
+    Done(SpeedTestResult),
+    Failed(String),
+}
+
+/// A past result from the history file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeedTestRecord {
+    pub timestamp: String,
+    pub result: SpeedTestResult,
+}
+
+/// Run a full test, reporting progress through `updates`
+pub fn run(
+    download_url: &str,
+    upload_url: &str,
+    updates: &UnboundedSender<SpeedTestUpdate>,
+) -> Result<SpeedTestResult, String> {
+    let download = HttpUrl::parse(download_url)?;
+    let upload = HttpUrl::parse(upload_url)?;
+
+    let latency_ms =
+        measure_latency(&download, updates).map_err(|e| format!("Latency test failed: {}", e))?;
+    let _ = updates.send(SpeedTestUpdate::Latency(latency_ms));
+    let download_mbps =
+        measure_download(&download, updates).map_err(|e| format!("Download failed: {}", e))?;
+    let upload_mbps =
+        measure_upload(&upload, updates).map_err(|e| format!("Upload failed: {}", e))?;
+
+    Ok(SpeedTestResult {
+        latency_ms,
+        download_mbps,
+        upload_mbps,
+    })
+}
+
+/// Median time to open a TCP connection to the download host
+fn measure_latency(url: &HttpUrl, updates: &UnboundedSender<SpeedTestUpdate>) -> io::Result<f64> {
+    let timeout = Duration::from_secs(config::SPEEDTEST_TIMEOUT_SECS);
+    let mut samples = Vec::with_capacity(LATENCY_SAMPLES);
+    for i in 0..LATENCY_SAMPLES {
+        let start = Instant::now();
+        url.connect(timeout)?;
+        samples.push(start.elapsed().as_secs_f64() * 1000.0);
+        let _ = updates.send(SpeedTestUpdate::Progress {
+            phase: SpeedTestPhase::Latency,
+            fraction: (i + 1) as f64 / LATENCY_SAMPLES as f64,
+            mbps: 0.0,
+        });
+    }
+    samples.sort_by(f64::total_cmp);
+    Ok(samples[samples.len() / 2])
+}
+
+fn measure_download(url: &HttpUrl, updates: &UnboundedSender<SpeedTestUpdate>) -> io::Result<f64> {
+    let mut stream = url.connect(Duration::from_secs(config::SPEEDTEST_TIMEOUT_SECS))?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        url.path, url.host
+    )?;
+
+    let limit = Duration::from_secs(config::SPEEDTEST_PHASE_SECS);
+    let mut buf = vec![0u8; UPLOAD_CHUNK];
+    let mut bytes = 0u64;
+    let start = Instant::now();
+    let mut last_update = start;
+    while start.elapsed() < limit {
+        let len = stream.read(&mut buf)?;
+        if len == 0 {
+            break;
+        }
+        if bytes == 0 && !buf.starts_with(b"HTTP/1.1 200") && !buf.starts_with(b"HTTP/1.0 200") {
+            let status = String::from_utf8_lossy(&buf[..len.min(32)]);
+            return Err(io::Error::other(format!(
+                "Unexpected response: {}",
+                status.lines().next().unwrap_or_default()
+            )));
+        }
+        bytes += len as u64;
+        if last_update.elapsed() >= Duration::from_millis(config::FRAME_INTERVAL_MS) {
+            last_update = Instant::now();
+            let _ = updates.send(SpeedTestUpdate::Progress {
+                phase: SpeedTestPhase::Download,
+                fraction: start.elapsed().as_secs_f64() / limit.as_secs_f64(),
+                mbps: mbps(bytes, start.elapsed()),
+            });
+        }
+    }
+    Ok(mbps(bytes, start.elapsed()))
+}
+
+fn measure_upload(url: &HttpUrl, updates: &UnboundedSender<SpeedTestUpdate>) -> io::Result<f64> {
+    let mut stream = url.connect(Duration::from_secs(config::SPEEDTEST_TIMEOUT_SECS))?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        url.path,
+        url.host,
+        config::SPEEDTEST_UPLOAD_BYTES
+    )?;
+
+    // The body is cut short if the phase runs out of time; only the bytes
+    // that made it out count
+    let limit = Duration::from_secs(config::SPEEDTEST_PHASE_SECS);
+    let chunk = vec![0u8; UPLOAD_CHUNK];
+    let mut bytes = 0u64;
+    let start = Instant::now();
+    let mut last_update = start;
+    while bytes < config::SPEEDTEST_UPLOAD_BYTES && start.elapsed() < limit {
+        let len = chunk
+            .len()
+            .min((config::SPEEDTEST_UPLOAD_BYTES - bytes) as usize);
+        stream.write_all(&chunk[..len])?;
+        bytes += len as u64;
+        if last_update.elapsed() >= Duration::from_millis(config::FRAME_INTERVAL_MS) {
+            last_update = Instant::now();
+            let _ = updates.send(SpeedTestUpdate::Progress {
+                phase: SpeedTestPhase::Upload,
+                fraction: (bytes as f64 / config::SPEEDTEST_UPLOAD_BYTES as f64)
+                    .max(start.elapsed().as_secs_f64() / limit.as_secs_f64()),
+                mbps: mbps(bytes, start.elapsed()),
+            });
+        }
+    }
+    stream.flush()?;
+    Ok(mbps(bytes, start.elapsed()))
+}
+
+fn mbps(bytes: u64, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        bytes as f64 * 8.0 / secs / 1_000_000.0
+    } else {
+        0.0
+    }
+}
+
+/// Append a result to the history file, writing a header for new files
+pub fn save_result(path: &Path, ssid: &str, result: &SpeedTestResult) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let is_new = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if is_new {
+        writeln!(file, "{}", CSV_HEADER)?;
+    }
+    writeln!(
+        file,
+        "{},{},{:.1},{:.2},{:.2}",
+        format_timestamp(SystemTime::now()),
+        csv_escape(ssid),
+        result.latency_ms,
+        result.download_mbps,
+        result.upload_mbps
+    )
+}
+
+/// Past results for a network, oldest first; unreadable lines are skipped
+pub fn load_history(path: &Path, ssid: &str) -> Vec<SpeedTestRecord> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = csv_split(line);
+            let [timestamp, record_ssid, latency, download, upload] = fields.as_slice() else {
+                return None;
+            };
+            (record_ssid == ssid).then_some(())?;
+            Some(SpeedTestRecord {
+                timestamp: timestamp.clone(),
+                result: SpeedTestResult {
+                    latency_ms: latency.parse().ok()?,
+                    download_mbps: download.parse().ok()?,
+                    upload_mbps: upload.parse().ok()?,
+                },
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,48 @@
+//! Band steering assistant
+//!
+//! Detects when the connected network is linked on 2.4 GHz while an access point
+//! of the same SSID is available on a faster band with a good signal.
+
+use crate::config;
+use crate::wifi::{Band, WifiInfo};
+
+/// A suggested reconnect to a faster band of the connected SSID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SteeringSuggestion {
+    pub ssid: String,
+    pub bssid: [u8; 6],
+    pub band: Band,
+    pub channel: u32,
+    pub rssi: i32,
+    pub signal: u8,
+}
+
+/// Find a faster-band access point for the connected network, if one is worth switching to
+pub fn find_faster_band(wifi: &WifiInfo) -> Option<SteeringSuggestion> {
+    if !wifi.is_connected {
+        return None;
+    }
+
+    let current = wifi.connected_bss()?;
+    if current.band() != Some(Band::Ghz2_4) {
+        return None;
+    }
+
+    wifi.bss_list
+        .iter()
+        .filter(|bss| {
+            matches!(bss.band(), Some(Band::Ghz5 | Band::Ghz6))
+                && bss.link_quality >= config::BAND_STEERING_MIN_SIGNAL
+        })
+        .max_by_key(|bss| bss.link_quality)
+        .and_then(|bss| {
+            Some(SteeringSuggestion {
+                ssid: wifi.ssid.clone(),
+                bssid: bss.bssid,
+                band: bss.band()?,
+                channel: bss.channel,
+                rssi: bss.rssi,
+                signal: bss.link_quality,
+            })
+        })
+}
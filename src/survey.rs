@@ -0,0 +1,136 @@
+//! Site survey capture
+//!
+//! Collects RSSI samples for every visible access point and groups them under
+//! user-supplied location labels, producing a per-label summary for a simple
+//! heatmap workflow.
+
+use crate::monitor::csv_escape;
+use crate::wifi::{WifiInfo, format_bssid};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Aggregated RSSI statistics for one access point
+#[derive(Debug, Clone)]
+pub struct RssiStats {
+    pub ssid: String,
+    pub samples: u32,
+    pub min: i32,
+    pub max: i32,
+    pub sum: i64,
+}
+
+impl RssiStats {
+    fn new(ssid: &str, rssi: i32) -> Self {
+        Self {
+            ssid: ssid.to_string(),
+            samples: 1,
+            min: rssi,
+            max: rssi,
+            sum: rssi as i64,
+        }
+    }
+
+    fn add(&mut self, rssi: i32) {
+        self.samples += 1;
+        self.min = self.min.min(rssi);
+        self.max = self.max.max(rssi);
+        self.sum += rssi as i64;
+    }
+
+    fn merge(&mut self, other: &RssiStats) {
+        self.samples += other.samples;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.sum += other.sum;
+    }
+
+    pub fn average(&self) -> f64 {
+        self.sum as f64 / self.samples.max(1) as f64
+    }
+}
+
+/// Samples per access point, keyed by BSSID
+type SampleSet = BTreeMap<[u8; 6], RssiStats>;
+
+/// An in-progress site survey
+#[derive(Debug, Default)]
+pub struct Survey {
+    pending: SampleSet,
+    pending_scans: u32,
+    labeled: BTreeMap<String, SampleSet>,
+}
+
+impl Survey {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one scan's worth of RSSI samples into the untagged sample set
+    pub fn record(&mut self, networks: &[Arc<WifiInfo>]) {
+        for wifi in networks {
+            for bss in &wifi.bss_list {
+                self.pending
+                    .entry(bss.bssid)
+                    .and_modify(|stats| stats.add(bss.rssi))
+                    .or_insert_with(|| RssiStats::new(&wifi.ssid, bss.rssi));
+            }
+        }
+        self.pending_scans += 1;
+    }
+
+    /// Number of scans recorded since the last tag
+    pub fn pending_scans(&self) -> u32 {
+        self.pending_scans
+    }
+
+    /// Number of location labels captured so far
+    pub fn label_count(&self) -> usize {
+        self.labeled.len()
+    }
+
+    /// Assign the untagged samples to a location label, returning the number of APs tagged
+    pub fn tag(&mut self, label: &str) -> usize {
+        let pending = std::mem::take(&mut self.pending);
+        self.pending_scans = 0;
+        let tagged = pending.len();
+
+        let set = self.labeled.entry(label.to_string()).or_default();
+        for (bssid, stats) in pending {
+            set.entry(bssid)
+                .and_modify(|existing| existing.merge(&stats))
+                .or_insert(stats);
+        }
+        tagged
+    }
+
+    /// Write a per-label, per-BSSID RSSI summary as CSV
+    pub fn export_summary(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(path)?;
+        writeln!(
+            file,
+            "label,ssid,bssid,samples,min_rssi_dbm,avg_rssi_dbm,max_rssi_dbm"
+        )?;
+        for (label, set) in &self.labeled {
+            for (bssid, stats) in set {
+                writeln!(
+                    file,
+                    "{},{},{},{},{},{:.1},{}",
+                    csv_escape(label),
+                    csv_escape(&stats.ssid),
+                    format_bssid(bssid),
+                    stats.samples,
+                    stats.min,
+                    stats.average(),
+                    stats.max
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
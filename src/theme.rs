@@ -1,24 +1,141 @@
+//! Color palettes
+//!
+//! Built-in themes are picked by name with `theme = "gruvbox"` or `--theme`.
+//! Custom ones are defined in the `[themes]` table of the config file, e.g.
+//! `[themes.mine]` with `base = "light"` and `yellow = "#B58900"`; colors it
+//! leaves out come from its base theme.
+
+use crate::config::ThemeSpec;
 use ratatui::style::Color;
+use std::collections::BTreeMap;
+
+/// Colors used by the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub background: Color,
+    pub foreground: Color,
+    pub red: Color,
+    pub green: Color,
+    pub yellow: Color,
+    pub blue: Color,
+    pub purple: Color,
+    pub cyan: Color,
+    pub bright_purple: Color,
+    /// Text and borders of dimmed or secondary elements
+    pub dimmed: Color,
+    /// Background of the selected list row
+    pub selection_bg: Color,
+}
+
+pub const DARK: Theme = Theme {
+    background: Color::Rgb(24, 23, 21),      // #181715
+    foreground: Color::Rgb(168, 163, 159),   // #A8A39F
+    red: Color::Rgb(152, 41, 15),            // #98290F
+    green: Color::Rgb(71, 154, 67),          // #479A43
+    yellow: Color::Rgb(127, 113, 17),        // #7F7111
+    blue: Color::Rgb(73, 127, 125),          // #497F7D
+    purple: Color::Rgb(127, 78, 47),         // #7F4E2F
+    cyan: Color::Rgb(56, 127, 88),           // #387F58
+    bright_purple: Color::Rgb(205, 124, 84), // #CD7C54
+    dimmed: Color::Rgb(60, 60, 60),          // #3C3C3C
+    selection_bg: Color::Rgb(65, 56, 41),    // #413829
+};
+
+pub const LIGHT: Theme = Theme {
+    background: Color::Rgb(250, 248, 245),   // #FAF8F5
+    foreground: Color::Rgb(60, 56, 54),      // #3C3836
+    red: Color::Rgb(192, 57, 43),            // #C0392B
+    green: Color::Rgb(46, 125, 50),          // #2E7D32
+    yellow: Color::Rgb(154, 103, 0),         // #9A6700
+    blue: Color::Rgb(42, 111, 151),          // #2A6F97
+    purple: Color::Rgb(142, 68, 173),        // #8E44AD
+    cyan: Color::Rgb(17, 122, 101),          // #117A65
+    bright_purple: Color::Rgb(160, 82, 45),  // #A0522D
+    dimmed: Color::Rgb(176, 171, 165),       // #B0ABA5
+    selection_bg: Color::Rgb(232, 225, 213), // #E8E1D5
+};
+
+pub const GRUVBOX: Theme = Theme {
+    background: Color::Rgb(40, 40, 40),      // #282828
+    foreground: Color::Rgb(235, 219, 178),   // #EBDBB2
+    red: Color::Rgb(204, 36, 29),            // #CC241D
+    green: Color::Rgb(152, 151, 26),         // #98971A
+    yellow: Color::Rgb(215, 153, 33),        // #D79921
+    blue: Color::Rgb(69, 133, 136),          // #458588
+    purple: Color::Rgb(177, 98, 134),        // #B16286
+    cyan: Color::Rgb(104, 157, 106),         // #689D6A
+    bright_purple: Color::Rgb(254, 128, 25), // #FE8019
+    dimmed: Color::Rgb(102, 92, 84),         // #665C54
+    selection_bg: Color::Rgb(60, 56, 54),    // #3C3836
+};
+
+/// Catppuccin Mocha
+pub const CATPPUCCIN: Theme = Theme {
+    background: Color::Rgb(30, 30, 46),       // #1E1E2E
+    foreground: Color::Rgb(205, 214, 244),    // #CDD6F4
+    red: Color::Rgb(243, 139, 168),           // #F38BA8
+    green: Color::Rgb(166, 227, 161),         // #A6E3A1
+    yellow: Color::Rgb(249, 226, 175),        // #F9E2AF
+    blue: Color::Rgb(137, 180, 250),          // #89B4FA
+    purple: Color::Rgb(203, 166, 247),        // #CBA6F7
+    cyan: Color::Rgb(148, 226, 213),          // #94E2D5
+    bright_purple: Color::Rgb(250, 179, 135), // #FAB387
+    dimmed: Color::Rgb(88, 91, 112),          // #585B70
+    selection_bg: Color::Rgb(49, 50, 68),     // #313244
+};
+
+/// Built-in themes by name
+pub const BUILT_IN: [(&str, Theme); 4] = [
+    ("dark", DARK),
+    ("light", LIGHT),
+    ("gruvbox", GRUVBOX),
+    ("catppuccin", CATPPUCCIN),
+];
+
+impl Default for Theme {
+    fn default() -> Self {
+        DARK
+    }
+}
+
+impl Theme {
+    pub fn built_in(name: &str) -> Option<Self> {
+        BUILT_IN
+            .iter()
+            .find(|(built_in, _)| built_in.eq_ignore_ascii_case(name))
+            .map(|&(_, theme)| theme)
+    }
+
+    /// Resolve a theme name against the `[themes]` config table, then the built-ins
+    pub fn from_config(name: &str, themes: &BTreeMap<String, ThemeSpec>) -> Result<Self, String> {
+        let Some(spec) = themes.get(name) else {
+            return Self::built_in(name).ok_or_else(|| format!("Unknown theme: {}", name));
+        };
 
-pub const BACKGROUND: Color = Color::Rgb(24, 23, 21); // #181715
-pub const FOREGROUND: Color = Color::Rgb(168, 163, 159); // #A8A39F
-// pub const BLACK: Color = Color::Rgb(60, 60, 48); // #3C3C30
-pub const RED: Color = Color::Rgb(152, 41, 15); // #98290F
-pub const GREEN: Color = Color::Rgb(71, 154, 67); // #479A43
-pub const YELLOW: Color = Color::Rgb(127, 113, 17); // #7F7111
-pub const BLUE: Color = Color::Rgb(73, 127, 125); // #497F7D
-pub const PURPLE: Color = Color::Rgb(127, 78, 47); // #7F4E2F
-pub const CYAN: Color = Color::Rgb(56, 127, 88); // #387F58
-// pub const WHITE: Color = Color::Rgb(128, 121, 116); // #807974
-
-// pub const BRIGHT_BLACK: Color = Color::Rgb(85, 84, 69); // #555445
-// pub const BRIGHT_RED: Color = Color::Rgb(224, 80, 42); // #E0502A
-// pub const BRIGHT_GREEN: Color = Color::Rgb(97, 224, 112); // #61E070
-// pub const BRIGHT_YELLOW: Color = Color::Rgb(214, 153, 39); // #D69927
-// pub const BRIGHT_BLUE: Color = Color::Rgb(121, 217, 217); // #79D9D9
-pub const BRIGHT_PURPLE: Color = Color::Rgb(205, 124, 84); // #CD7C54
-// pub const BRIGHT_CYAN: Color = Color::Rgb(89, 213, 153); // #59D599
-// pub const BRIGHT_WHITE: Color = Color::Rgb(255, 241, 233); // #FFF1E9
-
-pub const DIMMED: Color = Color::Rgb(60, 60, 60); // Dark Gray for dimmed state
-pub const SELECTION_BG: Color = Color::Rgb(65, 56, 41); // #413829
+        let base = spec.base.as_deref().unwrap_or("dark");
+        let mut theme =
+            Self::built_in(base).ok_or_else(|| format!("Unknown base theme: {}", base))?;
+        let colors = [
+            (&spec.background, &mut theme.background),
+            (&spec.foreground, &mut theme.foreground),
+            (&spec.red, &mut theme.red),
+            (&spec.green, &mut theme.green),
+            (&spec.yellow, &mut theme.yellow),
+            (&spec.blue, &mut theme.blue),
+            (&spec.purple, &mut theme.purple),
+            (&spec.cyan, &mut theme.cyan),
+            (&spec.bright_purple, &mut theme.bright_purple),
+            (&spec.dimmed, &mut theme.dimmed),
+            (&spec.selection_bg, &mut theme.selection_bg),
+        ];
+        for (value, color) in colors {
+            if let Some(value) = value {
+                // Accepts "#RRGGBB", ANSI names like "lightblue" and 256-color indices
+                *color = value
+                    .parse()
+                    .map_err(|_| format!("Invalid color in theme {}: {}", name, value))?;
+            }
+        }
+        Ok(theme)
+    }
+}
@@ -1,13 +1,25 @@
-use crate::app::AppState;
+use crate::action::Action;
+use crate::app::{AppState, ClickAreas, DetailsTab, OperationKind, ToastKind, fuzzy_indices};
+use crate::channels;
+use crate::compare;
 use crate::config;
-use crate::theme;
+use crate::counters::FrameRates;
+use crate::filter::ListFilter;
+use crate::logging;
+use crate::monitor::format_timestamp;
+use crate::net::Connectivity;
+use crate::oui;
+use crate::perf;
+use crate::theme::Theme;
+use crate::wifi::{Band, IpInfo, RadioState, format_bssid};
 use ratatui::{
     prelude::*,
     widgets::{
-        Block, BorderType, Borders, Clear, List, ListItem, Padding, Paragraph, Scrollbar,
-        ScrollbarOrientation, ScrollbarState, Wrap,
+        Bar, BarChart, BarGroup, Block, BorderType, Borders, Clear, Gauge, List, ListItem, Padding,
+        Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap,
     },
 };
+use std::time::Instant;
 
 fn display_auth_name(auth: &str) -> &str {
     match auth {
@@ -26,48 +38,74 @@ fn display_auth_name(auth: &str) -> &str {
     }
 }
 
+/// Help bar line of `key description` pairs, skipping actions with no key bound
+fn help_line(theme: &Theme, entries: Vec<(String, &'static str)>) -> Line<'static> {
+    let mut spans = Vec::new();
+    for (key, description) in entries.into_iter().filter(|(key, _)| !key.is_empty()) {
+        if !spans.is_empty() {
+            spans.push(Span::styled(" • ", Style::default().fg(theme.dimmed)));
+        }
+        spans.push(Span::styled(key, Style::default().fg(theme.foreground)));
+        spans.push(Span::styled(
+            format!(" {}", description),
+            Style::default().fg(theme.dimmed),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Single-line text input scrolled to keep the cursor visible
+fn input_line(
+    theme: &Theme,
+    text: &str,
+    cursor: usize,
+    width: usize,
+    focused: bool,
+) -> Line<'static> {
+    let width = width.max(1);
+    let skip = (cursor + 1).saturating_sub(width);
+    let chars: Vec<char> = text.chars().skip(skip).take(width).collect();
+    let cursor_x = cursor - skip;
+    let cursor_style = Style::default().bg(theme.foreground).fg(theme.background);
+
+    let mut spans: Vec<Span> = chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            if focused && i == cursor_x {
+                Span::styled(c.to_string(), cursor_style)
+            } else {
+                Span::raw(c.to_string())
+            }
+        })
+        .collect();
+    if focused && cursor_x == chars.len() {
+        spans.push(Span::styled(" ", cursor_style));
+    }
+    Line::from(spans)
+}
+
 pub fn render(frame: &mut Frame, state: &mut AppState) {
     let area = frame.area();
+    state.update_list_rows();
     let is_dimmed = state.is_popup_open();
     let icons = &state.ui.icon_set;
+    let theme = state.config.theme;
 
     // Set background color for the entire screen
     frame.render_widget(
-        Block::default().style(Style::default().bg(theme::BACKGROUND).fg(theme::FOREGROUND)),
+        Block::default().style(Style::default().bg(theme.background).fg(theme.foreground)),
         area,
     );
 
-    // Calculate dynamic dimensions to ensure perfect centering
-    // Adjust width/height to match the parity of the terminal size
-    let target_height = config::MAIN_WINDOW_HEIGHT;
-    let height = if area.height % 2 == 0 {
-        if target_height % 2 == 0 {
-            target_height
-        } else {
-            target_height + 1
-        }
-    } else {
-        if target_height % 2 != 0 {
-            target_height
-        } else {
-            target_height + 1
-        }
-    };
+    if area.width < config::MIN_WINDOW_WIDTH || area.height < config::MIN_WINDOW_HEIGHT {
+        state.ui.click_areas = ClickAreas::default();
+        render_too_small(frame, &theme, area);
+        return;
+    }
 
-    let target_width = config::MAIN_WINDOW_WIDTH;
-    let width = if area.width % 2 == 0 {
-        if target_width % 2 == 0 {
-            target_width
-        } else {
-            target_width + 1
-        }
-    } else {
-        if target_width % 2 != 0 {
-            target_width
-        } else {
-            target_width + 1
-        }
-    };
+    let height = window_length(area.height, config::MAX_WINDOW_HEIGHT);
+    let width = window_length(area.width, config::MAX_WINDOW_WIDTH);
 
     // Center the main window
     let vertical_layout = Layout::vertical([
@@ -86,19 +124,42 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
 
     let main_area = horizontal_layout[1];
 
-    let border_style = Style::default().fg(theme::DIMMED);
+    let border_style = Style::default().fg(theme.dimmed);
 
-    let title_style = Style::default()
-        .fg(theme::CYAN)
-        .add_modifier(Modifier::BOLD);
+    let title_style = Style::default().fg(theme.cyan).add_modifier(Modifier::BOLD);
 
-    let main_block = Block::default()
+    let mut main_block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .border_style(border_style)
         .title(format!(" WIFUI v{} ", env!("CARGO_PKG_VERSION")))
         .title_alignment(Alignment::Center)
         .title_style(title_style);
+    if let Some(radio) = state.network.radio {
+        let color = if radio.is_on() {
+            theme.green
+        } else {
+            theme.red
+        };
+        main_block = main_block.title_top(
+            Line::styled(format!(" {} ", radio.label()), Style::default().fg(color))
+                .right_aligned(),
+        );
+    }
+
+    if let Some(status) = &state.ui.status {
+        let color = if status.ok { theme.green } else { theme.red };
+        main_block = main_block.title_bottom(Line::from(vec![
+            Span::styled(
+                format!(" {} ", icons.outcome(status.ok)),
+                Style::default().fg(color),
+            ),
+            Span::styled(
+                format!("{} ", status.message),
+                Style::default().fg(theme.foreground),
+            ),
+        ]));
+    }
 
     frame.render_widget(main_block, main_area);
 
@@ -107,14 +168,36 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         horizontal: 2,
     });
 
+    let compact = main_area.width < config::COMPACT_WINDOW_WIDTH;
+    let help_height = if compact { 1 } else { 2 };
+    let search_height = if state.ui.is_searching || !state.inputs.search_input.value.is_empty() {
+        3
+    } else {
+        0
+    };
+    // Details, with rows for a revealed password, an evil twin warning and
+    // the latency monitor
+    let details_height = 11
+        + u16::from(state.ui.revealed_password.is_some())
+        + u16::from(selected_is_suspicious(state))
+        + u16::from(selected_is_monitored(state));
+    // Collapse the details pane when it would squeeze the list (+2 for its border)
+    let details_height = if inner_area.height
+        >= search_height + config::MIN_LIST_ROWS + 2 + details_height + help_height
+    {
+        details_height
+    } else {
+        0
+    };
+
     let mut constraints = vec![
-        Constraint::Min(9),     // Network list
-        Constraint::Length(10), // Details
-        Constraint::Length(2),  // Bottom bar
+        Constraint::Min(config::MIN_LIST_ROWS + 2), // Network list
+        Constraint::Length(details_height),
+        Constraint::Length(help_height), // Bottom bar
     ];
 
-    if state.ui.is_searching || !state.inputs.search_input.value.is_empty() {
-        constraints.insert(0, Constraint::Length(3));
+    if search_height > 0 {
+        constraints.insert(0, Constraint::Length(search_height));
     }
 
     let content_layout = Layout::vertical(constraints).split(inner_area);
@@ -138,11 +221,11 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
 
     if let Some(area) = search_area {
         let search_style = if is_dimmed {
-            Style::default().fg(theme::DIMMED)
+            Style::default().fg(theme.dimmed)
         } else if state.ui.is_searching {
-            Style::default().fg(theme::YELLOW)
+            Style::default().fg(theme.yellow)
         } else {
-            Style::default().fg(theme::CYAN)
+            Style::default().fg(theme.cyan)
         };
 
         let search_block = Block::default()
@@ -152,65 +235,18 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
             .border_style(search_style);
 
         let max_width = (area.width.saturating_sub(2)) as usize;
-        let input_len = state.inputs.search_input.value.chars().count();
-        let cursor_pos = state.inputs.search_input.cursor;
-
-        let (display_text, cursor_x) = if input_len < max_width {
-            (state.inputs.search_input.value.clone(), cursor_pos)
-        } else {
-            // If cursor is near the end, show the end
-            if cursor_pos >= max_width {
-                let skip = cursor_pos - max_width + 1;
-                let take = max_width;
-                let text: String = state
-                    .inputs
-                    .search_input
-                    .value
-                    .chars()
-                    .skip(skip)
-                    .take(take)
-                    .collect();
-                (text, max_width - 1)
-            } else {
-                // If cursor is at the beginning, show the beginning
-                let text: String = state
-                    .inputs
-                    .search_input
-                    .value
-                    .chars()
-                    .take(max_width)
-                    .collect();
-                (text, cursor_pos)
-            }
-        };
-
-        let mut spans = Vec::new();
-        let chars: Vec<char> = display_text.chars().collect();
-
-        for (i, c) in chars.iter().enumerate() {
-            if i == cursor_x && state.ui.is_searching && !is_dimmed {
-                spans.push(Span::styled(
-                    c.to_string(),
-                    Style::default().bg(theme::FOREGROUND).fg(theme::BACKGROUND),
-                ));
-            } else if is_dimmed {
-                spans.push(Span::styled(
-                    c.to_string(),
-                    Style::default().fg(theme::DIMMED),
-                ));
-            } else {
-                spans.push(Span::raw(c.to_string()));
-            }
-        }
-
-        if cursor_x == chars.len() && state.ui.is_searching && !is_dimmed {
-            spans.push(Span::styled(
-                " ",
-                Style::default().bg(theme::FOREGROUND).fg(theme::BACKGROUND),
-            ));
+        let mut line = input_line(
+            &theme,
+            &state.inputs.search_input.value,
+            state.inputs.search_input.cursor,
+            max_width,
+            state.ui.is_searching && !is_dimmed,
+        );
+        if is_dimmed {
+            line = line.style(Style::default().fg(theme.dimmed));
         }
 
-        let search_text = Paragraph::new(Line::from(spans)).block(search_block);
+        let search_text = Paragraph::new(line).block(search_block);
 
         frame.render_widget(search_text, area);
     }
@@ -232,26 +268,20 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
 
         let padded_block = Block::default()
             .title(" Networks ")
-            .title_style(
-                Style::default()
-                    .fg(theme::BLUE)
-                    .add_modifier(Modifier::BOLD),
-            )
+            .title_style(Style::default().fg(theme.blue).add_modifier(Modifier::BOLD))
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(theme::BLUE))
+            .border_style(Style::default().fg(theme.blue))
             .padding(Padding::new(0, 0, top_padding, 0));
 
         let spinner_paragraph = Paragraph::new(vec![
             Line::from(Span::styled(
                 spinner_char,
-                Style::default()
-                    .fg(theme::CYAN)
-                    .add_modifier(Modifier::BOLD),
+                Style::default().fg(theme.cyan).add_modifier(Modifier::BOLD),
             )),
             Line::from(Span::styled(
                 "Scanning networks...",
-                Style::default().fg(theme::FOREGROUND),
+                Style::default().fg(theme.foreground),
             )),
         ])
         .block(padded_block)
@@ -260,182 +290,322 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
 
         frame.render_widget(spinner_paragraph, combined_area);
     } else {
-    let list_items: Vec<ListItem> = state
-        .network
-        .filtered_wifi_list
-        .iter()
-        .map(|w| {
-            let mut ssid = w.ssid.clone();
-            let mut style = if is_dimmed {
-                Style::default().fg(theme::DIMMED)
-            } else {
-                Style::default()
-            };
+        // Drop the security column, then the signal column, before names get too narrow
+        let row_width =
+            (list_area.width as usize).saturating_sub(2 + Span::raw(icons.highlight()).width());
+        let show_signal = row_width >= config::MIN_LIST_NAME_WIDTH + 10;
+        let show_security = row_width >= config::MIN_LIST_NAME_WIDTH + 17;
+        // Last run's snapshot stays greyed out until the first scan replaces it
+        let rows_dimmed = is_dimmed || state.refresh.showing_snapshot;
+        let match_style = Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+
+        let list_items: Vec<ListItem> = state
+            .network
+            .list_rows
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|row| {
+                let mut columns = String::new();
+                if show_security {
+                    columns.push_str(&format!(" {:<6.6}", row.security));
+                }
+                if show_signal {
+                    columns.push_str(&format!(" {}", row.signal));
+                }
+                let name_width = row_width.saturating_sub(Span::raw(&columns).width());
+                let name = fit_width(&row.text, name_width);
+                let text = if row.matched.is_empty() {
+                    Line::raw(format!("{}{}", name, columns))
+                } else {
+                    // Underline the characters the search matched
+                    let mut spans: Vec<Span> = name
+                        .chars()
+                        .enumerate()
+                        .map(|(i, c)| {
+                            if row.matched.contains(&i) {
+                                Span::styled(c.to_string(), match_style)
+                            } else {
+                                Span::raw(c.to_string())
+                            }
+                        })
+                        .collect();
+                    spans.push(Span::raw(columns));
+                    Line::from(spans)
+                };
+
+                let mut style = if rows_dimmed {
+                    Style::default().fg(theme.dimmed)
+                } else {
+                    Style::default()
+                };
 
-            let prefix = if w.is_saved {
-                if !is_dimmed {
-                    style = style.fg(theme::BLUE);
+                if row.is_saved && !rows_dimmed {
+                    style = style.fg(theme.blue);
                 }
-                icons.saved()
-            } else if w.authentication == "Open" {
-                icons.open()
-            } else {
-                icons.locked()
-            };
 
-            ssid = format!("{}{}", prefix, ssid);
+                if row.profile_mismatch && !rows_dimmed {
+                    style = style.fg(theme.yellow);
+                }
 
-            if let Some(connected_ssid) = &state.network.connected_ssid
-                && w.ssid == *connected_ssid
-            {
-                ssid = format!("{}{}", ssid, icons.connected());
-                if is_dimmed {
-                    style = style.fg(theme::DIMMED).add_modifier(Modifier::BOLD);
-                } else {
-                    style = style.fg(theme::GREEN).add_modifier(Modifier::BOLD);
+                if row.suspicious && !rows_dimmed {
+                    style = style.fg(theme.red);
                 }
-            }
 
-            if w.is_saved {
-                if w.auto_connect {
-                    ssid = format!("{} {}", ssid, icons.auto_on());
-                } else {
-                    ssid = format!("{} {}", ssid, icons.auto_off());
+                if row.is_new && !rows_dimmed {
+                    style = style.fg(theme.cyan).add_modifier(Modifier::BOLD);
                 }
-            }
 
-            ListItem::new(ssid).style(style)
-        })
-        .collect();
+                // Gone from the latest scan, dropped after a few more
+                if row.fading {
+                    style = style.fg(theme.dimmed).add_modifier(Modifier::ITALIC);
+                }
 
-    let list_border_style = if is_dimmed {
-        Style::default().fg(theme::DIMMED)
-    } else {
-        Style::default().fg(theme::BLUE)
-    };
+                if row.is_connected {
+                    if rows_dimmed {
+                        style = style.fg(theme.dimmed).add_modifier(Modifier::BOLD);
+                    } else {
+                        style = style.fg(theme.green).add_modifier(Modifier::BOLD);
+                    }
+                }
 
-    let list_title_style = if is_dimmed {
-        Style::default()
-            .fg(theme::DIMMED)
-            .add_modifier(Modifier::BOLD)
-    } else {
-        Style::default()
-            .fg(theme::BLUE)
-            .add_modifier(Modifier::BOLD)
-    };
+                ListItem::new(text).style(style)
+            })
+            .collect();
 
-    let list = List::new(list_items)
-        .block(
-            Block::default()
-                .title(" Networks ")
-                .title_style(list_title_style)
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(list_border_style),
-        )
-        .highlight_symbol(icons.highlight())
-        .highlight_style(
+        let list_border_style = if is_dimmed {
+            Style::default().fg(theme.dimmed)
+        } else {
+            Style::default().fg(theme.blue)
+        };
+
+        let list_title_style = if is_dimmed {
             Style::default()
+                .fg(theme.dimmed)
                 .add_modifier(Modifier::BOLD)
-                .bg(if is_dimmed {
-                    theme::BACKGROUND
-                } else {
-                    theme::SELECTION_BG
-                }),
-        );
+        } else {
+            Style::default().fg(theme.blue).add_modifier(Modifier::BOLD)
+        };
 
-    frame.render_stateful_widget(list, list_area, &mut state.ui.l_state);
+        let list_title = match &state.monitor.survey {
+            Some(survey) => format!(" Networks [SURVEY: {} scans] ", survey.pending_scans()),
+            None if state.refresh.showing_snapshot => {
+                " Networks (cached, scanning...) ".to_string()
+            }
+            None => " Networks ".to_string(),
+        };
+        // Name the adapter when there is more than one to choose from
+        let list_title = match &state.network.active_interface {
+            Some(interface) if state.network.interfaces.len() > 1 => {
+                format!("{}[{}] ", list_title, interface.description)
+            }
+            _ => list_title,
+        };
 
-    let viewport_height = list_area.height.saturating_sub(2) as usize;
-    let content_len = state.network.filtered_wifi_list.len();
+        let mut list_block = Block::default();
+        if let Some(status) = list_status(state) {
+            list_block = list_block.title_bottom(Line::styled(
+                format!(" {} ", status),
+                Style::default().fg(theme.yellow),
+            ));
+        }
+        let list = List::new(list_items)
+            .block(
+                list_block
+                    .title(list_title)
+                    .title_style(list_title_style)
+                    .title_top(
+                        Line::styled(
+                            match state.network.filter {
+                                ListFilter::All => {
+                                    format!(" sort: {} ", state.network.sort.label())
+                                }
+                                filter => format!(
+                                    " {} only · sort: {} ",
+                                    filter.label(),
+                                    state.network.sort.label()
+                                ),
+                            },
+                            Style::default().fg(theme.dimmed),
+                        )
+                        .right_aligned(),
+                    )
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(list_border_style),
+            )
+            .highlight_symbol(icons.highlight())
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .bg(if is_dimmed {
+                        theme.background
+                    } else {
+                        theme.selection_bg
+                    }),
+            );
+
+        frame.render_stateful_widget(list, list_area, &mut state.ui.l_state);
+        state.ui.click_areas.list = list_area.inner(Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
 
-    let mut scroll_state = ScrollbarState::new(content_len)
-        .position(state.ui.l_state.selected().unwrap_or(0))
-        .viewport_content_length(viewport_height);
+        // An empty list is expected with the radio off, so say how to fix it
+        if let Some(radio) = state.network.radio.filter(|radio| !radio.is_on())
+            && state.network.filtered_wifi_list.is_empty()
+        {
+            let hint = match radio {
+                RadioState::HardwareOff => {
+                    "Use the device's wireless switch to turn it on".to_string()
+                }
+                _ => format!(
+                    "Press {} to turn it on",
+                    state.config.keymap.hint(Action::ToggleRadio)
+                ),
+            };
+            let inner = list_area.inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            });
+            let message = Paragraph::new(vec![
+                Line::from(Span::styled(
+                    "WiFi radio is off",
+                    Style::default().fg(theme.red).add_modifier(Modifier::BOLD),
+                )),
+                Line::from(Span::styled(hint, Style::default().fg(theme.dimmed))),
+            ])
+            .alignment(Alignment::Center);
+            let top = inner.height.saturating_sub(2) / 2;
+            frame.render_widget(
+                message,
+                Rect {
+                    y: inner.y + top,
+                    height: inner.height - top,
+                    ..inner
+                },
+            );
+        }
 
-    if content_len > viewport_height {
-        let scrollbar_style = if is_dimmed {
-            Style::default().fg(theme::DIMMED)
-        } else {
-            Style::default().fg(theme::BLUE)
-        };
+        let viewport_height = list_area.height.saturating_sub(2) as usize;
+        let content_len = state.network.filtered_wifi_list.len();
 
-        let scrollbar = Scrollbar::default()
-            .orientation(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(Some(""))
-            .end_symbol(Some(""))
-            .thumb_symbol("█")
-            .track_symbol(Some("│"))
-            .style(scrollbar_style);
-
-        frame.render_stateful_widget(
-            scrollbar,
-            list_area.inner(Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut scroll_state,
-        );
-    }
+        let mut scroll_state = ScrollbarState::new(content_len)
+            .position(state.ui.l_state.selected().unwrap_or(0))
+            .viewport_content_length(viewport_height);
 
-    if let Some(selected) = state.ui.l_state.selected()
-        && let Some(wifi) = state.network.filtered_wifi_list.get(selected)
-    {
-        let label_style = if is_dimmed {
-            Style::default().fg(theme::DIMMED)
-        } else {
-            Style::default().fg(theme::CYAN)
-        };
+        if content_len > viewport_height {
+            let scrollbar_style = if is_dimmed {
+                Style::default().fg(theme.dimmed)
+            } else {
+                Style::default().fg(theme.blue)
+            };
 
-        let value_style = if is_dimmed {
-            Style::default().fg(theme::DIMMED)
-        } else {
-            Style::default()
-        };
+            let scrollbar = Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(Some(""))
+                .end_symbol(Some(""))
+                .thumb_symbol("█")
+                .track_symbol(Some("│"))
+                .style(scrollbar_style);
+
+            frame.render_stateful_widget(
+                scrollbar,
+                list_area.inner(Margin {
+                    vertical: 1,
+                    horizontal: 0,
+                }),
+                &mut scroll_state,
+            );
+        }
 
-        let label = |text: &str| Span::styled(format!("{:>11} ", text), label_style);
+        if let Some(selected) = state.ui.l_state.selected()
+            && let Some(wifi) = state.network.filtered_wifi_list.get(selected)
+        {
+            let label_style = if is_dimmed {
+                Style::default().fg(theme.dimmed)
+            } else {
+                Style::default().fg(theme.cyan)
+            };
 
-        let sec_icon = if wifi.authentication == "Open" {
-            icons.open()
-        } else {
-            icons.locked()
-        };
-        let saved_icon = icons.saved();
-
-        let signal_bar_width = (wifi.signal as usize / 10).min(10);
-        let signal_color = if is_dimmed {
-            theme::DIMMED
-        } else if wifi.signal > 70 {
-            theme::GREEN
-        } else if wifi.signal > 40 {
-            theme::YELLOW
-        } else {
-            theme::RED
-        };
-        let signal_bar = "█".repeat(signal_bar_width) + &"░".repeat(10 - signal_bar_width);
+            let value_style = if is_dimmed {
+                Style::default().fg(theme.dimmed)
+            } else {
+                Style::default()
+            };
+
+            let label = |text: &str| Span::styled(format!("{:>11} ", text), label_style);
 
-        let mut info = vec![
-            if wifi.is_connected {
+            let sec_icon = if wifi.authentication == "Open" {
+                icons.open()
+            } else {
+                icons.locked()
+            };
+            let saved_icon = icons.saved();
+
+            let signal_bar_width = (wifi.signal as usize / 10).min(10);
+            let signal_color = if is_dimmed {
+                theme.dimmed
+            } else if wifi.signal > 70 {
+                theme.green
+            } else if wifi.signal > 40 {
+                theme.yellow
+            } else {
+                theme.red
+            };
+            let signal_bar = "█".repeat(signal_bar_width) + &"░".repeat(10 - signal_bar_width);
+            let history = state.monitor.signal_history.get(&wifi.ssid);
+            let sparkline: String = history
+                .map(|history| {
+                    history
+                        .iter()
+                        .map(|sample| {
+                            let last = config::SPARKLINE_CHARS.len() - 1;
+                            config::SPARKLINE_CHARS[(sample.signal as usize * last / 100).min(last)]
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            // Weakest and strongest RSSI over the history, read while connected
+            let rssi_range = history
+                .into_iter()
+                .flatten()
+                .filter_map(|sample| sample.rssi)
+                .fold(None, |range: Option<(i32, i32)>, rssi| {
+                    Some(range.map_or((rssi, rssi), |(min, max)| (min.min(rssi), max.max(rssi))))
+                })
+                .filter(|_| wifi.is_connected)
+                .map(|(min, max)| {
+                    if min == max {
+                        format!("  {} dBm", min)
+                    } else {
+                        format!("  {}…{} dBm", min, max)
+                    }
+                })
+                .unwrap_or_default();
+
+            let status_line = if wifi.is_connected {
                 Line::from(vec![
                     label("Status"),
                     Span::styled(
                         format!("{} Connected ", icons.connected().trim()),
                         if is_dimmed {
-                            Style::default().fg(theme::DIMMED)
+                            Style::default().fg(theme.dimmed)
                         } else {
                             Style::default()
-                                .fg(theme::GREEN)
+                                .fg(theme.green)
                                 .add_modifier(Modifier::BOLD)
                         },
                     ),
                     Span::styled(
                         format!("{}Saved", saved_icon),
                         if is_dimmed {
-                            Style::default().fg(theme::DIMMED)
+                            Style::default().fg(theme.dimmed)
                         } else {
-                            Style::default().fg(theme::BLUE)
+                            Style::default().fg(theme.blue)
                         },
                     ),
+                    connectivity_span(state, &wifi.ssid, is_dimmed),
                 ])
             } else if wifi.is_saved {
                 Line::from(vec![
@@ -443,9 +613,9 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
                     Span::styled(
                         format!("{}Saved", saved_icon),
                         if is_dimmed {
-                            Style::default().fg(theme::DIMMED)
+                            Style::default().fg(theme.dimmed)
                         } else {
-                            Style::default().fg(theme::BLUE)
+                            Style::default().fg(theme.blue)
                         },
                     ),
                 ])
@@ -455,42 +625,21 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
                     Span::styled(
                         "Available",
                         if is_dimmed {
-                            Style::default().fg(theme::DIMMED)
+                            Style::default().fg(theme.dimmed)
                         } else {
                             value_style
                         },
                     ),
                 ])
-            },
-            Line::from(vec![
+            };
+            let ssid_line = Line::from(vec![
                 label("SSID"),
                 Span::styled(
-                    format!("{}", wifi.ssid),
+                    wifi.display_ssid().into_owned(),
                     value_style.add_modifier(Modifier::BOLD),
                 ),
-            ]),
-            Line::from(vec![
-                label("Signal"),
-                Span::styled(format!("{}% ", wifi.signal), value_style),
-                Span::styled(signal_bar, Style::default().fg(signal_color)),
-            ]),
-            Line::from(vec![
-                label("Security"),
-                Span::styled(
-                    format!(
-                        "{}{} / {}",
-                        sec_icon,
-                        display_auth_name(&wifi.authentication),
-                        wifi.encryption
-                    ),
-                    value_style,
-                ),
-            ]),
-            Line::from(vec![
-                label("Standard"),
-                Span::styled(format!("{}", wifi.phy_type), value_style),
-            ]),
-            Line::from(vec![
+            ]);
+            let channel_line = Line::from(vec![
                 label("Channel"),
                 Span::styled(
                     format!(
@@ -500,125 +649,600 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
                     ),
                     value_style,
                 ),
-            ]),
-        ];
+            ]);
+            // Theoretical ceiling of the strongest access point, from its beacon
+            let max_rate_line = wifi.max_phy_rate().map(|rate| {
+                let caps = wifi
+                    .strongest_bss()
+                    .map(|bss| bss.capabilities)
+                    .unwrap_or_default();
+                Line::from(vec![
+                    label("Max Rate"),
+                    Span::styled(
+                        format!(
+                            "~{} Mbps ({}x{} @ {} MHz)",
+                            rate,
+                            caps.spatial_streams.max(1),
+                            caps.spatial_streams.max(1),
+                            caps.channel_width_mhz
+                        ),
+                        value_style,
+                    ),
+                ])
+            });
+            let link_speed_line = wifi.link_speed.map(|speed| {
+                Line::from(vec![
+                    label("Link Speed"),
+                    Span::styled(format!("{} Mbps", speed), value_style),
+                ])
+            });
+            let latency_line = (wifi.is_connected
+                && state.latency.ssid.as_deref() == Some(wifi.ssid.as_str()))
+            .then(|| {
+                let mut spans = vec![label("Latency")];
+                spans.extend(latency_spans(state, is_dimmed, value_style));
+                Line::from(spans)
+            });
+
+            let roam_line = state
+                .connection
+                .last_roam
+                .as_ref()
+                .filter(|roam| wifi.is_connected && roam.ssid == wifi.ssid)
+                .map(|roam| {
+                    let from = roam
+                        .from
+                        .map(|bssid| format!("{} {} ", format_bssid(&bssid), icons.arrow_right()))
+                        .unwrap_or_default();
+                    Line::from(vec![
+                        label("Roamed"),
+                        Span::styled(format!("{}{}", from, format_bssid(&roam.to)), value_style),
+                        Span::styled(
+                            format!("  {} ago", format_age(roam.at.elapsed())),
+                            Style::default().fg(theme.dimmed),
+                        ),
+                    ])
+                });
+
+            // Shown when the profile was renamed or no longer matches the network
+            let profile_line = (wifi.profile_name.is_some() || wifi.profile_security.is_some())
+                .then(|| {
+                    let mut spans = vec![label("Profile")];
+                    if let Some(name) = &wifi.profile_name {
+                        spans.push(Span::styled(format!("{}  ", name), value_style));
+                    }
+                    if let Some(saved_as) = wifi.profile_security {
+                        spans.push(Span::styled(
+                            format!("{} Saved as {}", icons.mismatch(), saved_as),
+                            if is_dimmed {
+                                Style::default().fg(theme.dimmed)
+                            } else {
+                                Style::default().fg(theme.yellow)
+                            },
+                        ));
+                        spans.push(Span::styled(
+                            format!(
+                                "  ({} to recreate)",
+                                state.config.keymap.hint(Action::RecreateProfile)
+                            ),
+                            Style::default().fg(theme.dimmed),
+                        ));
+                    }
+                    Line::from(spans)
+                });
+
+            let suspicion_line = state.suspicion(wifi).map(|suspicion| {
+                Line::from(vec![
+                    label("Warning"),
+                    Span::styled(
+                        format!(
+                            "{} Possible evil twin: {}",
+                            icons.suspicious(),
+                            suspicion.describe()
+                        ),
+                        if is_dimmed {
+                            Style::default().fg(theme.dimmed)
+                        } else {
+                            Style::default().fg(theme.red)
+                        },
+                    ),
+                ])
+            });
+
+            let password_line = state
+                .ui
+                .revealed_password
+                .as_ref()
+                .filter(|r| r.ssid == wifi.ssid)
+                .map(|revealed| {
+                    use secrecy::ExposeSecret;
+
+                    let secret = revealed.password.expose_secret();
+                    let (text, hint) = if revealed.visible {
+                        (secret.to_string(), "hide")
+                    } else {
+                        ("•".repeat(secret.chars().count()), "show")
+                    };
+                    Line::from(vec![
+                        label("Password"),
+                        Span::styled(text, value_style.add_modifier(Modifier::BOLD)),
+                        Span::styled(
+                            format!(
+                                "  ({} to {})",
+                                state.config.keymap.hint(Action::RevealPassword),
+                                hint
+                            ),
+                            Style::default().fg(theme.dimmed),
+                        ),
+                    ])
+                });
+
+            let mut info = Vec::new();
+            match state.ui.details_tab {
+                DetailsTab::Overview => {
+                    info.extend([
+                        status_line,
+                        ssid_line,
+                        Line::from(vec![
+                            label("Signal"),
+                            Span::styled(format!("{}% ", wifi.signal), value_style),
+                            Span::styled(
+                                wifi.rssi
+                                    .map(|rssi| format!("({} dBm) ", rssi))
+                                    .unwrap_or_default(),
+                                value_style,
+                            ),
+                            Span::styled(signal_bar, Style::default().fg(signal_color)),
+                            Span::styled(
+                                format!("  {}", sparkline),
+                                if is_dimmed {
+                                    Style::default().fg(theme.dimmed)
+                                } else {
+                                    Style::default().fg(theme.blue)
+                                },
+                            ),
+                            Span::styled(rssi_range, Style::default().fg(theme.dimmed)),
+                        ]),
+                        Line::from(vec![
+                            label("Security"),
+                            Span::styled(
+                                format!(
+                                    "{}{} / {}",
+                                    sec_icon,
+                                    display_auth_name(&wifi.authentication),
+                                    wifi.encryption
+                                ),
+                                value_style,
+                            ),
+                            Span::styled(
+                                wifi.strongest_bss()
+                                    .and_then(|bss| bss.capabilities.pmf)
+                                    .filter(|_| wifi.authentication != "Open")
+                                    .map(|pmf| format!(" · PMF {}", pmf.label()))
+                                    .unwrap_or_default(),
+                                Style::default().fg(theme.dimmed),
+                            ),
+                        ]),
+                    ]);
+                    info.extend(suspicion_line);
+                    info.extend(profile_line);
+                    info.extend(password_line);
+                    info.push(channel_line);
+                    info.extend(max_rate_line);
+
+                    if wifi.is_saved {
+                        let auto_text = if wifi.auto_connect {
+                            format!("{} Enabled", icons.auto_on())
+                        } else {
+                            format!("{} Disabled", icons.auto_off())
+                        };
+                        info.push(Line::from(vec![
+                            label("Auto-Conn"),
+                            Span::styled(auto_text, value_style),
+                        ]));
+                        if let Some(Some(metered)) = state.network.metered.get(&wifi.ssid) {
+                            let metered_text = if *metered {
+                                "Yes (apps limit background data)"
+                            } else {
+                                "No"
+                            };
+                            info.push(Line::from(vec![
+                                label("Metered"),
+                                Span::styled(metered_text, value_style),
+                            ]));
+                        }
+                    }
+
+                    info.extend(link_speed_line);
+                    info.extend(latency_line);
+                    info.extend(roam_line);
+                }
+                DetailsTab::Security => {
+                    // Extras come from the strongest access point's beacon, when the
+                    // backend exposes its information elements
+                    let caps = wifi.strongest_bss().map(|bss| bss.capabilities);
+                    let pmf = caps
+                        .and_then(|caps| caps.pmf)
+                        .map_or("Unknown", |pmf| pmf.label());
+                    let wps = match caps.and_then(|caps| caps.wps) {
+                        Some(true) => "Advertised",
+                        Some(false) => "Off",
+                        None => "Unknown",
+                    };
+                    info.extend([
+                        ssid_line,
+                        Line::from(vec![
+                            label("Auth"),
+                            Span::styled(
+                                format!("{}{}", sec_icon, display_auth_name(&wifi.authentication)),
+                                value_style,
+                            ),
+                        ]),
+                        Line::from(vec![
+                            label("Cipher"),
+                            Span::styled(wifi.encryption.clone(), value_style),
+                        ]),
+                        Line::from(vec![label("PMF"), Span::styled(pmf, value_style)]),
+                        Line::from(vec![label("WPS"), Span::styled(wps, value_style)]),
+                    ]);
+                    info.extend(suspicion_line);
+                    info.extend(profile_line);
+                    info.extend(password_line);
+                }
+                DetailsTab::Radio => {
+                    let mut bss_list: Vec<_> = wifi.bss_list.iter().collect();
+                    bss_list.sort_by_key(|bss| std::cmp::Reverse(bss.rssi));
+                    info.extend([
+                        Line::from(vec![
+                            label("SSID"),
+                            Span::styled(
+                                format!("{} ({} APs)", wifi.display_ssid(), bss_list.len()),
+                                value_style.add_modifier(Modifier::BOLD),
+                            ),
+                        ]),
+                        Line::from(vec![
+                            label("Standard"),
+                            Span::styled(wifi.phy_type.to_string(), value_style),
+                        ]),
+                        channel_line,
+                    ]);
+                    info.extend(max_rate_line);
+                    info.extend(link_speed_line);
+
+                    // One entry per access point, strongest first
+                    for (i, bss) in bss_list.iter().enumerate() {
+                        let is_current = wifi.connected_bssid == Some(bss.bssid);
+                        let caps = bss.capabilities;
+                        let mut stats =
+                            vec![format!("{} dBm", bss.rssi), format!("ch {}", bss.channel)];
+                        if caps.channel_width_mhz > 0 {
+                            stats.push(format!("{} MHz", caps.channel_width_mhz));
+                        }
+                        if let Some(rate) = caps.max_phy_rate_mbps() {
+                            stats.push(format!(
+                                "~{} Mbps {}x{}",
+                                rate,
+                                caps.spatial_streams.max(1),
+                                caps.spatial_streams.max(1)
+                            ));
+                        }
+                        if bss.beacon_interval > 0 {
+                            stats.push(format!("{} TU", bss.beacon_interval));
+                        }
+                        info.push(Line::from(vec![
+                            label(&format!("AP {}", i + 1)),
+                            Span::styled(format_bssid(&bss.bssid), value_style),
+                            Span::styled(
+                                oui::vendor(&bss.bssid)
+                                    .map(|name| format!(" {}", name))
+                                    .unwrap_or_default(),
+                                Style::default().fg(theme.dimmed),
+                            ),
+                            if is_current {
+                                Span::styled(
+                                    format!(" {}", icons.connected().trim()),
+                                    if is_dimmed {
+                                        Style::default().fg(theme.dimmed)
+                                    } else {
+                                        Style::default().fg(theme.green)
+                                    },
+                                )
+                            } else {
+                                Span::raw("")
+                            },
+                        ]));
+                        info.push(Line::from(vec![
+                            label(""),
+                            Span::styled(stats.join(" · "), value_style),
+                        ]));
+                    }
+                }
+                DetailsTab::Ip => {
+                    info.push(ssid_line);
+                    match &state.connection.ip_info {
+                        Some(ip_info) if wifi.is_connected => {
+                            info.extend(ip_rows(ip_info).into_iter().map(|(name, value)| {
+                                Line::from(vec![label(name), Span::styled(value, value_style)])
+                            }));
+                            info.extend(latency_line);
+                            info.extend(
+                                frame_rate_rows(
+                                    state,
+                                    // Inside the border and padding
+                                    details_area.width.saturating_sub(4) as usize,
+                                    is_dimmed,
+                                    value_style,
+                                )
+                                .into_iter()
+                                .map(|(name, spans)| {
+                                    let mut line = vec![label(name)];
+                                    line.extend(spans);
+                                    Line::from(line)
+                                }),
+                            );
+                        }
+                        _ => info.push(Line::from(vec![
+                            label("Addresses"),
+                            Span::styled(
+                                if wifi.is_connected {
+                                    "Loading..."
+                                } else {
+                                    "Not connected"
+                                },
+                                Style::default().fg(theme.dimmed),
+                            ),
+                        ])),
+                    }
+                }
+            }
 
-        if wifi.is_saved {
-            let auto_text = if wifi.auto_connect {
-                format!("{} Enabled", icons.auto_on())
+            let details_border_style = if is_dimmed {
+                Style::default().fg(theme.dimmed)
             } else {
-                format!("{} Disabled", icons.auto_off())
+                Style::default().fg(theme.purple)
             };
-            info.push(Line::from(vec![
-                label("Auto-Conn"),
-                Span::styled(auto_text, value_style),
-            ]));
-        }
-
-        if let Some(speed) = wifi.link_speed {
-            info.push(Line::from(vec![
-                label("Link Speed"),
-                Span::styled(format!("{} Mbps", speed), value_style),
-            ]));
-        }
-
-        let details_border_style = if is_dimmed {
-            Style::default().fg(theme::DIMMED)
-        } else {
-            Style::default().fg(theme::PURPLE)
-        };
 
-        let details_title_style = if is_dimmed {
-            Style::default()
-                .fg(theme::DIMMED)
-                .add_modifier(Modifier::BOLD)
-        } else {
-            Style::default()
-                .fg(theme::PURPLE)
-                .add_modifier(Modifier::BOLD)
-        };
+            let details_title_style = if is_dimmed {
+                Style::default()
+                    .fg(theme.dimmed)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+                    .fg(theme.purple)
+                    .add_modifier(Modifier::BOLD)
+            };
 
-        let paragraph = Paragraph::new(info).wrap(Wrap { trim: false }).block(
-            Block::default()
-                .title(" Details ")
-                .title_style(details_title_style)
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .border_style(details_border_style)
-                .padding(Padding::new(1, 1, 0, 0)),
-        );
-        frame.render_widget(paragraph, details_area);
-    }
+            let paragraph = Paragraph::new(info).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .title(details_tabs_title(
+                        state.ui.details_tab,
+                        details_title_style,
+                        Style::default().fg(theme.dimmed),
+                    ))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(details_border_style)
+                    .padding(Padding::new(1, 1, 0, 0)),
+            );
+            frame.render_widget(paragraph, details_area);
+        }
     }
 
-    let help_text = if state.ui.show_password_popup {
+    let mut help_text = if let Some(confirmation) = &state.ui.confirmation {
+        let mut spans = vec![
+            Span::styled("y", Style::default().fg(theme.foreground)),
+            Span::styled(" yes • ", Style::default().fg(theme.dimmed)),
+        ];
+        if confirmation.accept_open.is_some() {
+            spans.extend([
+                Span::styled("a", Style::default().fg(theme.foreground)),
+                Span::styled(" always • ", Style::default().fg(theme.dimmed)),
+            ]);
+        }
+        spans.extend([
+            Span::styled("n / esc", Style::default().fg(theme.foreground)),
+            Span::styled(" no", Style::default().fg(theme.dimmed)),
+        ]);
+        vec![Line::from(spans)]
+    } else if state.ui.show_command_palette {
+        vec![Line::from(vec![
+            Span::styled("↑/↓", Style::default().fg(theme.foreground)),
+            Span::styled(" select • ", Style::default().fg(theme.dimmed)),
+            Span::styled(icons.enter(), Style::default().fg(theme.foreground)),
+            Span::styled(" run • ", Style::default().fg(theme.dimmed)),
+            Span::styled("esc", Style::default().fg(theme.foreground)),
+            Span::styled(" cancel", Style::default().fg(theme.dimmed)),
+        ])]
+    } else if state.ui.show_command_line {
+        vec![Line::from(vec![
+            Span::styled(icons.enter(), Style::default().fg(theme.foreground)),
+            Span::styled(
+                " run (connect, forget, search, tag, quit…) • ",
+                Style::default().fg(theme.dimmed),
+            ),
+            Span::styled("esc", Style::default().fg(theme.foreground)),
+            Span::styled(" cancel", Style::default().fg(theme.dimmed)),
+        ])]
+    } else if state.ui.show_survey_label_popup {
+        vec![Line::from(vec![
+            Span::styled(icons.enter(), Style::default().fg(theme.foreground)),
+            Span::styled(" tag location • ", Style::default().fg(theme.dimmed)),
+            Span::styled("esc", Style::default().fg(theme.foreground)),
+            Span::styled(" cancel", Style::default().fg(theme.dimmed)),
+        ])]
+    } else if state.ui.show_share_password_popup {
+        vec![Line::from(vec![
+            Span::styled(icons.enter(), Style::default().fg(theme.foreground)),
+            Span::styled(" show QR • ", Style::default().fg(theme.dimmed)),
+            Span::styled("esc", Style::default().fg(theme.foreground)),
+            Span::styled(" cancel", Style::default().fg(theme.dimmed)),
+        ])]
+    } else if state.ui.show_password_popup {
         // Password input active - show password-specific shortcuts
         vec![Line::from(vec![
-            Span::styled(icons.enter(), Style::default().fg(theme::FOREGROUND)),
-            Span::styled(" connect • ", Style::default().fg(theme::DIMMED)),
-            Span::styled("esc", Style::default().fg(theme::FOREGROUND)),
-            Span::styled(" cancel", Style::default().fg(theme::DIMMED)),
+            Span::styled(icons.enter(), Style::default().fg(theme.foreground)),
+            Span::styled(" connect • ", Style::default().fg(theme.dimmed)),
+            Span::styled("F2", Style::default().fg(theme.foreground)),
+            Span::styled(
+                if state.ui.show_password_text {
+                    " hide • "
+                } else {
+                    " show • "
+                },
+                Style::default().fg(theme.dimmed),
+            ),
+            Span::styled("esc", Style::default().fg(theme.foreground)),
+            Span::styled(" cancel", Style::default().fg(theme.dimmed)),
+        ])]
+    } else if state.ui.show_profiles_popup {
+        vec![Line::from(vec![
+            Span::styled("j/k", Style::default().fg(theme.foreground)),
+            Span::styled(" select • ", Style::default().fg(theme.dimmed)),
+            Span::styled("J/K", Style::default().fg(theme.foreground)),
+            Span::styled(" move down / up • ", Style::default().fg(theme.dimmed)),
+            Span::styled("esc", Style::default().fg(theme.foreground)),
+            Span::styled(" close", Style::default().fg(theme.dimmed)),
+        ])]
+    } else if state.ui.show_help {
+        vec![Line::from(vec![
+            Span::styled("j/k", Style::default().fg(theme.foreground)),
+            Span::styled(" scroll • ", Style::default().fg(theme.dimmed)),
+            Span::styled("esc", Style::default().fg(theme.foreground)),
+            Span::styled(" close", Style::default().fg(theme.dimmed)),
+        ])]
+    } else if state.ui.show_channel_graph {
+        vec![Line::from(vec![
+            Span::styled(icons.tab_next(), Style::default().fg(theme.foreground)),
+            Span::styled(" next band • ", Style::default().fg(theme.dimmed)),
+            Span::styled(icons.tab_prev(), Style::default().fg(theme.foreground)),
+            Span::styled(" previous band • ", Style::default().fg(theme.dimmed)),
+            Span::styled("esc", Style::default().fg(theme.foreground)),
+            Span::styled(" close", Style::default().fg(theme.dimmed)),
+        ])]
+    } else if state.ui.show_speedtest_popup {
+        vec![Line::from(vec![
+            Span::styled(icons.enter(), Style::default().fg(theme.foreground)),
+            Span::styled(" run again • ", Style::default().fg(theme.dimmed)),
+            Span::styled("esc", Style::default().fg(theme.foreground)),
+            Span::styled(" close", Style::default().fg(theme.dimmed)),
+        ])]
+    } else if state.ui.show_wps_popup {
+        let mut spans = Vec::new();
+        if state.wps.result_rx.is_none() {
+            spans.push(Span::styled(
+                icons.enter(),
+                Style::default().fg(theme.foreground),
+            ));
+            spans.push(Span::styled(" retry • ", Style::default().fg(theme.dimmed)));
+        }
+        spans.push(Span::styled("esc", Style::default().fg(theme.foreground)));
+        spans.push(Span::styled(" close", Style::default().fg(theme.dimmed)));
+        vec![Line::from(spans)]
+    } else if state.ui.show_hotspot_popup {
+        vec![Line::from(vec![
+            Span::styled(icons.tab_next(), Style::default().fg(theme.foreground)),
+            Span::styled(" next • ", Style::default().fg(theme.dimmed)),
+            Span::styled(icons.enter(), Style::default().fg(theme.foreground)),
+            Span::styled(" start / stop • ", Style::default().fg(theme.dimmed)),
+            Span::styled("esc", Style::default().fg(theme.foreground)),
+            Span::styled(" close", Style::default().fg(theme.dimmed)),
+        ])]
+    } else if state.profiles.editor.is_some() {
+        vec![Line::from(vec![
+            Span::styled(icons.tab_next(), Style::default().fg(theme.foreground)),
+            Span::styled(" next • ", Style::default().fg(theme.dimmed)),
+            Span::styled(icons.space(), Style::default().fg(theme.foreground)),
+            Span::styled(" checkbox • ", Style::default().fg(theme.dimmed)),
+            Span::styled("h/l", Style::default().fg(theme.foreground)),
+            Span::styled(" security • ", Style::default().fg(theme.dimmed)),
+            Span::styled(icons.enter(), Style::default().fg(theme.foreground)),
+            Span::styled(" save • ", Style::default().fg(theme.dimmed)),
+            Span::styled("esc", Style::default().fg(theme.foreground)),
+            Span::styled(" cancel", Style::default().fg(theme.dimmed)),
         ])]
     } else if state.ui.show_manual_add_popup {
         // Manual add popup active - show relevant navigation & actions
         vec![
             Line::from(vec![
-                Span::styled(icons.tab_next(), Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" next • ", Style::default().fg(theme::DIMMED)),
-                Span::styled(icons.tab_prev(), Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" prev • ", Style::default().fg(theme::DIMMED)),
-                Span::styled(icons.enter(), Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" connect • ", Style::default().fg(theme::DIMMED)),
-                Span::styled("esc", Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" cancel", Style::default().fg(theme::DIMMED)),
+                Span::styled(icons.tab_next(), Style::default().fg(theme.foreground)),
+                Span::styled(" next • ", Style::default().fg(theme.dimmed)),
+                Span::styled(icons.tab_prev(), Style::default().fg(theme.foreground)),
+                Span::styled(" prev • ", Style::default().fg(theme.dimmed)),
+                Span::styled(icons.enter(), Style::default().fg(theme.foreground)),
+                Span::styled(" connect • ", Style::default().fg(theme.dimmed)),
+                Span::styled("esc", Style::default().fg(theme.foreground)),
+                Span::styled(" cancel", Style::default().fg(theme.dimmed)),
             ]),
             Line::from(vec![
-                Span::styled(icons.space(), Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" checkbox • ", Style::default().fg(theme::DIMMED)),
-                Span::styled("h/l/j/k", Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" dropdown", Style::default().fg(theme::DIMMED)),
+                Span::styled(icons.space(), Style::default().fg(theme.foreground)),
+                Span::styled(" checkbox • ", Style::default().fg(theme.dimmed)),
+                Span::styled("h/l/j/k", Style::default().fg(theme.foreground)),
+                Span::styled(" dropdown", Style::default().fg(theme.dimmed)),
             ]),
         ]
     } else if state.ui.is_searching || !state.inputs.search_input.value.is_empty() {
         // Search active - show search-specific shortcuts
         vec![Line::from(vec![
-            Span::styled(icons.enter(), Style::default().fg(theme::FOREGROUND)),
-            Span::styled(" apply • ", Style::default().fg(theme::DIMMED)),
-            Span::styled("esc esc", Style::default().fg(theme::FOREGROUND)),
-            Span::styled(" cancel", Style::default().fg(theme::DIMMED)),
+            Span::styled(icons.enter(), Style::default().fg(theme.foreground)),
+            Span::styled(" apply • ", Style::default().fg(theme.dimmed)),
+            Span::styled("↑/↓", Style::default().fg(theme.foreground)),
+            Span::styled(" history • ", Style::default().fg(theme.dimmed)),
+            Span::styled("esc esc", Style::default().fg(theme.foreground)),
+            Span::styled(" cancel", Style::default().fg(theme.dimmed)),
         ])]
     } else {
-        // Default global help
-        vec![
-            Line::from(vec![
-                Span::styled("q", Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" quit • ", Style::default().fg(theme::DIMMED)),
-                Span::styled("j/k", Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" nav • ", Style::default().fg(theme::DIMMED)),
-                Span::styled(icons.enter(), Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" conn / dconn • ", Style::default().fg(theme::DIMMED)),
-                Span::styled("f", Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" forget • ", Style::default().fg(theme::DIMMED)),
-                Span::styled("r", Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" refresh", Style::default().fg(theme::DIMMED)),
-            ]),
-            Line::from(vec![
-                Span::styled("a", Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" auto-conn • ", Style::default().fg(theme::DIMMED)),
-                Span::styled("s", Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" share • ", Style::default().fg(theme::DIMMED)),
-                Span::styled("n", Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" add • ", Style::default().fg(theme::DIMMED)),
-                Span::styled("/", Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" search • ", Style::default().fg(theme::DIMMED)),
-                Span::styled("esc", Style::default().fg(theme::FOREGROUND)),
-                Span::styled(" back", Style::default().fg(theme::DIMMED)),
-            ]),
-        ]
-    };
-    let help_paragraph = Paragraph::new(help_text)
-        .style(Style::default().fg(theme::DIMMED))
-        .alignment(Alignment::Center);
+        // Default global help, labelled with the active key bindings
+        let keymap = &state.config.keymap;
+        let key = |action: Action| match keymap.hint(action).as_str() {
+            "enter" => icons.enter().to_string(),
+            hint => hint.to_string(),
+        };
+        let nav = [key(Action::Down), key(Action::Up)]
+            .into_iter()
+            .filter(|k| !k.is_empty())
+            .collect::<Vec<_>>()
+            .join("/");
+        if compact {
+            vec![help_line(
+                &theme,
+                vec![
+                    (key(Action::Quit), "quit"),
+                    (nav, "nav"),
+                    (key(Action::Connect), "conn"),
+                    (key(Action::CommandPalette), "palette"),
+                ],
+            )]
+        } else {
+            vec![
+                help_line(
+                    &theme,
+                    vec![
+                        (key(Action::Quit), "quit"),
+                        (nav, "nav"),
+                        (key(Action::Connect), "conn / dconn"),
+                        (key(Action::Forget), "forget"),
+                        (key(Action::Refresh), "refresh"),
+                        (key(Action::Help), "help"),
+                    ],
+                ),
+                help_line(
+                    &theme,
+                    vec![
+                        (key(Action::ToggleAutoConnect), "auto-conn"),
+                        (key(Action::Share), "share"),
+                        (key(Action::AddNetwork), "add"),
+                        (key(Action::Search), "search"),
+                        (key(Action::CommandPalette), "palette"),
+                        (key(Action::Back), "back"),
+                    ],
+                ),
+            ]
+        }
+    };
+    help_text.truncate(usize::from(help_height));
+    let help_paragraph = Paragraph::new(help_text)
+        .style(Style::default().fg(theme.dimmed))
+        .alignment(Alignment::Center);
 
     frame.render_widget(help_paragraph, help_area);
 
@@ -634,29 +1258,174 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
                 Block::default()
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-                    .border_style(Style::default().fg(theme::YELLOW)),
+                    .border_style(Style::default().fg(theme.yellow)),
             )
-            .style(Style::default().fg(theme::FOREGROUND).bg(theme::BACKGROUND))
+            .style(Style::default().fg(theme.foreground).bg(theme.background))
             .alignment(Alignment::Center);
 
         frame.render_widget(Clear, loading_area);
         frame.render_widget(loading_paragraph, loading_area);
     }
 
-    if let Some(error) = &state.ui.error_message {
-        let error_area = Rect::new(area.x + 2, area.height - 4, area.width - 4, 3);
-        let error_paragraph = Paragraph::new(error.as_str())
+    if state.ui.show_debug_pane {
+        let pane_height = if state.ui.debug_pane_expanded {
+            config::DEBUG_PANE_HEIGHT.min(area.height)
+        } else {
+            3.min(area.height)
+        };
+        let pane_area = Rect::new(
+            area.x,
+            area.y + area.height - pane_height,
+            area.width,
+            pane_height,
+        );
+        let entries = logging::recent(pane_height.saturating_sub(2) as usize);
+        let lines: Vec<Line> = entries
+            .iter()
+            .map(|entry| {
+                let level_color = match entry.level {
+                    logging::Level::Debug => theme.dimmed,
+                    logging::Level::Info => theme.blue,
+                    logging::Level::Warn => theme.yellow,
+                    logging::Level::Error => theme.red,
+                };
+                let time = format_timestamp(entry.time);
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} ", &time[11..19]),
+                        Style::default().fg(theme.dimmed),
+                    ),
+                    Span::styled(
+                        format!("{:<5} ", entry.level.label()),
+                        Style::default().fg(level_color),
+                    ),
+                    Span::raw(entry.message.clone()),
+                ])
+            })
+            .collect();
+        let keymap = &state.config.keymap;
+        let title = format!(
+            " Debug Log ({} to {}, {} to hide) ",
+            keymap.hint(Action::ToggleDebugPane),
+            if state.ui.debug_pane_expanded {
+                "collapse"
+            } else {
+                "expand"
+            },
+            keymap.hint(Action::ToggleDebugLog)
+        );
+        let pane = Paragraph::new(lines)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-                    .border_style(Style::default().fg(theme::RED))
-                    .title(" ERROR "),
+                    .border_style(Style::default().fg(theme.bright_purple))
+                    .title(title),
             )
-            .style(Style::default().fg(theme::RED).bg(theme::BACKGROUND))
-            .wrap(Wrap { trim: true });
-        frame.render_widget(Clear, error_area);
-        frame.render_widget(error_paragraph, error_area);
+            .style(Style::default().fg(theme.foreground).bg(theme.background));
+        frame.render_widget(Clear, pane_area);
+        frame.render_widget(pane, pane_area);
+    }
+
+    if state.perf.enabled {
+        let calls = perf::call_stats();
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Frame:   ", Style::default().fg(theme.cyan)),
+                Span::raw(perf::format_duration(state.perf.last_frame)),
+            ]),
+            Line::from(vec![
+                Span::styled("Refresh: ", Style::default().fg(theme.cyan)),
+                Span::raw(
+                    state
+                        .perf
+                        .last_refresh
+                        .map(perf::format_duration)
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+            ]),
+        ];
+        for (name, stats) in &calls {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:<22} ", name), Style::default().fg(theme.dimmed)),
+                Span::raw(format!(
+                    "last {:>6} avg {:>6} max {:>6}",
+                    perf::format_duration(stats.last),
+                    perf::format_duration(stats.average()),
+                    perf::format_duration(stats.max)
+                )),
+            ]));
+        }
+
+        let overlay_width = 62.min(area.width);
+        let overlay_height = (lines.len() as u16 + 2).min(area.height);
+        let overlay_area = Rect::new(
+            area.x + area.width - overlay_width,
+            area.y,
+            overlay_width,
+            overlay_height,
+        );
+        let overlay = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme.bright_purple))
+                    .title(" Perf "),
+            )
+            .style(Style::default().fg(theme.foreground).bg(theme.background));
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(overlay, overlay_area);
+    }
+
+    if let Some((ssid, url)) = state
+        .connection
+        .connectivity
+        .as_ref()
+        .and_then(|(ssid, connectivity)| Some((ssid, connectivity.portal_url()?)))
+        && !is_dimmed
+    {
+        let banner_area = Rect::new(area.x + 2, area.height - 4, area.width - 4, 3);
+        let banner_paragraph = Paragraph::new(format!(
+            "{} needs a sign-in - press {} to open {}",
+            ssid,
+            state.config.keymap.hint(Action::OpenPortal),
+            url
+        ))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.yellow))
+                .title(" CAPTIVE PORTAL "),
+        )
+        .style(Style::default().fg(theme.foreground).bg(theme.background))
+        .wrap(Wrap { trim: true });
+        frame.render_widget(Clear, banner_area);
+        frame.render_widget(banner_paragraph, banner_area);
+    } else if let Some(suggestion) = &state.steering.suggestion
+        && !is_dimmed
+    {
+        let tip_area = Rect::new(area.x + 2, area.height - 4, area.width - 4, 3);
+        let tip_paragraph = Paragraph::new(format!(
+            "{} AP for {} on channel {} ({}%, {} dBm) - press B to switch",
+            suggestion.band.label(),
+            suggestion.ssid,
+            suggestion.channel,
+            suggestion.signal,
+            suggestion.rssi
+        ))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.yellow))
+                .title(" FASTER BAND "),
+        )
+        .style(Style::default().fg(theme.foreground).bg(theme.background))
+        .wrap(Wrap { trim: true });
+        frame.render_widget(Clear, tip_area);
+        frame.render_widget(tip_paragraph, tip_area);
     }
 
     if state.ui.show_password_popup {
@@ -669,76 +1438,182 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
             height: popup_height,
         };
 
-        let popup_text: String = state
+        let password = &state.inputs.password_input.value;
+        let popup_text: String = if state.ui.show_password_text {
+            password.clone()
+        } else {
+            password.chars().map(|_| '•').collect()
+        };
+
+        let max_width = (popup_area.width.saturating_sub(4)) as usize;
+        let line = input_line(
+            &theme,
+            &popup_text,
+            state.inputs.password_input.cursor,
+            max_width,
+            true,
+        );
+
+        let mut popup_block = Block::default()
+            .title(format!(
+                " Password for {} ",
+                state.connection.connecting_to_ssid.as_deref().unwrap_or("")
+            ))
+            .title_alignment(Alignment::Left)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.yellow))
+            .padding(Padding::new(1, 1, 0, 0)); // Add padding to center vertically
+        if let Some(error) = &state.ui.password_error {
+            popup_block = popup_block.title_bottom(Line::styled(
+                format!(" {} - try again ", error),
+                Style::default().fg(theme.red),
+            ));
+        }
+        if !password.is_empty() {
+            let (strength, color) = passphrase_strength(password, &theme);
+            popup_block = popup_block.title_top(
+                Line::styled(
+                    format!(" {} chars · {} ", password.chars().count(), strength),
+                    Style::default().fg(color),
+                )
+                .right_aligned(),
+            );
+        }
+
+        let popup = Paragraph::new(line)
+            .block(popup_block)
+            .style(Style::default().fg(theme.foreground).bg(theme.background))
+            .alignment(Alignment::Left);
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(popup, popup_area);
+    }
+
+    if state.ui.show_share_password_popup {
+        let networks_area = list_area;
+        let popup_height = 3;
+        let popup_area = Rect {
+            x: networks_area.x,
+            y: networks_area.y + networks_area.height.saturating_sub(popup_height),
+            width: networks_area.width,
+            height: popup_height,
+        };
+
+        let max_width = (popup_area.width.saturating_sub(4)) as usize;
+        let masked: String = state
             .inputs
-            .password_input
+            .share_password_input
             .value
             .chars()
             .map(|_| '•')
             .collect();
+        let line = input_line(
+            &theme,
+            &masked,
+            state.inputs.share_password_input.cursor,
+            max_width,
+            true,
+        );
 
-        let max_width = (popup_area.width.saturating_sub(4)) as usize;
-        let input_len = popup_text.chars().count();
-        let cursor_pos = state.inputs.password_input.cursor;
+        let popup_block = Block::default()
+            .title(format!(" Share passphrase for {} ", state.ui.qr_ssid))
+            .title_alignment(Alignment::Left)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.yellow))
+            .padding(Padding::new(1, 1, 0, 0));
 
-        let (display_text, cursor_x) = if input_len < max_width {
-            (popup_text, cursor_pos)
-        } else {
-            if cursor_pos >= max_width {
-                let skip = cursor_pos - max_width + 1;
-                let take = max_width;
-                let text: String = popup_text.chars().skip(skip).take(take).collect();
-                (text, max_width - 1)
-            } else {
-                let text: String = popup_text.chars().take(max_width).collect();
-                (text, cursor_pos)
-            }
+        let popup = Paragraph::new(line)
+            .block(popup_block)
+            .style(Style::default().fg(theme.foreground).bg(theme.background))
+            .alignment(Alignment::Left);
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(popup, popup_area);
+    }
+
+    if state.ui.show_survey_label_popup {
+        let networks_area = list_area;
+        let popup_height = 3;
+        let popup_area = Rect {
+            x: networks_area.x,
+            y: networks_area.y + networks_area.height.saturating_sub(popup_height),
+            width: networks_area.width,
+            height: popup_height,
         };
 
-        let mut spans = Vec::new();
-        let chars: Vec<char> = display_text.chars().collect();
+        let max_width = (popup_area.width.saturating_sub(4)) as usize;
+        let line = input_line(
+            &theme,
+            &state.inputs.survey_label_input.value,
+            state.inputs.survey_label_input.cursor,
+            max_width,
+            true,
+        );
 
-        for (i, c) in chars.iter().enumerate() {
-            if i == cursor_x {
-                spans.push(Span::styled(
-                    c.to_string(),
-                    Style::default().bg(theme::FOREGROUND).fg(theme::BACKGROUND),
-                ));
-            } else {
-                spans.push(Span::raw(c.to_string()));
-            }
-        }
+        let popup_block = Block::default()
+            .title(" Location label ")
+            .title_alignment(Alignment::Left)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.yellow))
+            .padding(Padding::new(1, 1, 0, 0));
 
-        if cursor_x == chars.len() {
-            spans.push(Span::styled(
-                " ",
-                Style::default().bg(theme::FOREGROUND).fg(theme::BACKGROUND),
-            ));
-        }
+        let popup = Paragraph::new(line)
+            .block(popup_block)
+            .style(Style::default().fg(theme.foreground).bg(theme.background))
+            .alignment(Alignment::Left);
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(popup, popup_area);
+    }
+
+    if state.ui.show_command_line {
+        let networks_area = list_area;
+        let popup_height = 3;
+        let popup_area = Rect {
+            x: networks_area.x,
+            y: networks_area.y + networks_area.height.saturating_sub(popup_height),
+            width: networks_area.width,
+            height: popup_height,
+        };
+
+        // Leave room for the ':' prompt
+        let max_width = (popup_area.width.saturating_sub(5)) as usize;
+        let mut line = input_line(
+            &theme,
+            &state.inputs.command_input.value,
+            state.inputs.command_input.cursor,
+            max_width,
+            true,
+        );
+        line.spans
+            .insert(0, Span::styled(":", Style::default().fg(theme.yellow)));
 
         let popup_block = Block::default()
-            .title(format!(
-                " Password for {} ",
-                state.connection.connecting_to_ssid.as_deref().unwrap_or("")
-            ))
+            .title(" Command ")
             .title_alignment(Alignment::Left)
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(theme::YELLOW))
-            .padding(Padding::new(1, 1, 0, 0)); // Add padding to center vertically
+            .border_style(Style::default().fg(theme.yellow))
+            .padding(Padding::new(1, 1, 0, 0));
 
-        let popup = Paragraph::new(Line::from(spans))
+        let popup = Paragraph::new(line)
             .block(popup_block)
-            .style(Style::default().fg(theme::FOREGROUND).bg(theme::BACKGROUND))
+            .style(Style::default().fg(theme.foreground).bg(theme.background))
             .alignment(Alignment::Left);
 
         frame.render_widget(Clear, popup_area);
         frame.render_widget(popup, popup_area);
     }
 
+    state.ui.click_areas.manual_popup = Rect::default();
+    state.ui.click_areas.manual_fields.clear();
     if state.ui.show_manual_add_popup {
         let networks_area = list_area;
-        let popup_height = 13;
+        let is_enterprise = state.inputs.manual_is_enterprise();
+        let popup_height = if is_enterprise { 18 } else { 13 };
         let popup_area = Rect {
             x: networks_area.x,
             y: networks_area.y + networks_area.height.saturating_sub(popup_height),
@@ -753,7 +1628,7 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
             .border_type(BorderType::Rounded)
             .title(" Add Network ")
             .title_alignment(Alignment::Center)
-            .style(Style::default().fg(theme::CYAN).bg(theme::BACKGROUND));
+            .style(Style::default().fg(theme.cyan).bg(theme.background));
 
         frame.render_widget(block.clone(), popup_area);
 
@@ -761,85 +1636,85 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
             vertical: 1,
             horizontal: 2,
         });
-        let layout = Layout::vertical([
-            Constraint::Length(3), // SSID
-            Constraint::Length(3), // Password
-            Constraint::Length(3), // Security
-            Constraint::Length(1), // Spacer
-            Constraint::Length(1), // Hidden + Connect
-        ])
-        .split(inner);
+        // Enterprise networks get the EAP fields between security and password
+        let (ssid_area, pass_area, sec_area, eap_area, ca_area, bottom_area) = if is_enterprise {
+            let layout = Layout::vertical([
+                Constraint::Length(3), // SSID
+                Constraint::Length(3), // Security
+                Constraint::Length(3), // EAP method + Identity
+                Constraint::Length(3), // Password
+                Constraint::Length(1), // Validate CA
+                Constraint::Length(1), // Spacer
+                Constraint::Length(1), // Hidden + Connect
+            ])
+            .split(inner);
+            (
+                layout[0],
+                layout[3],
+                layout[1],
+                Some(layout[2]),
+                Some(layout[4]),
+                layout[6],
+            )
+        } else {
+            let layout = Layout::vertical([
+                Constraint::Length(3), // SSID
+                Constraint::Length(3), // Password
+                Constraint::Length(3), // Security
+                Constraint::Length(1), // Spacer
+                Constraint::Length(1), // Hidden + Connect
+            ])
+            .split(inner);
+            (layout[0], layout[1], layout[2], None, None, layout[4])
+        };
+        state.ui.click_areas.manual_popup = popup_area;
+        state
+            .ui
+            .click_areas
+            .manual_fields
+            .extend([(0, ssid_area), (1, pass_area), (2, sec_area)]);
+        state
+            .ui
+            .click_areas
+            .manual_fields
+            .extend(ca_area.map(|area| (8, area)));
 
         // SSID Input
         let ssid_style = if state.inputs.manual_input_field == 0 {
-            Style::default().fg(theme::YELLOW)
+            Style::default().fg(theme.yellow)
         } else {
-            Style::default().fg(theme::FOREGROUND)
+            Style::default().fg(theme.foreground)
         };
         let ssid_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(" SSID ")
             .border_style(ssid_style)
-            .style(Style::default().bg(theme::BACKGROUND));
-
-        // SSID Cursor Logic
-        let max_width_ssid = (layout[0].width.saturating_sub(2)) as usize;
-        let ssid_text = &state.inputs.manual_ssid_input.value;
-        let ssid_len = ssid_text.chars().count();
-        let ssid_cursor = state.inputs.manual_ssid_input.cursor;
-
-        let (display_ssid, ssid_cursor_x) = if ssid_len < max_width_ssid {
-            (ssid_text.clone(), ssid_cursor)
-        } else {
-            if ssid_cursor >= max_width_ssid {
-                let skip = ssid_cursor - max_width_ssid + 1;
-                let take = max_width_ssid;
-                let text: String = ssid_text.chars().skip(skip).take(take).collect();
-                (text, max_width_ssid - 1)
-            } else {
-                let text: String = ssid_text.chars().take(max_width_ssid).collect();
-                (text, ssid_cursor)
-            }
-        };
-
-        let mut ssid_spans = Vec::new();
-        let ssid_chars: Vec<char> = display_ssid.chars().collect();
-        for (i, c) in ssid_chars.iter().enumerate() {
-            if i == ssid_cursor_x && state.inputs.manual_input_field == 0 {
-                ssid_spans.push(Span::styled(
-                    c.to_string(),
-                    Style::default().bg(theme::FOREGROUND).fg(theme::BACKGROUND),
-                ));
-            } else {
-                ssid_spans.push(Span::raw(c.to_string()));
-            }
-        }
-        if ssid_cursor_x == ssid_chars.len() && state.inputs.manual_input_field == 0 {
-            ssid_spans.push(Span::styled(
-                " ",
-                Style::default().bg(theme::FOREGROUND).fg(theme::BACKGROUND),
-            ));
-        }
-
-        let ssid_para = Paragraph::new(Line::from(ssid_spans)).block(ssid_block);
-        frame.render_widget(ssid_para, layout[0]);
+            .style(Style::default().bg(theme.background));
+
+        let ssid_para = Paragraph::new(input_line(
+            &theme,
+            &state.inputs.manual_ssid_input.value,
+            state.inputs.manual_ssid_input.cursor,
+            ssid_area.width.saturating_sub(2) as usize,
+            state.inputs.manual_input_field == 0,
+        ))
+        .block(ssid_block);
+        frame.render_widget(ssid_para, ssid_area);
 
         // Password Input
         let pass_style = if state.inputs.manual_input_field == 1 {
-            Style::default().fg(theme::YELLOW)
+            Style::default().fg(theme.yellow)
         } else {
-            Style::default().fg(theme::FOREGROUND)
+            Style::default().fg(theme.foreground)
         };
         let pass_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(" Password ")
             .border_style(pass_style)
-            .style(Style::default().bg(theme::BACKGROUND));
+            .style(Style::default().bg(theme.background));
 
-        // Password Cursor Logic
-        let max_width_pass = (layout[1].width.saturating_sub(2)) as usize;
         let pass_text: String = state
             .inputs
             .manual_password_input
@@ -847,71 +1722,42 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
             .chars()
             .map(|_| '•')
             .collect();
-        let pass_len = pass_text.chars().count();
-        let pass_cursor = state.inputs.manual_password_input.cursor;
-
-        let (display_pass, pass_cursor_x) = if pass_len < max_width_pass {
-            (pass_text, pass_cursor)
-        } else {
-            if pass_cursor >= max_width_pass {
-                let skip = pass_cursor - max_width_pass + 1;
-                let take = max_width_pass;
-                let text: String = pass_text.chars().skip(skip).take(take).collect();
-                (text, max_width_pass - 1)
-            } else {
-                let text: String = pass_text.chars().take(max_width_pass).collect();
-                (text, pass_cursor)
-            }
-        };
-
-        let mut pass_spans = Vec::new();
-        let pass_chars: Vec<char> = display_pass.chars().collect();
-        for (i, c) in pass_chars.iter().enumerate() {
-            if i == pass_cursor_x && state.inputs.manual_input_field == 1 {
-                pass_spans.push(Span::styled(
-                    c.to_string(),
-                    Style::default().bg(theme::FOREGROUND).fg(theme::BACKGROUND),
-                ));
-            } else {
-                pass_spans.push(Span::raw(c.to_string()));
-            }
-        }
-        if pass_cursor_x == pass_chars.len() && state.inputs.manual_input_field == 1 {
-            pass_spans.push(Span::styled(
-                " ",
-                Style::default().bg(theme::FOREGROUND).fg(theme::BACKGROUND),
-            ));
-        }
-
-        let pass_para = Paragraph::new(Line::from(pass_spans)).block(pass_block);
-        frame.render_widget(pass_para, layout[1]);
+        let pass_para = Paragraph::new(input_line(
+            &theme,
+            &pass_text,
+            state.inputs.manual_password_input.cursor,
+            pass_area.width.saturating_sub(2) as usize,
+            state.inputs.manual_input_field == 1,
+        ))
+        .block(pass_block);
+        frame.render_widget(pass_para, pass_area);
 
         // Security Selector
         let is_active = state.inputs.manual_input_field == 2;
         let sec_border_style = if is_active {
-            Style::default().fg(theme::YELLOW)
+            Style::default().fg(theme.yellow)
         } else {
-            Style::default().fg(theme::FOREGROUND)
+            Style::default().fg(theme.foreground)
         };
         let sec_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
             .title(" Security ")
             .border_style(sec_border_style)
-            .style(Style::default().bg(theme::BACKGROUND));
+            .style(Style::default().bg(theme.background));
 
         let arrow_style = if is_active {
-            Style::default().fg(theme::YELLOW)
+            Style::default().fg(theme.yellow)
         } else {
-            Style::default().fg(theme::DIMMED)
+            Style::default().fg(theme.dimmed)
         };
 
         let value_style = if is_active {
             Style::default()
-                .fg(theme::FOREGROUND)
+                .fg(theme.foreground)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(theme::FOREGROUND)
+            Style::default().fg(theme.foreground)
         };
 
         let sec_para = Paragraph::new(Line::from(vec![
@@ -921,17 +1767,100 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         ]))
         .block(sec_block)
         .alignment(Alignment::Center);
-        frame.render_widget(sec_para, layout[2]);
+        frame.render_widget(sec_para, sec_area);
+
+        if let Some(eap_area) = eap_area {
+            let eap_layout =
+                Layout::horizontal([Constraint::Length(24), Constraint::Min(10)]).split(eap_area);
+
+            // EAP Method Selector
+            let is_active = state.inputs.manual_input_field == 6;
+            let method_block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(" EAP Method ")
+                .border_style(if is_active {
+                    Style::default().fg(theme.yellow)
+                } else {
+                    Style::default().fg(theme.foreground)
+                })
+                .style(Style::default().bg(theme.background));
+            let method_style = if is_active {
+                Style::default()
+                    .fg(theme.foreground)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.foreground)
+            };
+            let method_para = Paragraph::new(Span::styled(
+                state.inputs.manual_eap_method.label(),
+                method_style,
+            ))
+            .block(method_block)
+            .alignment(Alignment::Center);
+            frame.render_widget(method_para, eap_layout[0]);
+
+            // Identity Input
+            let identity = &state.inputs.manual_identity_input;
+            let identity_block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .title(" Identity ")
+                .border_style(if state.inputs.manual_input_field == 7 {
+                    Style::default().fg(theme.yellow)
+                } else {
+                    Style::default().fg(theme.foreground)
+                })
+                .style(Style::default().bg(theme.background));
+            let identity_para = Paragraph::new(input_line(
+                &theme,
+                &identity.value,
+                identity.cursor,
+                eap_layout[1].width.saturating_sub(2) as usize,
+                state.inputs.manual_input_field == 7,
+            ))
+            .block(identity_block);
+            frame.render_widget(identity_para, eap_layout[1]);
+            state
+                .ui
+                .click_areas
+                .manual_fields
+                .extend([(6, eap_layout[0]), (7, eap_layout[1])]);
+        }
+
+        if let Some(ca_area) = ca_area {
+            let ca_style = if state.inputs.manual_input_field == 8 {
+                Style::default().fg(theme.yellow)
+            } else {
+                Style::default().fg(theme.foreground)
+            };
+            let ca_text = format!(
+                "{} Validate Server Certificate",
+                icons.checkbox(state.inputs.manual_validate_ca)
+            );
+            frame.render_widget(Paragraph::new(ca_text).style(ca_style), ca_area);
+        }
 
-        // Hidden Checkbox + Connect Button Row
-        let bottom_layout =
-            Layout::horizontal([Constraint::Min(20), Constraint::Length(15)]).split(layout[4]);
+        // Hidden and Random MAC Checkboxes + Cancel and Connect Buttons Row
+        let bottom_layout = Layout::horizontal([
+            Constraint::Length(20),
+            Constraint::Min(14),
+            Constraint::Length(10),
+            Constraint::Length(12),
+        ])
+        .split(bottom_area);
+        state.ui.click_areas.manual_fields.extend([
+            (3, bottom_layout[0]),
+            (9, bottom_layout[1]),
+            (5, bottom_layout[2]),
+            (4, bottom_layout[3]),
+        ]);
 
         // Hidden Checkbox
         let hidden_style = if state.inputs.manual_input_field == 3 {
-            Style::default().fg(theme::YELLOW)
+            Style::default().fg(theme.yellow)
         } else {
-            Style::default().fg(theme::FOREGROUND)
+            Style::default().fg(theme.foreground)
         };
         let hidden_text = format!(
             "{} Hidden Network",
@@ -940,94 +1869,1069 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
         let hidden_para = Paragraph::new(hidden_text).style(hidden_style);
         frame.render_widget(hidden_para, bottom_layout[0]);
 
+        // Random MAC Checkbox
+        let random_mac_style = if state.inputs.manual_input_field == 9 {
+            Style::default().fg(theme.yellow)
+        } else {
+            Style::default().fg(theme.foreground)
+        };
+        let random_mac_text = format!(
+            "{} Random MAC",
+            icons.checkbox(state.inputs.manual_random_mac)
+        );
+        let random_mac_para = Paragraph::new(random_mac_text).style(random_mac_style);
+        frame.render_widget(random_mac_para, bottom_layout[1]);
+
         // Connect Button
         let connect_btn = if state.inputs.manual_input_field == 4 {
             Paragraph::new(Line::from(vec![
-                Span::styled(icons.btn_left(), Style::default().fg(theme::GREEN)),
+                Span::styled(icons.btn_left(), Style::default().fg(theme.green)),
                 Span::styled(
                     "Connect",
-                    Style::default().bg(theme::GREEN).fg(theme::BACKGROUND),
+                    Style::default().bg(theme.green).fg(theme.background),
                 ),
                 Span::styled(
                     format!("{} ", icons.btn_right()),
-                    Style::default().fg(theme::GREEN),
+                    Style::default().fg(theme.green),
                 ),
             ]))
         } else {
-            Paragraph::new(" Connect  ").style(Style::default().fg(theme::GREEN))
+            Paragraph::new(" Connect  ").style(Style::default().fg(theme.green))
         }
         .alignment(Alignment::Right);
-        frame.render_widget(connect_btn, bottom_layout[1]);
-    }
-
-    if state.ui.show_key_logger {
-        if let Some((key, time)) = &state.ui.last_key_press {
-            if time.elapsed() < std::time::Duration::from_secs(2) {
-                let key_text = format!(" {} ", key);
-                let width = key_text.len() as u16 + 2;
-
-                // Position right below the bottom right of the main UI
-                let key_area = Rect::new(
-                    main_area.x + main_area.width - width,
-                    main_area.y + main_area.height,
-                    width,
-                    3,
-                );
-
-                let block = Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded)
-                    .border_style(Style::default().fg(theme::BRIGHT_PURPLE))
-                    .style(Style::default().bg(theme::BACKGROUND));
-
-                let paragraph = Paragraph::new(key_text)
-                    .block(block)
-                    .style(
-                        Style::default()
-                            .fg(theme::BRIGHT_PURPLE)
-                            .add_modifier(Modifier::BOLD),
-                    )
-                    .alignment(Alignment::Center);
+        frame.render_widget(connect_btn, bottom_layout[3]);
 
-                frame.render_widget(Clear, key_area);
-                frame.render_widget(paragraph, key_area);
-            }
+        // Cancel Button
+        let cancel_btn = if state.inputs.manual_input_field == 5 {
+            Paragraph::new(Line::from(vec![
+                Span::styled(icons.btn_left(), Style::default().fg(theme.red)),
+                Span::styled(
+                    "Cancel",
+                    Style::default().bg(theme.red).fg(theme.background),
+                ),
+                Span::styled(icons.btn_right(), Style::default().fg(theme.red)),
+            ]))
+        } else {
+            Paragraph::new(" Cancel ").style(Style::default().fg(theme.red))
         }
+        .alignment(Alignment::Right);
+        frame.render_widget(cancel_btn, bottom_layout[2]);
     }
 
-    // QR Code popup
-    if state.ui.show_qr_popup {
-        // Calculate QR popup size based on terminal size
-        let qr_height = state.ui.qr_code_lines.len() as u16 + 4; // +4 for borders and padding
-        let qr_width = state.ui.qr_code_lines.first().map(|l| l.len()).unwrap_or(0) as u16 + 4;
-
-        // Center the popup
-        let qr_x = area.width.saturating_sub(qr_width) / 2;
-        let qr_y = area.height.saturating_sub(qr_height) / 2;
-
-        let qr_area = Rect::new(
-            qr_x,
-            qr_y,
-            qr_width.min(area.width),
-            qr_height.min(area.height),
-        );
+    if state.ui.show_hotspot_popup {
+        let networks_area = list_area;
+        let popup_height = 12;
+        let popup_area = Rect {
+            x: networks_area.x,
+            y: networks_area.y + networks_area.height.saturating_sub(popup_height),
+            width: networks_area.width,
+            height: popup_height,
+        };
 
-        // Clear background
-        frame.render_widget(Clear, qr_area);
+        frame.render_widget(Clear, popup_area);
 
-        // QR code block
-        let qr_block = Block::default()
+        let block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(theme::CYAN))
-            .title(" Share WiFi (Scan with phone) ")
+            .title(" Mobile Hotspot ")
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(theme.cyan).bg(theme.background));
+
+        frame.render_widget(block, popup_area);
+
+        let inner = popup_area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let layout = Layout::vertical([
+            Constraint::Length(2), // State + clients
+            Constraint::Length(3), // SSID
+            Constraint::Length(3), // Passphrase
+            Constraint::Length(1), // Start / Stop
+        ])
+        .split(inner);
+
+        let hotspot = &state.hotspot;
+        let active = hotspot.status.as_ref().is_some_and(|s| s.active);
+        let (state_text, state_color) = match &hotspot.status {
+            None => ("Checking…".to_string(), theme.dimmed),
+            Some(s) if s.active => match hotspot.guest.as_ref().and_then(|g| g.remaining()) {
+                Some(left) => (
+                    format!(
+                        "On ({}, guest for {} min)",
+                        s.ssid,
+                        left.as_secs().div_ceil(60)
+                    ),
+                    theme.green,
+                ),
+                None => (format!("On ({})", s.ssid), theme.green),
+            },
+            Some(_) => ("Off".to_string(), theme.foreground),
+        };
+        let clients = match hotspot.status.as_ref() {
+            Some(s) if s.active => match (s.client_count, s.max_clients) {
+                (Some(count), Some(max)) => format!("{} / {}", count, max),
+                (Some(count), None) => count.to_string(),
+                (None, _) => "not reported".to_string(),
+            },
+            _ => "-".to_string(),
+        };
+        let status_para = Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled("State:   ", Style::default().fg(theme.dimmed)),
+                Span::styled(state_text, Style::default().fg(state_color)),
+            ]),
+            Line::from(vec![
+                Span::styled("Clients: ", Style::default().fg(theme.dimmed)),
+                Span::styled(clients, Style::default().fg(theme.foreground)),
+            ]),
+        ]);
+        frame.render_widget(status_para, layout[0]);
+
+        let field_style = |field: usize| {
+            if hotspot.field == field {
+                Style::default().fg(theme.yellow)
+            } else {
+                Style::default().fg(theme.foreground)
+            }
+        };
+
+        let ssid_input = &state.inputs.hotspot_ssid_input;
+        let ssid_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" SSID ")
+            .border_style(field_style(0))
+            .style(Style::default().bg(theme.background));
+        let ssid_para = Paragraph::new(input_line(
+            &theme,
+            &ssid_input.value,
+            ssid_input.cursor,
+            layout[1].width.saturating_sub(2) as usize,
+            hotspot.field == 0,
+        ))
+        .block(ssid_block);
+        frame.render_widget(ssid_para, layout[1]);
+
+        let pass_input = &state.inputs.hotspot_password_input;
+        let pass_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Passphrase ")
+            .border_style(field_style(1))
+            .style(Style::default().bg(theme.background));
+        let masked: String = pass_input.value.chars().map(|_| '•').collect();
+        let pass_para = Paragraph::new(input_line(
+            &theme,
+            &masked,
+            pass_input.cursor,
+            layout[2].width.saturating_sub(2) as usize,
+            hotspot.field == 1,
+        ))
+        .block(pass_block);
+        frame.render_widget(pass_para, layout[2]);
+
+        // Start / Stop Button
+        let (label, color) = if hotspot.is_busy {
+            ("Working…", theme.dimmed)
+        } else if active {
+            ("Stop", theme.red)
+        } else {
+            ("Start", theme.green)
+        };
+        let button = if hotspot.field == 2 {
+            Paragraph::new(Line::from(vec![
+                Span::styled(icons.btn_left(), Style::default().fg(color)),
+                Span::styled(label, Style::default().bg(color).fg(theme.background)),
+                Span::styled(
+                    format!("{} ", icons.btn_right()),
+                    Style::default().fg(color),
+                ),
+            ]))
+        } else {
+            Paragraph::new(format!(" {}  ", label)).style(Style::default().fg(color))
+        }
+        .alignment(Alignment::Right);
+        frame.render_widget(button, layout[3]);
+    }
+
+    if let Some(editor) = &state.profiles.editor {
+        let popup_height = 11;
+        let popup_area = Rect {
+            x: list_area.x,
+            y: list_area.y + list_area.height.saturating_sub(popup_height),
+            width: list_area.width,
+            height: popup_height,
+        };
+
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" Edit Profile: {} ", editor.ssid))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(theme.cyan).bg(theme.background));
+
+        frame.render_widget(block, popup_area);
+
+        let inner = popup_area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let layout = Layout::vertical([
+            Constraint::Length(3), // Security
+            Constraint::Length(3), // Password
+            Constraint::Length(1), // Spacer
+            Constraint::Length(1), // Checkboxes and buttons
+        ])
+        .split(inner);
+
+        let field_style = |field: usize| {
+            if editor.field == field {
+                Style::default().fg(theme.yellow)
+            } else {
+                Style::default().fg(theme.foreground)
+            }
+        };
+
+        // Security Selector, fixed for 802.1X profiles
+        let sec_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Security ")
+            .border_style(field_style(0))
+            .style(Style::default().bg(theme.background));
+        let sec_line = if editor.security.is_some() {
+            let arrow_style = if editor.field == 0 {
+                Style::default().fg(theme.yellow)
+            } else {
+                Style::default().fg(theme.dimmed)
+            };
+            Line::from(vec![
+                Span::styled(format!("{} ", icons.arrow_left()), arrow_style),
+                Span::styled(
+                    format!(" {} ", editor.security_label()),
+                    Style::default().fg(theme.foreground),
+                ),
+                Span::styled(format!(" {}", icons.arrow_right()), arrow_style),
+            ])
+        } else {
+            Line::styled(
+                format!("{} (can't be changed)", editor.security_label()),
+                Style::default().fg(theme.dimmed),
+            )
+        };
+        let sec_para = Paragraph::new(sec_line)
+            .block(sec_block)
+            .alignment(Alignment::Center);
+        frame.render_widget(sec_para, layout[0]);
+
+        let pass_input = &state.inputs.profile_password_input;
+        let pass_title = if editor.needs_password() {
+            " Password "
+        } else {
+            " Password (empty keeps the current one) "
+        };
+        let pass_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(pass_title)
+            .border_style(field_style(1))
+            .style(Style::default().bg(theme.background));
+        let masked: String = pass_input.value.chars().map(|_| '•').collect();
+        let pass_para = Paragraph::new(input_line(
+            &theme,
+            &masked,
+            pass_input.cursor,
+            layout[1].width.saturating_sub(2) as usize,
+            editor.field == 1,
+        ))
+        .block(pass_block);
+        frame.render_widget(pass_para, layout[1]);
+
+        let bottom_layout = Layout::horizontal([
+            Constraint::Length(28),
+            Constraint::Min(20),
+            Constraint::Length(10),
+            Constraint::Length(9),
+        ])
+        .split(layout[3]);
+
+        let auto_text = format!(
+            "{} Connect Automatically",
+            icons.checkbox(editor.auto_connect)
+        );
+        frame.render_widget(
+            Paragraph::new(auto_text).style(field_style(2)),
+            bottom_layout[0],
+        );
+        let hidden_text = format!("{} Hidden Network", icons.checkbox(editor.hidden));
+        frame.render_widget(
+            Paragraph::new(hidden_text).style(field_style(3)),
+            bottom_layout[1],
+        );
+
+        for (field, label, color, area) in [
+            (4, "Cancel", theme.red, bottom_layout[2]),
+            (5, "Save", theme.green, bottom_layout[3]),
+        ] {
+            let button = if editor.field == field {
+                Paragraph::new(Line::from(vec![
+                    Span::styled(icons.btn_left(), Style::default().fg(color)),
+                    Span::styled(label, Style::default().bg(color).fg(theme.background)),
+                    Span::styled(
+                        format!("{} ", icons.btn_right()),
+                        Style::default().fg(color),
+                    ),
+                ]))
+            } else {
+                Paragraph::new(format!(" {}  ", label)).style(Style::default().fg(color))
+            }
+            .alignment(Alignment::Right);
+            frame.render_widget(button, area);
+        }
+    }
+
+    if state.ui.show_channel_graph {
+        // Cover both the list and the details pane to give the bars some height
+        let popup_area = Rect {
+            height: details_area.bottom().saturating_sub(list_area.y),
+            ..list_area
+        };
+        frame.render_widget(Clear, popup_area);
+
+        let band = state.ui.channel_graph_band;
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" Channels · {} ", band.label()))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(theme.cyan).bg(theme.background));
+        frame.render_widget(block, popup_area);
+
+        let inner = popup_area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let layout = Layout::vertical([
+            Constraint::Min(3),    // Chart
+            Constraint::Length(1), // Legend
+            Constraint::Length(1), // Recommendation
+        ])
+        .split(inner);
+
+        let loads = channels::channel_loads(&state.network.wifi_list, band);
+        let best = channels::least_congested(&loads, band);
+        let connected_channel = state
+            .network
+            .wifi_list
+            .iter()
+            .find(|w| w.is_connected && Band::from_frequency(w.frequency) == Some(band))
+            .map(|w| w.channel);
+
+        let bars: Vec<Bar> = loads
+            .iter()
+            .map(|load| {
+                // A strong neighbour interferes more, so it is drawn hotter
+                let color = if load.networks == 0 {
+                    theme.dimmed
+                } else if load.strongest > 70 {
+                    theme.red
+                } else if load.strongest > 40 {
+                    theme.yellow
+                } else {
+                    theme.green
+                };
+                let label_style = if Some(load.channel) == best {
+                    Style::default()
+                        .fg(theme.green)
+                        .add_modifier(Modifier::BOLD)
+                } else if Some(load.channel) == connected_channel {
+                    Style::default().fg(theme.cyan).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.foreground)
+                };
+                Bar::default()
+                    .value(load.networks as u64)
+                    .label(Line::styled(load.channel.to_string(), label_style))
+                    .style(Style::default().fg(color))
+                    .value_style(Style::default().fg(theme.background).bg(color))
+            })
+            .collect();
+
+        let count = bars.len().max(1) as u16;
+        let gap = if count * 4 <= layout[0].width { 1 } else { 0 };
+        let bar_width = (layout[0].width.saturating_sub(gap * count) / count).clamp(1, 5);
+        let max = loads
+            .iter()
+            .map(|load| load.networks)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let chart = BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(bar_width)
+            .bar_gap(gap)
+            .max(max as u64);
+        frame.render_widget(chart, layout[0]);
+
+        let legend = Paragraph::new(Line::from(vec![
+            Span::styled(
+                "Height: networks • strongest signal ",
+                Style::default().fg(theme.dimmed),
+            ),
+            Span::styled("■ >70% ", Style::default().fg(theme.red)),
+            Span::styled("■ >40% ", Style::default().fg(theme.yellow)),
+            Span::styled("■ weaker", Style::default().fg(theme.green)),
+        ]));
+        frame.render_widget(legend, layout[1]);
+
+        let mut summary = match best {
+            Some(channel) => {
+                let interfering = channels::interference(&loads, band, channel);
+                vec![
+                    Span::styled("Least congested: ", Style::default().fg(theme.dimmed)),
+                    Span::styled(
+                        format!("ch {}", channel),
+                        Style::default()
+                            .fg(theme.green)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!(
+                            " ({} {})",
+                            interfering,
+                            if band == Band::Ghz2_4 {
+                                "overlapping networks"
+                            } else {
+                                "networks"
+                            }
+                        ),
+                        Style::default().fg(theme.foreground),
+                    ),
+                ]
+            }
+            None => Vec::new(),
+        };
+        if let Some(channel) = connected_channel {
+            summary.push(Span::styled(
+                " • Connected: ",
+                Style::default().fg(theme.dimmed),
+            ));
+            summary.push(Span::styled(
+                format!("ch {}", channel),
+                Style::default().fg(theme.cyan),
+            ));
+        }
+        frame.render_widget(Paragraph::new(Line::from(summary)), layout[2]);
+    }
+
+    if state.ui.show_help {
+        // Cover both the list and the details pane, like the channel graph
+        let popup_area = Rect {
+            height: details_area.bottom().saturating_sub(list_area.y),
+            ..list_area
+        };
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Keybindings ")
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(theme.cyan).bg(theme.background));
+        let inner = block.inner(popup_area).inner(Margin {
+            vertical: 0,
+            horizontal: 1,
+        });
+        frame.render_widget(block, popup_area);
+
+        let lines = help_lines(state, &theme);
+        let max_scroll = (lines.len() as u16).saturating_sub(inner.height);
+        state.ui.help_scroll = state.ui.help_scroll.min(max_scroll);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((state.ui.help_scroll, 0)),
+            inner,
+        );
+    }
+
+    if state.ui.show_speedtest_popup {
+        let networks_area = list_area;
+        let popup_height = 12;
+        let popup_area = Rect {
+            x: networks_area.x,
+            y: networks_area.y + networks_area.height.saturating_sub(popup_height),
+            width: networks_area.width,
+            height: popup_height,
+        };
+
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" Speed Test ")
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(theme.cyan).bg(theme.background));
+
+        frame.render_widget(block, popup_area);
+
+        let inner = popup_area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let layout = Layout::vertical([
+            Constraint::Length(1), // Network
+            Constraint::Length(1), // Progress
+            Constraint::Length(4), // Results
+            Constraint::Min(0),    // History
+        ])
+        .split(inner);
+
+        let speedtest = &state.speedtest;
+        let network_para = Paragraph::new(Line::from(vec![
+            Span::styled("Network:  ", Style::default().fg(theme.dimmed)),
+            Span::styled(
+                speedtest.ssid.clone().unwrap_or_default(),
+                Style::default().fg(theme.foreground),
+            ),
+        ]));
+        frame.render_widget(network_para, layout[0]);
+
+        let (gauge_label, ratio, color) = match speedtest.phase {
+            Some(phase) => (
+                format!("{}…", phase.label()),
+                speedtest.fraction,
+                theme.yellow,
+            ),
+            None if speedtest.upload_mbps.is_some() => ("Done".to_string(), 1.0, theme.green),
+            None => ("Idle".to_string(), 0.0, theme.dimmed),
+        };
+        let gauge = Gauge::default()
+            .gauge_style(Style::default().fg(color).bg(theme.background))
+            .label(Span::styled(
+                gauge_label,
+                Style::default().fg(theme.foreground),
+            ))
+            .ratio(ratio.clamp(0.0, 1.0));
+        frame.render_widget(gauge, layout[1]);
+
+        let value = |value: Option<f64>, unit: &str| match value {
+            Some(value) => format!("{:.1} {}", value, unit),
+            None => "-".to_string(),
+        };
+        let results_para = Paragraph::new(vec![
+            Line::default(),
+            Line::from(vec![
+                Span::styled("Latency:  ", Style::default().fg(theme.dimmed)),
+                Span::styled(
+                    value(speedtest.latency_ms, "ms"),
+                    Style::default().fg(theme.foreground),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Download: ", Style::default().fg(theme.dimmed)),
+                Span::styled(
+                    value(speedtest.download_mbps, "Mbps"),
+                    Style::default().fg(theme.foreground),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Upload:   ", Style::default().fg(theme.dimmed)),
+                Span::styled(
+                    value(speedtest.upload_mbps, "Mbps"),
+                    Style::default().fg(theme.foreground),
+                ),
+            ]),
+        ]);
+        frame.render_widget(results_para, layout[2]);
+
+        let mut history_lines = Vec::new();
+        if !speedtest.history.is_empty() {
+            history_lines.push(Line::from(Span::styled(
+                "Recent runs",
+                Style::default().fg(theme.dimmed),
+            )));
+        }
+        for record in speedtest
+            .history
+            .iter()
+            .rev()
+            .take(config::SPEEDTEST_HISTORY_SHOWN)
+        {
+            history_lines.push(Line::from(Span::styled(
+                format!(
+                    "{}  {:>6.1} ms  {:>7.1} ↓  {:>7.1} ↑ Mbps",
+                    record.timestamp,
+                    record.result.latency_ms,
+                    record.result.download_mbps,
+                    record.result.upload_mbps
+                ),
+                Style::default().fg(theme.foreground),
+            )));
+        }
+        frame.render_widget(Paragraph::new(history_lines), layout[3]);
+    }
+
+    if state.ui.show_wps_popup {
+        let networks_area = list_area;
+        let popup_height = 6;
+        let popup_area = Rect {
+            x: networks_area.x,
+            y: networks_area.y + networks_area.height.saturating_sub(popup_height),
+            width: networks_area.width,
+            height: popup_height,
+        };
+
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(" WPS Push-Button ")
+            .title_alignment(Alignment::Center)
+            .style(Style::default().fg(theme.cyan).bg(theme.background));
+
+        frame.render_widget(block, popup_area);
+
+        let inner = popup_area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let wps = &state.wps;
+        let status = match &wps.error {
+            _ if wps.result_rx.is_some() => Line::from(vec![
+                Span::styled(
+                    format!(
+                        "{} ",
+                        config::LOADING_CHARS[state.ui.loading_frame % config::LOADING_CHARS.len()]
+                    ),
+                    Style::default().fg(theme.yellow),
+                ),
+                Span::styled(
+                    "Press the WPS button on your router",
+                    Style::default().fg(theme.foreground),
+                ),
+            ]),
+            Some(error) => Line::from(Span::styled(error.as_str(), Style::default().fg(theme.red))),
+            None => Line::default(),
+        };
+        let para = Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled("Network: ", Style::default().fg(theme.dimmed)),
+                Span::styled(
+                    wps.ssid.clone().unwrap_or_default(),
+                    Style::default().fg(theme.foreground),
+                ),
+            ]),
+            Line::default(),
+            status,
+        ])
+        .wrap(Wrap { trim: true });
+        frame.render_widget(para, inner);
+    }
+
+    if let Some(confirmation) = &state.ui.confirmation {
+        let networks_area = list_area;
+        let popup_height = if confirmation.detail.is_some() { 5 } else { 4 };
+        let popup_area = Rect {
+            x: networks_area.x,
+            y: networks_area.y + networks_area.height.saturating_sub(popup_height),
+            width: networks_area.width,
+            height: popup_height,
+        };
+
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title(format!(" {} ", confirmation.title))
             .title_alignment(Alignment::Center)
-            .title_style(
+            .style(Style::default().fg(theme.red).bg(theme.background));
+
+        frame.render_widget(block, popup_area);
+
+        let inner = popup_area.inner(Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let mut lines = vec![Line::from(Span::styled(
+            confirmation.message.as_str(),
+            Style::default().fg(theme.foreground),
+        ))];
+        if let Some(detail) = &confirmation.detail {
+            lines.push(Line::from(Span::styled(
+                detail.as_str(),
+                Style::default().fg(theme.dimmed),
+            )));
+        }
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), inner);
+    }
+
+    // Toasts stack down the top-right corner, oldest on top
+    let mut toast_y = main_area.y;
+    for toast in &state.ui.toasts {
+        let (title, color) = match toast.kind {
+            ToastKind::Info => ("", theme.yellow),
+            ToastKind::Success => (" OK ", theme.green),
+            ToastKind::Warning => (" WARNING ", theme.yellow),
+            ToastKind::Error => (" ERROR ", theme.red),
+        };
+        let toast_width = (toast.message.chars().count() as u16 + 4)
+            .max(title.len() as u16 + 4)
+            .min(main_area.width);
+        let text_width = toast_width.saturating_sub(4).max(1);
+        let toast_height = (toast.message.chars().count() as u16)
+            .div_ceil(text_width)
+            .max(1)
+            + 2;
+        if toast_y + toast_height > main_area.y + main_area.height {
+            break;
+        }
+        let toast_area = Rect::new(
+            main_area.x + main_area.width - toast_width,
+            toast_y,
+            toast_width,
+            toast_height,
+        );
+        toast_y += toast_height;
+
+        let text_color = if toast.kind == ToastKind::Error {
+            theme.red
+        } else {
+            theme.foreground
+        };
+        let toast_paragraph = Paragraph::new(toast.message.as_str())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(color))
+                    .title(title),
+            )
+            .style(Style::default().fg(text_color).bg(theme.background))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+
+        frame.render_widget(Clear, toast_area);
+        frame.render_widget(toast_paragraph, toast_area);
+    }
+
+    if state.ui.show_key_logger
+        && let Some((key, time)) = &state.ui.last_key_press
+        && time.elapsed() < std::time::Duration::from_secs(2)
+    {
+        let key_text = format!(" {} ", key);
+        let width = key_text.len() as u16 + 2;
+
+        // Position right below the bottom right of the main UI, or over its
+        // bottom edge when it fills the terminal
+        let key_y = if main_area.bottom() + 3 <= area.bottom() {
+            main_area.bottom()
+        } else {
+            main_area.bottom() - 3
+        };
+        let key_area = Rect::new(main_area.x + main_area.width - width, key_y, width, 3);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.bright_purple))
+            .style(Style::default().bg(theme.background));
+
+        let paragraph = Paragraph::new(key_text)
+            .block(block)
+            .style(
                 Style::default()
-                    .fg(theme::CYAN)
+                    .fg(theme.bright_purple)
                     .add_modifier(Modifier::BOLD),
             )
-            .style(Style::default().bg(theme::BACKGROUND));
+            .alignment(Alignment::Center);
+
+        frame.render_widget(Clear, key_area);
+        frame.render_widget(paragraph, key_area);
+    }
+
+    if state.ui.show_compare_popup
+        && let Some((a, b)) = state.compared_networks()
+    {
+        let popup_width = 64.min(area.width);
+        let col_width = (popup_width.saturating_sub(16) / 2) as usize;
+        let band = |w: &crate::wifi::WifiInfo| {
+            crate::wifi::Band::from_frequency(w.frequency)
+                .map(|b| b.label())
+                .unwrap_or("Unknown")
+                .to_string()
+        };
+        let (score_a, score_b) = (compare::quality_score(a), compare::quality_score(b));
+
+        let rows: Vec<(&str, String, String)> = vec![
+            (
+                "SSID",
+                a.display_ssid().into_owned(),
+                b.display_ssid().into_owned(),
+            ),
+            ("Signal", format!("{}%", a.signal), format!("{}%", b.signal)),
+            ("Band", band(a), band(b)),
+            ("Channel", a.channel.to_string(), b.channel.to_string()),
+            ("Standard", a.phy_type.clone(), b.phy_type.clone()),
+            (
+                "Security",
+                display_auth_name(&a.authentication).to_string(),
+                display_auth_name(&b.authentication).to_string(),
+            ),
+            ("Score", score_a.to_string(), score_b.to_string()),
+        ];
+
+        let truncate = |text: &str| text.chars().take(col_width).collect::<String>();
+        let lines: Vec<Line> = rows
+            .into_iter()
+            .map(|(name, left, right)| {
+                let (left_style, right_style) = if name == "Score" {
+                    let better = Style::default()
+                        .fg(theme.green)
+                        .add_modifier(Modifier::BOLD);
+                    match score_a.cmp(&score_b) {
+                        std::cmp::Ordering::Greater => (better, Style::default()),
+                        std::cmp::Ordering::Less => (Style::default(), better),
+                        std::cmp::Ordering::Equal => (Style::default(), Style::default()),
+                    }
+                } else {
+                    (Style::default(), Style::default())
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:>10}  ", name), Style::default().fg(theme.cyan)),
+                    Span::styled(
+                        format!("{:<width$}", truncate(&left), width = col_width),
+                        left_style,
+                    ),
+                    Span::raw("  "),
+                    Span::styled(truncate(&right), right_style),
+                ])
+            })
+            .collect();
+
+        let popup_height = (lines.len() as u16 + 2).min(area.height);
+        let popup_area = Rect::new(
+            area.width.saturating_sub(popup_width) / 2,
+            area.height.saturating_sub(popup_height) / 2,
+            popup_width,
+            popup_height,
+        );
+
+        let compare_paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(theme.cyan))
+                    .title(" Compare Networks ")
+                    .title_alignment(Alignment::Center)
+                    .title_style(Style::default().fg(theme.cyan).add_modifier(Modifier::BOLD)),
+            )
+            .style(Style::default().fg(theme.foreground).bg(theme.background));
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(compare_paragraph, popup_area);
+    }
+
+    if state.ui.show_command_palette {
+        let actions = state.palette_actions();
+        let popup_width = 50.min(area.width);
+        let popup_height = (actions.len() as u16 + 3).min(area.height.saturating_sub(2));
+        let popup_area = Rect::new(
+            area.width.saturating_sub(popup_width) / 2,
+            area.height.saturating_sub(popup_height) / 2,
+            popup_width,
+            popup_height,
+        );
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.cyan))
+            .title(" Command Palette ")
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.cyan).add_modifier(Modifier::BOLD))
+            .style(Style::default().fg(theme.foreground).bg(theme.background));
+        let inner = block.inner(popup_area);
+        let palette_layout =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(inner);
+        let (input_area, list_area) = (palette_layout[0], palette_layout[1]);
+
+        let query = Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.cyan)),
+            Span::raw(state.inputs.palette_input.value.clone()),
+            Span::styled(" ", Style::default().bg(theme.foreground)),
+        ]);
+
+        let key_width = 7;
+        let label_width = (list_area.width as usize).saturating_sub(key_width + 3);
+        let query_lower = state.inputs.palette_input.value.to_lowercase();
+        let match_style = Style::default().fg(theme.cyan).add_modifier(Modifier::BOLD);
+        let items: Vec<ListItem> = actions
+            .iter()
+            .map(|action| {
+                let label = format!("{:<width$}", action.label(), width = label_width);
+                let matched =
+                    fuzzy_indices(&label.to_lowercase(), &query_lower).unwrap_or_default();
+                let mut spans: Vec<Span> = label
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| {
+                        if matched.contains(&i) {
+                            Span::styled(c.to_string(), match_style)
+                        } else {
+                            Span::raw(c.to_string())
+                        }
+                    })
+                    .collect();
+                spans.push(Span::styled(
+                    format!(
+                        "{:>width$}",
+                        state.config.keymap.hint(*action),
+                        width = key_width
+                    ),
+                    Style::default().fg(theme.dimmed),
+                ));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_symbol(icons.highlight())
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .bg(theme.selection_bg),
+            );
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(block, popup_area);
+        frame.render_widget(Paragraph::new(query), input_area);
+        frame.render_stateful_widget(list, list_area, &mut state.ui.palette_state);
+    }
+
+    if state.ui.show_profiles_popup {
+        let popup_width = 50.min(area.width);
+        let popup_height =
+            (state.profiles.list.len().max(1) as u16 + 2).min(area.height.saturating_sub(2));
+        let popup_area = Rect::new(
+            area.width.saturating_sub(popup_width) / 2,
+            area.height.saturating_sub(popup_height) / 2,
+            popup_width,
+            popup_height,
+        );
+
+        let title = if state.profiles.is_busy {
+            " Saved Profiles (saving…) "
+        } else {
+            " Saved Profiles "
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.cyan))
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.cyan).add_modifier(Modifier::BOLD))
+            .style(Style::default().fg(theme.foreground).bg(theme.background));
+
+        frame.render_widget(Clear, popup_area);
+        if state.profiles.list.is_empty() {
+            let message = if state.profiles.list_rx.is_some() {
+                "Loading…"
+            } else {
+                "No saved profiles"
+            };
+            let paragraph = Paragraph::new(message)
+                .style(Style::default().fg(theme.dimmed))
+                .alignment(Alignment::Center)
+                .block(block);
+            frame.render_widget(paragraph, popup_area);
+        } else {
+            // Numbered by priority, 1 is tried first
+            let items: Vec<ListItem> = state
+                .profiles
+                .list
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!("{:>2}. ", i + 1), Style::default().fg(theme.dimmed)),
+                        Span::raw(name.clone()),
+                    ]))
+                })
+                .collect();
+            let list = List::new(items)
+                .block(block)
+                .highlight_symbol(icons.highlight())
+                .highlight_style(
+                    Style::default()
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme.selection_bg),
+                );
+            frame.render_stateful_widget(list, popup_area, &mut state.profiles.list_state);
+        }
+    }
+
+    // QR Code popup
+    if state.ui.show_qr_popup {
+        use secrecy::ExposeSecret;
+
+        // Plain-text credentials for phones that can't scan the terminal QR
+        let password_text = match &state.ui.qr_password {
+            Some(pwd) if state.ui.show_qr_password => pwd.expose_secret().to_string(),
+            Some(pwd) => "•".repeat(pwd.expose_secret().chars().count()),
+            None => "(none)".to_string(),
+        };
+        let credential_lines = vec![
+            Line::from(vec![
+                Span::styled("SSID: ", Style::default().fg(theme.cyan)),
+                Span::raw(state.ui.qr_ssid.clone()),
+            ]),
+            Line::from(vec![
+                Span::styled("Password: ", Style::default().fg(theme.cyan)),
+                Span::raw(password_text),
+            ]),
+        ];
+        let credentials_width = credential_lines
+            .iter()
+            .map(|l| l.width())
+            .max()
+            .unwrap_or(0) as u16;
+
+        // Calculate QR popup size based on terminal size
+        let qr_lines_height = state.ui.qr_code_lines.len() as u16;
+        let qr_height = qr_lines_height + credential_lines.len() as u16 + 4; // +4 for borders and padding
+        let qr_width = (state.ui.qr_code_lines.first().map(|l| l.len()).unwrap_or(0) as u16)
+            .max(credentials_width)
+            + 4;
+
+        // Center the popup
+        let qr_x = area.width.saturating_sub(qr_width) / 2;
+        let qr_y = area.height.saturating_sub(qr_height) / 2;
+
+        let qr_area = Rect::new(
+            qr_x,
+            qr_y,
+            qr_width.min(area.width),
+            qr_height.min(area.height),
+        );
+
+        // Clear background
+        frame.render_widget(Clear, qr_area);
+
+        // QR code block
+        let qr_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.cyan))
+            .title(" Share WiFi (Scan with phone) ")
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.cyan).add_modifier(Modifier::BOLD))
+            .style(Style::default().bg(theme.background));
 
         frame.render_widget(qr_block.clone(), qr_area);
 
@@ -1037,21 +2941,481 @@ pub fn render(frame: &mut Frame, state: &mut AppState) {
             horizontal: 1,
         });
 
+        let [qr_code_area, credentials_area] =
+            Layout::vertical([Constraint::Length(qr_lines_height), Constraint::Fill(1)])
+                .areas(inner);
+
         let qr_text = state.ui.qr_code_lines.join("\n");
         let qr_paragraph = Paragraph::new(qr_text)
             .alignment(Alignment::Center)
-            .style(Style::default().fg(theme::FOREGROUND).bg(theme::BACKGROUND));
+            .style(Style::default().fg(theme.foreground).bg(theme.background));
 
-        frame.render_widget(qr_paragraph, inner);
+        frame.render_widget(qr_paragraph, qr_code_area);
+
+        let credentials_paragraph = Paragraph::new(credential_lines)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(theme.foreground).bg(theme.background));
+
+        frame.render_widget(credentials_paragraph, credentials_area);
 
         // Help text below QR code (clamp to terminal bounds)
         let help_y = qr_area.y.saturating_add(qr_area.height).saturating_add(1);
         if help_y < area.y.saturating_add(area.height) && area.width > 0 {
             let help_area = Rect::new(area.x, help_y, area.width, 1);
-            let help_text = Paragraph::new("Press ESC, q, or Enter to close")
+            let help = if state.ui.qr_password.is_some() {
+                if state.ui.show_qr_password {
+                    "p hide password • w save to file • ESC, q, or Enter to close"
+                } else {
+                    "p show password • w save to file • ESC, q, or Enter to close"
+                }
+            } else {
+                "w save to file • ESC, q, or Enter to close"
+            };
+            let help_text = Paragraph::new(help)
                 .alignment(Alignment::Center)
-                .style(Style::default().fg(theme::DIMMED));
+                .style(Style::default().fg(theme.dimmed));
             frame.render_widget(help_text, help_area);
         }
     }
 }
+
+/// Rough strength of a WPA passphrase and the color to show it in; WPA
+/// takes 8 to 63 characters, or a raw key of 64 hex digits
+fn passphrase_strength(passphrase: &str, theme: &Theme) -> (&'static str, Color) {
+    let len = passphrase.chars().count();
+    let classes = [
+        passphrase.chars().any(|c| c.is_lowercase()),
+        passphrase.chars().any(|c| c.is_uppercase()),
+        passphrase.chars().any(|c| c.is_numeric()),
+        passphrase.chars().any(|c| !c.is_alphanumeric()),
+    ]
+    .into_iter()
+    .filter(|&present| present)
+    .count();
+    match len {
+        0..8 => ("too short", theme.red),
+        64 if passphrase.chars().all(|c| c.is_ascii_hexdigit()) => ("hex key", theme.green),
+        64.. => ("too long", theme.red),
+        16.. if classes >= 3 => ("strong", theme.green),
+        12.. if classes >= 2 => ("fair", theme.blue),
+        _ => ("weak", theme.yellow),
+    }
+}
+
+/// `42s`, `5m` or `3h`
+fn format_age(age: std::time::Duration) -> String {
+    match age.as_secs() {
+        secs @ 0..60 => format!("{}s", secs),
+        secs @ 60..3600 => format!("{}m", secs / 60),
+        secs => format!("{}h", secs / 3600),
+    }
+}
+
+/// Pad `text` with spaces to `width` columns, or cut it short with an ellipsis
+fn fit_width(text: &str, width: usize) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut used = 0;
+    let mut buf = [0u8; 4];
+    for c in text.chars() {
+        let char_width = Span::raw(&*c.encode_utf8(&mut buf)).width();
+        if used + char_width > width {
+            // Make room for the ellipsis
+            while used + 1 > width && out.pop().is_some() {
+                used = Span::raw(out.as_str()).width();
+            }
+            if used < width {
+                out.push('…');
+                used += 1;
+            }
+            break;
+        }
+        out.push(c);
+        used += char_width;
+    }
+    out.extend(std::iter::repeat_n(' ', width.saturating_sub(used)));
+    out
+}
+
+/// Size of the main window along one axis: as large as allowed, with the
+/// same parity as the terminal so it centers exactly
+fn window_length(available: u16, max: u16) -> u16 {
+    let length = available.min(max);
+    length - (available - length) % 2
+}
+
+/// Shown instead of the UI when the terminal is below the minimum size
+fn render_too_small(frame: &mut Frame, theme: &Theme, area: Rect) {
+    let lines = vec![
+        Line::styled(
+            "Terminal too small",
+            Style::default()
+                .fg(theme.yellow)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Line::from(format!("{} x {}", area.width, area.height)),
+        Line::styled(
+            format!(
+                "Resize to at least {} x {}",
+                config::MIN_WINDOW_WIDTH,
+                config::MIN_WINDOW_HEIGHT
+            ),
+            Style::default().fg(theme.dimmed),
+        ),
+    ];
+    let text_area = Rect {
+        y: area.y + area.height.saturating_sub(lines.len() as u16) / 2,
+        height: (lines.len() as u16).min(area.height),
+        ..area
+    };
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        text_area,
+    );
+}
+
+/// Internet status of the connected network, shown after its Status line
+fn connectivity_span(state: &AppState, ssid: &str, is_dimmed: bool) -> Span<'static> {
+    let theme = &state.config.theme;
+    let Some((_, connectivity)) = state
+        .connection
+        .connectivity
+        .as_ref()
+        .filter(|(probed, _)| probed == ssid)
+    else {
+        return Span::raw("");
+    };
+    let color = match connectivity {
+        _ if is_dimmed => theme.dimmed,
+        Connectivity::Checking => theme.foreground,
+        Connectivity::Online => theme.green,
+        Connectivity::NoInternet => theme.red,
+        Connectivity::CaptivePortal(_) => theme.yellow,
+    };
+    Span::styled(
+        format!("  {}", connectivity.label()),
+        Style::default().fg(color),
+    )
+}
+
+/// Keys that work in every text field and popup; these can't be remapped
+const FIXED_KEY_GROUPS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "Text Fields",
+        &[
+            ("esc / ctrl+[", "Clear input"),
+            ("ctrl+backspace", "Delete word"),
+            ("ctrl+← / →", "Move cursor by word"),
+            ("home / end", "Move cursor to start / end"),
+            ("ctrl+v", "Paste"),
+            ("↑ / ↓", "Recent searches (search only)"),
+            ("f2", "Show / hide password (password only)"),
+        ],
+    ),
+    (
+        "Popups",
+        &[
+            ("tab / shift+tab", "Next / previous field"),
+            ("enter", "Confirm"),
+            ("esc / ctrl+[", "Close"),
+        ],
+    ),
+];
+
+/// Lines of the key binding overlay, built from the active keymap
+fn help_lines(state: &AppState, theme: &Theme) -> Vec<Line<'static>> {
+    let keymap = &state.config.keymap;
+    let key_width = 16;
+    let heading = Style::default().fg(theme.cyan).add_modifier(Modifier::BOLD);
+    let key_style = Style::default().fg(theme.foreground);
+    let label_style = Style::default().fg(theme.dimmed);
+    let row = |keys: String, label: &str| {
+        Line::from(vec![
+            Span::styled(format!("  {:<width$}", keys, width = key_width), key_style),
+            Span::styled(label.to_string(), label_style),
+        ])
+    };
+
+    let keys = |action: Action| -> String {
+        match action {
+            // One row for the whole 1-9 range
+            Action::QuickConnect(_) => {
+                let first = keymap.hint(Action::QuickConnect(1));
+                let last = keymap.hint(Action::QuickConnect(9));
+                format!("{}…{}", first, last)
+            }
+            _ => keymap.keys(action).join(" / "),
+        }
+    };
+
+    let mut groups: Vec<(&str, Vec<Action>)> = Action::HELP_GROUPS
+        .iter()
+        .map(|&(title, actions)| (title, actions.to_vec()))
+        .collect();
+    let other: Vec<Action> = keymap
+        .bound_actions()
+        .into_iter()
+        .filter(|action| {
+            !matches!(action, Action::QuickConnect(_))
+                && !Action::HELP_GROUPS
+                    .iter()
+                    .any(|(_, actions)| actions.contains(action))
+        })
+        .collect();
+    if !other.is_empty() {
+        groups.push(("Other", other));
+    }
+
+    let mut lines = Vec::new();
+    for (title, actions) in groups {
+        let rows: Vec<Line> = actions
+            .into_iter()
+            .filter_map(|action| {
+                let keys = keys(action);
+                let label = match action {
+                    Action::QuickConnect(_) => "Connect to network 1-9",
+                    _ => action.label(),
+                };
+                // Unbound actions are still in the command palette
+                (!keys.is_empty() && keys != "…").then(|| row(keys, label))
+            })
+            .collect();
+        if rows.is_empty() {
+            continue;
+        }
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        lines.push(Line::styled(title.to_string(), heading));
+        lines.extend(rows);
+    }
+    for (title, keys) in FIXED_KEY_GROUPS {
+        lines.push(Line::default());
+        lines.push(Line::styled(title.to_string(), heading));
+        lines.extend(
+            keys.iter()
+                .map(|(keys, label)| row(keys.to_string(), label)),
+        );
+    }
+    lines
+}
+
+/// Progress of the reconnect watchdog or a pending roam, for the list's bottom border
+fn list_status(state: &AppState) -> Option<String> {
+    if let Some(pending) = &state.ui.pending_keys {
+        return Some(format!("Keys: {}", pending.label()));
+    }
+    if let Some(forgotten) = &state.network.forgotten {
+        return Some(format!(
+            "Forgot {}: {} to undo ({}s)",
+            forgotten.ssid,
+            state.config.keymap.hint(Action::UndoForget),
+            forgotten.remaining().as_millis().div_ceil(1000)
+        ));
+    }
+    // Connection attempts have the spinner; other operations show here
+    if let Some(operation) = state
+        .tasks
+        .running
+        .iter()
+        .find(|op| op.kind != OperationKind::Connect)
+    {
+        return Some(format!("Working: {}...", operation.describe()));
+    }
+    let Some(ssid) = &state.watchdog.ssid else {
+        let (candidate, _) = state.roaming.candidate.as_ref()?;
+        let when = match state.roaming.wait() {
+            Some(wait) if !wait.is_zero() => format!("in {}s", wait.as_millis().div_ceil(1000)),
+            _ => "after the next scan".to_string(),
+        };
+        return Some(format!(
+            "Roaming: {} is stronger ({}% vs {}%), switching {}",
+            candidate.ssid, candidate.signal, candidate.current_signal, when
+        ));
+    };
+    let status = if state.connection.is_connecting {
+        format!(
+            "Watchdog: reconnecting to {} (attempt {}/{})",
+            ssid,
+            state.watchdog.attempts,
+            config::WATCHDOG_MAX_ATTEMPTS
+        )
+    } else if !state.network.wifi_list.iter().any(|w| w.ssid == *ssid) {
+        format!("Watchdog: waiting for {} to reappear", ssid)
+    } else {
+        let secs = state.watchdog.next_attempt.map_or(0, |t| {
+            t.saturating_duration_since(Instant::now())
+                .as_millis()
+                .div_ceil(1000)
+        });
+        format!("Watchdog: retrying {} in {}s", ssid, secs)
+    };
+    Some(status)
+}
+
+/// Whether the selected network is the connected one being pinged
+fn selected_is_monitored(state: &AppState) -> bool {
+    state
+        .ui
+        .l_state
+        .selected()
+        .and_then(|i| state.network.filtered_wifi_list.get(i))
+        .is_some_and(|wifi| {
+            wifi.is_connected && state.latency.ssid.as_deref() == Some(wifi.ssid.as_str())
+        })
+}
+
+fn selected_is_suspicious(state: &AppState) -> bool {
+    state
+        .ui
+        .l_state
+        .selected()
+        .and_then(|i| state.network.filtered_wifi_list.get(i))
+        .is_some_and(|wifi| state.suspicion(wifi).is_some())
+}
+
+/// Details block title listing every tab, with the selected one highlighted
+fn details_tabs_title(selected: DetailsTab, active: Style, inactive: Style) -> Line<'static> {
+    let mut spans = vec![Span::raw(" ")];
+    for (i, tab) in DetailsTab::ALL.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" │ ", inactive));
+        }
+        let style = if tab == selected { active } else { inactive };
+        spans.push(Span::styled(tab.label(), style));
+    }
+    spans.push(Span::raw(" "));
+    Line::from(spans)
+}
+
+/// Label and value of each known address of the connected interface
+fn ip_rows(info: &IpInfo) -> Vec<(&'static str, String)> {
+    let mut rows = Vec::new();
+    if let Some((addr, prefix)) = info.ipv4 {
+        rows.push(("IPv4", format!("{}/{}", addr, prefix)));
+    }
+    if let Some(addr) = info.preferred_ipv6() {
+        rows.push(("IPv6", addr.to_string()));
+    }
+    if let Some(gateway) = info.gateway {
+        rows.push(("Gateway", gateway.to_string()));
+    }
+    if !info.dns.is_empty() {
+        let servers: Vec<_> = info.dns.iter().map(|addr| addr.to_string()).collect();
+        rows.push(("DNS", servers.join(", ")));
+    }
+    if let Some(mac) = &info.mac {
+        rows.push(("MAC", format_bssid(mac)));
+    }
+    rows
+}
+
+/// TX/RX frame rates, retries and failures of the connected interface, each
+/// with a sparkline of the recent polls that fits in `width`
+fn frame_rate_rows(
+    state: &AppState,
+    width: usize,
+    is_dimmed: bool,
+    value_style: Style,
+) -> Vec<(&'static str, Vec<Span<'static>>)> {
+    let theme = &state.config.theme;
+    let history = &state.monitor.frame_rates;
+    let Some(latest) = history.latest() else {
+        return Vec::new();
+    };
+    // Label and rate before the chart, the retry share after it
+    let chart_width = width.saturating_sub(12 + 9 + 6);
+    let chart_style = |color| Style::default().fg(if is_dimmed { theme.dimmed } else { color });
+    let row = |rate: f64, field: fn(&FrameRates) -> f64, color| {
+        let series = history.series(field);
+        let recent = &series[series.len().saturating_sub(chart_width)..];
+        vec![
+            Span::styled(format!("{:>7}", format_rate(rate)), value_style),
+            Span::styled(format!("  {}", sparkline(recent)), chart_style(color)),
+        ]
+    };
+
+    let mut retries = row(latest.retries, |r| r.retries, theme.yellow);
+    if let Some(percent) = latest.retry_percent() {
+        retries.push(Span::styled(format!("  {:.0}%", percent), value_style));
+    }
+    let mut failed = row(latest.failed, |r| r.failed, theme.red);
+    if latest.failed > 0.0 {
+        failed[0].style = chart_style(theme.red);
+    }
+    vec![
+        ("TX", row(latest.tx, |r| r.tx, theme.blue)),
+        ("RX", row(latest.rx, |r| r.rx, theme.cyan)),
+        ("Retries", retries),
+        ("Failed", failed),
+    ]
+}
+
+/// Frames per second, e.g. `840/s` or `12.5k/s`
+fn format_rate(rate: f64) -> String {
+    if rate >= 1000.0 {
+        format!("{:.1}k/s", rate / 1000.0)
+    } else {
+        format!("{:.0}/s", rate)
+    }
+}
+
+/// Bars for a series, scaled to its largest value
+fn sparkline(series: &[f64]) -> String {
+    let max = series.iter().copied().fold(0.0, f64::max);
+    let last = config::SPARKLINE_CHARS.len() - 1;
+    series
+        .iter()
+        .map(|&value| {
+            let level = if max > 0.0 {
+                (value / max * last as f64).round() as usize
+            } else {
+                0
+            };
+            config::SPARKLINE_CHARS[level.min(last)]
+        })
+        .collect()
+}
+
+/// Gateway latency, jitter and loss, plus the internet round trip if pinged
+fn latency_spans(state: &AppState, is_dimmed: bool, value_style: Style) -> Vec<Span<'static>> {
+    let theme = &state.config.theme;
+    let latency = &state.latency;
+    let stats = &latency.gateway_stats;
+    let mut spans = Vec::new();
+    match (latency.gateway, stats.latest_ms()) {
+        (Some(_), Some(ms)) => {
+            spans.push(Span::styled(format!("{:.1} ms", ms), value_style));
+            if let Some(jitter) = stats.jitter_ms() {
+                spans.push(Span::styled(format!(" ±{:.1}", jitter), value_style));
+            }
+        }
+        (Some(_), None) if stats.is_empty() => {
+            spans.push(Span::styled("Measuring...", value_style))
+        }
+        (Some(_), None) => spans.push(Span::styled(
+            "No reply",
+            Style::default().fg(if is_dimmed { theme.dimmed } else { theme.red }),
+        )),
+        (None, _) => spans.push(Span::styled("No gateway", value_style)),
+    }
+    if !stats.is_empty() {
+        let loss = stats.loss_percent();
+        spans.push(Span::styled(
+            format!(" · {:.0}% loss", loss),
+            if loss > 0.0 && !is_dimmed {
+                Style::default().fg(theme.yellow)
+            } else {
+                value_style
+            },
+        ));
+    }
+    if let Some(ms) = latency.internet_stats.latest_ms() {
+        spans.push(Span::styled(
+            format!(" · {} {:.0} ms", config::INTERNET_PING_ADDR, ms),
+            value_style,
+        ));
+    }
+    spans
+}
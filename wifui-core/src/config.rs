@@ -0,0 +1,11 @@
+//! Timing constants of the WiFi calls
+
+// Windows needs a moment to register a new profile before connecting with it
+#[cfg(windows)]
+pub const PROFILE_REGISTRATION_DELAY_MS: u64 = 1500;
+#[cfg(windows)]
+pub const OPEN_PROFILE_REGISTRATION_DELAY_MS: u64 = 1000;
+pub const DISCONNECT_DELAY_MS: u64 = 500;
+
+// Debug log
+pub const DEBUG_LOG_CAPACITY: usize = 500;
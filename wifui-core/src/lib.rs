@@ -0,0 +1,14 @@
+//! Wi-Fi management behind wifui, without the terminal UI
+//!
+//! [`wifi`] scans, connects, manages saved profiles and the hotspot through
+//! the Native WiFi API on Windows and NetworkManager (`nmcli`) on Linux.
+//! Every call blocks, so async code should run them on a worker thread, e.g.
+//! with `tokio::task::spawn_blocking`. Connection changes arrive through
+//! [`wifi::subscribe`].
+
+pub mod config;
+pub mod error;
+pub mod logging;
+pub mod perf;
+pub mod random;
+pub mod wifi;
@@ -0,0 +1,188 @@
+//! Debug log
+//!
+//! Records WiFi API calls, their durations and failure reasons as `tracing`
+//! events. [`BufferLayer`] keeps the latest ones in a bounded buffer that the
+//! `--debug` pane renders inside the TUI, and the application's subscriber
+//! writes them to `--log-file`. Call timings are also forwarded to the
+//! `--perf` overlay.
+
+use crate::config;
+use crate::perf;
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static BUFFER: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+
+/// Severity of a log entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        }
+    }
+}
+
+/// A single log line
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: SystemTime,
+    pub level: Level,
+    pub message: String,
+}
+
+/// Start collecting log entries
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Emit an entry as a `tracing` event
+pub fn log(level: Level, message: impl Into<String>) {
+    if !is_enabled() {
+        return;
+    }
+    let message = message.into();
+    match level {
+        Level::Debug => tracing::debug!("{}", message),
+        Level::Info => tracing::info!("{}", message),
+        Level::Warn => tracing::warn!("{}", message),
+        Level::Error => tracing::error!("{}", message),
+    }
+}
+
+/// `tracing` layer that feeds the debug pane, dropping the oldest entry
+/// once the buffer is full
+pub struct BufferLayer;
+
+impl<S: Subscriber> Layer<S> for BufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let level = match *event.metadata().level() {
+            tracing::Level::ERROR => Level::Error,
+            tracing::Level::WARN => Level::Warn,
+            tracing::Level::INFO => Level::Info,
+            _ => Level::Debug,
+        };
+        let mut message = MessageVisitor(String::new());
+        event.record(&mut message);
+        let entry = LogEntry {
+            time: SystemTime::now(),
+            level,
+            message: message.0,
+        };
+        let Ok(mut buffer) = BUFFER.lock() else {
+            return;
+        };
+        if buffer.len() >= config::DEBUG_LOG_CAPACITY {
+            buffer.pop_front();
+        }
+        buffer.push_back(entry);
+    }
+}
+
+/// Text of an event's `message` field
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+pub fn debug(message: impl Into<String>) {
+    log(Level::Debug, message);
+}
+
+pub fn info(message: impl Into<String>) {
+    log(Level::Info, message);
+}
+
+pub fn warn(message: impl Into<String>) {
+    log(Level::Warn, message);
+}
+
+/// The most recent `count` entries, oldest first
+pub fn recent(count: usize) -> Vec<LogEntry> {
+    let Ok(buffer) = BUFFER.lock() else {
+        return Vec::new();
+    };
+    buffer
+        .iter()
+        .skip(buffer.len().saturating_sub(count))
+        .cloned()
+        .collect()
+}
+
+/// Run a WiFi call, logging its duration and any error it returns
+pub fn timed<T, E: Display>(name: &str, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    if !is_enabled() && !perf::is_enabled() {
+        return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    perf::record_call(name, elapsed);
+
+    let elapsed = perf::format_duration(elapsed);
+    match &result {
+        Ok(_) => log(Level::Debug, format!("{} ok in {}", name, elapsed)),
+        Err(e) => log(
+            Level::Error,
+            format!("{} failed in {}: {}", name, elapsed, e),
+        ),
+    }
+    result
+}
+
+/// Format a system time as an ISO 8601 UTC timestamp
+pub fn format_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil-from-days conversion (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        (rem % 3_600) / 60,
+        rem % 60
+    )
+}
@@ -0,0 +1,73 @@
+//! Performance instrumentation
+//!
+//! Collects per-call latency for WiFi API calls (recorded from the blocking
+//! worker threads) for the `--perf` overlay.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CALLS: Mutex<BTreeMap<String, CallStats>> = Mutex::new(BTreeMap::new());
+
+/// Latency statistics for one WiFi call
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CallStats {
+    pub count: u32,
+    pub last: Duration,
+    pub max: Duration,
+    pub total: Duration,
+}
+
+impl CallStats {
+    pub fn average(&self) -> Duration {
+        self.total / self.count.max(1)
+    }
+}
+
+/// Start collecting call latencies
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Record the duration of one call
+pub fn record_call(name: &str, duration: Duration) {
+    if !is_enabled() {
+        return;
+    }
+    let Ok(mut calls) = CALLS.lock() else {
+        return;
+    };
+    let stats = calls.entry(name.to_string()).or_default();
+    stats.count += 1;
+    stats.last = duration;
+    stats.max = stats.max.max(duration);
+    stats.total += duration;
+}
+
+/// Snapshot of all recorded calls, sorted by name
+pub fn call_stats() -> Vec<(String, CallStats)> {
+    let Ok(calls) = CALLS.lock() else {
+        return Vec::new();
+    };
+    calls
+        .iter()
+        .map(|(name, stats)| (name.clone(), *stats))
+        .collect()
+}
+
+/// Format a duration compactly for the overlay
+pub fn format_duration(duration: Duration) -> String {
+    if duration.as_millis() >= 1000 {
+        format!("{:.2}s", duration.as_secs_f64())
+    } else if duration.as_millis() >= 1 {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{}µs", duration.as_micros())
+    }
+}
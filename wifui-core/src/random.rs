@@ -0,0 +1,23 @@
+//! Random names and passphrases
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// Characters of generated names and passphrases, without look-alikes such as
+/// `0`/`O` and `1`/`l` so they can be typed off the screen
+const ALPHABET: &[u8] = b"abcdefghijkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Random characters from [`ALPHABET`]
+///
+/// std has no CSPRNG, but every `RandomState` carries SipHash keys seeded by
+/// the OS, which is unpredictable enough for a passphrase that lives an hour.
+pub fn random_string(len: usize) -> String {
+    let state = RandomState::new();
+    (0..len)
+        .map(|i| {
+            let mut hasher = state.build_hasher();
+            hasher.write_usize(i);
+            ALPHABET[(hasher.finish() % ALPHABET.len() as u64) as usize] as char
+        })
+        .collect()
+}
@@ -0,0 +1,325 @@
+//! Platform abstraction
+//!
+//! Each supported OS provides a [`WifiBackend`]; the free functions below
+//! forward to the backend compiled for the current target so the rest of the
+//! app stays platform-agnostic.
+
+use crate::error::WifiResult;
+use crate::logging;
+use crate::wifi::types::{
+    EnterpriseCredentials, HotspotStatus, InterfaceInfo, InterfaceStats, IpInfo, ProfileOptions,
+    RadioState, WifiInfo,
+};
+use secrecy::SecretString;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+#[cfg(windows)]
+const PLATFORM: crate::wifi::win32::Win32Backend = crate::wifi::win32::Win32Backend;
+#[cfg(target_os = "linux")]
+const PLATFORM: crate::wifi::linux::NetworkManagerBackend =
+    crate::wifi::linux::NetworkManagerBackend;
+
+/// WiFi operations a platform must provide
+///
+/// Saved profiles are looked up by SSID or by profile name, which differ once
+/// a profile has been renamed.
+pub trait WifiBackend {
+    /// Ask the adapter to rescan; results show up in later `get_wifi_networks` calls
+    fn scan_networks(&self) -> WifiResult<()>;
+
+    /// Visible networks in no particular order; the list sorts them for display
+    fn get_wifi_networks(&self) -> WifiResult<Vec<WifiInfo>>;
+
+    fn get_connected_ssid(&self) -> WifiResult<Option<String>>;
+
+    /// Connect using an existing saved profile
+    fn connect_profile(&self, ssid: &str) -> WifiResult<()>;
+
+    /// Connect using a saved profile, restricted to a single access point
+    fn connect_profile_bssid(&self, ssid: &str, bssid: [u8; 6]) -> WifiResult<()>;
+
+    /// Save a profile with the given credentials and connect to it
+    fn connect_with_password(
+        &self,
+        ssid: &str,
+        password: &SecretString,
+        auth: &str,
+        cipher: &str,
+        options: ProfileOptions,
+    ) -> WifiResult<()>;
+
+    /// Save an 802.1X profile with the given credentials and connect to it
+    fn connect_enterprise(
+        &self,
+        ssid: &str,
+        auth: &str,
+        credentials: &EnterpriseCredentials,
+        options: ProfileOptions,
+    ) -> WifiResult<()>;
+
+    /// Save a profile for an open network and connect to it
+    fn connect_open(&self, ssid: &str, options: ProfileOptions) -> WifiResult<()>;
+
+    /// Join a network through WPS push-button; blocks until the router's
+    /// button is pressed or the attempt times out
+    fn connect_wps(&self, ssid: &str) -> WifiResult<()>;
+
+    fn disconnect(&self) -> WifiResult<()>;
+
+    /// Disconnect and wait for it to complete, with a delay after
+    fn disconnect_and_wait(&self) -> WifiResult<()> {
+        logging::timed("disconnect_and_wait", || {
+            self.disconnect()?;
+
+            // Wait for disconnect to complete by polling connection status
+            let max_wait = std::time::Duration::from_secs(5);
+            let start = std::time::Instant::now();
+
+            while start.elapsed() < max_wait {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                match self.get_connected_ssid() {
+                    Ok(None) => break,       // Successfully disconnected
+                    Ok(Some(_)) => continue, // Still connected, keep waiting
+                    Err(_) => break,         // Error checking, proceed anyway
+                }
+            }
+
+            // Add a small delay after disconnect to ensure clean state
+            std::thread::sleep(std::time::Duration::from_millis(
+                crate::config::DISCONNECT_DELAY_MS,
+            ));
+
+            Ok(())
+        })
+    }
+
+    /// Saved profile names, most preferred first
+    fn get_saved_profiles(&self) -> WifiResult<Vec<String>>;
+
+    /// Name of the saved profile for an SSID, which differs from the SSID
+    /// once the profile is renamed
+    fn saved_profile_name(&self, ssid: &str) -> WifiResult<Option<String>>;
+
+    /// Saved profile as a WLAN profile XML document, key included
+    fn export_profile(&self, ssid: &str) -> WifiResult<String>;
+
+    /// Validate and save a WLAN profile XML document, returning the profile
+    /// name. A profile of the same name is only replaced with `overwrite`,
+    /// else this fails with `WifiError::ProfileExists`.
+    fn import_profile(&self, xml: &str, overwrite: bool) -> WifiResult<String>;
+
+    /// Move a saved profile to a position in the preference order (0 = first)
+    fn set_profile_position(&self, ssid: &str, position: u32) -> WifiResult<()>;
+
+    fn set_auto_connect(&self, ssid: &str, enable: bool) -> WifiResult<()>;
+
+    /// Whether a saved profile connects with a randomized hardware address
+    fn mac_randomization(&self, ssid: &str) -> WifiResult<bool>;
+
+    /// Turn hardware address randomization on or off for a saved profile;
+    /// takes effect on the next connection
+    fn set_mac_randomization(&self, ssid: &str, enable: bool) -> WifiResult<()>;
+
+    /// Whether a saved profile is marked as a metered connection
+    fn is_metered(&self, ssid: &str) -> WifiResult<bool>;
+
+    /// Mark a saved profile as metered or unrestricted
+    fn set_metered(&self, ssid: &str, metered: bool) -> WifiResult<()>;
+
+    fn forget_network(&self, ssid: &str) -> WifiResult<()>;
+
+    /// Give a saved profile a new name; the network it connects to is unchanged
+    fn rename_profile(&self, name: &str, new_name: &str) -> WifiResult<()>;
+
+    /// The stored passphrase of a saved profile, if it has one
+    fn get_wifi_password(&self, ssid: &str) -> WifiResult<Option<SecretString>>;
+
+    fn list_interfaces(&self) -> WifiResult<Vec<InterfaceInfo>>;
+
+    /// Direct all subsequent calls at the given interface
+    fn set_active_interface(&self, interface: &InterfaceInfo);
+
+    fn hotspot_status(&self) -> WifiResult<HotspotStatus>;
+
+    /// Configure the hotspot and start sharing this device's connection
+    fn start_hotspot(&self, ssid: &str, passphrase: &SecretString) -> WifiResult<()>;
+
+    fn stop_hotspot(&self) -> WifiResult<()>;
+
+    /// Stop the hotspot and drop its configuration, so the passphrase it was
+    /// shared with stops working
+    fn remove_hotspot(&self) -> WifiResult<()>;
+
+    /// Gateway of the WiFi connection, pinged by the latency monitor
+    fn default_gateway(&self) -> WifiResult<Option<Ipv4Addr>>;
+
+    /// Send one ICMP echo; `None` if no reply arrived within the timeout
+    fn ping(&self, addr: Ipv4Addr, timeout: Duration) -> WifiResult<Option<Duration>>;
+
+    /// Addresses, gateway, DNS servers and MAC of the connected interface
+    fn ip_info(&self) -> WifiResult<IpInfo>;
+
+    /// Frame counters of the connected interface, polled to chart throughput
+    fn interface_stats(&self) -> WifiResult<InterfaceStats>;
+
+    fn radio_state(&self) -> WifiResult<RadioState>;
+
+    /// Switch the WiFi radio on or off in software
+    fn set_radio(&self, on: bool) -> WifiResult<()>;
+}
+
+pub fn scan_networks() -> WifiResult<()> {
+    PLATFORM.scan_networks()
+}
+
+pub fn get_wifi_networks() -> WifiResult<Vec<WifiInfo>> {
+    PLATFORM.get_wifi_networks()
+}
+
+pub fn get_connected_ssid() -> WifiResult<Option<String>> {
+    PLATFORM.get_connected_ssid()
+}
+
+pub fn connect_profile(ssid: &str) -> WifiResult<()> {
+    PLATFORM.connect_profile(ssid)
+}
+
+pub fn connect_profile_bssid(ssid: &str, bssid: [u8; 6]) -> WifiResult<()> {
+    PLATFORM.connect_profile_bssid(ssid, bssid)
+}
+
+pub fn connect_with_password(
+    ssid: &str,
+    password: &SecretString,
+    auth: &str,
+    cipher: &str,
+    options: ProfileOptions,
+) -> WifiResult<()> {
+    PLATFORM.connect_with_password(ssid, password, auth, cipher, options)
+}
+
+pub fn connect_enterprise(
+    ssid: &str,
+    auth: &str,
+    credentials: &EnterpriseCredentials,
+    options: ProfileOptions,
+) -> WifiResult<()> {
+    PLATFORM.connect_enterprise(ssid, auth, credentials, options)
+}
+
+pub fn connect_open(ssid: &str, options: ProfileOptions) -> WifiResult<()> {
+    PLATFORM.connect_open(ssid, options)
+}
+
+pub fn connect_wps(ssid: &str) -> WifiResult<()> {
+    PLATFORM.connect_wps(ssid)
+}
+
+pub fn disconnect() -> WifiResult<()> {
+    PLATFORM.disconnect()
+}
+
+pub fn disconnect_and_wait() -> WifiResult<()> {
+    PLATFORM.disconnect_and_wait()
+}
+
+pub fn get_saved_profiles() -> WifiResult<Vec<String>> {
+    PLATFORM.get_saved_profiles()
+}
+
+pub fn saved_profile_name(ssid: &str) -> WifiResult<Option<String>> {
+    PLATFORM.saved_profile_name(ssid)
+}
+
+pub fn export_profile(ssid: &str) -> WifiResult<String> {
+    PLATFORM.export_profile(ssid)
+}
+
+pub fn import_profile(xml: &str, overwrite: bool) -> WifiResult<String> {
+    PLATFORM.import_profile(xml, overwrite)
+}
+
+pub fn set_profile_position(ssid: &str, position: u32) -> WifiResult<()> {
+    PLATFORM.set_profile_position(ssid, position)
+}
+
+pub fn set_auto_connect(ssid: &str, enable: bool) -> WifiResult<()> {
+    PLATFORM.set_auto_connect(ssid, enable)
+}
+
+pub fn is_metered(ssid: &str) -> WifiResult<bool> {
+    PLATFORM.is_metered(ssid)
+}
+
+pub fn set_metered(ssid: &str, metered: bool) -> WifiResult<()> {
+    PLATFORM.set_metered(ssid, metered)
+}
+
+pub fn mac_randomization(ssid: &str) -> WifiResult<bool> {
+    PLATFORM.mac_randomization(ssid)
+}
+
+pub fn set_mac_randomization(ssid: &str, enable: bool) -> WifiResult<()> {
+    PLATFORM.set_mac_randomization(ssid, enable)
+}
+
+pub fn forget_network(ssid: &str) -> WifiResult<()> {
+    PLATFORM.forget_network(ssid)
+}
+
+pub fn rename_profile(name: &str, new_name: &str) -> WifiResult<()> {
+    PLATFORM.rename_profile(name, new_name)
+}
+
+pub fn get_wifi_password(ssid: &str) -> WifiResult<Option<SecretString>> {
+    PLATFORM.get_wifi_password(ssid)
+}
+
+pub fn list_interfaces() -> WifiResult<Vec<InterfaceInfo>> {
+    PLATFORM.list_interfaces()
+}
+
+pub fn set_active_interface(interface: &InterfaceInfo) {
+    PLATFORM.set_active_interface(interface)
+}
+
+pub fn hotspot_status() -> WifiResult<HotspotStatus> {
+    PLATFORM.hotspot_status()
+}
+
+pub fn start_hotspot(ssid: &str, passphrase: &SecretString) -> WifiResult<()> {
+    PLATFORM.start_hotspot(ssid, passphrase)
+}
+
+pub fn stop_hotspot() -> WifiResult<()> {
+    PLATFORM.stop_hotspot()
+}
+
+pub fn remove_hotspot() -> WifiResult<()> {
+    PLATFORM.remove_hotspot()
+}
+
+pub fn default_gateway() -> WifiResult<Option<Ipv4Addr>> {
+    PLATFORM.default_gateway()
+}
+
+pub fn ping(addr: Ipv4Addr, timeout: Duration) -> WifiResult<Option<Duration>> {
+    PLATFORM.ping(addr, timeout)
+}
+
+pub fn ip_info() -> WifiResult<IpInfo> {
+    PLATFORM.ip_info()
+}
+
+pub fn interface_stats() -> WifiResult<InterfaceStats> {
+    PLATFORM.interface_stats()
+}
+
+pub fn radio_state() -> WifiResult<RadioState> {
+    PLATFORM.radio_state()
+}
+
+pub fn set_radio(on: bool) -> WifiResult<()> {
+    PLATFORM.set_radio(on)
+}
@@ -0,0 +1,595 @@
+use crate::config;
+use crate::error::{WifiError, WifiResult};
+use crate::logging;
+use crate::wifi::handle::WifiManager;
+use crate::wifi::ie::parse_capabilities;
+use crate::wifi::profile::{
+    get_saved_profiles, is_profile_auto_connect, profile_authentication, resolve_profile_name,
+};
+use crate::wifi::profile_xml::{create_eap_user_xml, create_profile_xml};
+use crate::wifi::types::{
+    Band, BssInfo, EnterpriseCredentials, ProfileOptions, WifiInfo, format_bssid, security_family,
+};
+use secrecy::SecretString;
+use std::collections::HashMap;
+use windows::{
+    Win32::{
+        Foundation::ERROR_SUCCESS,
+        NetworkManagement::{
+            Ndis::{NDIS_OBJECT_HEADER, NDIS_OBJECT_TYPE_DEFAULT},
+            WiFi::*,
+        },
+    },
+    core::PCWSTR,
+};
+
+/// Connect using an existing saved profile
+pub fn connect_profile(ssid: &str) -> WifiResult<()> {
+    logging::timed("connect_profile", || connect_profile_to(ssid, None))
+}
+
+/// Connect using an existing saved profile, restricted to a single access point
+pub fn connect_profile_bssid(ssid: &str, bssid: [u8; 6]) -> WifiResult<()> {
+    logging::timed("connect_profile_bssid", || {
+        connect_profile_to(ssid, Some(bssid))
+    })
+}
+
+fn connect_profile_to(ssid: &str, bssid: Option<[u8; 6]>) -> WifiResult<()> {
+    let ssid = resolve_profile_name(ssid);
+    let manager = WifiManager::shared()?;
+    let handle = manager.handle();
+    let guid = manager.interface_guid()?;
+
+    unsafe {
+        let ssid_wide: Vec<u16> = ssid.encode_utf16().chain(std::iter::once(0)).collect();
+        let p_profile_name = PCWSTR(ssid_wide.as_ptr());
+
+        let mut bssid_list = bssid.map(|bssid| DOT11_BSSID_LIST {
+            Header: NDIS_OBJECT_HEADER {
+                Type: NDIS_OBJECT_TYPE_DEFAULT as u8,
+                Revision: DOT11_BSSID_LIST_REVISION_1 as u8,
+                Size: std::mem::size_of::<DOT11_BSSID_LIST>() as u16,
+            },
+            uNumOfEntries: 1,
+            uTotalNumOfEntries: 1,
+            BSSIDs: bssid,
+        });
+
+        let connection_params = WLAN_CONNECTION_PARAMETERS {
+            wlanConnectionMode: wlan_connection_mode_profile,
+            strProfile: p_profile_name,
+            pDot11Ssid: std::ptr::null_mut(),
+            pDesiredBssidList: bssid_list
+                .as_mut()
+                .map_or(std::ptr::null_mut(), |list| list as *mut _),
+            dot11BssType: dot11_BSS_type_infrastructure,
+            dwFlags: 0,
+        };
+
+        let result = WlanConnect(handle.as_raw(), &guid, &connection_params, None);
+
+        if result != ERROR_SUCCESS.0 {
+            return Err(WifiError::ConnectionFailed { code: result });
+        }
+    }
+    Ok(())
+}
+
+fn set_profile(manager: &WifiManager, xml: &str) -> WifiResult<()> {
+    let guid = manager.interface_guid()?;
+    unsafe {
+        let xml_wide: Vec<u16> = xml.encode_utf16().chain(std::iter::once(0)).collect();
+        let p_profile_xml = PCWSTR(xml_wide.as_ptr());
+
+        let mut reason_code = 0;
+        let result = WlanSetProfile(
+            manager.handle().as_raw(),
+            &guid,
+            0,
+            p_profile_xml,
+            None,
+            true,
+            None,
+            &mut reason_code,
+        );
+
+        if result != ERROR_SUCCESS.0 {
+            return Err(WifiError::ProfileAddFailed {
+                code: result,
+                reason: reason_code,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Connect with a password (creates a profile then connects)
+pub fn connect_with_password(
+    ssid: &str,
+    password: &SecretString,
+    auth: &str,
+    cipher: &str,
+    options: ProfileOptions,
+) -> WifiResult<()> {
+    logging::timed("connect_with_password", || {
+        let profile_xml = create_profile_xml(ssid, auth, cipher, Some(password), None, options);
+        set_profile(WifiManager::shared()?, &profile_xml)?;
+
+        // Give the system a moment to register the profile
+        std::thread::sleep(std::time::Duration::from_millis(
+            config::PROFILE_REGISTRATION_DELAY_MS,
+        ));
+
+        connect_profile(ssid)
+    })
+}
+
+/// Connect to a WPA2/WPA3 Enterprise network (creates an 802.1X profile,
+/// stores the user's credentials for it, then connects)
+pub fn connect_enterprise(
+    ssid: &str,
+    auth: &str,
+    credentials: &EnterpriseCredentials,
+    options: ProfileOptions,
+) -> WifiResult<()> {
+    logging::timed("connect_enterprise", || {
+        let profile_xml = create_profile_xml(ssid, auth, "AES", None, Some(credentials), options);
+        let manager = WifiManager::shared()?;
+        set_profile(manager, &profile_xml)?;
+
+        let guid = manager.interface_guid()?;
+        unsafe {
+            let ssid_wide: Vec<u16> = ssid.encode_utf16().chain(std::iter::once(0)).collect();
+            let user_xml = create_eap_user_xml(credentials);
+            let user_xml_wide: Vec<u16> =
+                user_xml.encode_utf16().chain(std::iter::once(0)).collect();
+
+            let result = WlanSetProfileEapXmlUserData(
+                manager.handle().as_raw(),
+                &guid,
+                PCWSTR(ssid_wide.as_ptr()),
+                WLAN_SET_EAPHOST_FLAGS(0),
+                PCWSTR(user_xml_wide.as_ptr()),
+                None,
+            );
+
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::EapCredentialsFailed { code: result });
+            }
+        }
+
+        // Give the system a moment to register the profile
+        std::thread::sleep(std::time::Duration::from_millis(
+            config::PROFILE_REGISTRATION_DELAY_MS,
+        ));
+
+        connect_profile(ssid)
+    })
+}
+
+/// Connect to an open (unsecured) network
+pub fn connect_open(ssid: &str, options: ProfileOptions) -> WifiResult<()> {
+    logging::timed("connect_open", || {
+        let profile_xml = create_profile_xml(ssid, "Open", "None", None, None, options);
+        set_profile(WifiManager::shared()?, &profile_xml)?;
+
+        // Give the system a moment to register the profile
+        std::thread::sleep(std::time::Duration::from_millis(
+            config::OPEN_PROFILE_REGISTRATION_DELAY_MS,
+        ));
+
+        connect_profile(ssid)
+    })
+}
+
+/// Disconnect from the current network
+pub fn disconnect() -> WifiResult<()> {
+    logging::timed("disconnect", || {
+        let manager = WifiManager::shared()?;
+        let handle = manager.handle();
+        let guid = manager.interface_guid()?;
+
+        unsafe {
+            let result = WlanDisconnect(handle.as_raw(), &guid, None);
+
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::DisconnectFailed { code: result });
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Get the currently connected SSID, if any
+pub fn get_connected_ssid() -> WifiResult<Option<String>> {
+    logging::timed("get_connected_ssid", || {
+        let manager = WifiManager::shared()?;
+        let handle = manager.handle();
+        let guid = manager.interface_guid()?;
+
+        let mut connected_ssid = None;
+
+        unsafe {
+            let mut data_size = 0;
+            let mut data_ptr: *mut std::ffi::c_void = std::ptr::null_mut();
+            let mut opcode_value_type = wlan_opcode_value_type_invalid;
+
+            let result = WlanQueryInterface(
+                handle.as_raw(),
+                &guid,
+                wlan_intf_opcode_current_connection,
+                None,
+                &mut data_size,
+                &mut data_ptr,
+                Some(&mut opcode_value_type),
+            );
+
+            if result == ERROR_SUCCESS.0 {
+                let connection_attributes = &*(data_ptr as *const WLAN_CONNECTION_ATTRIBUTES);
+                if connection_attributes.isState == wlan_interface_state_connected {
+                    let ssid_len = connection_attributes
+                        .wlanAssociationAttributes
+                        .dot11Ssid
+                        .uSSIDLength as usize;
+                    let ssid_bytes = &connection_attributes
+                        .wlanAssociationAttributes
+                        .dot11Ssid
+                        .ucSSID[..ssid_len];
+                    connected_ssid = Some(String::from_utf8_lossy(ssid_bytes).to_string());
+                }
+                WlanFreeMemory(data_ptr);
+            }
+        }
+
+        Ok(connected_ssid)
+    })
+}
+
+/// Get list of available WiFi networks
+#[allow(non_upper_case_globals)]
+pub fn get_wifi_networks() -> WifiResult<Vec<WifiInfo>> {
+    logging::timed("get_wifi_networks", || {
+        let manager = WifiManager::shared()?;
+        let handle = manager.handle();
+        let guid = manager.interface_guid()?;
+
+        let wifi_list: Vec<WifiInfo>;
+
+        unsafe {
+            let mut available_network_list: *mut WLAN_AVAILABLE_NETWORK_LIST = std::ptr::null_mut();
+            let result = WlanGetAvailableNetworkList(
+                handle.as_raw(),
+                &guid,
+                WLAN_AVAILABLE_NETWORK_INCLUDE_ALL_ADHOC_PROFILES
+                    | WLAN_AVAILABLE_NETWORK_INCLUDE_ALL_MANUAL_HIDDEN_PROFILES,
+                None,
+                &mut available_network_list,
+            );
+
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::NetworkListFailed { code: result });
+            }
+
+            // Get current connection info for link speed
+            let mut current_connection: Option<(String, u32, [u8; 6])> = None;
+            let mut current_rssi = None;
+            let mut data_size = 0;
+            let mut data_ptr: *mut std::ffi::c_void = std::ptr::null_mut();
+            let mut opcode_value_type = wlan_opcode_value_type_invalid;
+
+            let result_query = WlanQueryInterface(
+                handle.as_raw(),
+                &guid,
+                wlan_intf_opcode_current_connection,
+                None,
+                &mut data_size,
+                &mut data_ptr,
+                Some(&mut opcode_value_type),
+            );
+
+            if result_query == ERROR_SUCCESS.0 {
+                let conn = &*(data_ptr as *const WLAN_CONNECTION_ATTRIBUTES);
+                if conn.isState == wlan_interface_state_connected {
+                    let ssid_len = conn.wlanAssociationAttributes.dot11Ssid.uSSIDLength as usize;
+                    let ssid_bytes = &conn.wlanAssociationAttributes.dot11Ssid.ucSSID[..ssid_len];
+                    let ssid = String::from_utf8_lossy(ssid_bytes).to_string();
+                    let tx_rate = conn.wlanAssociationAttributes.ulTxRate;
+                    let bssid = conn.wlanAssociationAttributes.dot11Bssid;
+                    current_connection = Some((ssid, tx_rate, bssid));
+                }
+                WlanFreeMemory(data_ptr);
+            }
+
+            // The scan's RSSI is as old as the scan; this one is current
+            if current_connection.is_some() {
+                let mut data_size = 0;
+                let mut data_ptr: *mut std::ffi::c_void = std::ptr::null_mut();
+                let result = WlanQueryInterface(
+                    handle.as_raw(),
+                    &guid,
+                    wlan_intf_opcode_rssi,
+                    None,
+                    &mut data_size,
+                    &mut data_ptr,
+                    None,
+                );
+                if result == ERROR_SUCCESS.0 {
+                    current_rssi = Some(*(data_ptr as *const i32));
+                    WlanFreeMemory(data_ptr);
+                }
+            }
+
+            // Get BSS List to find channel, frequency and rate
+            let mut bss_list: *mut WLAN_BSS_LIST = std::ptr::null_mut();
+            let result_bss = WlanGetNetworkBssList(
+                handle.as_raw(),
+                &guid,
+                None,
+                dot11_BSS_type_any,
+                false,
+                None,
+                &mut bss_list,
+            );
+
+            let mut bss_entries: &[WLAN_BSS_ENTRY] = &[];
+            if result_bss == ERROR_SUCCESS.0 && !bss_list.is_null() {
+                let num_bss = (*bss_list).dwNumberOfItems;
+                bss_entries = std::slice::from_raw_parts(
+                    (*bss_list).wlanBssEntries.as_ptr(),
+                    num_bss as usize,
+                );
+            }
+
+            let num_items = (*available_network_list).dwNumberOfItems;
+            let items = std::slice::from_raw_parts(
+                (*available_network_list).Network.as_ptr(),
+                num_items as usize,
+            );
+
+            let mut wifi_map: HashMap<(String, String), WifiInfo> = HashMap::new();
+
+            for item in items {
+                let ssid_len = item.dot11Ssid.uSSIDLength as usize;
+                let ssid_bytes = &item.dot11Ssid.ucSSID[..ssid_len];
+                // Some access points hide their SSID by sending it as all zeros
+                let ssid = if ssid_bytes.iter().all(|&b| b == 0) {
+                    String::new()
+                } else {
+                    String::from_utf8_lossy(ssid_bytes).to_string()
+                };
+
+                // Collect every BSS entry advertising this SSID
+                let matching_bss: Vec<&WLAN_BSS_ENTRY> = bss_entries
+                    .iter()
+                    .filter(|bss| {
+                        let bss_ssid_len = bss.dot11Ssid.uSSIDLength as usize;
+                        if bss_ssid_len != ssid_len {
+                            return false;
+                        }
+                        &bss.dot11Ssid.ucSSID[..bss_ssid_len] == ssid_bytes
+                    })
+                    .collect();
+
+                let bss_list: Vec<BssInfo> = matching_bss
+                    .iter()
+                    .map(|bss| {
+                        // The IE blob is stored ulIeOffset bytes past the start of the entry
+                        let ies = std::slice::from_raw_parts(
+                            (*bss as *const WLAN_BSS_ENTRY as *const u8)
+                                .add(bss.ulIeOffset as usize),
+                            bss.ulIeSize as usize,
+                        );
+                        let is_6ghz =
+                            Band::from_frequency(bss.ulChCenterFrequency) == Some(Band::Ghz6);
+                        BssInfo {
+                            bssid: bss.dot11Bssid,
+                            rssi: bss.lRssi,
+                            link_quality: bss.uLinkQuality.min(100) as u8,
+                            frequency: bss.ulChCenterFrequency,
+                            channel: frequency_to_channel(bss.ulChCenterFrequency),
+                            beacon_interval: bss.usBeaconPeriod,
+                            capabilities: parse_capabilities(ies, is_6ghz),
+                        }
+                    })
+                    .collect();
+
+                // Find best BSS entry for this SSID
+                let best_bss = matching_bss.iter().max_by_key(|bss| bss.lRssi);
+
+                let (frequency, channel) = if let Some(bss) = best_bss {
+                    let freq = bss.ulChCenterFrequency;
+                    (freq, frequency_to_channel(freq))
+                } else {
+                    (0, 0)
+                };
+
+                let mut link_speed = None;
+                let mut rssi = None;
+                let mut is_connected = false;
+                let mut connected_bssid = None;
+                if let Some((ref conn_ssid, conn_rate, conn_bssid)) = current_connection
+                    && *conn_ssid == ssid
+                {
+                    link_speed = Some(conn_rate / 1000); // Kbps to Mbps
+                    rssi = current_rssi;
+                    is_connected = true;
+                    connected_bssid = Some(conn_bssid);
+                }
+
+                let authentication = match item.dot11DefaultAuthAlgorithm {
+                    DOT11_AUTH_ALGO_80211_OPEN => "Open",
+                    DOT11_AUTH_ALGO_80211_SHARED_KEY => "Shared",
+                    DOT11_AUTH_ALGO_WPA => "WPA",
+                    DOT11_AUTH_ALGO_WPA_PSK => "WPA-PSK",
+                    DOT11_AUTH_ALGO_WPA_NONE => "WPA-None",
+                    DOT11_AUTH_ALGO_RSNA => "WPA2",
+                    DOT11_AUTH_ALGO_RSNA_PSK => "WPA2-PSK",
+                    DOT11_AUTH_ALGO_WPA3 => "WPA3",
+                    DOT11_AUTH_ALGO_WPA3_SAE => "WPA3-SAE",
+                    _ => "Unknown",
+                }
+                .to_string();
+
+                let encryption = match item.dot11DefaultCipherAlgorithm {
+                    DOT11_CIPHER_ALGO_NONE => "None",
+                    DOT11_CIPHER_ALGO_WEP40 => "WEP",
+                    DOT11_CIPHER_ALGO_TKIP => "TKIP",
+                    DOT11_CIPHER_ALGO_CCMP => "AES",
+                    DOT11_CIPHER_ALGO_WEP104 => "WEP",
+                    DOT11_CIPHER_ALGO_WPA_USE_GROUP => "WPA-Group",
+                    DOT11_CIPHER_ALGO_GCMP => "GCMP",
+                    _ => "Unknown",
+                }
+                .to_string();
+
+                let is_saved = (item.dwFlags & WLAN_AVAILABLE_NETWORK_HAS_PROFILE) != 0;
+                // Profiles are named after their SSID unless they were renamed
+                let profile_name = String::from_utf16_lossy(&item.strProfileName)
+                    .trim_matches(char::from(0))
+                    .to_string();
+                let mut auto_connect = false;
+                if is_saved {
+                    let name = if profile_name.is_empty() {
+                        &ssid
+                    } else {
+                        &profile_name
+                    };
+                    auto_connect = is_profile_auto_connect(handle, &guid, name);
+                }
+
+                let phy_types = std::slice::from_raw_parts(
+                    item.dot11PhyTypes.as_ptr(),
+                    item.uNumberOfPhyTypes as usize,
+                );
+
+                let phy_type = if let Some(phy) = phy_types.first() {
+                    match *phy {
+                        dot11_phy_type_ofdm => "802.11a",
+                        dot11_phy_type_hrdsss => "802.11b",
+                        dot11_phy_type_erp => "802.11g",
+                        dot11_phy_type_ht => "802.11n (Wi-Fi 4)",
+                        dot11_phy_type_vht => "802.11ac (Wi-Fi 5)",
+                        dot11_phy_type_he => "802.11ax (Wi-Fi 6)",
+                        dot11_phy_type_eht => "802.11be (Wi-Fi 7)",
+                        _ => "Legacy/Unknown",
+                    }
+                    .to_string()
+                } else {
+                    "Unknown".to_string()
+                };
+
+                let signal = item.wlanSignalQuality as u8;
+
+                let new_info = WifiInfo {
+                    ssid: ssid.clone(),
+                    authentication: authentication.clone(),
+                    encryption,
+                    signal,
+                    is_saved,
+                    is_connected,
+                    auto_connect,
+                    phy_type,
+                    channel,
+                    frequency,
+                    link_speed,
+                    rssi,
+                    bss_list,
+                    connected_bssid,
+                    profile_security: None,
+                    profile_name: (is_saved && !profile_name.is_empty() && profile_name != ssid)
+                        .then_some(profile_name),
+                };
+
+                // Hidden access points can't be told apart by SSID, so list each one
+                if new_info.is_hidden() {
+                    for bss in &new_info.bss_list {
+                        wifi_map
+                            .entry((hidden_key(&bss.bssid), String::new()))
+                            .or_insert_with(|| WifiInfo {
+                                signal: bss.link_quality,
+                                channel: bss.channel,
+                                frequency: bss.frequency,
+                                bss_list: vec![bss.clone()],
+                                ..new_info.clone()
+                            });
+                    }
+                    continue;
+                }
+
+                wifi_map
+                    .entry((ssid, authentication))
+                    .and_modify(|info| {
+                        if new_info.is_saved {
+                            info.is_saved = true;
+                        }
+                        if new_info.profile_name.is_some() {
+                            info.profile_name = new_info.profile_name.clone();
+                        }
+                        if new_info.is_connected {
+                            info.is_connected = true;
+                            info.link_speed = new_info.link_speed;
+                            info.rssi = new_info.rssi;
+                            info.connected_bssid = new_info.connected_bssid;
+                        }
+                        if new_info.signal > info.signal {
+                            info.signal = new_info.signal;
+                        }
+                    })
+                    .or_insert(new_info);
+            }
+
+            // A profile whose security no longer matches the access point isn't
+            // attached to it, so the network shows up as unsaved
+            let profiles = get_saved_profiles().unwrap_or_default();
+            let saved: Vec<String> = wifi_map
+                .values()
+                .filter(|w| w.is_saved)
+                .map(|w| w.ssid.clone())
+                .collect();
+            for info in wifi_map.values_mut() {
+                if info.is_hidden() || saved.contains(&info.ssid) || !profiles.contains(&info.ssid)
+                {
+                    continue;
+                }
+                if let Some(auth) = profile_authentication(handle, &guid, &info.ssid) {
+                    let family = security_family(&auth);
+                    if family != security_family(&info.authentication) {
+                        info.profile_security = Some(family);
+                    }
+                }
+            }
+
+            wifi_list = wifi_map.into_values().collect();
+
+            if !bss_list.is_null() {
+                WlanFreeMemory(bss_list as *mut _);
+            }
+            WlanFreeMemory(available_network_list as *mut _);
+        }
+
+        Ok(wifi_list)
+    })
+}
+
+/// Map key of a hidden access point; the NUL prefix can't appear in a listed SSID
+fn hidden_key(bssid: &[u8; 6]) -> String {
+    format!("\0{}", format_bssid(bssid))
+}
+
+/// Convert a channel center frequency (kHz) to its channel number
+fn frequency_to_channel(freq: u32) -> u32 {
+    if (2412000..=2484000).contains(&freq) {
+        if freq == 2484000 {
+            14
+        } else {
+            (freq - 2407000) / 5000
+        }
+    } else if (5000000..=5900000).contains(&freq) {
+        (freq - 5000000) / 5000
+    } else if (5925000..=7125000).contains(&freq) {
+        (freq - 5950000) / 5000
+    } else {
+        0
+    }
+}
@@ -0,0 +1,188 @@
+use crate::error::{WifiError, WifiResult};
+use crate::logging;
+use crate::wifi::types::InterfaceInfo;
+use std::sync::{Mutex, OnceLock};
+use windows::{
+    Win32::{
+        Foundation::{ERROR_SUCCESS, HANDLE},
+        NetworkManagement::WiFi::*,
+    },
+    core::GUID,
+};
+
+/// Interface chosen in the adapter picker; `None` uses the first one reported
+static ACTIVE_INTERFACE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Handle shared by every WLAN operation, opened on first use
+static MANAGER: OnceLock<WifiManager> = OnceLock::new();
+
+/// Direct all subsequent WLAN calls at the given interface
+pub fn set_active_interface(interface: &InterfaceInfo) {
+    if let Ok(mut active) = ACTIVE_INTERFACE.lock() {
+        *active = Some(interface.id.clone());
+    }
+    if let Some(manager) = MANAGER.get() {
+        manager.forget_interface();
+    }
+}
+
+/// Whether events from this interface concern the active adapter
+pub fn is_active_interface(guid: &GUID) -> bool {
+    match ACTIVE_INTERFACE.lock() {
+        Ok(active) => active.as_ref().is_none_or(|id| *id == format_guid(guid)),
+        Err(_) => true,
+    }
+}
+
+/// Opaque interface ID used by `InterfaceInfo`
+fn format_guid(guid: &GUID) -> String {
+    format!("{:?}", guid)
+}
+
+/// List all WLAN interfaces on the system
+pub fn list_interfaces() -> WifiResult<Vec<InterfaceInfo>> {
+    logging::timed("list_interfaces", || {
+        let manager = WifiManager::shared()?;
+        // Adapters may have been plugged in or removed since the GUID was resolved
+        manager.forget_interface();
+        manager.handle().interfaces()
+    })
+}
+
+/// Long-lived WLAN handle plus the GUID of the interface operations target
+///
+/// Opening a handle per call is slow and races with other handles being
+/// closed, so all operations except the event listener go through this.
+#[derive(Debug)]
+pub struct WifiManager {
+    handle: WlanHandle,
+    /// Active interface, resolved on first use after a change
+    guid: Mutex<Option<GUID>>,
+}
+
+impl WifiManager {
+    /// The process-wide manager, opening its handle if needed
+    pub fn shared() -> WifiResult<&'static Self> {
+        if let Some(manager) = MANAGER.get() {
+            return Ok(manager);
+        }
+        let manager = Self {
+            handle: WlanHandle::open()?,
+            guid: Mutex::new(None),
+        };
+        // Another thread may have won the race; its handle is kept and ours closed
+        Ok(MANAGER.get_or_init(|| manager))
+    }
+
+    pub fn handle(&self) -> &WlanHandle {
+        &self.handle
+    }
+
+    /// Get the active interface GUID, falling back to the first interface
+    pub fn interface_guid(&self) -> WifiResult<GUID> {
+        if let Some(guid) = self.guid.lock().ok().and_then(|guid| *guid) {
+            return Ok(guid);
+        }
+
+        let interfaces = self.handle.enum_interfaces()?;
+        let active = ACTIVE_INTERFACE
+            .lock()
+            .ok()
+            .and_then(|active| active.clone());
+        let guid = interfaces
+            .iter()
+            .find(|(_, info)| Some(&info.id) == active.as_ref())
+            .or(interfaces.first())
+            .map(|(guid, _)| *guid)
+            .ok_or(WifiError::NoInterface)?;
+        if let Ok(mut cached) = self.guid.lock() {
+            *cached = Some(guid);
+        }
+        Ok(guid)
+    }
+
+    /// Resolve the interface again on the next call
+    fn forget_interface(&self) {
+        if let Ok(mut guid) = self.guid.lock() {
+            *guid = None;
+        }
+    }
+}
+
+/// Safe wrapper around WLAN handle that automatically closes on drop
+#[derive(Debug)]
+pub struct WlanHandle {
+    handle: HANDLE,
+}
+
+impl WlanHandle {
+    /// Open a new WLAN handle
+    pub fn open() -> WifiResult<Self> {
+        let mut negotiated_version = 0;
+        let mut handle = HANDLE::default();
+        unsafe {
+            let result = WlanOpenHandle(2, None, &mut negotiated_version, &mut handle);
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::HandleOpenFailed { code: result });
+            }
+        }
+        Ok(Self { handle })
+    }
+
+    /// Get the raw handle for API calls
+    pub fn as_raw(&self) -> HANDLE {
+        self.handle
+    }
+
+    /// Enumerate the WLAN interfaces
+    pub fn interfaces(&self) -> WifiResult<Vec<InterfaceInfo>> {
+        Ok(self
+            .enum_interfaces()?
+            .into_iter()
+            .map(|(_, info)| info)
+            .collect())
+    }
+
+    fn enum_interfaces(&self) -> WifiResult<Vec<(GUID, InterfaceInfo)>> {
+        unsafe {
+            let mut interface_list: *mut WLAN_INTERFACE_INFO_LIST = std::ptr::null_mut();
+            let result = WlanEnumInterfaces(self.handle, None, &mut interface_list);
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::InterfaceEnumFailed { code: result });
+            }
+
+            let count = (*interface_list).dwNumberOfItems as usize;
+            let infos = std::slice::from_raw_parts((*interface_list).InterfaceInfo.as_ptr(), count);
+            let interfaces = infos
+                .iter()
+                .map(|info| {
+                    let len = info
+                        .strInterfaceDescription
+                        .iter()
+                        .position(|&c| c == 0)
+                        .unwrap_or(info.strInterfaceDescription.len());
+                    let interface = InterfaceInfo {
+                        id: format_guid(&info.InterfaceGuid),
+                        description: String::from_utf16_lossy(&info.strInterfaceDescription[..len]),
+                        is_connected: info.isState == wlan_interface_state_connected,
+                    };
+                    (info.InterfaceGuid, interface)
+                })
+                .collect();
+            WlanFreeMemory(interface_list as *mut _);
+            Ok(interfaces)
+        }
+    }
+}
+
+// The WLAN client handle may be used from any thread
+unsafe impl Send for WlanHandle {}
+unsafe impl Sync for WlanHandle {}
+
+impl Drop for WlanHandle {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = WlanCloseHandle(self.handle, None);
+        }
+    }
+}
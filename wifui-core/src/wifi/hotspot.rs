@@ -0,0 +1,130 @@
+//! Mobile hotspot control through the WinRT tethering manager
+
+use crate::error::{WifiError, WifiResult};
+use crate::wifi::types::HotspotStatus;
+use crate::{logging, random};
+use secrecy::{ExposeSecret, SecretString};
+use windows::{
+    Networking::{
+        Connectivity::NetworkInformation,
+        NetworkOperators::{
+            NetworkOperatorTetheringAccessPointConfiguration, NetworkOperatorTetheringManager,
+            NetworkOperatorTetheringOperationResult, TetheringOperationStatus,
+            TetheringOperationalState,
+        },
+    },
+    core::HSTRING,
+};
+
+/// Length of the passphrase [`remove_hotspot`] replaces a shared one with
+const REKEY_PASSPHRASE_LEN: usize = 16;
+
+fn hotspot_error(e: windows::core::Error) -> WifiError {
+    WifiError::Hotspot(e.message().to_string())
+}
+
+/// Tethering manager for the connection currently providing internet access
+fn manager() -> WifiResult<NetworkOperatorTetheringManager> {
+    let profile = NetworkInformation::GetInternetConnectionProfile()
+        .map_err(|_| WifiError::Hotspot("No internet connection to share".to_string()))?;
+    NetworkOperatorTetheringManager::CreateFromConnectionProfile(&profile).map_err(hotspot_error)
+}
+
+/// Turn a failed tethering operation into an error
+fn check_result(result: &NetworkOperatorTetheringOperationResult) -> WifiResult<()> {
+    let status = result.Status().map_err(hotspot_error)?;
+    if status == TetheringOperationStatus::Success {
+        return Ok(());
+    }
+    let message = result
+        .AdditionalErrorMessage()
+        .map(|m| m.to_string())
+        .unwrap_or_default();
+    Err(WifiError::Hotspot(if message.is_empty() {
+        format!("operation failed ({})", status.0)
+    } else {
+        message
+    }))
+}
+
+/// Get the hotspot state, SSID and connected client count
+pub fn hotspot_status() -> WifiResult<HotspotStatus> {
+    logging::timed("hotspot_status", || {
+        let manager = manager()?;
+        let config = manager
+            .GetCurrentAccessPointConfiguration()
+            .map_err(hotspot_error)?;
+        let state = manager.TetheringOperationalState().map_err(hotspot_error)?;
+        Ok(HotspotStatus {
+            active: state == TetheringOperationalState::On,
+            ssid: config.Ssid().map_err(hotspot_error)?.to_string(),
+            client_count: manager.ClientCount().ok(),
+            max_clients: manager.MaxClientCount().ok(),
+        })
+    })
+}
+
+/// Configure the access point and start tethering
+pub fn start_hotspot(ssid: &str, passphrase: &SecretString) -> WifiResult<()> {
+    logging::timed("start_hotspot", || {
+        let manager = manager()?;
+        let config =
+            NetworkOperatorTetheringAccessPointConfiguration::new().map_err(hotspot_error)?;
+        config
+            .SetSsid(&HSTRING::from(ssid))
+            .map_err(hotspot_error)?;
+        config
+            .SetPassphrase(&HSTRING::from(passphrase.expose_secret()))
+            .map_err(hotspot_error)?;
+        manager
+            .ConfigureAccessPointAsync(&config)
+            .and_then(|op| op.get())
+            .map_err(hotspot_error)?;
+        let result = manager
+            .StartTetheringAsync()
+            .and_then(|op| op.get())
+            .map_err(hotspot_error)?;
+        check_result(&result)
+    })
+}
+
+/// Stop tethering
+pub fn stop_hotspot() -> WifiResult<()> {
+    logging::timed("stop_hotspot", || {
+        let result = manager()?
+            .StopTetheringAsync()
+            .and_then(|op| op.get())
+            .map_err(hotspot_error)?;
+        check_result(&result)
+    })
+}
+
+/// Stop tethering and re-key the access point
+///
+/// Windows keeps exactly one hotspot configuration and can't delete it, so the
+/// SSID stays but the passphrase is replaced with one nobody has seen.
+pub fn remove_hotspot() -> WifiResult<()> {
+    logging::timed("remove_hotspot", || {
+        let manager = manager()?;
+        if manager.TetheringOperationalState().map_err(hotspot_error)?
+            == TetheringOperationalState::On
+        {
+            let result = manager
+                .StopTetheringAsync()
+                .and_then(|op| op.get())
+                .map_err(hotspot_error)?;
+            check_result(&result)?;
+        }
+        let config = manager
+            .GetCurrentAccessPointConfiguration()
+            .map_err(hotspot_error)?;
+        let passphrase = random::random_string(REKEY_PASSPHRASE_LEN);
+        config
+            .SetPassphrase(&HSTRING::from(passphrase))
+            .map_err(hotspot_error)?;
+        manager
+            .ConfigureAccessPointAsync(&config)
+            .and_then(|op| op.get())
+            .map_err(hotspot_error)
+    })
+}
@@ -0,0 +1,59 @@
+//! Gateway lookup and ICMP echo through the IP Helper API
+
+use crate::error::{WifiError, WifiResult};
+use crate::logging;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+use windows::Win32::NetworkManagement::IpHelper::{
+    GetBestRoute, ICMP_ECHO_REPLY, IcmpCloseHandle, IcmpCreateFile, IcmpSendEcho, MIB_IPFORWARDROW,
+};
+
+const NO_ERROR: u32 = 0;
+const IP_SUCCESS: u32 = 0;
+/// Echo payload size, the same as Windows' `ping`
+const PAYLOAD_LEN: usize = 32;
+
+/// Next hop of the route internet traffic takes
+pub fn default_gateway() -> WifiResult<Option<Ipv4Addr>> {
+    logging::timed("default_gateway", || {
+        let mut row = MIB_IPFORWARDROW::default();
+        // Any public address will do; only the chosen route matters
+        let destination = u32::from_ne_bytes(Ipv4Addr::new(1, 1, 1, 1).octets());
+        let result = unsafe { GetBestRoute(destination, None, &mut row) };
+        if result != NO_ERROR {
+            return Err(WifiError::RouteLookupFailed { code: result });
+        }
+        let next_hop = Ipv4Addr::from(row.dwForwardNextHop.to_ne_bytes());
+        Ok((!next_hop.is_unspecified()).then_some(next_hop))
+    })
+}
+
+/// Send one echo request, returning the round trip time if a reply came back
+pub fn ping(addr: Ipv4Addr, timeout: Duration) -> WifiResult<Option<Duration>> {
+    let handle =
+        unsafe { IcmpCreateFile() }.map_err(|e| WifiError::Ping(e.message().to_string()))?;
+
+    let payload = [0u8; PAYLOAD_LEN];
+    // Room for one reply plus its echoed payload and an ICMP error header
+    let mut reply = vec![0u8; size_of::<ICMP_ECHO_REPLY>() + PAYLOAD_LEN + 8];
+    let count = unsafe {
+        IcmpSendEcho(
+            handle,
+            u32::from_ne_bytes(addr.octets()),
+            payload.as_ptr().cast(),
+            PAYLOAD_LEN as u16,
+            None,
+            reply.as_mut_ptr().cast(),
+            reply.len() as u32,
+            timeout.as_millis() as u32,
+        )
+    };
+    let _ = unsafe { IcmpCloseHandle(handle) };
+
+    // No replies means the request timed out or the host is unreachable
+    if count == 0 {
+        return Ok(None);
+    }
+    let reply = unsafe { std::ptr::read_unaligned(reply.as_ptr().cast::<ICMP_ECHO_REPLY>()) };
+    Ok((reply.Status == IP_SUCCESS).then(|| Duration::from_millis(u64::from(reply.RoundTripTime))))
+}
@@ -0,0 +1,203 @@
+//! Information element parsing
+//!
+//! Extracts the PHY capabilities an access point advertises in its beacon
+//! (HT/VHT/HE/EHT elements) and estimates the theoretical maximum data rate,
+//! plus the security extras of its RSN and WPS elements.
+
+use crate::wifi::types::Generation;
+
+const IE_HT_CAPABILITIES: u8 = 45;
+const IE_RSN: u8 = 48;
+const IE_HT_OPERATION: u8 = 61;
+const IE_VHT_CAPABILITIES: u8 = 191;
+const IE_VHT_OPERATION: u8 = 192;
+const IE_VENDOR: u8 = 221;
+const IE_EXTENSION: u8 = 255;
+const EXT_HE_CAPABILITIES: u8 = 35;
+const EXT_EHT_CAPABILITIES: u8 = 108;
+/// Microsoft OUI and vendor type of the WPS element
+const WPS_VENDOR_PREFIX: [u8; 4] = [0x00, 0x50, 0xF2, 0x04];
+
+/// Protected Management Frames (802.11w) setting of an access point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pmf {
+    Disabled,
+    Optional,
+    Required,
+}
+
+impl Pmf {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Pmf::Disabled => "Off",
+            Pmf::Optional => "Optional",
+            Pmf::Required => "Required",
+        }
+    }
+}
+
+/// PHY capabilities advertised by an access point
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    pub generation: Option<Generation>,
+    pub spatial_streams: u8,
+    pub channel_width_mhz: u16,
+    /// `None` when the platform doesn't report the beacon's elements
+    pub pmf: Option<Pmf>,
+    /// Advertises WPS push-button / PIN setup; `None` when unknown
+    pub wps: Option<bool>,
+}
+
+impl Capabilities {
+    /// Theoretical maximum PHY rate in Mbps (highest MCS, shortest guard interval)
+    pub fn max_phy_rate_mbps(&self) -> Option<u32> {
+        let generation = self.generation?;
+        let streams = self.spatial_streams.max(1) as f32;
+
+        // Per-stream rates at the top MCS for each channel width
+        let per_stream = match (generation, self.channel_width_mhz) {
+            (Generation::Wifi4, 40..) => 150.0,
+            (Generation::Wifi4, _) => 72.2,
+            (Generation::Wifi5, 160..) => 866.7,
+            (Generation::Wifi5, 80) => 433.3,
+            (Generation::Wifi5, 40) => 200.0,
+            (Generation::Wifi5, _) => 86.7,
+            (Generation::Wifi6 | Generation::Wifi6E, 160..) => 1201.0,
+            (Generation::Wifi6 | Generation::Wifi6E, 80) => 600.5,
+            (Generation::Wifi6 | Generation::Wifi6E, 40) => 286.8,
+            (Generation::Wifi6 | Generation::Wifi6E, _) => 143.4,
+            (Generation::Wifi7, 320..) => 2882.4,
+            (Generation::Wifi7, 160) => 1441.2,
+            (Generation::Wifi7, 80) => 720.6,
+            (Generation::Wifi7, 40) => 344.1,
+            (Generation::Wifi7, _) => 172.1,
+        };
+
+        Some((per_stream * streams).round() as u32)
+    }
+}
+
+/// Parse the information element blob of a BSS entry
+pub fn parse_capabilities(ies: &[u8], is_6ghz: bool) -> Capabilities {
+    let mut caps = Capabilities {
+        channel_width_mhz: 20,
+        pmf: Some(Pmf::Disabled),
+        wps: Some(false),
+        ..Default::default()
+    };
+    let mut ht_streams = 0;
+    let mut vht_streams = 0;
+    let mut he_streams = 0;
+    let mut he_width = 20;
+
+    for (id, data) in elements(ies) {
+        match id {
+            IE_HT_CAPABILITIES if data.len() >= 7 => {
+                caps.generation = caps.generation.max(Some(Generation::Wifi4));
+                // Rx MCS bitmask: one byte per spatial stream
+                ht_streams = data[3..7].iter().filter(|&&b| b != 0).count() as u8;
+            }
+            IE_RSN => caps.pmf = Some(rsn_pmf(data)),
+            IE_VENDOR if data.starts_with(&WPS_VENDOR_PREFIX) => caps.wps = Some(true),
+            IE_HT_OPERATION
+                if data.len() >= 2
+                // STA channel width: any width allowed
+                && data[1] & 0x04 != 0 =>
+            {
+                caps.channel_width_mhz = caps.channel_width_mhz.max(40);
+            }
+            IE_VHT_CAPABILITIES if data.len() >= 6 => {
+                caps.generation = caps.generation.max(Some(Generation::Wifi5));
+                vht_streams = mcs_map_streams(u16::from_le_bytes([data[4], data[5]]));
+            }
+            IE_VHT_OPERATION if data.len() >= 3 => {
+                let (width, ccfs0, ccfs1) = (data[0], data[1], data[2]);
+                let mhz = match width {
+                    1 if ccfs1 != 0 && ccfs0.abs_diff(ccfs1) == 8 => 160,
+                    1 => 80,
+                    2 | 3 => 160,
+                    _ => 0,
+                };
+                caps.channel_width_mhz = caps.channel_width_mhz.max(mhz);
+            }
+            IE_EXTENSION if !data.is_empty() => match data[0] {
+                EXT_HE_CAPABILITIES if data.len() >= 20 => {
+                    caps.generation = caps.generation.max(Some(if is_6ghz {
+                        Generation::Wifi6E
+                    } else {
+                        Generation::Wifi6
+                    }));
+                    // Element ID extension (1) + MAC caps (6) precede the PHY caps
+                    let width_set = data[7] >> 1;
+                    he_width = if width_set & 0x04 != 0 {
+                        160
+                    } else if width_set & 0x02 != 0 {
+                        80
+                    } else if width_set & 0x01 != 0 {
+                        40
+                    } else {
+                        20
+                    };
+                    // Rx HE-MCS map for <= 80 MHz follows the 11 PHY cap bytes
+                    he_streams = mcs_map_streams(u16::from_le_bytes([data[18], data[19]]));
+                }
+                EXT_EHT_CAPABILITIES if data.len() >= 4 => {
+                    caps.generation = Some(Generation::Wifi7);
+                    // PHY caps start after the extension ID and 2 MAC cap bytes
+                    if is_6ghz && data[3] & 0x02 != 0 {
+                        he_width = 320;
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    // 6 GHz APs carry no HT/VHT operation elements, so fall back to HE caps there
+    if is_6ghz {
+        caps.channel_width_mhz = caps.channel_width_mhz.max(he_width);
+    }
+    caps.spatial_streams = he_streams.max(vht_streams).max(ht_streams);
+    caps
+}
+
+/// PMF bits of an RSN element's capabilities field
+fn rsn_pmf(data: &[u8]) -> Pmf {
+    // Version (2) and group cipher (4), then the counted pairwise cipher and
+    // AKM suite lists of 4 bytes per entry
+    let mut pos = 6;
+    for _ in 0..2 {
+        let Some(&[lo, hi]) = data.get(pos..pos + 2) else {
+            return Pmf::Disabled;
+        };
+        pos += 2 + 4 * usize::from(u16::from_le_bytes([lo, hi]));
+    }
+    // Bit 6: management frame protection required, bit 7: capable
+    match data.get(pos) {
+        Some(b) if b & 0x40 != 0 => Pmf::Required,
+        Some(b) if b & 0x80 != 0 => Pmf::Optional,
+        _ => Pmf::Disabled,
+    }
+}
+
+/// Iterate over (element ID, body) pairs, stopping at the first truncated element
+fn elements(mut ies: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+    std::iter::from_fn(move || {
+        let [id, len, rest @ ..] = ies else {
+            return None;
+        };
+        let len = *len as usize;
+        if rest.len() < len {
+            return None;
+        }
+        let (body, tail) = rest.split_at(len);
+        ies = tail;
+        Some((*id, body))
+    })
+}
+
+/// Count spatial streams in a VHT/HE MCS map (2 bits per stream, 3 = unsupported)
+fn mcs_map_streams(map: u16) -> u8 {
+    (0..8).filter(|&i| (map >> (i * 2)) & 0x3 != 0x3).count() as u8
+}
@@ -0,0 +1,108 @@
+//! IP configuration of the WiFi interface through GetAdaptersAddresses
+
+use crate::error::{WifiError, WifiResult};
+use crate::logging;
+use crate::wifi::handle::WifiManager;
+use crate::wifi::types::IpInfo;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use windows::{
+    Win32::{
+        Foundation::{ERROR_BUFFER_OVERFLOW, ERROR_SUCCESS},
+        NetworkManagement::IpHelper::{
+            ConvertInterfaceLuidToGuid, GAA_FLAG_INCLUDE_GATEWAYS, GAA_FLAG_SKIP_ANYCAST,
+            GAA_FLAG_SKIP_MULTICAST, GetAdaptersAddresses, IP_ADAPTER_ADDRESSES_LH,
+        },
+        Networking::WinSock::{
+            AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR_IN, SOCKADDR_IN6, SOCKET_ADDRESS,
+        },
+    },
+    core::GUID,
+};
+
+/// Addresses, gateway, DNS servers and MAC of the active WiFi interface
+pub fn ip_info() -> WifiResult<IpInfo> {
+    logging::timed("ip_info", || {
+        let interface = WifiManager::shared()?.interface_guid()?;
+        let flags = GAA_FLAG_INCLUDE_GATEWAYS | GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST;
+
+        // The adapter list can grow between the size query and the call, so retry
+        let mut size = 0u32;
+        let mut buffer: Vec<u64> = Vec::new();
+        let mut result = ERROR_BUFFER_OVERFLOW.0;
+        for _ in 0..3 {
+            // u64 elements keep the list suitably aligned
+            buffer = vec![0u64; (size as usize).div_ceil(8)];
+            let list = (!buffer.is_empty()).then(|| buffer.as_mut_ptr().cast());
+            result =
+                unsafe { GetAdaptersAddresses(AF_UNSPEC.0 as u32, flags, None, list, &mut size) };
+            if result != ERROR_BUFFER_OVERFLOW.0 {
+                break;
+            }
+        }
+        if result != ERROR_SUCCESS.0 {
+            return Err(WifiError::AdapterAddressesFailed { code: result });
+        }
+
+        let mut adapter = buffer.as_ptr().cast::<IP_ADAPTER_ADDRESSES_LH>();
+        while let Some(current) = unsafe { adapter.as_ref() } {
+            let mut guid = GUID::zeroed();
+            let converted = unsafe { ConvertInterfaceLuidToGuid(&current.Luid, &mut guid) };
+            if converted == ERROR_SUCCESS && guid == interface {
+                return Ok(unsafe { adapter_info(current) });
+            }
+            adapter = current.Next;
+        }
+        Ok(IpInfo::default())
+    })
+}
+
+unsafe fn adapter_info(adapter: &IP_ADAPTER_ADDRESSES_LH) -> IpInfo {
+    let mut info = IpInfo::default();
+    if adapter.PhysicalAddressLength == 6 {
+        info.mac = adapter.PhysicalAddress[..6].try_into().ok();
+    }
+
+    let mut unicast = adapter.FirstUnicastAddress;
+    while let Some(entry) = unsafe { unicast.as_ref() } {
+        match unsafe { socket_addr(&entry.Address) } {
+            Some(IpAddr::V4(addr)) if info.ipv4.is_none() => {
+                info.ipv4 = Some((addr, entry.OnLinkPrefixLength));
+            }
+            Some(IpAddr::V6(addr)) => info.ipv6.push(addr),
+            _ => {}
+        }
+        unicast = entry.Next;
+    }
+
+    // Prefer the IPv4 gateway, which is what most networks route through
+    let mut gateway = adapter.FirstGatewayAddress;
+    while let Some(entry) = unsafe { gateway.as_ref() } {
+        if let Some(addr) = unsafe { socket_addr(&entry.Address) }
+            && !info.gateway.is_some_and(|g| g.is_ipv4())
+        {
+            info.gateway = Some(addr);
+        }
+        gateway = entry.Next;
+    }
+
+    let mut dns = adapter.FirstDnsServerAddress;
+    while let Some(entry) = unsafe { dns.as_ref() } {
+        info.dns.extend(unsafe { socket_addr(&entry.Address) });
+        dns = entry.Next;
+    }
+    info
+}
+
+unsafe fn socket_addr(address: &SOCKET_ADDRESS) -> Option<IpAddr> {
+    let family = unsafe { address.lpSockaddr.as_ref()? }.sa_family;
+    if family == AF_INET {
+        let addr = unsafe { &*address.lpSockaddr.cast::<SOCKADDR_IN>() };
+        let octets = unsafe { addr.sin_addr.S_un.S_addr }.to_ne_bytes();
+        Some(IpAddr::V4(Ipv4Addr::from(octets)))
+    } else if family == AF_INET6 {
+        let addr = unsafe { &*address.lpSockaddr.cast::<SOCKADDR_IN6>() };
+        Some(IpAddr::V6(Ipv6Addr::from(unsafe { addr.sin6_addr.u.Byte })))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,1217 @@
+//! Linux backend driving NetworkManager through `nmcli`
+//!
+//! Connection profiles are looked up by name, which NetworkManager sets to the
+//! SSID when it creates them, matching how the Windows backend names profiles.
+//! A connection that was renamed is found by its SSID instead.
+
+use crate::error::{WRONG_PASSWORD_REASON, WifiError, WifiResult};
+use crate::logging;
+use crate::wifi::backend::WifiBackend;
+use crate::wifi::profile_xml::{create_profile_xml, parse_profile_xml};
+use crate::wifi::types::{
+    BssInfo, ConnectionEvent, EapMethod, EnterpriseCredentials, HotspotStatus, InterfaceInfo,
+    InterfaceStats, IpInfo, ProfileOptions, RadioState, WifiInfo, format_bssid, parse_bssid,
+};
+use secrecy::{ExposeSecret, SecretString};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Device chosen in the adapter picker; `None` lets NetworkManager decide
+static ACTIVE_DEVICE: Mutex<Option<String>> = Mutex::new(None);
+
+/// SSIDs of saved connections by UUID, which rarely change, so each is only
+/// looked up once
+static CONNECTION_SSIDS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+const WIFI_CONNECTION_TYPE: &str = "802-11-wireless";
+
+/// `NM_DEVICE_STATE_REASON_NO_SECRETS`
+const NM_REASON_NO_SECRETS: u32 = 7;
+
+/// Name of the hotspot's connection profile, the one `nmcli device wifi
+/// hotspot` would create
+const HOTSPOT_CONNECTION: &str = "Hotspot";
+
+/// Secret properties, as `nmcli connection edit` names them
+const PSK: &str = "802-11-wireless-security.psk";
+const WEP_KEY: &str = "802-11-wireless-security.wep-key0";
+const EAP_PASSWORD: &str = "802-1x.password";
+
+pub struct NetworkManagerBackend;
+
+impl WifiBackend for NetworkManagerBackend {
+    fn scan_networks(&self) -> WifiResult<()> {
+        logging::timed("scan_networks", || {
+            nmcli_on_device(&["device", "wifi", "rescan"]).map(|_| ())
+        })
+    }
+
+    fn get_wifi_networks(&self) -> WifiResult<Vec<WifiInfo>> {
+        logging::timed("get_wifi_networks", get_wifi_networks)
+    }
+
+    fn get_connected_ssid(&self) -> WifiResult<Option<String>> {
+        logging::timed("get_connected_ssid", get_connected_ssid)
+    }
+
+    fn connect_profile(&self, ssid: &str) -> WifiResult<()> {
+        logging::timed("connect_profile", || {
+            let ssid = &connection_id(ssid);
+            nmcli_on_device(&["connection", "up", "id", ssid]).map(|_| ())
+        })
+    }
+
+    fn connect_profile_bssid(&self, ssid: &str, bssid: [u8; 6]) -> WifiResult<()> {
+        logging::timed("connect_profile_bssid", || {
+            let ssid = &connection_id(ssid);
+            let bssid = format_bssid(&bssid);
+            nmcli_on_device(&["connection", "up", "id", ssid, "ap", &bssid]).map(|_| ())
+        })
+    }
+
+    fn connect_with_password(
+        &self,
+        ssid: &str,
+        password: &SecretString,
+        auth: &str,
+        _cipher: &str,
+        options: ProfileOptions,
+    ) -> WifiResult<()> {
+        logging::timed("connect_with_password", || {
+            // `device wifi connect` only takes the password on its command
+            // line, so the connection is added with its security spelled out
+            // and the key stored separately
+            let (mut settings, key) = match auth {
+                "Shared" | "WEP" => (vec!["wifi-sec.key-mgmt", "none"], WEP_KEY),
+                "WPA3-SAE" => (vec!["wifi-sec.key-mgmt", "sae"], PSK),
+                _ => (vec!["wifi-sec.key-mgmt", "wpa-psk"], PSK),
+            };
+            if options.require_pmf && matches!(auth, "WPA2-PSK" | "WPA3-SAE") {
+                settings.extend(["wifi-sec.pmf", "required"]);
+            }
+            add_and_activate(ssid, &settings, &[(key, password)], options)
+        })
+    }
+
+    fn connect_enterprise(
+        &self,
+        ssid: &str,
+        auth: &str,
+        credentials: &EnterpriseCredentials,
+        options: ProfileOptions,
+    ) -> WifiResult<()> {
+        logging::timed("connect_enterprise", || {
+            let eap = match credentials.method {
+                EapMethod::Peap => "peap",
+                EapMethod::Ttls => "ttls",
+            };
+            let validate_ca = if credentials.validate_ca { "yes" } else { "no" };
+            let mut settings = vec![
+                "wifi-sec.key-mgmt",
+                "wpa-eap",
+                "802-1x.eap",
+                eap,
+                "802-1x.phase2-auth",
+                "mschapv2",
+                "802-1x.identity",
+                &credentials.identity,
+                // Saved by NetworkManager, but set after the connection is added
+                "802-1x.password-flags",
+                "0",
+                "802-1x.system-ca-certs",
+                validate_ca,
+            ];
+            // WPA3-Enterprise is WPA2-Enterprise with protected management frames required
+            if auth == "WPA3ENT" || options.require_pmf {
+                settings.extend(["wifi-sec.pmf", "required"]);
+            }
+            add_and_activate(
+                ssid,
+                &settings,
+                &[(EAP_PASSWORD, &credentials.password)],
+                options,
+            )
+        })
+    }
+
+    fn connect_open(&self, ssid: &str, options: ProfileOptions) -> WifiResult<()> {
+        logging::timed("connect_open", || {
+            if options.random_mac {
+                return add_and_activate(ssid, &[], &[], options);
+            }
+            let mut args = vec!["device", "wifi", "connect", ssid];
+            if options.hidden {
+                args.extend(["hidden", "yes"]);
+            }
+            nmcli_on_device(&args).map(|_| ())
+        })
+    }
+
+    fn connect_wps(&self, _ssid: &str) -> WifiResult<()> {
+        // NetworkManager only starts WPS enrollment while a secret agent is
+        // prompting for the password, which nmcli can't do non-interactively
+        Err(WifiError::Wps(
+            "Push-button setup isn't available through NetworkManager; use the password"
+                .to_string(),
+        ))
+    }
+
+    fn disconnect(&self) -> WifiResult<()> {
+        logging::timed("disconnect", || {
+            let device = match active_device() {
+                Some(device) => Some(device),
+                None => wifi_devices()?
+                    .into_iter()
+                    .find(|d| d.is_connected)
+                    .map(|d| d.id),
+            };
+            match device {
+                Some(device) => nmcli(&["device", "disconnect", &device]).map(|_| ()),
+                None => Ok(()),
+            }
+        })
+    }
+
+    fn get_saved_profiles(&self) -> WifiResult<Vec<String>> {
+        logging::timed("get_saved_profiles", || {
+            Ok(connection_priorities()?
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect())
+        })
+    }
+
+    fn saved_profile_name(&self, ssid: &str) -> WifiResult<Option<String>> {
+        logging::timed("saved_profile_name", || saved_connection_name(ssid))
+    }
+
+    fn set_profile_position(&self, ssid: &str, position: u32) -> WifiResult<()> {
+        // NetworkManager has no ordered list, so rewrite autoconnect priorities
+        // to count down from the top of the new order
+        logging::timed("set_profile_position", || {
+            let ssid = connection_id(ssid);
+            let current = connection_priorities()?;
+            let mut order: Vec<&str> = current
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .filter(|&name| name != ssid)
+                .collect();
+            order.insert((position as usize).min(order.len()), &ssid);
+
+            let count = order.len() as i32;
+            for (i, name) in order.into_iter().enumerate() {
+                let priority = count - i as i32;
+                if current.iter().any(|(n, p)| n == name && *p == priority) {
+                    continue;
+                }
+                nmcli(&[
+                    "connection",
+                    "modify",
+                    "id",
+                    name,
+                    "connection.autoconnect-priority",
+                    &priority.to_string(),
+                ])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn set_auto_connect(&self, ssid: &str, enable: bool) -> WifiResult<()> {
+        logging::timed("set_auto_connect", || {
+            let ssid = &connection_id(ssid);
+            let value = if enable { "yes" } else { "no" };
+            nmcli(&[
+                "connection",
+                "modify",
+                "id",
+                ssid,
+                "connection.autoconnect",
+                value,
+            ])
+            .map(|_| ())
+        })
+    }
+
+    fn is_metered(&self, ssid: &str) -> WifiResult<bool> {
+        logging::timed("is_metered", || {
+            let ssid = &connection_id(ssid);
+            // `unknown` lets NetworkManager guess, e.g. from a phone's hotspot hints
+            let metered = connection_setting(ssid, "connection.metered")?;
+            Ok(matches!(metered.as_str(), "yes" | "guess-yes"))
+        })
+    }
+
+    fn set_metered(&self, ssid: &str, metered: bool) -> WifiResult<()> {
+        logging::timed("set_metered", || {
+            let ssid = &connection_id(ssid);
+            nmcli(&[
+                "connection",
+                "modify",
+                "id",
+                ssid,
+                "connection.metered",
+                if metered { "yes" } else { "no" },
+            ])
+            .map(|_| ())
+        })
+    }
+
+    fn mac_randomization(&self, ssid: &str) -> WifiResult<bool> {
+        logging::timed("mac_randomization", || {
+            let ssid = &connection_id(ssid);
+            // `stable` is a random address kept per network, like Windows uses
+            let policy = connection_setting(ssid, "802-11-wireless.cloned-mac-address")?;
+            Ok(matches!(policy.as_str(), "random" | "stable"))
+        })
+    }
+
+    fn set_mac_randomization(&self, ssid: &str, enable: bool) -> WifiResult<()> {
+        logging::timed("set_mac_randomization", || {
+            let ssid = &connection_id(ssid);
+            nmcli(&[
+                "connection",
+                "modify",
+                "id",
+                ssid,
+                "802-11-wireless.cloned-mac-address",
+                if enable { "stable" } else { "permanent" },
+            ])
+            .map(|_| ())
+        })
+    }
+
+    fn forget_network(&self, ssid: &str) -> WifiResult<()> {
+        logging::timed("forget_network", || {
+            let ssid = &connection_id(ssid);
+            nmcli(&["connection", "delete", "id", ssid]).map(|_| ())
+        })
+    }
+
+    fn rename_profile(&self, name: &str, new_name: &str) -> WifiResult<()> {
+        logging::timed("rename_profile", || {
+            let name = &connection_id(name);
+            nmcli(&[
+                "connection",
+                "modify",
+                "id",
+                name,
+                "connection.id",
+                new_name,
+            ])
+            .map(|_| ())
+        })
+    }
+
+    fn get_wifi_password(&self, ssid: &str) -> WifiResult<Option<SecretString>> {
+        logging::timed("get_wifi_password", || {
+            let ssid = &connection_id(ssid);
+            let output = nmcli(&[
+                "--show-secrets",
+                "-g",
+                "802-11-wireless-security.psk",
+                "connection",
+                "show",
+                "id",
+                ssid,
+            ])?;
+            let password = output.lines().next().map(unescape).unwrap_or_default();
+            Ok((!password.is_empty()).then(|| SecretString::from(password)))
+        })
+    }
+
+    fn export_profile(&self, ssid: &str) -> WifiResult<String> {
+        // Written in the Windows profile format so it can be imported on either platform
+        logging::timed("export_profile", || {
+            let ssid = &connection_id(ssid);
+            let auth = match connection_setting(ssid, "802-11-wireless-security.key-mgmt")
+                .unwrap_or_default()
+                .as_str()
+            {
+                "" => "Open",
+                "wpa-psk" => "WPA2-PSK",
+                "sae" => "WPA3-SAE",
+                other => {
+                    return Err(WifiError::Nmcli(format!(
+                        "Exporting {} networks is not supported",
+                        other
+                    )));
+                }
+            };
+            let network_ssid = connection_setting(ssid, "802-11-wireless.ssid")?;
+            let options = ProfileOptions {
+                hidden: connection_setting(ssid, "802-11-wireless.hidden")? == "yes",
+                random_mac: self.mac_randomization(ssid)?,
+                require_pmf: connection_setting(ssid, "802-11-wireless-security.pmf")
+                    .unwrap_or_default()
+                    .contains("required"),
+            };
+            let auto_connect = connection_setting(ssid, "connection.autoconnect")? == "yes";
+            let password = self.get_wifi_password(ssid)?;
+
+            let xml =
+                create_profile_xml(&network_ssid, auth, "AES", password.as_ref(), None, options);
+            Ok(if auto_connect {
+                xml.replace(
+                    "<connectionMode>manual</connectionMode>",
+                    "<connectionMode>auto</connectionMode>",
+                )
+            } else {
+                xml
+            })
+        })
+    }
+
+    fn import_profile(&self, xml: &str, overwrite: bool) -> WifiResult<String> {
+        logging::timed("import_profile", || {
+            let profile = parse_profile_xml(xml)?;
+            let key_mgmt = match profile.authentication.as_str() {
+                "open" => None,
+                "WPA2PSK" | "WPAPSK" => Some("wpa-psk"),
+                "WPA3SAE" => Some("sae"),
+                other => {
+                    return Err(WifiError::Nmcli(format!(
+                        "Importing {} profiles is not supported",
+                        other
+                    )));
+                }
+            };
+            if key_mgmt.is_some() && profile.key_protected {
+                return Err(WifiError::InvalidProfileXml(
+                    "the key is encrypted; export it with the key in plain text".to_string(),
+                ));
+            }
+
+            let exists = saved_connections()?
+                .iter()
+                .any(|connection| connection.name == profile.name);
+            if exists && !overwrite {
+                return Err(WifiError::ProfileExists(profile.name));
+            }
+
+            // Build the connection under a temporary name first, so a failed
+            // import leaves the one it replaces untouched
+            let staging = format!("{}.wifui-import", profile.name);
+            let mut args = vec![
+                "connection",
+                "add",
+                "type",
+                "wifi",
+                "con-name",
+                &staging,
+                "ssid",
+                &profile.ssid,
+                "connection.autoconnect",
+                if profile.auto_connect { "yes" } else { "no" },
+            ];
+            if let Some(key_mgmt) = key_mgmt {
+                args.extend(["wifi-sec.key-mgmt", key_mgmt]);
+            }
+            if profile.hidden {
+                args.extend(["802-11-wireless.hidden", "yes"]);
+            }
+            if profile.random_mac {
+                args.extend(["802-11-wireless.cloned-mac-address", "stable"]);
+            }
+
+            nmcli(&args)?;
+            let replaced = match (key_mgmt, &profile.key) {
+                (Some(_), Some(key)) => set_secrets(&staging, &[(PSK, key)]),
+                _ => Ok(()),
+            }
+            .and_then(|()| {
+                if exists {
+                    nmcli(&["connection", "delete", "id", &profile.name])?;
+                }
+                nmcli(&[
+                    "connection",
+                    "modify",
+                    "id",
+                    &staging,
+                    "connection.id",
+                    &profile.name,
+                ])
+                .map(|_| ())
+            });
+            if let Err(e) = replaced {
+                let _ = nmcli(&["connection", "delete", "id", &staging]);
+                return Err(e);
+            }
+            Ok(profile.name)
+        })
+    }
+
+    fn list_interfaces(&self) -> WifiResult<Vec<InterfaceInfo>> {
+        logging::timed("list_interfaces", wifi_devices)
+    }
+
+    fn set_active_interface(&self, interface: &InterfaceInfo) {
+        if let Ok(mut active) = ACTIVE_DEVICE.lock() {
+            *active = Some(interface.id.clone());
+        }
+    }
+
+    fn hotspot_status(&self) -> WifiResult<HotspotStatus> {
+        logging::timed("hotspot_status", || {
+            let output = nmcli(&["-t", "-f", "NAME", "connection", "show", "--active"])?;
+            let active = output
+                .lines()
+                .any(|line| unescape(line) == HOTSPOT_CONNECTION);
+            let ssid = nmcli(&[
+                "-g",
+                "802-11-wireless.ssid",
+                "connection",
+                "show",
+                "id",
+                HOTSPOT_CONNECTION,
+            ])
+            .map(|output| unescape(output.trim()))
+            .unwrap_or_default();
+            // NetworkManager doesn't track the stations associated to the hotspot
+            Ok(HotspotStatus {
+                active,
+                ssid,
+                client_count: None,
+                max_clients: None,
+            })
+        })
+    }
+
+    fn start_hotspot(&self, ssid: &str, passphrase: &SecretString) -> WifiResult<()> {
+        logging::timed("start_hotspot", || {
+            // What `device wifi hotspot` sets up, which only takes the
+            // passphrase on its command line
+            let _ = nmcli(&["connection", "delete", "id", HOTSPOT_CONNECTION]);
+            nmcli_on_device(&[
+                "connection",
+                "add",
+                "type",
+                "wifi",
+                "con-name",
+                HOTSPOT_CONNECTION,
+                "ssid",
+                ssid,
+                "connection.autoconnect",
+                "no",
+                "802-11-wireless.mode",
+                "ap",
+                "ipv4.method",
+                "shared",
+                "wifi-sec.key-mgmt",
+                "wpa-psk",
+                "wifi-sec.proto",
+                "rsn",
+                "wifi-sec.pairwise",
+                "ccmp",
+                "wifi-sec.group",
+                "ccmp",
+            ])?;
+            set_secrets(HOTSPOT_CONNECTION, &[(PSK, passphrase)])?;
+            nmcli_on_device(&["connection", "up", "id", HOTSPOT_CONNECTION]).map(|_| ())
+        })
+    }
+
+    fn stop_hotspot(&self) -> WifiResult<()> {
+        logging::timed("stop_hotspot", || {
+            nmcli(&["connection", "down", "id", HOTSPOT_CONNECTION]).map(|_| ())
+        })
+    }
+
+    fn remove_hotspot(&self) -> WifiResult<()> {
+        logging::timed("remove_hotspot", || {
+            // Deleting the active connection also takes the hotspot down
+            nmcli(&["connection", "delete", "id", HOTSPOT_CONNECTION]).map(|_| ())
+        })
+    }
+
+    fn default_gateway(&self) -> WifiResult<Option<Ipv4Addr>> {
+        logging::timed("default_gateway", || {
+            let Some(device) = connected_device()? else {
+                return Ok(None);
+            };
+            let output = nmcli(&["-g", "IP4.GATEWAY", "device", "show", &device])?;
+            Ok(output
+                .lines()
+                .next()
+                .and_then(|line| line.trim().parse().ok()))
+        })
+    }
+
+    fn ping(&self, addr: Ipv4Addr, timeout: Duration) -> WifiResult<Option<Duration>> {
+        // Raw ICMP sockets need privileges, so go through the setuid ping binary
+        let output = Command::new("ping")
+            .args(["-n", "-c", "1", "-W"])
+            .arg(timeout.as_secs().max(1).to_string())
+            .arg(addr.to_string())
+            .output()
+            .map_err(|e| WifiError::Ping(e.to_string()))?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        // e.g. "64 bytes from 192.168.1.1: icmp_seq=1 ttl=64 time=2.41 ms"
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .find_map(|word| word.strip_prefix("time="))
+            .and_then(|ms| ms.parse::<f64>().ok())
+            .map(|ms| Duration::from_secs_f64(ms / 1000.0)))
+    }
+
+    fn ip_info(&self) -> WifiResult<IpInfo> {
+        logging::timed("ip_info", || {
+            let mut info = IpInfo::default();
+            let Some(device) = connected_device()? else {
+                return Ok(info);
+            };
+            let output = nmcli(&[
+                "-t",
+                "-f",
+                "GENERAL.HWADDR,IP4.ADDRESS,IP4.GATEWAY,IP4.DNS,IP6.ADDRESS,IP6.GATEWAY,IP6.DNS",
+                "device",
+                "show",
+                &device,
+            ])?;
+            // e.g. "IP4.ADDRESS[1]:192.168.1.20/24" or "GENERAL.HWADDR:AA\:BB\:..."
+            for line in output.lines() {
+                let fields = split_fields(line);
+                let (Some(key), Some(value)) = (fields.first(), fields.get(1)) else {
+                    continue;
+                };
+                let key = key.split('[').next().unwrap_or_default();
+                let addr = value.split('/').next().unwrap_or_default();
+                match key {
+                    "GENERAL.HWADDR" => info.mac = parse_bssid(value),
+                    "IP4.ADDRESS" if info.ipv4.is_none() => {
+                        let prefix = value.split('/').nth(1).and_then(|p| p.parse().ok());
+                        if let Ok(addr) = addr.parse() {
+                            info.ipv4 = Some((addr, prefix.unwrap_or(32)));
+                        }
+                    }
+                    "IP6.ADDRESS" => info.ipv6.extend(addr.parse::<Ipv6Addr>().ok()),
+                    // IPv4 comes first, so an IPv6 gateway only fills in when there is none
+                    "IP4.GATEWAY" | "IP6.GATEWAY" if info.gateway.is_none() => {
+                        info.gateway = addr.parse().ok();
+                    }
+                    "IP4.DNS" | "IP6.DNS" => info.dns.extend(addr.parse::<IpAddr>().ok()),
+                    _ => {}
+                }
+            }
+            Ok(info)
+        })
+    }
+
+    fn interface_stats(&self) -> WifiResult<InterfaceStats> {
+        logging::timed("interface_stats", || {
+            let mut stats = InterfaceStats::default();
+            let Some(device) = connected_device()? else {
+                return Ok(stats);
+            };
+            let counter = |name: &str| {
+                std::fs::read_to_string(format!("/sys/class/net/{}/statistics/{}", device, name))
+                    .ok()
+                    .and_then(|value| value.trim().parse::<u64>().ok())
+                    .unwrap_or(0)
+            };
+            stats.tx_frames = counter("tx_packets");
+            stats.rx_frames = counter("rx_packets");
+            stats.failed = counter("tx_errors");
+
+            // The kernel only counts packets; retries come from the driver, if
+            // `iw` is installed, e.g. "\ttx retries:\t12"
+            if let Ok(output) = Command::new("iw")
+                .args(["dev", &device, "station", "dump"])
+                .output()
+            {
+                for line in String::from_utf8_lossy(&output.stdout).lines() {
+                    let Some((key, value)) = line.trim().split_once(':') else {
+                        continue;
+                    };
+                    let Ok(value) = value.trim().parse::<u64>() else {
+                        continue;
+                    };
+                    match key {
+                        "tx retries" => stats.retries = value,
+                        "tx failed" => stats.failed = value,
+                        _ => {}
+                    }
+                }
+            }
+            Ok(stats)
+        })
+    }
+
+    fn radio_state(&self) -> WifiResult<RadioState> {
+        logging::timed("radio_state", || {
+            // e.g. "enabled:disabled" when only the software switch is off
+            let output = nmcli(&["-t", "-f", "WIFI-HW,WIFI", "radio"])?;
+            let fields = split_fields(output.trim());
+            Ok(match (fields.first(), fields.get(1)) {
+                (Some(hw), _) if hw != "enabled" => RadioState::HardwareOff,
+                (_, Some(sw)) if sw == "enabled" => RadioState::On,
+                _ => RadioState::Off,
+            })
+        })
+    }
+
+    fn set_radio(&self, on: bool) -> WifiResult<()> {
+        logging::timed("set_radio", || {
+            if on && self.radio_state()? == RadioState::HardwareOff {
+                return Err(WifiError::RadioHardwareOff);
+            }
+            nmcli(&["radio", "wifi", if on { "on" } else { "off" }]).map(|_| ())
+        })
+    }
+}
+
+/// Watches `nmcli monitor` for connection changes on a background thread
+#[derive(Debug)]
+pub struct WifiListener {
+    monitor: Child,
+}
+
+impl Drop for WifiListener {
+    fn drop(&mut self) {
+        let _ = self.monitor.kill();
+        let _ = self.monitor.wait();
+    }
+}
+
+/// Start listening for WiFi connection events
+pub fn start_wifi_listener(sender: UnboundedSender<ConnectionEvent>) -> WifiResult<WifiListener> {
+    logging::timed("start_wifi_listener", || {
+        let devices: Vec<String> = wifi_devices()?.into_iter().map(|d| d.id).collect();
+        let mut monitor = Command::new("nmcli")
+            .arg("monitor")
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| WifiError::Nmcli(e.to_string()))?;
+        let stdout = monitor
+            .stdout
+            .take()
+            .ok_or_else(|| WifiError::Nmcli("monitor produced no output".to_string()))?;
+
+        std::thread::spawn(move || {
+            // Connection the device is activating, reported before it fails
+            let mut attempting = String::new();
+
+            // Lines look like "wlan0: connected" or "wlan0: using connection 'Home'"
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                let Some((device, status)) = line.split_once(": ") else {
+                    continue;
+                };
+                if !devices.iter().any(|d| d == device)
+                    || active_device().is_some_and(|active| active != device)
+                {
+                    continue;
+                }
+                logging::debug(format!("nmcli monitor: {}", line));
+                if let Some(name) = status.strip_prefix("using connection ") {
+                    attempting = name.trim_matches('\'').to_string();
+                    continue;
+                }
+                let event = match status {
+                    "connected" => match get_connected_ssid() {
+                        Ok(Some(ssid)) => ConnectionEvent::Connected(ssid),
+                        _ => continue,
+                    },
+                    "disconnected" => ConnectionEvent::Disconnected,
+                    "connection failed" => {
+                        let (reason_code, reason_str) = failure_reason(device);
+                        ConnectionEvent::Failed {
+                            ssid: std::mem::take(&mut attempting),
+                            reason_code,
+                            reason_str,
+                        }
+                    }
+                    _ => continue,
+                };
+                if sender.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(WifiListener { monitor })
+    })
+}
+
+/// Why the device's last activation failed, as `GENERAL.REASON` reports it,
+/// e.g. "7 (Secrets were required, but not provided)"
+fn failure_reason(device: &str) -> (u32, String) {
+    let reason = nmcli(&["-g", "GENERAL.REASON", "device", "show", device]).unwrap_or_default();
+    let (code, text) = reason.trim().split_once(' ').unwrap_or((reason.trim(), ""));
+    match code.parse::<u32>() {
+        // NetworkManager asks for new secrets when the handshake fails, and
+        // nmcli has no agent to give them
+        Ok(NM_REASON_NO_SECRETS) => (WRONG_PASSWORD_REASON, "Incorrect Password".to_string()),
+        _ => {
+            let text = text.trim_matches(['(', ')']);
+            let text = if text.is_empty() {
+                "Activation failed"
+            } else {
+                text
+            };
+            (0, text.to_string())
+        }
+    }
+}
+
+fn get_wifi_networks() -> WifiResult<Vec<WifiInfo>> {
+    let saved = saved_connections()?;
+    // A connection named after the SSID wins over renamed ones for it
+    let mut by_ssid: HashMap<&str, &SavedConnection> = HashMap::new();
+    for connection in &saved {
+        let entry = by_ssid.entry(&connection.ssid).or_insert(connection);
+        if connection.name == connection.ssid {
+            *entry = connection;
+        }
+    }
+    let output = nmcli_on_device(&[
+        "-t",
+        "-f",
+        "IN-USE,SSID,BSSID,CHAN,FREQ,RATE,SIGNAL,SECURITY",
+        "device",
+        "wifi",
+        "list",
+        "--rescan",
+        "no",
+    ])?;
+
+    let mut wifi_map: HashMap<(String, String), WifiInfo> = HashMap::new();
+    // Saved key management per SSID, only looked up for networks in range
+    let mut key_mgmt: HashMap<String, String> = HashMap::new();
+    for line in output.lines() {
+        let fields = split_fields(line);
+        let [
+            in_use,
+            ssid,
+            bssid,
+            channel,
+            frequency,
+            rate,
+            signal,
+            security,
+        ] = &fields[..]
+        else {
+            continue;
+        };
+        let bssid = parse_bssid(bssid).unwrap_or_default();
+        let channel: u32 = channel.parse().unwrap_or(0);
+        // nmcli reports MHz; the rest of the app uses kHz like the WLAN API
+        let frequency = leading_number(frequency) * 1000;
+        let signal = leading_number(signal).min(100) as u8;
+        let is_connected = in_use == "*";
+        let (authentication, encryption) = security_to_auth(security);
+
+        let bss = BssInfo {
+            bssid,
+            // Same linear quality <-> dBm mapping the WLAN API uses
+            rssi: signal as i32 / 2 - 100,
+            link_quality: signal,
+            frequency,
+            channel,
+            ..Default::default()
+        };
+
+        // Hidden access points can't be told apart by SSID, so list each one;
+        // the NUL prefix can't appear in a listed SSID
+        let key = if ssid.is_empty() {
+            format!("\0{}", format_bssid(&bssid))
+        } else {
+            ssid.clone()
+        };
+        let connection = by_ssid.get(ssid.as_str()).copied();
+        let info = wifi_map
+            .entry((key, authentication.to_string()))
+            .or_insert_with(|| WifiInfo {
+                ssid: ssid.clone(),
+                authentication: authentication.to_string(),
+                encryption: encryption.to_string(),
+                is_saved: connection.is_some(),
+                auto_connect: connection.is_some_and(|c| c.autoconnect),
+                phy_type: "Unknown".to_string(),
+                profile_name: connection
+                    .filter(|c| c.name != *ssid)
+                    .map(|c| c.name.clone()),
+                ..Default::default()
+            });
+        if info.bss_list.is_empty()
+            && let Some(connection) = connection
+        {
+            let saved_key_mgmt = key_mgmt.entry(ssid.clone()).or_insert_with(|| {
+                connection_setting(&connection.name, "802-11-wireless-security.key-mgmt")
+                    .unwrap_or_default()
+            });
+            info.profile_security = profile_mismatch(saved_key_mgmt, security);
+        }
+
+        if signal > info.signal || info.bss_list.is_empty() {
+            info.signal = signal;
+            info.channel = channel;
+            info.frequency = frequency;
+        }
+        if is_connected {
+            info.is_connected = true;
+            info.link_speed = Some(leading_number(rate));
+            info.connected_bssid = Some(bssid);
+        }
+        info.bss_list.push(bss);
+    }
+
+    // nmcli's signal is from the last scan; the kernel has the current one
+    if let Some(info) = wifi_map.values_mut().find(|info| info.is_connected) {
+        info.rssi = connected_device()
+            .ok()
+            .flatten()
+            .and_then(|device| link_rssi(&device));
+    }
+
+    Ok(wifi_map.into_values().collect())
+}
+
+/// Signal level of a device's link in dBm from `/proc/net/wireless`, e.g.
+/// "wlan0: 0000   54.  -56.  -256 ..."
+fn link_rssi(device: &str) -> Option<i32> {
+    let table = std::fs::read_to_string("/proc/net/wireless").ok()?;
+    table.lines().find_map(|line| {
+        let fields = line.trim_start().strip_prefix(device)?.strip_prefix(':')?;
+        let level = fields.split_whitespace().nth(2)?;
+        level.trim_end_matches('.').parse().ok()
+    })
+}
+
+fn get_connected_ssid() -> WifiResult<Option<String>> {
+    let output = nmcli_on_device(&[
+        "-t",
+        "-f",
+        "ACTIVE,SSID",
+        "device",
+        "wifi",
+        "list",
+        "--rescan",
+        "no",
+    ])?;
+    Ok(output
+        .lines()
+        .find_map(|line| match &split_fields(line)[..] {
+            [active, ssid] if active == "yes" && !ssid.is_empty() => Some(ssid.clone()),
+            _ => None,
+        }))
+}
+
+/// A saved WiFi connection
+struct SavedConnection {
+    name: String,
+    ssid: String,
+    autoconnect: bool,
+}
+
+/// Saved WiFi connections with the SSID each is for
+fn saved_connections() -> WifiResult<Vec<SavedConnection>> {
+    let output = nmcli(&[
+        "-t",
+        "-f",
+        "NAME,UUID,TYPE,AUTOCONNECT",
+        "connection",
+        "show",
+    ])?;
+    let mut ssids = CONNECTION_SSIDS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    Ok(output
+        .lines()
+        .filter_map(|line| match &split_fields(line)[..] {
+            [name, uuid, kind, autoconnect] if kind == WIFI_CONNECTION_TYPE => {
+                let ssid = match ssids.get(uuid) {
+                    Some(ssid) => ssid.clone(),
+                    None => match nmcli(&[
+                        "-g",
+                        "802-11-wireless.ssid",
+                        "connection",
+                        "show",
+                        "uuid",
+                        uuid,
+                    ]) {
+                        Ok(output) => {
+                            let ssid = output.lines().next().map(unescape).unwrap_or_default();
+                            ssids.insert(uuid.clone(), ssid.clone());
+                            ssid
+                        }
+                        // Most connections are named after their SSID
+                        Err(_) => name.clone(),
+                    },
+                };
+                Some(SavedConnection {
+                    name: name.clone(),
+                    ssid,
+                    autoconnect: autoconnect == "yes",
+                })
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+/// Name of the saved connection for an SSID, else `ssid` itself so nmcli
+/// reports it missing
+fn connection_id(ssid: &str) -> String {
+    saved_connection_name(ssid)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| ssid.to_string())
+}
+
+/// Name of the saved connection for an SSID: one called `ssid`, else one for
+/// that SSID
+fn saved_connection_name(ssid: &str) -> WifiResult<Option<String>> {
+    let saved = saved_connections()?;
+    if saved.iter().any(|connection| connection.name == ssid) {
+        return Ok(Some(ssid.to_string()));
+    }
+    Ok(saved
+        .into_iter()
+        .find(|connection| connection.ssid == ssid)
+        .map(|connection| connection.name))
+}
+
+/// Add a WiFi connection named after its SSID with extra `settings` and
+/// `secrets`, replacing any earlier one, and bring it up
+fn add_and_activate(
+    ssid: &str,
+    settings: &[&str],
+    secrets: &[(&str, &SecretString)],
+    options: ProfileOptions,
+) -> WifiResult<()> {
+    let mut args = vec![
+        "connection",
+        "add",
+        "type",
+        "wifi",
+        "con-name",
+        ssid,
+        "ssid",
+        ssid,
+    ];
+    args.extend(settings);
+    if options.hidden {
+        args.extend(["802-11-wireless.hidden", "yes"]);
+    }
+    if options.random_mac {
+        args.extend(["802-11-wireless.cloned-mac-address", "stable"]);
+    }
+
+    // Replace any earlier profile rather than adding a duplicate with the same name
+    let _ = nmcli(&["connection", "delete", "id", ssid]);
+    nmcli_on_device(&args)?;
+    if !secrets.is_empty() {
+        set_secrets(ssid, secrets)?;
+    }
+    nmcli_on_device(&["connection", "up", "id", ssid]).map(|_| ())
+}
+
+/// Store secrets in a saved connection by feeding `nmcli connection edit` on
+/// stdin, so they never show up in the command line other users can read
+fn set_secrets(id: &str, secrets: &[(&str, &SecretString)]) -> WifiResult<()> {
+    let mut script = String::new();
+    for (property, secret) in secrets {
+        script.push_str(&format!("set {} {}\n", property, secret.expose_secret()));
+    }
+    script.push_str("save persistent\nquit\n");
+    let output = nmcli_with_input(&["connection", "edit", "id", id], &script)?;
+    // The editor reports a rejected value on stdout and still exits cleanly
+    match output.lines().find(|line| line.contains("Error:")) {
+        Some(error) => Err(WifiError::Nmcli(
+            error.trim().trim_start_matches("Error: ").to_string(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// A single setting of a saved connection, unescaped
+fn connection_setting(id: &str, field: &str) -> WifiResult<String> {
+    let output = nmcli(&["-g", field, "connection", "show", "id", id])?;
+    Ok(output.lines().next().map(unescape).unwrap_or_default())
+}
+
+/// Saved WiFi connections with their autoconnect priority, highest first
+fn connection_priorities() -> WifiResult<Vec<(String, i32)>> {
+    let output = nmcli(&[
+        "-t",
+        "-f",
+        "NAME,TYPE,AUTOCONNECT-PRIORITY",
+        "connection",
+        "show",
+    ])?;
+    let mut connections: Vec<(String, i32)> = output
+        .lines()
+        .filter_map(|line| match &split_fields(line)[..] {
+            [name, kind, priority] if kind == WIFI_CONNECTION_TYPE => {
+                Some((name.clone(), priority.parse().unwrap_or(0)))
+            }
+            _ => None,
+        })
+        .collect();
+    // Stable sort keeps nmcli's order among equal priorities
+    connections.sort_by_key(|&(_, priority)| std::cmp::Reverse(priority));
+    Ok(connections)
+}
+
+fn wifi_devices() -> WifiResult<Vec<InterfaceInfo>> {
+    let output = nmcli(&["-t", "-f", "DEVICE,TYPE,STATE", "device"])?;
+    Ok(output
+        .lines()
+        .filter_map(|line| match &split_fields(line)[..] {
+            [device, kind, state] if kind == "wifi" => Some(InterfaceInfo {
+                id: device.clone(),
+                description: device.clone(),
+                is_connected: state == "connected",
+            }),
+            _ => None,
+        })
+        .collect())
+}
+
+/// The chosen device, or else the first connected WiFi device
+fn connected_device() -> WifiResult<Option<String>> {
+    if let Some(device) = active_device() {
+        return Ok(Some(device));
+    }
+    Ok(wifi_devices()?
+        .into_iter()
+        .find(|device| device.is_connected)
+        .map(|device| device.id))
+}
+
+fn active_device() -> Option<String> {
+    ACTIVE_DEVICE.lock().ok().and_then(|active| active.clone())
+}
+
+/// Run nmcli and return its standard output
+fn nmcli(args: &[&str]) -> WifiResult<String> {
+    let output = Command::new("nmcli")
+        .args(args)
+        .output()
+        .map_err(|e| WifiError::Nmcli(e.to_string()))?;
+    nmcli_output(output)
+}
+
+/// Run nmcli with `input` on its standard input
+fn nmcli_with_input(args: &[&str], input: &str) -> WifiResult<String> {
+    let mut child = Command::new("nmcli")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| WifiError::Nmcli(e.to_string()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|e| WifiError::Nmcli(e.to_string()))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| WifiError::Nmcli(e.to_string()))?;
+    nmcli_output(output)
+}
+
+/// Standard output of a finished nmcli, or the error it reported
+fn nmcli_output(output: std::process::Output) -> WifiResult<String> {
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // How `device wifi connect` and `connection up` report a rejected key
+        if stderr.contains("Secrets were required") {
+            return Err(WifiError::WrongPassword);
+        }
+        return Err(WifiError::Nmcli(
+            stderr.trim().trim_start_matches("Error: ").to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Run nmcli, restricted to the active device if one was chosen
+fn nmcli_on_device(args: &[&str]) -> WifiResult<String> {
+    match active_device() {
+        Some(device) => {
+            let mut args = args.to_vec();
+            args.extend(["ifname", &device]);
+            nmcli(&args)
+        }
+        None => nmcli(args),
+    }
+}
+
+/// Split a `nmcli -t` line on unescaped colons
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => field.extend(chars.next()),
+            ':' => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn unescape(value: &str) -> String {
+    split_fields(value).join(":")
+}
+
+/// Parse the number at the start of values like "5180 MHz" or "540 Mbit/s"
+fn leading_number(text: &str) -> u32 {
+    text.split_whitespace()
+        .next()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Security family of a saved connection's `key-mgmt` when the access point's
+/// `security` doesn't offer it
+fn profile_mismatch(key_mgmt: &str, security: &str) -> Option<&'static str> {
+    let enterprise = security.contains("802.1X");
+    let (family, offered) = match key_mgmt {
+        "" => ("Open", security_to_auth(security).0 == "Open"),
+        "none" | "ieee8021x" => ("WEP", security.contains("WEP")),
+        "wpa-psk" => (
+            "WPA2-Personal",
+            !enterprise && (security.contains("WPA1") || security.contains("WPA2")),
+        ),
+        "sae" => ("WPA3-Personal", !enterprise && security.contains("WPA3")),
+        "wpa-eap" | "wpa-eap-suite-b-192" => ("WPA2-Enterprise", enterprise),
+        _ => return None,
+    };
+    (!offered).then_some(family)
+}
+
+/// Map nmcli's SECURITY column onto the auth/cipher names the WLAN API uses
+fn security_to_auth(security: &str) -> (&'static str, &'static str) {
+    if security.is_empty() || security == "--" || security == "OWE" {
+        ("Open", "None")
+    } else if security.contains("802.1X") {
+        if security.contains("WPA3") {
+            ("WPA3", "AES")
+        } else {
+            ("WPA2", "AES")
+        }
+    } else if security.contains("WPA2") {
+        ("WPA2-PSK", "AES")
+    } else if security.contains("WPA3") {
+        ("WPA3-SAE", "AES")
+    } else if security.contains("WPA1") {
+        ("WPA-PSK", "TKIP")
+    } else if security.contains("WEP") {
+        ("Shared", "WEP")
+    } else {
+        ("Unknown", "Unknown")
+    }
+}
@@ -1,5 +1,6 @@
 use crate::error::{WifiError, WifiResult, wlan_reason_to_string};
-use crate::wifi::handle::WlanHandle;
+use crate::logging;
+use crate::wifi::handle::{WlanHandle, is_active_interface};
 use crate::wifi::types::ConnectionEvent;
 use tokio::sync::mpsc::UnboundedSender;
 use windows::Win32::{Foundation::ERROR_SUCCESS, NetworkManagement::WiFi::*};
@@ -49,10 +50,38 @@ unsafe extern "system" fn notification_callback(
         )
     };
 
+    if !is_active_interface(&data.InterfaceGuid) {
+        return;
+    }
+
+    if data.NotificationSource == WLAN_NOTIFICATION_SOURCE_MSM {
+        if data.NotificationCode == wlan_notification_msm_roaming_end.0 as u32
+            && data.dwDataSize >= std::mem::size_of::<WLAN_MSM_NOTIFICATION_DATA>() as u32
+        {
+            // SAFETY: pData points to WLAN_MSM_NOTIFICATION_DATA for MSM
+            // notifications, and we checked the size above.
+            let msm_data = unsafe { &*(data.pData as *const WLAN_MSM_NOTIFICATION_DATA) };
+            let ssid_len = msm_data.dot11Ssid.uSSIDLength as usize;
+            let ssid = String::from_utf8_lossy(&msm_data.dot11Ssid.ucSSID[..ssid_len]).to_string();
+            let _ = sender.send(ConnectionEvent::Roamed {
+                ssid,
+                bssid: msm_data.dot11MacAddr,
+            });
+        }
+        return;
+    }
+
     if data.NotificationSource != WLAN_NOTIFICATION_SOURCE_ACM {
         return;
     }
 
+    if data.NotificationCode == wlan_notification_acm_scan_complete.0 as u32
+        || data.NotificationCode == wlan_notification_acm_scan_fail.0 as u32
+    {
+        let _ = sender.send(ConnectionEvent::ScanComplete);
+        return;
+    }
+
     if data.NotificationCode == wlan_notification_acm_connection_complete.0 as u32
         || data.NotificationCode == wlan_notification_acm_connection_attempt_fail.0 as u32
         || data.NotificationCode == wlan_notification_acm_disconnected.0 as u32
@@ -69,6 +98,10 @@ unsafe extern "system" fn notification_callback(
         let ssid_len = conn_data.dot11Ssid.uSSIDLength as usize;
         let ssid_bytes = &conn_data.dot11Ssid.ucSSID[..ssid_len];
         let ssid = String::from_utf8_lossy(ssid_bytes).to_string();
+        logging::debug(format!(
+            "WLAN notification {} for {} (reason 0x{:X})",
+            data.NotificationCode, ssid, conn_data.wlanReasonCode
+        ));
 
         if data.NotificationCode == wlan_notification_acm_connection_complete.0 as u32 {
             let _ = sender.send(ConnectionEvent::Connected(ssid));
@@ -89,31 +122,34 @@ unsafe extern "system" fn notification_callback(
 
 /// Start listening for WiFi connection events
 pub fn start_wifi_listener(sender: UnboundedSender<ConnectionEvent>) -> WifiResult<WifiListener> {
-    let wlan_handle = WlanHandle::open()?;
-    let handle = wlan_handle.as_raw();
-
-    // Box the sender to pass as context
-    let context = Box::into_raw(Box::new(sender));
-
-    unsafe {
-        let result = WlanRegisterNotification(
-            handle,
-            WLAN_NOTIFICATION_SOURCE_ACM,
-            false,
-            Some(notification_callback),
-            Some(context as *mut std::ffi::c_void),
-            None,
-            None,
-        );
-
-        if result != ERROR_SUCCESS.0 {
-            let _ = Box::from_raw(context as *mut UnboundedSender<ConnectionEvent>); // Cleanup
-            return Err(WifiError::NotificationRegistrationFailed { code: result });
+    logging::timed("start_wifi_listener", || {
+        let wlan_handle = WlanHandle::open()?;
+        let handle = wlan_handle.as_raw();
+
+        // Box the sender to pass as context
+        let context = Box::into_raw(Box::new(sender));
+
+        unsafe {
+            // MSM reports roaming between access points of the same network
+            let result = WlanRegisterNotification(
+                handle,
+                WLAN_NOTIFICATION_SOURCE_ACM | WLAN_NOTIFICATION_SOURCE_MSM,
+                false,
+                Some(notification_callback),
+                Some(context as *mut std::ffi::c_void),
+                None,
+                None,
+            );
+
+            if result != ERROR_SUCCESS.0 {
+                let _ = Box::from_raw(context as *mut UnboundedSender<ConnectionEvent>); // Cleanup
+                return Err(WifiError::NotificationRegistrationFailed { code: result });
+            }
         }
-    }
 
-    Ok(WifiListener {
-        handle: wlan_handle,
-        context: context as *mut std::ffi::c_void,
+        Ok(WifiListener {
+            handle: wlan_handle,
+            context: context as *mut std::ffi::c_void,
+        })
     })
 }
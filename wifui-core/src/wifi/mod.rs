@@ -0,0 +1,104 @@
+//! WiFi management module for WifUI
+//!
+//! This module provides functionality for managing WiFi connections on Windows
+//! and Linux, including scanning, connecting, disconnecting, and monitoring
+//! connection events.
+
+mod backend;
+#[cfg(windows)]
+mod connection;
+#[cfg(windows)]
+mod handle;
+#[cfg(windows)]
+mod hotspot;
+#[cfg(windows)]
+mod icmp;
+#[cfg_attr(not(windows), allow(dead_code))]
+mod ie;
+#[cfg(windows)]
+mod ipinfo;
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(windows)]
+mod listener;
+#[cfg(windows)]
+mod profile;
+#[cfg_attr(not(windows), allow(dead_code))]
+mod profile_xml;
+#[cfg(windows)]
+mod radio;
+#[cfg(windows)]
+mod scanning;
+#[cfg(windows)]
+mod stats;
+mod types;
+#[cfg(windows)]
+mod win32;
+#[cfg(windows)]
+mod wps;
+
+use crate::error::WifiResult;
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+// Re-export public API
+pub use backend::{
+    connect_enterprise, connect_open, connect_profile, connect_profile_bssid,
+    connect_with_password, connect_wps, default_gateway, disconnect, disconnect_and_wait,
+    export_profile, forget_network, get_connected_ssid, get_saved_profiles, get_wifi_networks,
+    get_wifi_password, hotspot_status, import_profile, interface_stats, ip_info, is_metered,
+    list_interfaces, mac_randomization, ping, radio_state, remove_hotspot, rename_profile,
+    saved_profile_name, scan_networks, set_active_interface, set_auto_connect,
+    set_mac_randomization, set_metered, set_profile_position, set_radio, start_hotspot,
+    stop_hotspot,
+};
+pub use ie::Pmf;
+#[cfg(target_os = "linux")]
+pub use linux::{WifiListener, start_wifi_listener};
+#[cfg(windows)]
+pub use listener::{WifiListener, start_wifi_listener};
+pub use profile_xml::{ProfileEdit, ProfileXml, edit_profile_xml, parse_profile_xml};
+pub use types::{
+    Band, ConnectionEvent, EapMethod, EnterpriseCredentials, HotspotStatus, InterfaceInfo,
+    InterfaceStats, IpInfo, ProfileOptions, RadioState, WifiInfo, format_bssid, parse_bssid,
+    security_family,
+};
+
+/// Start the connection listener and receive its events as a stream
+///
+/// Events stop when the returned [`ConnectionEvents`] is dropped.
+pub fn subscribe() -> WifiResult<ConnectionEvents> {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    Ok(ConnectionEvents {
+        _listener: start_wifi_listener(tx)?,
+        rx,
+    })
+}
+
+/// Stream of connection events that owns its listener
+pub struct ConnectionEvents {
+    _listener: WifiListener,
+    rx: UnboundedReceiver<ConnectionEvent>,
+}
+
+impl ConnectionEvents {
+    /// Wait for the next event from async code
+    pub async fn recv(&mut self) -> Option<ConnectionEvent> {
+        self.rx.recv().await
+    }
+
+    /// Wait for the next event from a plain thread
+    pub fn blocking_recv(&mut self) -> Option<ConnectionEvent> {
+        self.rx.blocking_recv()
+    }
+}
+
+impl Stream for ConnectionEvents {
+    type Item = ConnectionEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ConnectionEvent>> {
+        self.rx.poll_recv(cx)
+    }
+}
@@ -0,0 +1,512 @@
+use crate::error::{WifiError, WifiResult};
+use crate::logging;
+use crate::wifi::handle::{WifiManager, WlanHandle};
+use crate::wifi::profile_xml::{PROFILE_V3_NS, parse_profile_xml};
+use secrecy::SecretString;
+use windows::{
+    Win32::{
+        Foundation::{ERROR_ALREADY_EXISTS, ERROR_SUCCESS},
+        NetworkManagement::{WiFi::*, WindowsConnectionManager::*},
+    },
+    core::{PCWSTR, PWSTR},
+};
+
+/// WLAN_PROFILE_GET_PLAINTEXT_KEY flag to retrieve password from profile
+const WLAN_PROFILE_GET_PLAINTEXT_KEY: u32 = 4;
+
+/// Check if a profile has auto-connect enabled
+pub fn is_profile_auto_connect(
+    handle: &WlanHandle,
+    guid: &windows::core::GUID,
+    profile_name: &str,
+) -> bool {
+    profile_xml(handle, guid, profile_name)
+        .is_some_and(|xml| xml.contains("<connectionMode>auto</connectionMode>"))
+}
+
+/// The `authentication` a saved profile was created with, e.g. `WPA2PSK`
+pub fn profile_authentication(
+    handle: &WlanHandle,
+    guid: &windows::core::GUID,
+    profile_name: &str,
+) -> Option<String> {
+    let xml = profile_xml(handle, guid, profile_name)?;
+    parse_profile_xml(&xml).ok().map(|p| p.authentication)
+}
+
+/// A saved profile's XML without its key, `None` if there's no such profile
+fn profile_xml(
+    handle: &WlanHandle,
+    guid: &windows::core::GUID,
+    profile_name: &str,
+) -> Option<String> {
+    unsafe {
+        let profile_name_wide: Vec<u16> = profile_name
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        let p_profile_name = PCWSTR(profile_name_wide.as_ptr());
+        let mut p_profile_xml = PWSTR::null();
+        let mut flags = 0;
+
+        let result = WlanGetProfile(
+            handle.as_raw(),
+            guid,
+            p_profile_name,
+            None,
+            &mut p_profile_xml,
+            Some(&mut flags),
+            None,
+        );
+
+        if result == ERROR_SUCCESS.0 && !p_profile_xml.is_null() {
+            let xml = p_profile_xml.to_string().unwrap_or_default();
+            WlanFreeMemory(p_profile_xml.as_ptr() as *mut _);
+            return Some(xml);
+        }
+    }
+    None
+}
+
+/// Get list of saved WiFi profile names, in the order Windows prefers them
+pub fn get_saved_profiles() -> WifiResult<Vec<String>> {
+    logging::timed("get_saved_profiles", || {
+        let manager = WifiManager::shared()?;
+        let handle = manager.handle();
+        let guid = manager.interface_guid()?;
+
+        let mut profiles = Vec::new();
+
+        unsafe {
+            let mut profile_list: *mut WLAN_PROFILE_INFO_LIST = std::ptr::null_mut();
+            let result = WlanGetProfileList(handle.as_raw(), &guid, None, &mut profile_list);
+
+            if result == ERROR_SUCCESS.0 {
+                let num_items = (*profile_list).dwNumberOfItems;
+                let items = std::slice::from_raw_parts(
+                    (*profile_list).ProfileInfo.as_ptr(),
+                    num_items as usize,
+                );
+
+                for item in items {
+                    let name = String::from_utf16_lossy(&item.strProfileName);
+                    // Trim null characters if any
+                    let name = name.trim_matches(char::from(0)).to_string();
+                    if !name.is_empty() {
+                        profiles.push(name);
+                    }
+                }
+                WlanFreeMemory(profile_list as *mut _);
+            }
+        }
+
+        Ok(profiles)
+    })
+}
+
+/// Name of the saved profile for an SSID
+///
+/// Profiles are named after their SSID unless renamed, so a profile called
+/// `ssid` wins, then one whose SSID it is. Falls back to `ssid` itself so the
+/// caller gets Windows' usual "not found" error.
+pub fn resolve_profile_name(ssid: &str) -> String {
+    find_profile_name(ssid)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| ssid.to_string())
+}
+
+/// Name of the saved profile for an SSID, `None` if there is none
+pub fn find_profile_name(ssid: &str) -> WifiResult<Option<String>> {
+    let profiles = get_saved_profiles()?;
+    if profiles.iter().any(|name| name == ssid) {
+        return Ok(Some(ssid.to_string()));
+    }
+    let manager = WifiManager::shared()?;
+    let guid = manager.interface_guid()?;
+    Ok(profiles.into_iter().find(|name| {
+        profile_xml(manager.handle(), &guid, name)
+            .and_then(|xml| parse_profile_xml(&xml).ok())
+            .is_some_and(|profile| profile.ssid == ssid)
+    }))
+}
+
+/// Give a saved profile a new name, keeping its place in the preference order
+pub fn rename_profile(name: &str, new_name: &str) -> WifiResult<()> {
+    logging::timed("rename_profile", || {
+        let manager = WifiManager::shared()?;
+        let guid = manager.interface_guid()?;
+
+        unsafe {
+            let old_wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+            let new_wide: Vec<u16> = new_name.encode_utf16().chain(std::iter::once(0)).collect();
+            let result = WlanRenameProfile(
+                manager.handle().as_raw(),
+                &guid,
+                PCWSTR(old_wide.as_ptr()),
+                PCWSTR(new_wide.as_ptr()),
+                None,
+            );
+
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::ProfileRenameFailed { code: result });
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Move a profile to a position in the interface's preference order
+pub fn set_profile_position(ssid: &str, position: u32) -> WifiResult<()> {
+    logging::timed("set_profile_position", || {
+        let ssid = resolve_profile_name(ssid);
+        let manager = WifiManager::shared()?;
+        let handle = manager.handle();
+        let guid = manager.interface_guid()?;
+
+        unsafe {
+            let ssid_wide: Vec<u16> = ssid.encode_utf16().chain(std::iter::once(0)).collect();
+            let p_profile_name = PCWSTR(ssid_wide.as_ptr());
+
+            let result =
+                WlanSetProfilePosition(handle.as_raw(), &guid, p_profile_name, position, None);
+
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::ProfilePositionFailed { code: result });
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Set auto-connect for a profile
+///
+/// Note: Uses WLAN_PROFILE_GET_PLAINTEXT_KEY flag to get the actual key material,
+/// which prevents Windows from reauthenticating when the profile is set back.
+pub fn set_auto_connect(ssid: &str, enable: bool) -> WifiResult<()> {
+    logging::timed("set_auto_connect", || {
+        let ssid = resolve_profile_name(ssid);
+        let manager = WifiManager::shared()?;
+        let handle = manager.handle();
+        let guid = manager.interface_guid()?;
+
+        // WLAN_PROFILE_GET_PLAINTEXT_KEY = 4
+        // This flag is needed to get the actual key material so we can set the profile
+        // back without triggering reauthentication
+        const WLAN_PROFILE_GET_PLAINTEXT_KEY: u32 = 4;
+
+        unsafe {
+            let profile_name_wide: Vec<u16> =
+                ssid.encode_utf16().chain(std::iter::once(0)).collect();
+            let p_profile_name = PCWSTR(profile_name_wide.as_ptr());
+            let mut p_profile_xml = PWSTR::null();
+            let mut flags = WLAN_PROFILE_GET_PLAINTEXT_KEY;
+
+            let result = WlanGetProfile(
+                handle.as_raw(),
+                &guid,
+                p_profile_name,
+                None,
+                &mut p_profile_xml,
+                Some(&mut flags),
+                None,
+            );
+
+            if result != ERROR_SUCCESS.0 || p_profile_xml.is_null() {
+                return Err(WifiError::ProfileGetFailed { code: result });
+            }
+
+            let xml = p_profile_xml.to_string().unwrap_or_default();
+            WlanFreeMemory(p_profile_xml.as_ptr() as *mut _);
+
+            let new_mode = if enable { "auto" } else { "manual" };
+            let new_xml = if xml.contains("<connectionMode>auto</connectionMode>") {
+                xml.replace(
+                    "<connectionMode>auto</connectionMode>",
+                    &format!("<connectionMode>{}</connectionMode>", new_mode),
+                )
+            } else if xml.contains("<connectionMode>manual</connectionMode>") {
+                xml.replace(
+                    "<connectionMode>manual</connectionMode>",
+                    &format!("<connectionMode>{}</connectionMode>", new_mode),
+                )
+            } else {
+                return Err(WifiError::ProfileXmlInvalid);
+            };
+
+            let xml_wide: Vec<u16> = new_xml.encode_utf16().chain(std::iter::once(0)).collect();
+            let p_new_profile_xml = PCWSTR(xml_wide.as_ptr());
+
+            let mut reason_code = 0;
+            let result = WlanSetProfile(
+                handle.as_raw(),
+                &guid,
+                0,
+                p_new_profile_xml,
+                None,
+                true,
+                None,
+                &mut reason_code,
+            );
+
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::ProfileSetFailed {
+                    code: result,
+                    reason: reason_code,
+                });
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Check if a profile connects with a randomized hardware address
+pub fn is_mac_randomized(ssid: &str) -> WifiResult<bool> {
+    logging::timed("is_mac_randomized", || {
+        let xml = plaintext_profile_xml(ssid)?;
+        Ok(xml.contains("<enableRandomization>true</enableRandomization>"))
+    })
+}
+
+/// Turn hardware address randomization on or off for a profile
+///
+/// The value is written explicitly either way, so turning it off overrides
+/// the system-wide "Random hardware addresses" setting for this network.
+pub fn set_mac_randomization(ssid: &str, enable: bool) -> WifiResult<()> {
+    logging::timed("set_mac_randomization", || {
+        let xml = plaintext_profile_xml(ssid)?;
+        let value = if enable { "true" } else { "false" };
+        let new_xml = match (
+            xml.find("<enableRandomization>"),
+            xml.find("</enableRandomization>"),
+        ) {
+            (Some(start), Some(end)) => format!(
+                "{}<enableRandomization>{}{}",
+                &xml[..start],
+                value,
+                &xml[end..]
+            ),
+            _ => {
+                let end = xml
+                    .rfind("</WLANProfile>")
+                    .ok_or(WifiError::ProfileXmlInvalid)?;
+                format!(
+                    "{}<MacRandomization xmlns=\"{}\"><enableRandomization>{}</enableRandomization></MacRandomization>{}",
+                    &xml[..end],
+                    PROFILE_V3_NS,
+                    value,
+                    &xml[end..]
+                )
+            }
+        };
+
+        let manager = WifiManager::shared()?;
+        let guid = manager.interface_guid()?;
+        unsafe {
+            let xml_wide: Vec<u16> = new_xml.encode_utf16().chain(std::iter::once(0)).collect();
+            let mut reason_code = 0;
+            let result = WlanSetProfile(
+                manager.handle().as_raw(),
+                &guid,
+                0,
+                PCWSTR(xml_wide.as_ptr()),
+                None,
+                true,
+                None,
+                &mut reason_code,
+            );
+
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::ProfileSetFailed {
+                    code: result,
+                    reason: reason_code,
+                });
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Check if a profile is set as a metered connection
+pub fn is_metered(ssid: &str) -> WifiResult<bool> {
+    logging::timed("is_metered", || {
+        let ssid = resolve_profile_name(ssid);
+        let guid = WifiManager::shared()?.interface_guid()?;
+
+        unsafe {
+            let ssid_wide: Vec<u16> = ssid.encode_utf16().chain(std::iter::once(0)).collect();
+            let mut size = 0;
+            let mut data: *mut u8 = std::ptr::null_mut();
+            let result = WcmQueryProperty(
+                Some(&guid),
+                PCWSTR(ssid_wide.as_ptr()),
+                wcm_intf_property_connection_cost,
+                None,
+                &mut size,
+                &mut data,
+            );
+
+            if result != ERROR_SUCCESS.0 || data.is_null() {
+                return Err(WifiError::CostQueryFailed { code: result });
+            }
+            let cost = (*(data as *const WCM_CONNECTION_COST_DATA)).ConnectionCost;
+            WcmFreeMemory(data as *mut _);
+
+            let metered = (WCM_CONNECTION_COST_FIXED.0 | WCM_CONNECTION_COST_VARIABLE.0) as u32;
+            Ok(cost & metered != 0)
+        }
+    })
+}
+
+/// Set a profile as a metered (fixed cost) or unrestricted connection
+pub fn set_metered(ssid: &str, metered: bool) -> WifiResult<()> {
+    logging::timed("set_metered", || {
+        let ssid = resolve_profile_name(ssid);
+        let guid = WifiManager::shared()?.interface_guid()?;
+        let cost = if metered {
+            WCM_CONNECTION_COST_FIXED
+        } else {
+            WCM_CONNECTION_COST_UNRESTRICTED
+        };
+        let data = WCM_CONNECTION_COST_DATA {
+            ConnectionCost: cost.0 as u32,
+            CostSource: WCM_CONNECTION_COST_SOURCE_USER,
+        };
+
+        unsafe {
+            let ssid_wide: Vec<u16> = ssid.encode_utf16().chain(std::iter::once(0)).collect();
+            let bytes = std::slice::from_raw_parts(
+                &data as *const WCM_CONNECTION_COST_DATA as *const u8,
+                std::mem::size_of::<WCM_CONNECTION_COST_DATA>(),
+            );
+            let result = WcmSetProperty(
+                Some(&guid),
+                PCWSTR(ssid_wide.as_ptr()),
+                wcm_intf_property_connection_cost,
+                None,
+                Some(bytes),
+            );
+
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::CostSetFailed { code: result });
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Forget (delete) a saved network profile
+pub fn forget_network(ssid: &str) -> WifiResult<()> {
+    logging::timed("forget_network", || {
+        let ssid = resolve_profile_name(ssid);
+        let manager = WifiManager::shared()?;
+        let handle = manager.handle();
+        let guid = manager.interface_guid()?;
+
+        unsafe {
+            let ssid_wide: Vec<u16> = ssid.encode_utf16().chain(std::iter::once(0)).collect();
+            let p_profile_name = PCWSTR(ssid_wide.as_ptr());
+
+            let result = WlanDeleteProfile(handle.as_raw(), &guid, p_profile_name, None);
+
+            // ERROR_NOT_FOUND (1168) is acceptable - the profile doesn't exist
+            if result != ERROR_SUCCESS.0 && result != 1168 {
+                return Err(WifiError::ProfileDeleteFailed { code: result });
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Get a saved profile's XML, with the key in plain text when permitted
+fn plaintext_profile_xml(ssid: &str) -> WifiResult<String> {
+    let ssid = resolve_profile_name(ssid);
+    let manager = WifiManager::shared()?;
+    let handle = manager.handle();
+    let guid = manager.interface_guid()?;
+
+    unsafe {
+        let profile_name_wide: Vec<u16> = ssid.encode_utf16().chain(std::iter::once(0)).collect();
+        let p_profile_name = PCWSTR(profile_name_wide.as_ptr());
+        let mut p_profile_xml = PWSTR::null();
+        let mut flags = WLAN_PROFILE_GET_PLAINTEXT_KEY;
+
+        let result = WlanGetProfile(
+            handle.as_raw(),
+            &guid,
+            p_profile_name,
+            None,
+            &mut p_profile_xml,
+            Some(&mut flags),
+            None,
+        );
+
+        if result != ERROR_SUCCESS.0 || p_profile_xml.is_null() {
+            return Err(WifiError::ProfileGetFailed { code: result });
+        }
+
+        let xml = p_profile_xml.to_string().unwrap_or_default();
+        WlanFreeMemory(p_profile_xml.as_ptr() as *mut _);
+        Ok(xml)
+    }
+}
+
+/// Get WiFi password from a saved profile
+/// Returns None if profile doesn't exist or has no password (open network)
+pub fn get_wifi_password(ssid: &str) -> WifiResult<Option<SecretString>> {
+    logging::timed("get_wifi_password", || {
+        let xml = plaintext_profile_xml(ssid)?;
+
+        if let Some(start) = xml.find("<keyMaterial>") {
+            if let Some(end) = xml.find("</keyMaterial>") {
+                let password = xml[start + 13..end].to_string();
+                if !password.is_empty() {
+                    return Ok(Some(SecretString::from(password)));
+                }
+            }
+        }
+
+        Ok(None)
+    })
+}
+
+/// Export a saved profile's XML, including its key so it can be imported elsewhere
+pub fn export_profile(ssid: &str) -> WifiResult<String> {
+    logging::timed("export_profile", || plaintext_profile_xml(ssid))
+}
+
+/// Validate a profile XML document and install it, returning the profile name
+pub fn import_profile(xml: &str, overwrite: bool) -> WifiResult<String> {
+    logging::timed("import_profile", || {
+        let profile = parse_profile_xml(xml)?;
+        let manager = WifiManager::shared()?;
+        let handle = manager.handle();
+        let guid = manager.interface_guid()?;
+
+        unsafe {
+            let xml_wide: Vec<u16> = xml.encode_utf16().chain(std::iter::once(0)).collect();
+            let mut reason_code = 0;
+            let result = WlanSetProfile(
+                handle.as_raw(),
+                &guid,
+                0,
+                PCWSTR(xml_wide.as_ptr()),
+                None,
+                overwrite,
+                None,
+                &mut reason_code,
+            );
+
+            if result == ERROR_ALREADY_EXISTS.0 {
+                return Err(WifiError::ProfileExists(profile.name));
+            }
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::ProfileAddFailed {
+                    code: result,
+                    reason: reason_code,
+                });
+            }
+        }
+        Ok(profile.name)
+    })
+}
@@ -0,0 +1,578 @@
+//! WLAN profile XML
+//!
+//! Builds the profile documents Windows stores for each network and parses
+//! them back, so exported profiles can be validated before they are
+//! installed on either platform.
+
+use crate::error::{WifiError, WifiResult};
+use crate::wifi::types::{EapMethod, EnterpriseCredentials, ProfileOptions};
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::reader::Reader;
+use quick_xml::writer::Writer;
+use secrecy::{ExposeSecret, SecretString};
+use std::io::Cursor;
+
+const EAP_HOST_CONFIG_NS: &str = "http://www.microsoft.com/provisioning/EapHostConfig";
+const EAP_COMMON_NS: &str = "http://www.microsoft.com/provisioning/EapCommon";
+const BASE_EAP_CONNECTION_NS: &str =
+    "http://www.microsoft.com/provisioning/BaseEapConnectionPropertiesV1";
+
+/// Namespace of the Windows 10 profile schema that added `MacRandomization`
+pub const PROFILE_V3_NS: &str = "http://www.microsoft.com/networking/WLAN/profile/v3";
+
+/// Namespace of the schema that added `transitionMode` for WPA3-SAE
+const PROFILE_V4_NS: &str = "http://www.microsoft.com/networking/WLAN/profile/v4";
+
+/// EAP type numbers and the author ID of Microsoft's TTLS implementation
+const EAP_TYPE_PEAP: &str = "25";
+const EAP_TYPE_MSCHAPV2: &str = "26";
+const EAP_TYPE_TTLS: &str = "21";
+const EAP_TTLS_AUTHOR_ID: &str = "311";
+
+/// Create a WiFi profile XML document
+///
+/// With `eap` set, the profile uses 802.1X and carries an EAPConfig block
+/// instead of a shared key; the credentials themselves are stored separately
+/// with [`create_eap_user_xml`]. With `random_mac` the adapter connects with a
+/// randomized hardware address. With `require_pmf`, WPA3-SAE profiles leave
+/// transition mode so they never fall back to WPA2 without PMF; Windows has no
+/// profile setting for WPA2 itself.
+pub fn create_profile_xml(
+    ssid: &str,
+    auth: &str,
+    cipher: &str,
+    password: Option<&SecretString>,
+    eap: Option<&EnterpriseCredentials>,
+    options: ProfileOptions,
+) -> String {
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    let _ = writer.write_event(Event::Decl(BytesDecl::new("1.0", None, None)));
+
+    let mut wlan_profile = BytesStart::new("WLANProfile");
+    wlan_profile.push_attribute((
+        "xmlns",
+        "http://www.microsoft.com/networking/WLAN/profile/v1",
+    ));
+    let _ = writer.write_event(Event::Start(wlan_profile));
+
+    write_element(&mut writer, "name", ssid);
+
+    let _ = writer.write_event(Event::Start(BytesStart::new("SSIDConfig")));
+    let _ = writer.write_event(Event::Start(BytesStart::new("SSID")));
+    write_element(&mut writer, "name", ssid);
+    let _ = writer.write_event(Event::End(BytesEnd::new("SSID")));
+
+    if options.hidden {
+        write_element(&mut writer, "nonBroadcast", "true");
+    }
+    let _ = writer.write_event(Event::End(BytesEnd::new("SSIDConfig")));
+
+    write_element(&mut writer, "connectionType", "ESS");
+    write_element(&mut writer, "connectionMode", "manual");
+
+    let _ = writer.write_event(Event::Start(BytesStart::new("MSM")));
+    let _ = writer.write_event(Event::Start(BytesStart::new("security")));
+    let _ = writer.write_event(Event::Start(BytesStart::new("authEncryption")));
+
+    let (xml_auth, final_cipher) = xml_auth_cipher(auth, cipher);
+    write_element(&mut writer, "authentication", xml_auth);
+    write_element(&mut writer, "encryption", final_cipher);
+    write_element(
+        &mut writer,
+        "useOneX",
+        if eap.is_some() { "true" } else { "false" },
+    );
+    if xml_auth == "WPA3SAE" && options.require_pmf {
+        write_ns_element(&mut writer, "transitionMode", PROFILE_V4_NS, "false");
+    }
+    let _ = writer.write_event(Event::End(BytesEnd::new("authEncryption")));
+
+    if let Some(credentials) = eap {
+        write_onex(&mut writer, credentials);
+    } else if let Some(pwd) = password {
+        let _ = writer.write_event(Event::Start(BytesStart::new("sharedKey")));
+        write_element(&mut writer, "keyType", "passPhrase");
+        write_element(&mut writer, "protected", "false");
+        write_element(&mut writer, "keyMaterial", pwd.expose_secret());
+        let _ = writer.write_event(Event::End(BytesEnd::new("sharedKey")));
+    }
+
+    let _ = writer.write_event(Event::End(BytesEnd::new("security")));
+    let _ = writer.write_event(Event::End(BytesEnd::new("MSM")));
+
+    if options.random_mac {
+        start_element(&mut writer, "MacRandomization", Some(PROFILE_V3_NS));
+        write_element(&mut writer, "enableRandomization", "true");
+        end_element(&mut writer, "MacRandomization");
+    }
+
+    let _ = writer.write_event(Event::End(BytesEnd::new("WLANProfile")));
+
+    String::from_utf8(writer.into_inner().into_inner()).unwrap_or_default()
+}
+
+/// `authentication` and `encryption` values for a security as reported by scans
+fn xml_auth_cipher(auth: &str, cipher: &str) -> (&'static str, &'static str) {
+    let (xml_auth, xml_cipher) = match auth {
+        "WPA3-SAE" => ("WPA3SAE", "AES"),
+        "WPA3ENT" => ("WPA3ENT", "AES"),
+        "WPA3ENT192" => ("WPA3ENT192", "AES"),
+        "WPA3" => ("WPA3ENT192", "AES"),
+        "WPA2-PSK" => ("WPA2PSK", "AES"),
+        "WPA2" => ("WPA2", "AES"),
+        "WPA-PSK" => ("WPAPSK", if cipher == "AES" { "AES" } else { "TKIP" }),
+        "WPA" => ("WPA", if cipher == "AES" { "AES" } else { "TKIP" }),
+        "Shared" | "WEP" => ("shared", "WEP"),
+        "Open" | "open" => ("open", "none"),
+        _ => ("WPA2PSK", "AES"),
+    };
+    (xml_auth, if cipher == "GCMP" { "GCMP" } else { xml_cipher })
+}
+
+fn write_element<W: std::io::Write>(writer: &mut Writer<W>, name: &str, value: &str) {
+    let _ = writer.write_event(Event::Start(BytesStart::new(name)));
+    let _ = writer.write_event(Event::Text(BytesText::new(value)));
+    let _ = writer.write_event(Event::End(BytesEnd::new(name)));
+}
+
+fn start_element<W: std::io::Write>(writer: &mut Writer<W>, name: &str, xmlns: Option<&str>) {
+    let mut start = BytesStart::new(name);
+    if let Some(ns) = xmlns {
+        start.push_attribute(("xmlns", ns));
+    }
+    let _ = writer.write_event(Event::Start(start));
+}
+
+fn end_element<W: std::io::Write>(writer: &mut Writer<W>, name: &str) {
+    let _ = writer.write_event(Event::End(BytesEnd::new(name)));
+}
+
+fn write_ns_element<W: std::io::Write>(writer: &mut Writer<W>, name: &str, ns: &str, value: &str) {
+    start_element(writer, name, Some(ns));
+    let _ = writer.write_event(Event::Text(BytesText::new(value)));
+    end_element(writer, name);
+}
+
+/// Write the `<OneX>` block with the EapHostConfig for the chosen method
+fn write_onex<W: std::io::Write>(writer: &mut Writer<W>, credentials: &EnterpriseCredentials) {
+    start_element(
+        writer,
+        "OneX",
+        Some("http://www.microsoft.com/networking/OneX/v1"),
+    );
+    write_element(writer, "authMode", "user");
+    start_element(writer, "EAPConfig", None);
+    start_element(writer, "EapHostConfig", Some(EAP_HOST_CONFIG_NS));
+
+    let (eap_type, author_id) = match credentials.method {
+        EapMethod::Peap => (EAP_TYPE_PEAP, "0"),
+        EapMethod::Ttls => (EAP_TYPE_TTLS, EAP_TTLS_AUTHOR_ID),
+    };
+    start_element(writer, "EapMethod", None);
+    write_ns_element(writer, "Type", EAP_COMMON_NS, eap_type);
+    write_ns_element(writer, "VendorId", EAP_COMMON_NS, "0");
+    write_ns_element(writer, "VendorType", EAP_COMMON_NS, "0");
+    write_ns_element(writer, "AuthorId", EAP_COMMON_NS, author_id);
+    end_element(writer, "EapMethod");
+
+    start_element(writer, "Config", Some(EAP_HOST_CONFIG_NS));
+    match credentials.method {
+        EapMethod::Peap => write_peap_config(writer, credentials.validate_ca),
+        EapMethod::Ttls => write_ttls_config(writer),
+    }
+    end_element(writer, "Config");
+
+    end_element(writer, "EapHostConfig");
+    end_element(writer, "EAPConfig");
+    end_element(writer, "OneX");
+}
+
+fn write_peap_config<W: std::io::Write>(writer: &mut Writer<W>, validate_ca: bool) {
+    let peap_ns = "http://www.microsoft.com/provisioning/MsPeapConnectionPropertiesV1";
+    let peap_v2_ns = "http://www.microsoft.com/provisioning/MsPeapConnectionPropertiesV2";
+
+    start_element(writer, "Eap", Some(BASE_EAP_CONNECTION_NS));
+    write_element(writer, "Type", EAP_TYPE_PEAP);
+    start_element(writer, "EapType", Some(peap_ns));
+
+    start_element(writer, "ServerValidation", None);
+    write_element(writer, "DisableUserPromptForServerValidation", "false");
+    end_element(writer, "ServerValidation");
+    write_element(writer, "FastReconnect", "true");
+    write_element(writer, "InnerEapOptional", "false");
+
+    start_element(writer, "Eap", Some(BASE_EAP_CONNECTION_NS));
+    write_element(writer, "Type", EAP_TYPE_MSCHAPV2);
+    start_element(
+        writer,
+        "EapType",
+        Some("http://www.microsoft.com/provisioning/MsChapV2ConnectionPropertiesV1"),
+    );
+    write_element(writer, "UseWinLogonCredentials", "false");
+    end_element(writer, "EapType");
+    end_element(writer, "Eap");
+
+    write_element(writer, "EnableQuarantineChecks", "false");
+    write_element(writer, "RequireCryptoBinding", "false");
+    start_element(writer, "PeapExtensions", None);
+    write_ns_element(
+        writer,
+        "PerformServerValidation",
+        peap_v2_ns,
+        if validate_ca { "true" } else { "false" },
+    );
+    write_ns_element(writer, "AcceptServerName", peap_v2_ns, "false");
+    end_element(writer, "PeapExtensions");
+
+    end_element(writer, "EapType");
+    end_element(writer, "Eap");
+}
+
+fn write_ttls_config<W: std::io::Write>(writer: &mut Writer<W>) {
+    start_element(
+        writer,
+        "EapTtls",
+        Some("http://www.microsoft.com/provisioning/EapTtlsConnectionPropertiesV1"),
+    );
+
+    // Windows' TTLS always checks the server certificate; leaving the prompt
+    // enabled lets the user trust an unknown CA instead of failing outright
+    start_element(writer, "ServerValidation", None);
+    write_element(writer, "ServerNames", "");
+    write_element(writer, "DisablePrompt", "false");
+    end_element(writer, "ServerValidation");
+
+    start_element(writer, "Phase2Authentication", None);
+    start_element(writer, "MSCHAPv2Authentication", None);
+    write_element(writer, "UseWinlogonCredentials", "false");
+    end_element(writer, "MSCHAPv2Authentication");
+    end_element(writer, "Phase2Authentication");
+
+    start_element(writer, "Phase1Identity", None);
+    write_element(writer, "IdentityPrivacy", "false");
+    end_element(writer, "Phase1Identity");
+
+    end_element(writer, "EapTtls");
+}
+
+/// Create the EapHostUserCredentials XML holding the identity and password
+/// of an 802.1X profile, for `WlanSetProfileEapXmlUserData`
+pub fn create_eap_user_xml(credentials: &EnterpriseCredentials) -> String {
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    let identity = credentials.identity.as_str();
+    let password = credentials.password.expose_secret();
+
+    let mut root = BytesStart::new("EapHostUserCredentials");
+    root.push_attribute((
+        "xmlns",
+        "http://www.microsoft.com/provisioning/EapHostUserCredentials",
+    ));
+    root.push_attribute(("xmlns:eapCommon", EAP_COMMON_NS));
+    root.push_attribute((
+        "xmlns:baseEap",
+        "http://www.microsoft.com/provisioning/BaseEapMethodUserCredentials",
+    ));
+    let _ = writer.write_event(Event::Start(root));
+
+    let (eap_type, author_id) = match credentials.method {
+        EapMethod::Peap => (EAP_TYPE_PEAP, "0"),
+        EapMethod::Ttls => (EAP_TYPE_TTLS, EAP_TTLS_AUTHOR_ID),
+    };
+    start_element(&mut writer, "EapMethod", None);
+    write_element(&mut writer, "eapCommon:Type", eap_type);
+    write_element(&mut writer, "eapCommon:AuthorId", author_id);
+    end_element(&mut writer, "EapMethod");
+
+    match credentials.method {
+        EapMethod::Peap => {
+            let mut creds = BytesStart::new("Credentials");
+            creds.push_attribute((
+                "xmlns:baseEap",
+                "http://www.microsoft.com/provisioning/BaseEapUserPropertiesV1",
+            ));
+            creds.push_attribute((
+                "xmlns:MsPeap",
+                "http://www.microsoft.com/provisioning/MsPeapUserPropertiesV1",
+            ));
+            creds.push_attribute((
+                "xmlns:MsChapV2",
+                "http://www.microsoft.com/provisioning/MsChapV2UserPropertiesV1",
+            ));
+            let _ = writer.write_event(Event::Start(creds));
+
+            start_element(&mut writer, "baseEap:Eap", None);
+            write_element(&mut writer, "baseEap:Type", EAP_TYPE_PEAP);
+            start_element(&mut writer, "MsPeap:EapType", None);
+            write_element(&mut writer, "MsPeap:RoutingIdentity", identity);
+            start_element(&mut writer, "baseEap:Eap", None);
+            write_element(&mut writer, "baseEap:Type", EAP_TYPE_MSCHAPV2);
+            start_element(&mut writer, "MsChapV2:EapType", None);
+            write_element(&mut writer, "MsChapV2:Username", identity);
+            write_element(&mut writer, "MsChapV2:Password", password);
+            write_element(&mut writer, "MsChapV2:LogonDomain", "");
+            end_element(&mut writer, "MsChapV2:EapType");
+            end_element(&mut writer, "baseEap:Eap");
+            end_element(&mut writer, "MsPeap:EapType");
+            end_element(&mut writer, "baseEap:Eap");
+
+            end_element(&mut writer, "Credentials");
+        }
+        EapMethod::Ttls => {
+            start_element(&mut writer, "Credentials", None);
+            start_element(
+                &mut writer,
+                "EapTtls",
+                Some("http://www.microsoft.com/provisioning/EapTtlsUserPropertiesV1"),
+            );
+            write_element(&mut writer, "Username", identity);
+            write_element(&mut writer, "Password", password);
+            end_element(&mut writer, "EapTtls");
+            end_element(&mut writer, "Credentials");
+        }
+    }
+
+    end_element(&mut writer, "EapHostUserCredentials");
+
+    String::from_utf8(writer.into_inner().into_inner()).unwrap_or_default()
+}
+
+/// The parts of a profile XML document needed to install it
+#[derive(Debug, Default)]
+pub struct ProfileXml {
+    pub name: String,
+    pub ssid: String,
+    /// `authentication` value, e.g. `WPA2PSK`, `WPA3SAE` or `open`
+    pub authentication: String,
+    pub encryption: String,
+    pub key: Option<SecretString>,
+    /// The key material is encrypted for the machine that exported it
+    pub key_protected: bool,
+    pub hidden: bool,
+    pub auto_connect: bool,
+    pub one_x: bool,
+    /// Connects with a randomized hardware address
+    pub random_mac: bool,
+}
+
+fn invalid(reason: impl ToString) -> WifiError {
+    WifiError::InvalidProfileXml(reason.to_string())
+}
+
+/// Parse a profile XML document, checking it has what WlanSetProfile needs
+pub fn parse_profile_xml(xml: &str) -> WifiResult<ProfileXml> {
+    // Text isn't trimmed: entity references split values into several events
+    let mut reader = Reader::from_str(xml);
+
+    let mut profile = ProfileXml::default();
+    let mut authentication = None;
+    let mut ssid_hex = None;
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event().map_err(invalid)? {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                if path.is_empty() && name != "WLANProfile" {
+                    return Err(invalid("not a WLANProfile document"));
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::Text(t) => text.push_str(&t.decode().map_err(invalid)?),
+            Event::GeneralRef(r) => match r.resolve_char_ref().map_err(invalid)? {
+                Some(c) => text.push(c),
+                None => {
+                    let entity = r.decode().map_err(invalid)?;
+                    let value = resolve_predefined_entity(&entity)
+                        .ok_or_else(|| invalid(format!("unknown entity &{};", entity)))?;
+                    text.push_str(value);
+                }
+            },
+            Event::End(_) => {
+                let value = std::mem::take(&mut text);
+                let names: Vec<&str> = path.iter().map(String::as_str).collect();
+                match names.as_slice() {
+                    ["WLANProfile", "name"] => profile.name = value,
+                    ["WLANProfile", "SSIDConfig", "SSID", "name"] => profile.ssid = value,
+                    ["WLANProfile", "SSIDConfig", "SSID", "hex"] => ssid_hex = Some(value),
+                    ["WLANProfile", "SSIDConfig", "nonBroadcast"] => {
+                        profile.hidden = value == "true"
+                    }
+                    ["WLANProfile", "connectionMode"] => profile.auto_connect = value == "auto",
+                    [.., "authEncryption", "authentication"] => authentication = Some(value),
+                    [.., "authEncryption", "encryption"] => profile.encryption = value,
+                    [.., "authEncryption", "useOneX"] => profile.one_x = value == "true",
+                    [.., "sharedKey", "protected"] => profile.key_protected = value == "true",
+                    [.., "MacRandomization", "enableRandomization"] => {
+                        profile.random_mac = value == "true"
+                    }
+                    [.., "sharedKey", "keyMaterial"] => {
+                        profile.key = Some(SecretString::from(value))
+                    }
+                    _ => {}
+                }
+                path.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if profile.ssid.is_empty()
+        && let Some(hex) = ssid_hex
+    {
+        profile.ssid = decode_hex_ssid(&hex).ok_or_else(|| invalid("bad SSID hex"))?;
+    }
+    if profile.name.is_empty() {
+        return Err(invalid("missing profile <name>"));
+    }
+    if profile.ssid.is_empty() {
+        return Err(invalid("missing SSID"));
+    }
+    profile.authentication = authentication.ok_or_else(|| invalid("missing <authentication>"))?;
+    if profile.authentication != "open" && !profile.one_x && profile.key.is_none() {
+        return Err(invalid("secured profile has no <keyMaterial>"));
+    }
+
+    Ok(profile)
+}
+
+/// Changes made to a saved profile, applied with [`edit_profile_xml`]
+#[derive(Debug, Clone)]
+pub struct ProfileEdit {
+    pub auto_connect: bool,
+    pub hidden: bool,
+    /// New security as reported by scans (`Open`, `WPA2-PSK`, `WPA3-SAE`);
+    /// `None` keeps the current one, which 802.1X profiles always do
+    pub authentication: Option<String>,
+    /// New passphrase; `None` keeps the stored key
+    pub password: Option<SecretString>,
+}
+
+/// Apply an edit to a saved profile's XML, leaving the rest of the document
+/// (EAP settings, MAC randomization, schema extensions) as it was
+pub fn edit_profile_xml(xml: &str, edit: &ProfileEdit) -> WifiResult<String> {
+    let profile = parse_profile_xml(xml)?;
+    let security = match &edit.authentication {
+        Some(_) if profile.one_x => {
+            return Err(invalid("the security of 802.1X profiles can't be changed"));
+        }
+        Some(auth) => Some(xml_auth_cipher(auth, "AES")),
+        None => None,
+    };
+    let authentication = security.map_or(profile.authentication.as_str(), |(auth, _)| auth);
+    let drop_key = authentication == "open";
+    let add_key = !drop_key && !profile.one_x && profile.key.is_none();
+    let password = edit
+        .password
+        .as_ref()
+        .map(|p| p.expose_secret().to_string());
+    if add_key && password.is_none() {
+        return Err(invalid("a secured network needs a password"));
+    }
+
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Cursor::new(Vec::new()));
+    let mut path: Vec<String> = Vec::new();
+    // Depth of the element whose content is being skipped, and whether its
+    // end tag is still written because only its text was replaced
+    let mut skipping: Option<(usize, bool)> = None;
+
+    loop {
+        let event = reader.read_event().map_err(invalid)?;
+        match &event {
+            Event::Eof => break,
+            Event::Start(e) => {
+                path.push(String::from_utf8_lossy(e.local_name().as_ref()).into_owned());
+                if skipping.is_some() {
+                    continue;
+                }
+                let names: Vec<&str> = path.iter().map(String::as_str).collect();
+                let replacement = match names.as_slice() {
+                    ["WLANProfile", "connectionMode"] => {
+                        Some(if edit.auto_connect { "auto" } else { "manual" }.to_string())
+                    }
+                    [.., "authEncryption", "authentication"] => {
+                        security.map(|(auth, _)| auth.to_string())
+                    }
+                    [.., "authEncryption", "encryption"] => {
+                        security.map(|(_, cipher)| cipher.to_string())
+                    }
+                    [.., "sharedKey", "keyType"] => password.as_ref().map(|_| "passPhrase".into()),
+                    [.., "sharedKey", "protected"] => password.as_ref().map(|_| "false".into()),
+                    [.., "sharedKey", "keyMaterial"] => password.clone(),
+                    _ => None,
+                };
+                // nonBroadcast is written again before </SSIDConfig> if still wanted
+                let dropped = match names.as_slice() {
+                    ["WLANProfile", "SSIDConfig", "nonBroadcast"] => true,
+                    [.., "authEncryption", "transitionMode"] => authentication != "WPA3SAE",
+                    [.., "security", "sharedKey"] => drop_key,
+                    _ => false,
+                };
+                if dropped {
+                    skipping = Some((path.len(), false));
+                    continue;
+                }
+                let _ = writer.write_event(event);
+                if let Some(value) = replacement {
+                    let _ = writer.write_event(Event::Text(BytesText::new(&value)));
+                    skipping = Some((path.len(), true));
+                }
+            }
+            Event::End(_) => {
+                let depth = path.len();
+                match skipping {
+                    Some((start, _)) if depth > start => {}
+                    Some((_, keep_end)) => {
+                        skipping = None;
+                        if keep_end {
+                            let _ = writer.write_event(event);
+                        }
+                    }
+                    None => {
+                        let names: Vec<&str> = path.iter().map(String::as_str).collect();
+                        match names.as_slice() {
+                            ["WLANProfile", "SSIDConfig"] if edit.hidden => {
+                                write_element(&mut writer, "nonBroadcast", "true");
+                            }
+                            [.., "MSM", "security"] if add_key => {
+                                start_element(&mut writer, "sharedKey", None);
+                                write_element(&mut writer, "keyType", "passPhrase");
+                                write_element(&mut writer, "protected", "false");
+                                write_element(
+                                    &mut writer,
+                                    "keyMaterial",
+                                    password.as_deref().unwrap_or_default(),
+                                );
+                                end_element(&mut writer, "sharedKey");
+                            }
+                            _ => {}
+                        }
+                        let _ = writer.write_event(event);
+                    }
+                }
+                path.pop();
+            }
+            _ if skipping.is_some() => {}
+            _ => {
+                let _ = writer.write_event(event);
+            }
+        }
+    }
+
+    String::from_utf8(writer.into_inner().into_inner()).map_err(invalid)
+}
+
+fn decode_hex_ssid(hex: &str) -> Option<String> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
@@ -0,0 +1,104 @@
+//! Software radio switch of the WiFi interface
+
+use crate::error::{WifiError, WifiResult};
+use crate::logging;
+use crate::wifi::handle::{WifiManager, WlanHandle};
+use crate::wifi::types::RadioState;
+use windows::Win32::{Foundation::ERROR_SUCCESS, NetworkManagement::WiFi::*};
+
+/// Radio state of the active interface; off if any PHY is off
+pub fn radio_state() -> WifiResult<RadioState> {
+    logging::timed("radio_state", || {
+        let manager = WifiManager::shared()?;
+        let handle = manager.handle();
+        let guid = manager.interface_guid()?;
+        let phys = query_phys(handle, &guid)?;
+
+        let state = if phys
+            .iter()
+            .any(|phy| phy.dot11HardwareRadioState == dot11_radio_state_off)
+        {
+            RadioState::HardwareOff
+        } else if phys
+            .iter()
+            .any(|phy| phy.dot11SoftwareRadioState == dot11_radio_state_off)
+        {
+            RadioState::Off
+        } else {
+            RadioState::On
+        };
+        Ok(state)
+    })
+}
+
+/// Turn the software radio of every PHY of the active interface on or off
+pub fn set_radio(on: bool) -> WifiResult<()> {
+    logging::timed("set_radio", || {
+        let manager = WifiManager::shared()?;
+        let handle = manager.handle();
+        let guid = manager.interface_guid()?;
+        let phys = query_phys(handle, &guid)?;
+        if on
+            && phys
+                .iter()
+                .any(|phy| phy.dot11HardwareRadioState == dot11_radio_state_off)
+        {
+            return Err(WifiError::RadioHardwareOff);
+        }
+
+        for phy in phys {
+            let state = WLAN_PHY_RADIO_STATE {
+                dwPhyIndex: phy.dwPhyIndex,
+                dot11SoftwareRadioState: if on {
+                    dot11_radio_state_on
+                } else {
+                    dot11_radio_state_off
+                },
+                // Ignored when setting; only the software switch can be changed
+                dot11HardwareRadioState: phy.dot11HardwareRadioState,
+            };
+            let result = unsafe {
+                WlanSetInterface(
+                    handle.as_raw(),
+                    &guid,
+                    wlan_intf_opcode_radio_state,
+                    std::mem::size_of::<WLAN_PHY_RADIO_STATE>() as u32,
+                    &state as *const _ as *const std::ffi::c_void,
+                    None,
+                )
+            };
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::RadioSetFailed { code: result });
+            }
+        }
+        Ok(())
+    })
+}
+
+fn query_phys(
+    handle: &WlanHandle,
+    guid: &windows::core::GUID,
+) -> WifiResult<Vec<WLAN_PHY_RADIO_STATE>> {
+    unsafe {
+        let mut data_size = 0u32;
+        let mut data_ptr: *mut std::ffi::c_void = std::ptr::null_mut();
+        let result = WlanQueryInterface(
+            handle.as_raw(),
+            guid,
+            wlan_intf_opcode_radio_state,
+            None,
+            &mut data_size,
+            &mut data_ptr,
+            None,
+        );
+        if result != ERROR_SUCCESS.0 {
+            return Err(WifiError::RadioQueryFailed { code: result });
+        }
+
+        let radio = &*(data_ptr as *const WLAN_RADIO_STATE);
+        let count = (radio.dwNumberOfPhys as usize).min(radio.PhyRadioState.len());
+        let phys = radio.PhyRadioState[..count].to_vec();
+        WlanFreeMemory(data_ptr);
+        Ok(phys)
+    }
+}
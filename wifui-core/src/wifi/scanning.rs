@@ -0,0 +1,21 @@
+use crate::error::{WifiError, WifiResult};
+use crate::logging;
+use crate::wifi::handle::WifiManager;
+use windows::Win32::{Foundation::ERROR_SUCCESS, NetworkManagement::WiFi::*};
+
+/// Trigger a network scan
+pub fn scan_networks() -> WifiResult<()> {
+    logging::timed("scan_networks", || {
+        let manager = WifiManager::shared()?;
+        let handle = manager.handle();
+        let guid = manager.interface_guid()?;
+
+        unsafe {
+            let result = WlanScan(handle.as_raw(), &guid, None, None, None);
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::ScanFailed { code: result });
+            }
+        }
+        Ok(())
+    })
+}
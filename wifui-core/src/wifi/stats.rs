@@ -0,0 +1,56 @@
+//! Frame counters of the WiFi interface
+
+use crate::error::{WifiError, WifiResult};
+use crate::logging;
+use crate::wifi::handle::WifiManager;
+use crate::wifi::types::InterfaceStats;
+use windows::Win32::{Foundation::ERROR_SUCCESS, NetworkManagement::WiFi::*};
+
+/// Counters of the active interface, summed over its PHYs
+pub fn interface_stats() -> WifiResult<InterfaceStats> {
+    logging::timed("interface_stats", || {
+        let manager = WifiManager::shared()?;
+        let guid = manager.interface_guid()?;
+        unsafe {
+            let mut data_size = 0u32;
+            let mut data_ptr: *mut std::ffi::c_void = std::ptr::null_mut();
+            let result = WlanQueryInterface(
+                manager.handle().as_raw(),
+                &guid,
+                wlan_intf_opcode_statistics,
+                None,
+                &mut data_size,
+                &mut data_ptr,
+                None,
+            );
+            if result != ERROR_SUCCESS.0 {
+                return Err(WifiError::StatsQueryFailed { code: result });
+            }
+
+            let stats = &*(data_ptr as *const WLAN_STATISTICS);
+            // PhyCounters is a variable-length array; trust the size over the count
+            let room = (data_size as usize)
+                .saturating_sub(std::mem::offset_of!(WLAN_STATISTICS, PhyCounters))
+                / std::mem::size_of::<WLAN_PHY_FRAME_STATISTICS>();
+            let count = (stats.dwNumberOfPhys as usize).min(room);
+            let phys = std::slice::from_raw_parts(stats.PhyCounters.as_ptr(), count);
+
+            let mut counters = InterfaceStats::default();
+            for phy in phys {
+                counters.tx_frames += phy.ullTransmittedFrameCount;
+                counters.rx_frames += phy.ullReceivedFrameCount;
+                counters.retries += phy.ullRetryCount;
+                counters.failed += phy.ullFailedCount;
+            }
+            // Some drivers only keep the MAC counters
+            if counters.tx_frames == 0 && counters.rx_frames == 0 {
+                let (ucast, mcast) = (&stats.MacUcastCounters, &stats.MacMcastCounters);
+                counters.tx_frames =
+                    ucast.ullTransmittedFrameCount + mcast.ullTransmittedFrameCount;
+                counters.rx_frames = ucast.ullReceivedFrameCount + mcast.ullReceivedFrameCount;
+            }
+            WlanFreeMemory(data_ptr);
+            Ok(counters)
+        }
+    })
+}
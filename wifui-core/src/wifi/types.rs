@@ -0,0 +1,389 @@
+use crate::wifi::ie::Capabilities;
+use secrecy::SecretString;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// A WLAN adapter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceInfo {
+    /// Platform identifier (interface GUID on Windows, device name on Linux)
+    pub id: String,
+    pub description: String,
+    pub is_connected: bool,
+}
+
+/// WiFi frequency band
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Band {
+    Ghz2_4,
+    Ghz5,
+    Ghz6,
+}
+
+impl Band {
+    /// Derive the band from a channel center frequency in kHz
+    pub fn from_frequency(frequency: u32) -> Option<Self> {
+        match frequency {
+            2_400_000..=2_500_000 => Some(Band::Ghz2_4),
+            5_000_000..=5_900_000 => Some(Band::Ghz5),
+            5_925_000..=7_125_000 => Some(Band::Ghz6),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Band::Ghz2_4 => "2.4 GHz",
+            Band::Ghz5 => "5 GHz",
+            Band::Ghz6 => "6 GHz",
+        }
+    }
+
+    /// Compact label for list badges
+    pub fn short_label(&self) -> &'static str {
+        match self {
+            Band::Ghz2_4 => "2.4G",
+            Band::Ghz5 => "5G",
+            Band::Ghz6 => "6G",
+        }
+    }
+}
+
+/// Wi-Fi generation derived from the PHY type and band
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Generation {
+    Wifi4,
+    Wifi5,
+    Wifi6,
+    Wifi6E,
+    Wifi7,
+}
+
+impl Generation {
+    /// Derive the generation from a PHY type string and the band it was seen on
+    pub fn from_phy(phy_type: &str, band: Option<Band>) -> Option<Self> {
+        if phy_type.contains("Wi-Fi 7") {
+            Some(Generation::Wifi7)
+        } else if phy_type.contains("Wi-Fi 6") {
+            if band == Some(Band::Ghz6) {
+                Some(Generation::Wifi6E)
+            } else {
+                Some(Generation::Wifi6)
+            }
+        } else if phy_type.contains("Wi-Fi 5") {
+            Some(Generation::Wifi5)
+        } else if phy_type.contains("Wi-Fi 4") {
+            Some(Generation::Wifi4)
+        } else {
+            None
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Generation::Wifi4 => "4",
+            Generation::Wifi5 => "5",
+            Generation::Wifi6 => "6",
+            Generation::Wifi6E => "6E",
+            Generation::Wifi7 => "7",
+        }
+    }
+}
+
+/// A single access point (BSS) advertising an SSID
+#[derive(Debug, Default, Clone)]
+pub struct BssInfo {
+    pub bssid: [u8; 6],
+    pub rssi: i32,
+    pub link_quality: u8,
+    pub frequency: u32,
+    pub channel: u32,
+    /// Beacon interval in time units (1024 µs); 0 when the platform doesn't report it
+    pub beacon_interval: u16,
+    pub capabilities: Capabilities,
+}
+
+impl BssInfo {
+    pub fn band(&self) -> Option<Band> {
+        Band::from_frequency(self.frequency)
+    }
+}
+
+/// Format a BSSID as a colon-separated MAC address
+pub fn format_bssid(bssid: &[u8; 6]) -> String {
+    bssid
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Parse a colon-separated MAC address as written by [`format_bssid`]
+pub fn parse_bssid(text: &str) -> Option<[u8; 6]> {
+    let mut bssid = [0u8; 6];
+    let mut parts = text.split(':');
+    for byte in &mut bssid {
+        *byte = u8::from_str_radix(parts.next()?, 16).ok()?;
+    }
+    parts.next().is_none().then_some(bssid)
+}
+
+/// Security family of an authentication name, whether it comes from a scan
+/// (`WPA2-PSK`) or a WLAN profile (`WPA2PSK`)
+pub fn security_family(authentication: &str) -> &'static str {
+    match authentication
+        .to_ascii_uppercase()
+        .replace('-', "")
+        .as_str()
+    {
+        "OPEN" => "Open",
+        "SHARED" | "WEP" => "WEP",
+        "WPAPSK" => "WPA-Personal",
+        "WPA" => "WPA-Enterprise",
+        "WPA2PSK" => "WPA2-Personal",
+        "WPA2" => "WPA2-Enterprise",
+        "WPA3SAE" => "WPA3-Personal",
+        "WPA3" | "WPA3ENT" | "WPA3ENT192" => "WPA3-Enterprise",
+        _ => "Unknown",
+    }
+}
+
+/// Shown in place of the empty SSID of a network that doesn't broadcast it
+pub const HIDDEN_SSID_LABEL: &str = "<Hidden Network>";
+
+/// WiFi network information
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct WifiInfo {
+    /// Empty for hidden networks, which are listed once per access point
+    pub ssid: String,
+    pub authentication: String,
+    pub encryption: String,
+    pub signal: u8,
+    #[serde(rename = "saved")]
+    pub is_saved: bool,
+    #[serde(rename = "connected")]
+    pub is_connected: bool,
+    pub auto_connect: bool,
+    pub phy_type: String,
+    pub channel: u32,
+    pub frequency: u32,
+    pub link_speed: Option<u32>,
+    /// Live RSSI of the connected access point in dBm, read on each refresh
+    pub rssi: Option<i32>,
+    #[serde(skip)]
+    pub bss_list: Vec<BssInfo>,
+    #[serde(skip)]
+    pub connected_bssid: Option<[u8; 6]>,
+    /// Security family of a saved profile for this SSID that the access point
+    /// no longer offers, e.g. after a router switched from WPA2 to WPA3
+    #[serde(skip)]
+    pub profile_security: Option<&'static str>,
+    /// Name of the saved profile when it isn't the SSID, e.g. after a rename
+    #[serde(skip)]
+    pub profile_name: Option<String>,
+}
+
+/// Outer EAP method of an 802.1X network; both tunnel MSCHAPv2 inside
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EapMethod {
+    #[default]
+    Peap,
+    Ttls,
+}
+
+impl EapMethod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EapMethod::Peap => "PEAP (MSCHAPv2)",
+            EapMethod::Ttls => "TTLS (MSCHAPv2)",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            EapMethod::Peap => EapMethod::Ttls,
+            EapMethod::Ttls => EapMethod::Peap,
+        }
+    }
+}
+
+/// Credentials for a WPA2/WPA3 Enterprise network
+#[derive(Debug, Clone)]
+pub struct EnterpriseCredentials {
+    pub method: EapMethod,
+    pub identity: String,
+    pub password: SecretString,
+    /// Verify the RADIUS server certificate against the system's trusted CAs
+    pub validate_ca: bool,
+}
+
+/// How a new profile is set up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProfileOptions {
+    /// The network doesn't broadcast its SSID
+    pub hidden: bool,
+    /// Connect with a randomized hardware address
+    pub random_mac: bool,
+    /// Insist on protected management frames (802.11w) for WPA2/WPA3
+    pub require_pmf: bool,
+}
+
+/// State of this device's mobile hotspot
+#[derive(Debug, Clone, Default)]
+pub struct HotspotStatus {
+    pub active: bool,
+    pub ssid: String,
+    /// Connected clients; `None` when the platform doesn't report it
+    pub client_count: Option<u32>,
+    pub max_clients: Option<u32>,
+}
+
+/// Whether the WiFi radio is transmitting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadioState {
+    On,
+    /// Switched off in software; can be turned back on from here
+    Off,
+    /// Switched off by a hardware switch or airplane-mode key
+    HardwareOff,
+}
+
+impl RadioState {
+    pub fn is_on(&self) -> bool {
+        *self == RadioState::On
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RadioState::On => "Radio On",
+            RadioState::Off => "Radio Off",
+            RadioState::HardwareOff => "Radio Off (hardware switch)",
+        }
+    }
+}
+
+/// IP configuration of the WiFi interface
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpInfo {
+    /// Address with its prefix length
+    pub ipv4: Option<(Ipv4Addr, u8)>,
+    pub ipv6: Vec<Ipv6Addr>,
+    pub gateway: Option<IpAddr>,
+    pub dns: Vec<IpAddr>,
+    pub mac: Option<[u8; 6]>,
+}
+
+impl IpInfo {
+    /// The most useful IPv6 address: global before link-local
+    pub fn preferred_ipv6(&self) -> Option<Ipv6Addr> {
+        self.ipv6
+            .iter()
+            .find(|addr| !addr.is_unicast_link_local())
+            .or(self.ipv6.first())
+            .copied()
+    }
+}
+
+/// Frame counters of the connected interface since the adapter started
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InterfaceStats {
+    pub tx_frames: u64,
+    pub rx_frames: u64,
+    /// Transmissions that needed at least one retry
+    pub retries: u64,
+    /// Frames given up on after all retries
+    pub failed: u64,
+}
+
+/// Connection events from the WiFi listener
+#[derive(Debug, Clone)]
+pub enum ConnectionEvent {
+    Connected(String),
+    Disconnected,
+    Failed {
+        ssid: String,
+        reason_code: u32,
+        reason_str: String,
+    },
+    /// A scan of the active interface finished, successfully or not
+    // nmcli monitor does not report scans, so Linux falls back to the scan delay
+    #[cfg_attr(not(windows), allow(dead_code))]
+    ScanComplete,
+    /// The connection moved to another access point of the same network
+    // NetworkManager doesn't report roams, so Linux relies on the scans
+    #[cfg_attr(not(windows), allow(dead_code))]
+    Roamed {
+        ssid: String,
+        bssid: [u8; 6],
+    },
+}
+
+impl WifiInfo {
+    pub fn is_hidden(&self) -> bool {
+        self.ssid.is_empty()
+    }
+
+    /// The SSID, or a placeholder with the BSSID for a hidden network
+    pub fn display_ssid(&self) -> Cow<'_, str> {
+        match self.bss_list.first() {
+            _ if !self.is_hidden() => Cow::Borrowed(&self.ssid),
+            Some(bss) => Cow::Owned(format!(
+                "{} {}",
+                HIDDEN_SSID_LABEL,
+                format_bssid(&bss.bssid)
+            )),
+            None => Cow::Borrowed(HIDDEN_SSID_LABEL),
+        }
+    }
+
+    /// Name of the saved profile for this network
+    pub fn profile(&self) -> &str {
+        self.profile_name.as_deref().unwrap_or(&self.ssid)
+    }
+
+    /// The BSS entry this machine is currently associated with, if connected
+    pub fn connected_bss(&self) -> Option<&BssInfo> {
+        let bssid = self.connected_bssid?;
+        self.bss_list.iter().find(|bss| bss.bssid == bssid)
+    }
+
+    /// The access point with the strongest signal
+    pub fn strongest_bss(&self) -> Option<&BssInfo> {
+        self.bss_list.iter().max_by_key(|bss| bss.rssi)
+    }
+
+    /// Wi-Fi generation of the strongest access point
+    pub fn generation(&self) -> Option<Generation> {
+        self.strongest_bss()
+            .and_then(|bss| bss.capabilities.generation)
+            .or_else(|| Generation::from_phy(&self.phy_type, Band::from_frequency(self.frequency)))
+    }
+
+    /// Theoretical maximum PHY rate of the strongest access point, in Mbps
+    pub fn max_phy_rate(&self) -> Option<u32> {
+        self.strongest_bss()?.capabilities.max_phy_rate_mbps()
+    }
+
+    /// Number of distinct access points (BSSIDs) seen for this SSID
+    pub fn ap_count(&self) -> usize {
+        let mut bssids: Vec<[u8; 6]> = self.bss_list.iter().map(|bss| bss.bssid).collect();
+        bssids.sort_unstable();
+        bssids.dedup();
+        bssids.len()
+    }
+
+    /// Compact security label for list badges, `-E` marking Enterprise
+    pub fn security_badge(&self) -> &str {
+        match self.authentication.as_str() {
+            "WPA-PSK" => "WPA",
+            "WPA2-PSK" => "WPA2",
+            "WPA3-SAE" => "WPA3",
+            "WPA" => "WPA-E",
+            "WPA2" => "WPA2-E",
+            "WPA3" | "WPA3ENT" | "WPA3ENT192" => "WPA3-E",
+            "Shared" => "WEP",
+            auth => auth,
+        }
+    }
+}
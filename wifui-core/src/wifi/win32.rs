@@ -0,0 +1,171 @@
+//! Windows backend built on the Native WiFi (WLAN) API
+
+use crate::error::WifiResult;
+use crate::wifi::backend::WifiBackend;
+use crate::wifi::types::{
+    EnterpriseCredentials, HotspotStatus, InterfaceInfo, InterfaceStats, IpInfo, ProfileOptions,
+    RadioState, WifiInfo,
+};
+use crate::wifi::{
+    connection, handle, hotspot, icmp, ipinfo, profile, radio, scanning, stats, wps,
+};
+use secrecy::SecretString;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+pub struct Win32Backend;
+
+impl WifiBackend for Win32Backend {
+    fn scan_networks(&self) -> WifiResult<()> {
+        scanning::scan_networks()
+    }
+
+    fn get_wifi_networks(&self) -> WifiResult<Vec<WifiInfo>> {
+        connection::get_wifi_networks()
+    }
+
+    fn get_connected_ssid(&self) -> WifiResult<Option<String>> {
+        connection::get_connected_ssid()
+    }
+
+    fn connect_profile(&self, ssid: &str) -> WifiResult<()> {
+        connection::connect_profile(ssid)
+    }
+
+    fn connect_profile_bssid(&self, ssid: &str, bssid: [u8; 6]) -> WifiResult<()> {
+        connection::connect_profile_bssid(ssid, bssid)
+    }
+
+    fn connect_with_password(
+        &self,
+        ssid: &str,
+        password: &SecretString,
+        auth: &str,
+        cipher: &str,
+        options: ProfileOptions,
+    ) -> WifiResult<()> {
+        connection::connect_with_password(ssid, password, auth, cipher, options)
+    }
+
+    fn connect_enterprise(
+        &self,
+        ssid: &str,
+        auth: &str,
+        credentials: &EnterpriseCredentials,
+        options: ProfileOptions,
+    ) -> WifiResult<()> {
+        connection::connect_enterprise(ssid, auth, credentials, options)
+    }
+
+    fn connect_open(&self, ssid: &str, options: ProfileOptions) -> WifiResult<()> {
+        connection::connect_open(ssid, options)
+    }
+
+    fn connect_wps(&self, ssid: &str) -> WifiResult<()> {
+        wps::connect_wps(ssid)
+    }
+
+    fn disconnect(&self) -> WifiResult<()> {
+        connection::disconnect()
+    }
+
+    fn get_saved_profiles(&self) -> WifiResult<Vec<String>> {
+        profile::get_saved_profiles()
+    }
+
+    fn saved_profile_name(&self, ssid: &str) -> WifiResult<Option<String>> {
+        profile::find_profile_name(ssid)
+    }
+
+    fn export_profile(&self, ssid: &str) -> WifiResult<String> {
+        profile::export_profile(ssid)
+    }
+
+    fn import_profile(&self, xml: &str, overwrite: bool) -> WifiResult<String> {
+        profile::import_profile(xml, overwrite)
+    }
+
+    fn set_profile_position(&self, ssid: &str, position: u32) -> WifiResult<()> {
+        profile::set_profile_position(ssid, position)
+    }
+
+    fn set_auto_connect(&self, ssid: &str, enable: bool) -> WifiResult<()> {
+        profile::set_auto_connect(ssid, enable)
+    }
+
+    fn is_metered(&self, ssid: &str) -> WifiResult<bool> {
+        profile::is_metered(ssid)
+    }
+
+    fn set_metered(&self, ssid: &str, metered: bool) -> WifiResult<()> {
+        profile::set_metered(ssid, metered)
+    }
+
+    fn mac_randomization(&self, ssid: &str) -> WifiResult<bool> {
+        profile::is_mac_randomized(ssid)
+    }
+
+    fn set_mac_randomization(&self, ssid: &str, enable: bool) -> WifiResult<()> {
+        profile::set_mac_randomization(ssid, enable)
+    }
+
+    fn forget_network(&self, ssid: &str) -> WifiResult<()> {
+        profile::forget_network(ssid)
+    }
+
+    fn rename_profile(&self, name: &str, new_name: &str) -> WifiResult<()> {
+        profile::rename_profile(name, new_name)
+    }
+
+    fn get_wifi_password(&self, ssid: &str) -> WifiResult<Option<SecretString>> {
+        profile::get_wifi_password(ssid)
+    }
+
+    fn list_interfaces(&self) -> WifiResult<Vec<InterfaceInfo>> {
+        handle::list_interfaces()
+    }
+
+    fn set_active_interface(&self, interface: &InterfaceInfo) {
+        handle::set_active_interface(interface)
+    }
+
+    fn hotspot_status(&self) -> WifiResult<HotspotStatus> {
+        hotspot::hotspot_status()
+    }
+
+    fn start_hotspot(&self, ssid: &str, passphrase: &SecretString) -> WifiResult<()> {
+        hotspot::start_hotspot(ssid, passphrase)
+    }
+
+    fn stop_hotspot(&self) -> WifiResult<()> {
+        hotspot::stop_hotspot()
+    }
+
+    fn remove_hotspot(&self) -> WifiResult<()> {
+        hotspot::remove_hotspot()
+    }
+
+    fn default_gateway(&self) -> WifiResult<Option<Ipv4Addr>> {
+        icmp::default_gateway()
+    }
+
+    fn ping(&self, addr: Ipv4Addr, timeout: Duration) -> WifiResult<Option<Duration>> {
+        icmp::ping(addr, timeout)
+    }
+
+    fn ip_info(&self) -> WifiResult<IpInfo> {
+        ipinfo::ip_info()
+    }
+
+    fn interface_stats(&self) -> WifiResult<InterfaceStats> {
+        stats::interface_stats()
+    }
+
+    fn radio_state(&self) -> WifiResult<RadioState> {
+        radio::radio_state()
+    }
+
+    fn set_radio(&self, on: bool) -> WifiResult<()> {
+        radio::set_radio(on)
+    }
+}
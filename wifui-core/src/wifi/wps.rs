@@ -0,0 +1,109 @@
+//! WPS push-button connect through the WinRT WiFiAdapter API
+//!
+//! The Native WiFi API has no WPS support, so this goes through
+//! `Windows.Devices.WiFi`, which asks the router for push-button enrollment
+//! and saves the profile it hands out.
+
+use crate::error::{WifiError, WifiResult};
+use crate::logging;
+use crate::wifi::handle::WifiManager;
+use windows::{
+    Devices::WiFi::{
+        WiFiAdapter, WiFiAvailableNetwork, WiFiConnectionMethod, WiFiConnectionStatus,
+        WiFiReconnectionKind, WiFiWpsConfigurationStatus, WiFiWpsKind,
+    },
+    Security::Credentials::PasswordCredential,
+    core::HSTRING,
+};
+
+fn wps_error(e: windows::core::Error) -> WifiError {
+    WifiError::Wps(e.message().to_string())
+}
+
+/// The WinRT adapter behind the active WLAN interface
+fn active_adapter() -> WifiResult<WiFiAdapter> {
+    let guid = WifiManager::shared()?.interface_guid()?;
+    let adapters = WiFiAdapter::FindAllAdaptersAsync()
+        .and_then(|op| op.get())
+        .map_err(wps_error)?;
+    adapters
+        .into_iter()
+        .find(|adapter| {
+            adapter
+                .NetworkAdapter()
+                .and_then(|network_adapter| network_adapter.NetworkAdapterId())
+                .is_ok_and(|id| id == guid)
+        })
+        .ok_or(WifiError::NoInterface)
+}
+
+/// The adapter's latest scan entry for an SSID
+fn find_network(adapter: &WiFiAdapter, ssid: &str) -> WifiResult<WiFiAvailableNetwork> {
+    let networks = adapter
+        .NetworkReport()
+        .and_then(|report| report.AvailableNetworks())
+        .map_err(wps_error)?;
+    networks
+        .into_iter()
+        .find(|network| network.Ssid().is_ok_and(|s| s == ssid))
+        .ok_or_else(|| WifiError::Wps(format!("{} is not in range", ssid)))
+}
+
+/// Join a network by pressing its router's WPS button
+pub fn connect_wps(ssid: &str) -> WifiResult<()> {
+    logging::timed("connect_wps", || {
+        let adapter = active_adapter()?;
+        // The WinRT adapter keeps its own scan results
+        adapter
+            .ScanAsync()
+            .and_then(|op| op.get())
+            .map_err(wps_error)?;
+        let network = find_network(&adapter, ssid)?;
+
+        let config = adapter
+            .GetWpsConfigurationAsync(&network)
+            .and_then(|op| op.get())
+            .map_err(wps_error)?;
+        let supports_push_button = config.Status().map_err(wps_error)?
+            == WiFiWpsConfigurationStatus::Success
+            && config
+                .SupportedWpsKinds()
+                .map_err(wps_error)?
+                .into_iter()
+                .any(|kind| kind == WiFiWpsKind::PushButton);
+        if !supports_push_button {
+            return Err(WifiError::Wps(format!(
+                "{} doesn't advertise WPS push-button",
+                ssid
+            )));
+        }
+
+        let result = adapter
+            .ConnectWithPasswordCredentialAndSsidAndConnectionMethodAsync(
+                &network,
+                WiFiReconnectionKind::Automatic,
+                None::<&PasswordCredential>,
+                &HSTRING::new(),
+                WiFiConnectionMethod::WpsPushButton,
+            )
+            .and_then(|op| op.get())
+            .and_then(|result| result.ConnectionStatus())
+            .map_err(wps_error)?;
+        match result {
+            WiFiConnectionStatus::Success => Ok(()),
+            WiFiConnectionStatus::Timeout => Err(WifiError::Wps(
+                "Timed out waiting for the WPS button".to_string(),
+            )),
+            WiFiConnectionStatus::AccessRevoked => Err(WifiError::Wps(
+                "WiFi access is turned off in Windows privacy settings".to_string(),
+            )),
+            WiFiConnectionStatus::NetworkNotAvailable => {
+                Err(WifiError::Wps(format!("{} is no longer in range", ssid)))
+            }
+            status => Err(WifiError::Wps(format!(
+                "Connection failed (status {})",
+                status.0
+            ))),
+        }
+    })
+}